@@ -0,0 +1,3 @@
+"""Python bindings for PattiKadhai's Kokoro-82M ONNX TTS engine."""
+
+from .kokoro_tts_py import *  # noqa: F401,F403
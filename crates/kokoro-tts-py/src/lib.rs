@@ -0,0 +1,159 @@
+//! Python bindings for `kokoro_tts`.
+//!
+//! Exposes the same phonemization, tokenization and chunking logic the
+//! desktop app uses, returning audio as NumPy arrays.
+//!
+//! ```python
+//! import kokoro_tts
+//!
+//! tts = kokoro_tts.Kokoro("model_quantized.onnx", "voices/")
+//! samples, sample_rate = tts.create("Once upon a time.", voice="af_nova")
+//! kokoro_tts.save_wav("out.wav", samples, sample_rate)
+//! ```
+
+use std::path::PathBuf;
+
+use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+create_exception!(kokoro_tts, KokoroError, PyException);
+
+fn to_py_err(e: kokoro_tts::KokoroError) -> PyErr {
+    KokoroError::new_err(e.to_string())
+}
+
+/// The Kokoro TTS engine (ONNX model plus voice embeddings).
+#[pyclass(name = "Kokoro")]
+struct PyKokoro {
+    inner: kokoro_tts::Kokoro,
+}
+
+#[pymethods]
+impl PyKokoro {
+    /// Load the model and voices.
+    ///
+    /// `voices_path` is a directory of `*.bin` voices or a single NPZ archive.
+    #[new]
+    fn new(py: Python<'_>, model_path: PathBuf, voices_path: PathBuf) -> PyResult<Self> {
+        let inner = py
+            .detach(|| kokoro_tts::Kokoro::new(&model_path, &voices_path))
+            .map_err(to_py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Synthesize text. Returns `(samples, sample_rate)` with float32 samples.
+    #[pyo3(signature = (text, voice = "af_nova", speed = 1.0, lang = "en-us"))]
+    fn create<'py>(
+        &mut self,
+        py: Python<'py>,
+        text: &str,
+        voice: &str,
+        speed: f32,
+        lang: &str,
+    ) -> PyResult<(Bound<'py, PyArray1<f32>>, u32)> {
+        let inner = &mut self.inner;
+        let audio = py
+            .detach(|| inner.create(text, voice, speed, lang))
+            .map_err(to_py_err)?;
+        Ok((audio.samples.into_pyarray(py), audio.sample_rate))
+    }
+
+    /// Synthesize IPA phonemes directly, skipping phonemization.
+    /// Returns `(samples, sample_rate)` with float32 samples.
+    #[pyo3(signature = (phonemes, voice = "af_nova", speed = 1.0))]
+    fn create_from_phonemes<'py>(
+        &mut self,
+        py: Python<'py>,
+        phonemes: &str,
+        voice: &str,
+        speed: f32,
+    ) -> PyResult<(Bound<'py, PyArray1<f32>>, u32)> {
+        let inner = &mut self.inner;
+        let audio = py
+            .detach(|| inner.create_from_phonemes(phonemes, voice, speed))
+            .map_err(to_py_err)?;
+        Ok((audio.samples.into_pyarray(py), audio.sample_rate))
+    }
+
+    /// List available voice names.
+    fn voices(&self) -> Vec<String> {
+        self.inner.voices()
+    }
+}
+
+/// Convert text to IPA phonemes (misaki with espeak-ng fallback).
+#[pyfunction]
+#[pyo3(signature = (text, lang = "en-us"))]
+fn phonemize(py: Python<'_>, text: &str, lang: &str) -> PyResult<String> {
+    py.detach(|| kokoro_tts::phonemize::phonemize(text, lang))
+        .map_err(to_py_err)
+}
+
+/// Convert an IPA phoneme string to Kokoro token IDs (int64, unpadded).
+#[pyfunction]
+fn tokenize<'py>(py: Python<'py>, phonemes: &str) -> Bound<'py, PyArray1<i64>> {
+    kokoro_tts::tokenize::tokenize(phonemes).into_pyarray(py)
+}
+
+/// Save float32 mono samples as a WAV file.
+#[pyfunction]
+#[pyo3(signature = (path, samples, sample_rate = kokoro_tts::audio::SAMPLE_RATE))]
+fn save_wav(path: PathBuf, samples: PyReadonlyArray1<'_, f32>, sample_rate: u32) -> PyResult<()> {
+    let samples = samples.as_slice()?;
+    kokoro_tts::audio::save_wav(&path, samples, sample_rate).map_err(to_py_err)
+}
+
+/// Native module, re-exported by the `kokoro_tts` Python package.
+#[pymodule]
+fn kokoro_tts_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyKokoro>()?;
+    m.add_function(wrap_pyfunction!(phonemize, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize, m)?)?;
+    m.add_function(wrap_pyfunction!(save_wav, m)?)?;
+    m.add("KokoroError", m.py().get_type::<KokoroError>())?;
+    m.add("SAMPLE_RATE", kokoro_tts::audio::SAMPLE_RATE)?;
+    m.add("MAX_PHONEME_LEN", kokoro_tts::tokenize::MAX_PHONEME_LEN)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(py: Python<'_>) -> Bound<'_, PyModule> {
+        pyo3::wrap_pymodule!(kokoro_tts_py)(py).into_bound(py)
+    }
+
+    #[test]
+    fn module_exports_constants_and_functions() {
+        Python::attach(|py| {
+            let m = module(py);
+            let rate: u32 = m.getattr("SAMPLE_RATE").unwrap().extract().unwrap();
+            assert_eq!(rate, kokoro_tts::audio::SAMPLE_RATE);
+            let max: usize = m.getattr("MAX_PHONEME_LEN").unwrap().extract().unwrap();
+            assert_eq!(max, kokoro_tts::tokenize::MAX_PHONEME_LEN);
+            for name in ["Kokoro", "phonemize", "tokenize", "save_wav", "KokoroError"] {
+                assert!(m.hasattr(name).unwrap(), "missing {}", name);
+            }
+        });
+    }
+
+    #[test]
+    fn phonemize_matches_the_crate() {
+        Python::attach(|py| {
+            let m = module(py);
+            let expected = kokoro_tts::phonemize::phonemize("Hello world", "en-us");
+            let result = m.getattr("phonemize").unwrap().call1(("Hello world",));
+            match expected {
+                Ok(phonemes) => {
+                    let got: String = result.unwrap().extract().unwrap();
+                    assert_eq!(got, phonemes);
+                }
+                // Errors surface as the module's exception type
+                Err(_) => assert!(result.unwrap_err().is_instance_of::<KokoroError>(py)),
+            }
+        });
+    }
+}
@@ -0,0 +1,552 @@
+//! AAC-LC encoder producing raw access units for an MP4 container.
+//!
+//! Kept as simple as the MP3 encoder: long sine windows only, one
+//! scalefactor for the whole spectrum picked by a rate loop, and the
+//! cheapest codebook per band. Good enough for narration at 64 kbps.
+
+mod tables;
+
+use std::f64::consts::PI;
+
+use crate::KokoroError;
+use tables::{SCALEFACTOR_CODES, SCALEFACTOR_LENS, SPECTRUM};
+
+/// Samples per channel in one access unit.
+pub const FRAME_SAMPLES: usize = 1024;
+/// Largest magnitude the escape codebook can carry.
+const MAX_QUANT: u32 = 8191;
+/// Decoder input buffer limit per channel, in bits.
+const MAX_CHANNEL_BITS: usize = 6144;
+/// `global_gain` the decoder treats as unity (its scalefactor offset).
+const UNITY_GAIN: f64 = 156.0;
+
+const SWB_48K: [usize; 50] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 48, 56, 64, 72, 80, 88, 96, 108, 120, 132, 144, 160,
+    176, 196, 216, 240, 264, 292, 320, 352, 384, 416, 448, 480, 512, 544, 576, 608, 640, 672, 704,
+    736, 768, 800, 832, 864, 896, 928, 1024,
+];
+const SWB_32K: [usize; 52] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 48, 56, 64, 72, 80, 88, 96, 108, 120, 132, 144, 160,
+    176, 196, 216, 240, 264, 292, 320, 352, 384, 416, 448, 480, 512, 544, 576, 608, 640, 672, 704,
+    736, 768, 800, 832, 864, 896, 928, 960, 992, 1024,
+];
+const SWB_24K: [usize; 48] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 44, 52, 60, 68, 76, 84, 92, 100, 108, 116, 124, 136,
+    148, 160, 172, 188, 204, 220, 240, 260, 284, 308, 336, 364, 396, 432, 468, 508, 552, 600, 652,
+    704, 768, 832, 896, 960, 1024,
+];
+const SWB_16K: [usize; 44] = [
+    0, 8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 100, 112, 124, 136, 148, 160, 172, 184, 196, 212,
+    228, 244, 260, 280, 300, 320, 344, 368, 396, 424, 456, 492, 532, 572, 616, 664, 716, 772, 832,
+    896, 960, 1024,
+];
+const SWB_8K: [usize; 41] = [
+    0, 12, 24, 36, 48, 60, 72, 84, 96, 108, 120, 132, 144, 156, 172, 188, 204, 220, 236, 252, 268,
+    288, 308, 328, 348, 372, 396, 420, 448, 476, 508, 544, 580, 620, 664, 712, 764, 820, 880, 944,
+    1024,
+];
+
+/// Supported rates with their sampling frequency index and band edges.
+const RATES: [(u32, u8, &[usize]); 9] = [
+    (48000, 3, &SWB_48K),
+    (44100, 4, &SWB_48K),
+    (32000, 5, &SWB_32K),
+    (24000, 6, &SWB_24K),
+    (22050, 7, &SWB_24K),
+    (16000, 8, &SWB_16K),
+    (12000, 9, &SWB_16K),
+    (11025, 10, &SWB_16K),
+    (8000, 11, &SWB_8K),
+];
+
+/// Spectrum codebook layout: values per codeword, signed values (no sign
+/// bits), largest magnitude and the radix of the codeword index.
+struct Codebook {
+    dims: usize,
+    signed: bool,
+    lav: u32,
+    radix: usize,
+}
+
+const CODEBOOKS: [Codebook; 11] = [
+    Codebook { dims: 4, signed: true, lav: 1, radix: 3 },
+    Codebook { dims: 4, signed: true, lav: 1, radix: 3 },
+    Codebook { dims: 4, signed: false, lav: 2, radix: 3 },
+    Codebook { dims: 4, signed: false, lav: 2, radix: 3 },
+    Codebook { dims: 2, signed: true, lav: 4, radix: 9 },
+    Codebook { dims: 2, signed: true, lav: 4, radix: 9 },
+    Codebook { dims: 2, signed: false, lav: 7, radix: 8 },
+    Codebook { dims: 2, signed: false, lav: 7, radix: 8 },
+    Codebook { dims: 2, signed: false, lav: 12, radix: 13 },
+    Codebook { dims: 2, signed: false, lav: 12, radix: 13 },
+    Codebook { dims: 2, signed: false, lav: MAX_QUANT, radix: 17 },
+];
+
+/// Whether AAC can carry this sample rate without resampling.
+pub fn supports_rate(sample_rate: u32) -> bool {
+    RATES.iter().any(|r| r.0 == sample_rate)
+}
+
+/// A sensible bitrate in kbps for speech at this layout.
+pub fn default_bitrate(channels: u16) -> u32 {
+    64 * channels.clamp(1, 2) as u32
+}
+
+/// Streaming AAC-LC encoder for mono or stereo input.
+pub struct AacEncoder {
+    channels: usize,
+    rate_index: u8,
+    bands: &'static [usize],
+    frame_bits: usize,
+    pending: Vec<f32>,
+    /// Previous frame of input per channel, the first half of each block.
+    previous: Vec<Vec<f64>>,
+    mdct: Mdct,
+}
+
+impl AacEncoder {
+    pub fn new(sample_rate: u32, channels: u16, bitrate_kbps: u32) -> Result<Self, KokoroError> {
+        let &(_, rate_index, bands) = RATES
+            .iter()
+            .find(|r| r.0 == sample_rate)
+            .ok_or_else(|| KokoroError::Audio(format!("AAC can't carry {} Hz audio", sample_rate)))?;
+        if !(1..=2).contains(&channels) {
+            return Err(KokoroError::Audio(format!(
+                "AAC supports mono or stereo, not {} channels",
+                channels
+            )));
+        }
+        if bitrate_kbps < 8 {
+            return Err(KokoroError::Audio(format!("{} kbps is too low for AAC", bitrate_kbps)));
+        }
+        let frame_bits = (bitrate_kbps as u64 * 1000 * FRAME_SAMPLES as u64 / sample_rate as u64)
+            as usize;
+        Ok(Self {
+            channels: channels as usize,
+            rate_index,
+            bands,
+            frame_bits: frame_bits.min(MAX_CHANNEL_BITS * channels as usize),
+            pending: Vec::new(),
+            previous: vec![vec![0.0; FRAME_SAMPLES]; channels as usize],
+            mdct: Mdct::new(),
+        })
+    }
+
+    /// The two-byte AudioSpecificConfig (AAC-LC, 1024-sample frames).
+    pub fn audio_specific_config(&self) -> [u8; 2] {
+        let config = 2u16 << 11 | (self.rate_index as u16) << 7 | (self.channels as u16) << 3;
+        config.to_be_bytes()
+    }
+
+    /// Feed interleaved samples and return any complete access units.
+    pub fn encode(&mut self, samples: &[f32]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(samples);
+        let frame_len = FRAME_SAMPLES * self.channels;
+        let mut units = Vec::new();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            let frame = self.pending[start..start + frame_len].to_vec();
+            units.push(self.encode_frame(&frame));
+            start += frame_len;
+        }
+        self.pending.drain(..start);
+        units
+    }
+
+    /// Pad out the last frame and flush the overlap. The decoded stream
+    /// starts with `FRAME_SAMPLES` of priming silence.
+    pub fn finish(mut self) -> Vec<Vec<u8>> {
+        let frame_len = FRAME_SAMPLES * self.channels;
+        let padding = (frame_len - self.pending.len() % frame_len) % frame_len;
+        let silence = vec![0.0; padding + frame_len];
+        self.encode(&silence)
+    }
+
+    fn encode_frame(&mut self, frame: &[f32]) -> Vec<u8> {
+        let mut bits = BitWriter::default();
+        // SCE or CPE header, without a common window
+        if self.channels == 1 {
+            bits.put(0, 3);
+            bits.put(0, 4);
+        } else {
+            bits.put(1, 3);
+            bits.put(0, 4);
+            bits.put(0, 1);
+        }
+        let mut remaining = self.frame_bits.saturating_sub(bits.len() + 3 + 7);
+        for ch in 0..self.channels {
+            let current: Vec<f64> = frame
+                .iter()
+                .skip(ch)
+                .step_by(self.channels)
+                .map(|&s| s as f64)
+                .collect();
+            let mut block = std::mem::replace(&mut self.previous[ch], current.clone());
+            block.extend_from_slice(&current);
+            let spectrum = self.mdct.forward(&block);
+            let budget = (remaining / (self.channels - ch)).min(MAX_CHANNEL_BITS);
+            let stream = quantize(&spectrum, self.bands, budget);
+            remaining = remaining.saturating_sub(stream.bits);
+            write_ics(&mut bits, &stream, self.bands);
+        }
+        bits.put(7, 3); // END
+        bits.into_bytes()
+    }
+}
+
+/// One channel's quantized spectrum with its band codebooks.
+struct ChannelStream {
+    q: Vec<i32>,
+    global_gain: u32,
+    max_sfb: usize,
+    codebooks: Vec<usize>,
+    bits: usize,
+}
+
+/// Find the smallest global gain whose coded spectrum fits the budget.
+fn quantize(spectrum: &[f64], bands: &[usize], budget: usize) -> ChannelStream {
+    let x34: Vec<f64> = spectrum.iter().map(|x| x.abs().powf(0.75)).collect();
+    let attempt = |gain: u32| -> Option<ChannelStream> {
+        let step = 2f64.powf(-0.1875 * (gain as f64 - UNITY_GAIN));
+        let mut q = Vec::with_capacity(x34.len());
+        for (x, s) in x34.iter().zip(spectrum) {
+            let value = x * step + 0.4054;
+            if value > MAX_QUANT as f64 {
+                return None;
+            }
+            let magnitude = value as i32;
+            q.push(if *s < 0.0 { -magnitude } else { magnitude });
+        }
+        let stream = layout(q, gain, bands);
+        (stream.bits <= budget).then_some(stream)
+    };
+
+    let (mut low, mut high) = (0u32, 255u32);
+    while low < high {
+        let mid = (low + high) / 2;
+        if attempt(mid).is_some() {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    (low..=255)
+        .find_map(attempt)
+        .unwrap_or_else(|| layout(vec![0; spectrum.len()], 255, bands))
+}
+
+/// Pick a codebook per band and count the bits of the whole ICS.
+fn layout(q: Vec<i32>, global_gain: u32, bands: &[usize]) -> ChannelStream {
+    let num_bands = bands.len() - 1;
+    let mut codebooks = Vec::with_capacity(num_bands);
+    let mut bits = 8 + 11 + 3; // global gain, ics_info, pulse/tns/gain flags
+    for band in 0..num_bands {
+        let values = &q[bands[band]..bands[band + 1]];
+        let (codebook, cost) = best_codebook(values);
+        codebooks.push(codebook);
+        bits += cost;
+    }
+    let max_sfb = codebooks.iter().rposition(|&cb| cb != 0).map_or(0, |i| i + 1);
+    codebooks.truncate(max_sfb);
+    bits += sections(&codebooks).iter().map(|&(_, len)| 4 + 5 * (len / 31 + 1)).sum::<usize>();
+    // Every coded band repeats the global gain: a 1-bit zero delta
+    bits += codebooks.iter().filter(|&&cb| cb != 0).count() * SCALEFACTOR_LENS[60] as usize;
+    ChannelStream {
+        q,
+        global_gain,
+        max_sfb,
+        codebooks,
+        bits,
+    }
+}
+
+/// Runs of bands sharing a codebook, as (codebook, band count).
+fn sections(codebooks: &[usize]) -> Vec<(usize, usize)> {
+    let mut sections: Vec<(usize, usize)> = Vec::new();
+    for &cb in codebooks {
+        match sections.last_mut() {
+            Some((last, len)) if *last == cb => *len += 1,
+            _ => sections.push((cb, 1)),
+        }
+    }
+    sections
+}
+
+fn best_codebook(values: &[i32]) -> (usize, usize) {
+    let max = values.iter().map(|v| v.unsigned_abs()).max().unwrap_or(0);
+    if max == 0 {
+        return (0, 0);
+    }
+    CODEBOOKS
+        .iter()
+        .enumerate()
+        .filter(|(_, book)| max <= book.lav)
+        .map(|(i, _)| (i + 1, spectrum_bits(values, i + 1)))
+        .min_by_key(|&(_, cost)| cost)
+        .expect("the escape codebook carries every quantized value")
+}
+
+/// Codeword index for a group of values, plus its sign and escape bits.
+fn codeword(group: &[i32], codebook: usize) -> (usize, usize) {
+    let book = &CODEBOOKS[codebook - 1];
+    let mut index = 0;
+    let mut extra = 0;
+    for &v in group {
+        let digit = if book.signed {
+            (v + book.lav as i32) as usize
+        } else {
+            let magnitude = v.unsigned_abs();
+            if magnitude != 0 {
+                extra += 1;
+            }
+            if codebook == 11 && magnitude >= 16 {
+                extra += escape_len(magnitude);
+                16
+            } else {
+                magnitude as usize
+            }
+        };
+        index = index * book.radix + digit;
+    }
+    (index, extra)
+}
+
+fn escape_len(magnitude: u32) -> usize {
+    let n = (31 - magnitude.leading_zeros()) as usize - 4;
+    2 * n + 5
+}
+
+fn spectrum_bits(values: &[i32], codebook: usize) -> usize {
+    let lens = SPECTRUM[codebook - 1].1;
+    values
+        .chunks(CODEBOOKS[codebook - 1].dims)
+        .map(|group| {
+            let (index, extra) = codeword(group, codebook);
+            lens[index] as usize + extra
+        })
+        .sum()
+}
+
+fn write_ics(bits: &mut BitWriter, stream: &ChannelStream, bands: &[usize]) {
+    bits.put(stream.global_gain, 8);
+    // ics_info: reserved, ONLY_LONG_SEQUENCE, sine window, max_sfb, no prediction
+    bits.put(0, 1);
+    bits.put(0, 2);
+    bits.put(0, 1);
+    bits.put(stream.max_sfb as u32, 6);
+    bits.put(0, 1);
+
+    for (codebook, mut len) in sections(&stream.codebooks) {
+        bits.put(codebook as u32, 4);
+        while len >= 31 {
+            bits.put(31, 5);
+            len -= 31;
+        }
+        bits.put(len as u32, 5);
+    }
+    for &codebook in &stream.codebooks {
+        if codebook != 0 {
+            bits.put(SCALEFACTOR_CODES[60], SCALEFACTOR_LENS[60] as u32);
+        }
+    }
+    bits.put(0, 1); // pulse
+    bits.put(0, 1); // tns
+    bits.put(0, 1); // gain control
+
+    for (band, &codebook) in stream.codebooks.iter().enumerate() {
+        if codebook == 0 {
+            continue;
+        }
+        let (codes, lens) = SPECTRUM[codebook - 1];
+        let book = &CODEBOOKS[codebook - 1];
+        for group in stream.q[bands[band]..bands[band + 1]].chunks(book.dims) {
+            let (index, _) = codeword(group, codebook);
+            bits.put(codes[index], lens[index] as u32);
+            if book.signed {
+                continue;
+            }
+            for &v in group {
+                if v != 0 {
+                    bits.put((v < 0) as u32, 1);
+                }
+            }
+            if codebook == 11 {
+                for &v in group {
+                    let magnitude = v.unsigned_abs();
+                    if magnitude >= 16 {
+                        let n = 31 - magnitude.leading_zeros() - 4;
+                        bits.put((1 << n) - 1, n);
+                        bits.put(0, 1);
+                        bits.put(magnitude - (1 << (n + 4)), n + 4);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sine-windowed 2048-point MDCT computed with a 512-point complex FFT.
+struct Mdct {
+    window: Vec<f64>,
+    /// exp(-iπ(n + 1/4)/1024), the pre-twiddle
+    pre: Vec<(f64, f64)>,
+    /// exp(-iπk/1024), the post-twiddle
+    post: Vec<(f64, f64)>,
+    /// exp(-2πik/512) for the FFT butterflies
+    roots: Vec<(f64, f64)>,
+}
+
+impl Mdct {
+    const N: usize = 2 * FRAME_SAMPLES;
+    const M: usize = FRAME_SAMPLES;
+    /// The decoder's IMDCT is scaled by 1/2048, so its overlap-add returns
+    /// a quarter of the input.
+    const SCALE: f64 = 4.0;
+
+    fn new() -> Self {
+        let polar = |angle: f64| (angle.cos(), angle.sin());
+        Self {
+            window: (0..Self::N)
+                .map(|n| (PI / Self::N as f64 * (n as f64 + 0.5)).sin())
+                .collect(),
+            pre: (0..Self::M / 2)
+                .map(|n| polar(-PI * (n as f64 + 0.25) / Self::M as f64))
+                .collect(),
+            post: (0..Self::M / 2)
+                .map(|k| polar(-PI * k as f64 / Self::M as f64))
+                .collect(),
+            roots: (0..Self::M / 4)
+                .map(|k| polar(-2.0 * PI * k as f64 / (Self::M / 2) as f64))
+                .collect(),
+        }
+    }
+
+    fn forward(&self, block: &[f64]) -> Vec<f64> {
+        let (n, m) = (Self::N, Self::M);
+        let x: Vec<f64> = block.iter().zip(&self.window).map(|(s, w)| s * w).collect();
+        // Fold into a DCT-IV input
+        let mut v = vec![0.0; m];
+        for i in 0..n / 4 {
+            v[i] = -x[3 * n / 4 + i] - x[3 * n / 4 - 1 - i];
+            v[n / 4 + i] = x[i] - x[n / 2 - 1 - i];
+        }
+        let mut z: Vec<(f64, f64)> = (0..m / 2)
+            .map(|i| mul((v[2 * i], v[m - 1 - 2 * i]), self.pre[i]))
+            .collect();
+        self.fft(&mut z);
+        let mut out = vec![0.0; m];
+        for (k, &value) in z.iter().enumerate() {
+            let (re, im) = mul(value, self.post[k]);
+            out[2 * k] = re * Self::SCALE;
+            out[m - 1 - 2 * k] = -im * Self::SCALE;
+        }
+        out
+    }
+
+    /// In-place radix-2 FFT.
+    fn fft(&self, data: &mut [(f64, f64)]) {
+        let len = data.len();
+        let mut j = 0;
+        for i in 1..len {
+            let mut bit = len >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        let mut size = 2;
+        while size <= len {
+            let stride = len / size;
+            for start in (0..len).step_by(size) {
+                for k in 0..size / 2 {
+                    let t = mul(data[start + k + size / 2], self.roots[k * stride]);
+                    let u = data[start + k];
+                    data[start + k] = (u.0 + t.0, u.1 + t.1);
+                    data[start + k + size / 2] = (u.0 - t.0, u.1 - t.1);
+                }
+            }
+            size *= 2;
+        }
+    }
+}
+
+fn mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+/// MSB-first bit writer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    pending: u32,
+}
+
+impl BitWriter {
+    fn put(&mut self, value: u32, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (value as u64 & ((1u64 << bits) - 1));
+        self.pending += bits;
+        while self.pending >= 8 {
+            self.pending -= 8;
+            self.bytes.push((self.acc >> self.pending) as u8);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len() * 8 + self.pending as usize
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        if self.pending > 0 {
+            self.bytes.push((self.acc << (8 - self.pending)) as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_mdct_matches_the_definition() {
+        let mdct = Mdct::new();
+        let block: Vec<f64> = (0..2048).map(|i| ((i * 7919) % 1000) as f64 / 500.0 - 1.0).collect();
+        let fast = mdct.forward(&block);
+        for k in [0, 1, 100, 511, 1023] {
+            let direct: f64 = (0..2048)
+                .map(|n| {
+                    block[n]
+                        * mdct.window[n]
+                        * (2.0 * PI / 2048.0 * (n as f64 + 0.5 + 512.0) * (k as f64 + 0.5)).cos()
+                })
+                .sum::<f64>()
+                * Mdct::SCALE;
+            assert!((fast[k] - direct).abs() < 1e-6, "bin {}: {} vs {}", k, fast[k], direct);
+        }
+    }
+
+    #[test]
+    fn config_describes_lc_at_the_rate() {
+        let encoder = AacEncoder::new(24000, 1, 64).unwrap();
+        // object type 2, frequency index 6, one channel
+        assert_eq!(encoder.audio_specific_config(), [0x13, 0x08]);
+        assert!(AacEncoder::new(96000, 1, 64).is_err());
+        assert!(AacEncoder::new(24000, 3, 64).is_err());
+    }
+
+    #[test]
+    fn escape_values_round_trip_their_length() {
+        assert_eq!(escape_len(16), 5);
+        assert_eq!(escape_len(31), 5);
+        assert_eq!(escape_len(32), 7);
+        assert_eq!(escape_len(8191), 21);
+    }
+}
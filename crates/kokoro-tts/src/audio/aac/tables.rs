@@ -0,0 +1,349 @@
+//! AAC Huffman code tables (ISO/IEC 14496-3 Tables 4.A.1-4.A.12).
+
+const CODES_1: [u32; 81] = [
+    0x7F8, 0x1F1, 0x7FD, 0x3F5, 0x68, 0x3F0, 0x7F7, 0x1EC,
+    0x7F5, 0x3F1, 0x72, 0x3F4, 0x74, 0x11, 0x76, 0x1EB,
+    0x6C, 0x3F6, 0x7FC, 0x1E1, 0x7F1, 0x1F0, 0x61, 0x1F6,
+    0x7F2, 0x1EA, 0x7FB, 0x1F2, 0x69, 0x1ED, 0x77, 0x17,
+    0x6F, 0x1E6, 0x64, 0x1E5, 0x67, 0x15, 0x62, 0x12,
+    0x0, 0x14, 0x65, 0x16, 0x6D, 0x1E9, 0x63, 0x1E4,
+    0x6B, 0x13, 0x71, 0x1E3, 0x70, 0x1F3, 0x7FE, 0x1E7,
+    0x7F3, 0x1EF, 0x60, 0x1EE, 0x7F0, 0x1E2, 0x7FA, 0x3F3,
+    0x6A, 0x1E8, 0x75, 0x10, 0x73, 0x1F4, 0x6E, 0x3F7,
+    0x7F6, 0x1E0, 0x7F9, 0x3F2, 0x66, 0x1F5, 0x7FF, 0x1F7,
+    0x7F4,
+];
+const LENS_1: [u8; 81] = [
+    11, 9, 11, 10, 7, 10, 11, 9, 11, 10, 7, 10, 7, 5, 7, 9,
+    7, 10, 11, 9, 11, 9, 7, 9, 11, 9, 11, 9, 7, 9, 7, 5,
+    7, 9, 7, 9, 7, 5, 7, 5, 1, 5, 7, 5, 7, 9, 7, 9,
+    7, 5, 7, 9, 7, 9, 11, 9, 11, 9, 7, 9, 11, 9, 11, 10,
+    7, 9, 7, 5, 7, 9, 7, 10, 11, 9, 11, 10, 7, 9, 11, 9,
+    11,
+];
+
+const CODES_2: [u32; 81] = [
+    0x1F3, 0x6F, 0x1FD, 0xEB, 0x23, 0xEA, 0x1F7, 0xE8,
+    0x1FA, 0xF2, 0x2D, 0x70, 0x20, 0x6, 0x2B, 0x6E,
+    0x28, 0xE9, 0x1F9, 0x66, 0xF8, 0xE7, 0x1B, 0xF1,
+    0x1F4, 0x6B, 0x1F5, 0xEC, 0x2A, 0x6C, 0x2C, 0xA,
+    0x27, 0x67, 0x1A, 0xF5, 0x24, 0x8, 0x1F, 0x9,
+    0x0, 0x7, 0x1D, 0xB, 0x30, 0xEF, 0x1C, 0x64,
+    0x1E, 0xC, 0x29, 0xF3, 0x2F, 0xF0, 0x1FC, 0x71,
+    0x1F2, 0xF4, 0x21, 0xE6, 0xF7, 0x68, 0x1F8, 0xEE,
+    0x22, 0x65, 0x31, 0x2, 0x26, 0xED, 0x25, 0x6A,
+    0x1FB, 0x72, 0x1FE, 0x69, 0x2E, 0xF6, 0x1FF, 0x6D,
+    0x1F6,
+];
+const LENS_2: [u8; 81] = [
+    9, 7, 9, 8, 6, 8, 9, 8, 9, 8, 6, 7, 6, 5, 6, 7,
+    6, 8, 9, 7, 8, 8, 6, 8, 9, 7, 9, 8, 6, 7, 6, 5,
+    6, 7, 6, 8, 6, 5, 6, 5, 3, 5, 6, 5, 6, 8, 6, 7,
+    6, 5, 6, 8, 6, 8, 9, 7, 9, 8, 6, 8, 8, 7, 9, 8,
+    6, 7, 6, 4, 6, 8, 6, 7, 9, 7, 9, 7, 6, 8, 9, 7,
+    9,
+];
+
+const CODES_3: [u32; 81] = [
+    0x0, 0x9, 0xEF, 0xB, 0x19, 0xF0, 0x1EB, 0x1E6,
+    0x3F2, 0xA, 0x35, 0x1EF, 0x34, 0x37, 0x1E9, 0x1ED,
+    0x1E7, 0x3F3, 0x1EE, 0x3ED, 0x1FFA, 0x1EC, 0x1F2, 0x7F9,
+    0x7F8, 0x3F8, 0xFF8, 0x8, 0x38, 0x3F6, 0x36, 0x75,
+    0x3F1, 0x3EB, 0x3EC, 0xFF4, 0x18, 0x76, 0x7F4, 0x39,
+    0x74, 0x3EF, 0x1F3, 0x1F4, 0x7F6, 0x1E8, 0x3EA, 0x1FFC,
+    0xF2, 0x1F1, 0xFFB, 0x3F5, 0x7F3, 0xFFC, 0xEE, 0x3F7,
+    0x7FFE, 0x1F0, 0x7F5, 0x7FFD, 0x1FFB, 0x3FFA, 0xFFFF, 0xF1,
+    0x3F0, 0x3FFC, 0x1EA, 0x3EE, 0x3FFB, 0xFF6, 0xFFA, 0x7FFC,
+    0x7F2, 0xFF5, 0xFFFE, 0x3F4, 0x7F7, 0x7FFB, 0xFF7, 0xFF9,
+    0x7FFA,
+];
+const LENS_3: [u8; 81] = [
+    1, 4, 8, 4, 5, 8, 9, 9, 10, 4, 6, 9, 6, 6, 9, 9,
+    9, 10, 9, 10, 13, 9, 9, 11, 11, 10, 12, 4, 6, 10, 6, 7,
+    10, 10, 10, 12, 5, 7, 11, 6, 7, 10, 9, 9, 11, 9, 10, 13,
+    8, 9, 12, 10, 11, 12, 8, 10, 15, 9, 11, 15, 13, 14, 16, 8,
+    10, 14, 9, 10, 14, 12, 12, 15, 11, 12, 16, 10, 11, 15, 12, 12,
+    15,
+];
+
+const CODES_4: [u32; 81] = [
+    0x7, 0x16, 0xF6, 0x18, 0x8, 0xEF, 0x1EF, 0xF3,
+    0x7F8, 0x19, 0x17, 0xED, 0x15, 0x1, 0xE2, 0xF0,
+    0x70, 0x3F0, 0x1EE, 0xF1, 0x7FA, 0xEE, 0xE4, 0x3F2,
+    0x7F6, 0x3EF, 0x7FD, 0x5, 0x14, 0xF2, 0x9, 0x4,
+    0xE5, 0xF4, 0xE8, 0x3F4, 0x6, 0x2, 0xE7, 0x3,
+    0x0, 0x6B, 0xE3, 0x69, 0x1F3, 0xEB, 0xE6, 0x3F6,
+    0x6E, 0x6A, 0x1F4, 0x3EC, 0x1F0, 0x3F9, 0xF5, 0xEC,
+    0x7FB, 0xEA, 0x6F, 0x3F7, 0x7F9, 0x3F3, 0xFFF, 0xE9,
+    0x6D, 0x3F8, 0x6C, 0x68, 0x1F5, 0x3EE, 0x1F2, 0x7F4,
+    0x7F7, 0x3F1, 0xFFE, 0x3ED, 0x1F1, 0x7F5, 0x7FE, 0x3F5,
+    0x7FC,
+];
+const LENS_4: [u8; 81] = [
+    4, 5, 8, 5, 4, 8, 9, 8, 11, 5, 5, 8, 5, 4, 8, 8,
+    7, 10, 9, 8, 11, 8, 8, 10, 11, 10, 11, 4, 5, 8, 4, 4,
+    8, 8, 8, 10, 4, 4, 8, 4, 4, 7, 8, 7, 9, 8, 8, 10,
+    7, 7, 9, 10, 9, 10, 8, 8, 11, 8, 7, 10, 11, 10, 12, 8,
+    7, 10, 7, 7, 9, 10, 9, 11, 11, 10, 12, 10, 9, 11, 11, 10,
+    11,
+];
+
+const CODES_5: [u32; 81] = [
+    0x1FFF, 0xFF7, 0x7F4, 0x7E8, 0x3F1, 0x7EE, 0x7F9, 0xFF8,
+    0x1FFD, 0xFFD, 0x7F1, 0x3E8, 0x1E8, 0xF0, 0x1EC, 0x3EE,
+    0x7F2, 0xFFA, 0xFF4, 0x3EF, 0x1F2, 0xE8, 0x70, 0xEC,
+    0x1F0, 0x3EA, 0x7F3, 0x7EB, 0x1EB, 0xEA, 0x1A, 0x8,
+    0x19, 0xEE, 0x1EF, 0x7ED, 0x3F0, 0xF2, 0x73, 0xB,
+    0x0, 0xA, 0x71, 0xF3, 0x7E9, 0x7EF, 0x1EE, 0xEF,
+    0x18, 0x9, 0x1B, 0xEB, 0x1E9, 0x7EC, 0x7F6, 0x3EB,
+    0x1F3, 0xED, 0x72, 0xE9, 0x1F1, 0x3ED, 0x7F7, 0xFF6,
+    0x7F0, 0x3E9, 0x1ED, 0xF1, 0x1EA, 0x3EC, 0x7F8, 0xFF9,
+    0x1FFC, 0xFFC, 0xFF5, 0x7EA, 0x3F3, 0x3F2, 0x7F5, 0xFFB,
+    0x1FFE,
+];
+const LENS_5: [u8; 81] = [
+    13, 12, 11, 11, 10, 11, 11, 12, 13, 12, 11, 10, 9, 8, 9, 10,
+    11, 12, 12, 10, 9, 8, 7, 8, 9, 10, 11, 11, 9, 8, 5, 4,
+    5, 8, 9, 11, 10, 8, 7, 4, 1, 4, 7, 8, 11, 11, 9, 8,
+    5, 4, 5, 8, 9, 11, 11, 10, 9, 8, 7, 8, 9, 10, 11, 12,
+    11, 10, 9, 8, 9, 10, 11, 12, 13, 12, 12, 11, 10, 10, 11, 12,
+    13,
+];
+
+const CODES_6: [u32; 81] = [
+    0x7FE, 0x3FD, 0x1F1, 0x1EB, 0x1F4, 0x1EA, 0x1F0, 0x3FC,
+    0x7FD, 0x3F6, 0x1E5, 0xEA, 0x6C, 0x71, 0x68, 0xF0,
+    0x1E6, 0x3F7, 0x1F3, 0xEF, 0x32, 0x27, 0x28, 0x26,
+    0x31, 0xEB, 0x1F7, 0x1E8, 0x6F, 0x2E, 0x8, 0x4,
+    0x6, 0x29, 0x6B, 0x1EE, 0x1EF, 0x72, 0x2D, 0x2,
+    0x0, 0x3, 0x2F, 0x73, 0x1FA, 0x1E7, 0x6E, 0x2B,
+    0x7, 0x1, 0x5, 0x2C, 0x6D, 0x1EC, 0x1F9, 0xEE,
+    0x30, 0x24, 0x2A, 0x25, 0x33, 0xEC, 0x1F2, 0x3F8,
+    0x1E4, 0xED, 0x6A, 0x70, 0x69, 0x74, 0xF1, 0x3FA,
+    0x7FF, 0x3F9, 0x1F6, 0x1ED, 0x1F8, 0x1E9, 0x1F5, 0x3FB,
+    0x7FC,
+];
+const LENS_6: [u8; 81] = [
+    11, 10, 9, 9, 9, 9, 9, 10, 11, 10, 9, 8, 7, 7, 7, 8,
+    9, 10, 9, 8, 6, 6, 6, 6, 6, 8, 9, 9, 7, 6, 4, 4,
+    4, 6, 7, 9, 9, 7, 6, 4, 4, 4, 6, 7, 9, 9, 7, 6,
+    4, 4, 4, 6, 7, 9, 9, 8, 6, 6, 6, 6, 6, 8, 9, 10,
+    9, 8, 7, 7, 7, 7, 8, 10, 11, 10, 9, 9, 9, 9, 9, 10,
+    11,
+];
+
+const CODES_7: [u32; 64] = [
+    0x0, 0x5, 0x37, 0x74, 0xF2, 0x1EB, 0x3ED, 0x7F7,
+    0x4, 0xC, 0x35, 0x71, 0xEC, 0xEE, 0x1EE, 0x1F5,
+    0x36, 0x34, 0x72, 0xEA, 0xF1, 0x1E9, 0x1F3, 0x3F5,
+    0x73, 0x70, 0xEB, 0xF0, 0x1F1, 0x1F0, 0x3EC, 0x3FA,
+    0xF3, 0xED, 0x1E8, 0x1EF, 0x3EF, 0x3F1, 0x3F9, 0x7FB,
+    0x1ED, 0xEF, 0x1EA, 0x1F2, 0x3F3, 0x3F8, 0x7F9, 0x7FC,
+    0x3EE, 0x1EC, 0x1F4, 0x3F4, 0x3F7, 0x7F8, 0xFFD, 0xFFE,
+    0x7F6, 0x3F0, 0x3F2, 0x3F6, 0x7FA, 0x7FD, 0xFFC, 0xFFF,
+];
+const LENS_7: [u8; 64] = [
+    1, 3, 6, 7, 8, 9, 10, 11, 3, 4, 6, 7, 8, 8, 9, 9,
+    6, 6, 7, 8, 8, 9, 9, 10, 7, 7, 8, 8, 9, 9, 10, 10,
+    8, 8, 9, 9, 10, 10, 10, 11, 9, 8, 9, 9, 10, 10, 11, 11,
+    10, 9, 9, 10, 10, 11, 12, 12, 11, 10, 10, 10, 11, 11, 12, 12,
+];
+
+const CODES_8: [u32; 64] = [
+    0xE, 0x5, 0x10, 0x30, 0x6F, 0xF1, 0x1FA, 0x3FE,
+    0x3, 0x0, 0x4, 0x12, 0x2C, 0x6A, 0x75, 0xF8,
+    0xF, 0x2, 0x6, 0x14, 0x2E, 0x69, 0x72, 0xF5,
+    0x2F, 0x11, 0x13, 0x2A, 0x32, 0x6C, 0xEC, 0xFA,
+    0x71, 0x2B, 0x2D, 0x31, 0x6D, 0x70, 0xF2, 0x1F9,
+    0xEF, 0x68, 0x33, 0x6B, 0x6E, 0xEE, 0xF9, 0x3FC,
+    0x1F8, 0x74, 0x73, 0xED, 0xF0, 0xF6, 0x1F6, 0x1FD,
+    0x3FD, 0xF3, 0xF4, 0xF7, 0x1F7, 0x1FB, 0x1FC, 0x3FF,
+];
+const LENS_8: [u8; 64] = [
+    5, 4, 5, 6, 7, 8, 9, 10, 4, 3, 4, 5, 6, 7, 7, 8,
+    5, 4, 4, 5, 6, 7, 7, 8, 6, 5, 5, 6, 6, 7, 8, 8,
+    7, 6, 6, 6, 7, 7, 8, 9, 8, 7, 6, 7, 7, 8, 8, 10,
+    9, 7, 7, 8, 8, 8, 9, 9, 10, 8, 8, 8, 9, 9, 9, 10,
+];
+
+const CODES_9: [u32; 169] = [
+    0x0, 0x5, 0x37, 0xE7, 0x1DE, 0x3CE, 0x3D9, 0x7C8,
+    0x7CD, 0xFC8, 0xFDD, 0x1FE4, 0x1FEC, 0x4, 0xC, 0x35,
+    0x72, 0xEA, 0xED, 0x1E2, 0x3D1, 0x3D3, 0x3E0, 0x7D8,
+    0xFCF, 0xFD5, 0x36, 0x34, 0x71, 0xE8, 0xEC, 0x1E1,
+    0x3CF, 0x3DD, 0x3DB, 0x7D0, 0xFC7, 0xFD4, 0xFE4, 0xE6,
+    0x70, 0xE9, 0x1DD, 0x1E3, 0x3D2, 0x3DC, 0x7CC, 0x7CA,
+    0x7DE, 0xFD8, 0xFEA, 0x1FDB, 0x1DF, 0xEB, 0x1DC, 0x1E6,
+    0x3D5, 0x3DE, 0x7CB, 0x7DD, 0x7DC, 0xFCD, 0xFE2, 0xFE7,
+    0x1FE1, 0x3D0, 0x1E0, 0x1E4, 0x3D6, 0x7C5, 0x7D1, 0x7DB,
+    0xFD2, 0x7E0, 0xFD9, 0xFEB, 0x1FE3, 0x1FE9, 0x7C4, 0x1E5,
+    0x3D7, 0x7C6, 0x7CF, 0x7DA, 0xFCB, 0xFDA, 0xFE3, 0xFE9,
+    0x1FE6, 0x1FF3, 0x1FF7, 0x7D3, 0x3D8, 0x3E1, 0x7D4, 0x7D9,
+    0xFD3, 0xFDE, 0x1FDD, 0x1FD9, 0x1FE2, 0x1FEA, 0x1FF1, 0x1FF6,
+    0x7D2, 0x3D4, 0x3DA, 0x7C7, 0x7D7, 0x7E2, 0xFCE, 0xFDB,
+    0x1FD8, 0x1FEE, 0x3FF0, 0x1FF4, 0x3FF2, 0x7E1, 0x3DF, 0x7C9,
+    0x7D6, 0xFCA, 0xFD0, 0xFE5, 0xFE6, 0x1FEB, 0x1FEF, 0x3FF3,
+    0x3FF4, 0x3FF5, 0xFE0, 0x7CE, 0x7D5, 0xFC6, 0xFD1, 0xFE1,
+    0x1FE0, 0x1FE8, 0x1FF0, 0x3FF1, 0x3FF8, 0x3FF6, 0x7FFC, 0xFE8,
+    0x7DF, 0xFC9, 0xFD7, 0xFDC, 0x1FDC, 0x1FDF, 0x1FED, 0x1FF5,
+    0x3FF9, 0x3FFB, 0x7FFD, 0x7FFE, 0x1FE7, 0xFCC, 0xFD6, 0xFDF,
+    0x1FDE, 0x1FDA, 0x1FE5, 0x1FF2, 0x3FFA, 0x3FF7, 0x3FFC, 0x3FFD,
+    0x7FFF,
+];
+const LENS_9: [u8; 169] = [
+    1, 3, 6, 8, 9, 10, 10, 11, 11, 12, 12, 13, 13, 3, 4, 6,
+    7, 8, 8, 9, 10, 10, 10, 11, 12, 12, 6, 6, 7, 8, 8, 9,
+    10, 10, 10, 11, 12, 12, 12, 8, 7, 8, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 9, 8, 9, 9, 10, 10, 11, 11, 11, 12, 12, 12,
+    13, 10, 9, 9, 10, 11, 11, 11, 12, 11, 12, 12, 13, 13, 11, 9,
+    10, 11, 11, 11, 12, 12, 12, 12, 13, 13, 13, 11, 10, 10, 11, 11,
+    12, 12, 13, 13, 13, 13, 13, 13, 11, 10, 10, 11, 11, 11, 12, 12,
+    13, 13, 14, 13, 14, 11, 10, 11, 11, 12, 12, 12, 12, 13, 13, 14,
+    14, 14, 12, 11, 11, 12, 12, 12, 13, 13, 13, 14, 14, 14, 15, 12,
+    11, 12, 12, 12, 13, 13, 13, 13, 14, 14, 15, 15, 13, 12, 12, 12,
+    13, 13, 13, 13, 14, 14, 14, 14, 15,
+];
+
+const CODES_10: [u32; 169] = [
+    0x22, 0x8, 0x1D, 0x26, 0x5F, 0xD3, 0x1CF, 0x3D0,
+    0x3D7, 0x3ED, 0x7F0, 0x7F6, 0xFFD, 0x7, 0x0, 0x1,
+    0x9, 0x20, 0x54, 0x60, 0xD5, 0xDC, 0x1D4, 0x3CD,
+    0x3DE, 0x7E7, 0x1C, 0x2, 0x6, 0xC, 0x1E, 0x28,
+    0x5B, 0xCD, 0xD9, 0x1CE, 0x1DC, 0x3D9, 0x3F1, 0x25,
+    0xB, 0xA, 0xD, 0x24, 0x57, 0x61, 0xCC, 0xDD,
+    0x1CC, 0x1DE, 0x3D3, 0x3E7, 0x5D, 0x21, 0x1F, 0x23,
+    0x27, 0x59, 0x64, 0xD8, 0xDF, 0x1D2, 0x1E2, 0x3DD,
+    0x3EE, 0xD1, 0x55, 0x29, 0x56, 0x58, 0x62, 0xCE,
+    0xE0, 0xE2, 0x1DA, 0x3D4, 0x3E3, 0x7EB, 0x1C9, 0x5E,
+    0x5A, 0x5C, 0x63, 0xCA, 0xDA, 0x1C7, 0x1CA, 0x1E0,
+    0x3DB, 0x3E8, 0x7EC, 0x1E3, 0xD2, 0xCB, 0xD0, 0xD7,
+    0xDB, 0x1C6, 0x1D5, 0x1D8, 0x3CA, 0x3DA, 0x7EA, 0x7F1,
+    0x1E1, 0xD4, 0xCF, 0xD6, 0xDE, 0xE1, 0x1D0, 0x1D6,
+    0x3D1, 0x3D5, 0x3F2, 0x7EE, 0x7FB, 0x3E9, 0x1CD, 0x1C8,
+    0x1CB, 0x1D1, 0x1D7, 0x1DF, 0x3CF, 0x3E0, 0x3EF, 0x7E6,
+    0x7F8, 0xFFA, 0x3EB, 0x1DD, 0x1D3, 0x1D9, 0x1DB, 0x3D2,
+    0x3CC, 0x3DC, 0x3EA, 0x7ED, 0x7F3, 0x7F9, 0xFF9, 0x7F2,
+    0x3CE, 0x1E4, 0x3CB, 0x3D8, 0x3D6, 0x3E2, 0x3E5, 0x7E8,
+    0x7F4, 0x7F5, 0x7F7, 0xFFB, 0x7FA, 0x3EC, 0x3DF, 0x3E1,
+    0x3E4, 0x3E6, 0x3F0, 0x7E9, 0x7EF, 0xFF8, 0xFFE, 0xFFC,
+    0xFFF,
+];
+const LENS_10: [u8; 169] = [
+    6, 5, 6, 6, 7, 8, 9, 10, 10, 10, 11, 11, 12, 5, 4, 4,
+    5, 6, 7, 7, 8, 8, 9, 10, 10, 11, 6, 4, 5, 5, 6, 6,
+    7, 8, 8, 9, 9, 10, 10, 6, 5, 5, 5, 6, 7, 7, 8, 8,
+    9, 9, 10, 10, 7, 6, 6, 6, 6, 7, 7, 8, 8, 9, 9, 10,
+    10, 8, 7, 6, 7, 7, 7, 8, 8, 8, 9, 10, 10, 11, 9, 7,
+    7, 7, 7, 8, 8, 9, 9, 9, 10, 10, 11, 9, 8, 8, 8, 8,
+    8, 9, 9, 9, 10, 10, 11, 11, 9, 8, 8, 8, 8, 8, 9, 9,
+    10, 10, 10, 11, 11, 10, 9, 9, 9, 9, 9, 9, 10, 10, 10, 11,
+    11, 12, 10, 9, 9, 9, 9, 10, 10, 10, 10, 11, 11, 11, 12, 11,
+    10, 9, 10, 10, 10, 10, 10, 11, 11, 11, 11, 12, 11, 10, 10, 10,
+    10, 10, 10, 11, 11, 12, 12, 12, 12,
+];
+
+const CODES_11: [u32; 289] = [
+    0x0, 0x6, 0x19, 0x3D, 0x9C, 0xC6, 0x1A7, 0x390,
+    0x3C2, 0x3DF, 0x7E6, 0x7F3, 0xFFB, 0x7EC, 0xFFA, 0xFFE,
+    0x38E, 0x5, 0x1, 0x8, 0x14, 0x37, 0x42, 0x92,
+    0xAF, 0x191, 0x1A5, 0x1B5, 0x39E, 0x3C0, 0x3A2, 0x3CD,
+    0x7D6, 0xAE, 0x17, 0x7, 0x9, 0x18, 0x39, 0x40,
+    0x8E, 0xA3, 0xB8, 0x199, 0x1AC, 0x1C1, 0x3B1, 0x396,
+    0x3BE, 0x3CA, 0x9D, 0x3C, 0x15, 0x16, 0x1A, 0x3B,
+    0x44, 0x91, 0xA5, 0xBE, 0x196, 0x1AE, 0x1B9, 0x3A1,
+    0x391, 0x3A5, 0x3D5, 0x94, 0x9A, 0x36, 0x38, 0x3A,
+    0x41, 0x8C, 0x9B, 0xB0, 0xC3, 0x19E, 0x1AB, 0x1BC,
+    0x39F, 0x38F, 0x3A9, 0x3CF, 0x93, 0xBF, 0x3E, 0x3F,
+    0x43, 0x45, 0x9E, 0xA7, 0xB9, 0x194, 0x1A2, 0x1BA,
+    0x1C3, 0x3A6, 0x3A7, 0x3BB, 0x3D4, 0x9F, 0x1A0, 0x8F,
+    0x8D, 0x90, 0x98, 0xA6, 0xB6, 0xC4, 0x19F, 0x1AF,
+    0x1BF, 0x399, 0x3BF, 0x3B4, 0x3C9, 0x3E7, 0xA8, 0x1B6,
+    0xAB, 0xA4, 0xAA, 0xB2, 0xC2, 0xC5, 0x198, 0x1A4,
+    0x1B8, 0x38C, 0x3A4, 0x3C4, 0x3C6, 0x3DD, 0x3E8, 0xAD,
+    0x3AF, 0x192, 0xBD, 0xBC, 0x18E, 0x197, 0x19A, 0x1A3,
+    0x1B1, 0x38D, 0x398, 0x3B7, 0x3D3, 0x3D1, 0x3DB, 0x7DD,
+    0xB4, 0x3DE, 0x1A9, 0x19B, 0x19C, 0x1A1, 0x1AA, 0x1AD,
+    0x1B3, 0x38B, 0x3B2, 0x3B8, 0x3CE, 0x3E1, 0x3E0, 0x7D2,
+    0x7E5, 0xB7, 0x7E3, 0x1BB, 0x1A8, 0x1A6, 0x1B0, 0x1B2,
+    0x1B7, 0x39B, 0x39A, 0x3BA, 0x3B5, 0x3D6, 0x7D7, 0x3E4,
+    0x7D8, 0x7EA, 0xBA, 0x7E8, 0x3A0, 0x1BD, 0x1B4, 0x38A,
+    0x1C4, 0x392, 0x3AA, 0x3B0, 0x3BC, 0x3D7, 0x7D4, 0x7DC,
+    0x7DB, 0x7D5, 0x7F0, 0xC1, 0x7FB, 0x3C8, 0x3A3, 0x395,
+    0x39D, 0x3AC, 0x3AE, 0x3C5, 0x3D8, 0x3E2, 0x3E6, 0x7E4,
+    0x7E7, 0x7E0, 0x7E9, 0x7F7, 0x190, 0x7F2, 0x393, 0x1BE,
+    0x1C0, 0x394, 0x397, 0x3AD, 0x3C3, 0x3C1, 0x3D2, 0x7DA,
+    0x7D9, 0x7DF, 0x7EB, 0x7F4, 0x7FA, 0x195, 0x7F8, 0x3BD,
+    0x39C, 0x3AB, 0x3A8, 0x3B3, 0x3B9, 0x3D0, 0x3E3, 0x3E5,
+    0x7E2, 0x7DE, 0x7ED, 0x7F1, 0x7F9, 0x7FC, 0x193, 0xFFD,
+    0x3DC, 0x3B6, 0x3C7, 0x3CC, 0x3CB, 0x3D9, 0x3DA, 0x7D3,
+    0x7E1, 0x7EE, 0x7EF, 0x7F5, 0x7F6, 0xFFC, 0xFFF, 0x19D,
+    0x1C2, 0xB5, 0xA1, 0x96, 0x97, 0x95, 0x99, 0xA0,
+    0xA2, 0xAC, 0xA9, 0xB1, 0xB3, 0xBB, 0xC0, 0x18F,
+    0x4,
+];
+const LENS_11: [u8; 289] = [
+    4, 5, 6, 7, 8, 8, 9, 10, 10, 10, 11, 11, 12, 11, 12, 12,
+    10, 5, 4, 5, 6, 7, 7, 8, 8, 9, 9, 9, 10, 10, 10, 10,
+    11, 8, 6, 5, 5, 6, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10,
+    10, 10, 8, 7, 6, 6, 6, 7, 7, 8, 8, 8, 9, 9, 9, 10,
+    10, 10, 10, 8, 8, 7, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9,
+    10, 10, 10, 10, 8, 8, 7, 7, 7, 7, 8, 8, 8, 9, 9, 9,
+    9, 10, 10, 10, 10, 8, 9, 8, 8, 8, 8, 8, 8, 8, 9, 9,
+    9, 10, 10, 10, 10, 10, 8, 9, 8, 8, 8, 8, 8, 8, 9, 9,
+    9, 10, 10, 10, 10, 10, 10, 8, 10, 9, 8, 8, 9, 9, 9, 9,
+    9, 10, 10, 10, 10, 10, 10, 11, 8, 10, 9, 9, 9, 9, 9, 9,
+    9, 10, 10, 10, 10, 10, 10, 11, 11, 8, 11, 9, 9, 9, 9, 9,
+    9, 10, 10, 10, 10, 10, 11, 10, 11, 11, 8, 11, 10, 9, 9, 10,
+    9, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 8, 11, 10, 10, 10,
+    10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 9, 11, 10, 9,
+    9, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11, 9, 11, 10,
+    10, 10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11, 9, 12,
+    10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11, 12, 12, 9,
+    9, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 9,
+    5,
+];
+
+/// Spectrum codebooks 1-11 as (codes, lengths), indexed by codebook - 1.
+pub(super) const SPECTRUM: [(&[u32], &[u8]); 11] = [
+    (&CODES_1, &LENS_1),
+    (&CODES_2, &LENS_2),
+    (&CODES_3, &LENS_3),
+    (&CODES_4, &LENS_4),
+    (&CODES_5, &LENS_5),
+    (&CODES_6, &LENS_6),
+    (&CODES_7, &LENS_7),
+    (&CODES_8, &LENS_8),
+    (&CODES_9, &LENS_9),
+    (&CODES_10, &LENS_10),
+    (&CODES_11, &LENS_11),
+];
+
+/// Scalefactor delta codebook, indexed by delta + 60.
+pub(super) const SCALEFACTOR_CODES: [u32; 121] = [
+    0x3FFE8, 0x3FFE6, 0x3FFE7, 0x3FFE5, 0x7FFF5, 0x7FFF1, 0x7FFED, 0x7FFF6,
+    0x7FFEE, 0x7FFEF, 0x7FFF0, 0x7FFFC, 0x7FFFD, 0x7FFFF, 0x7FFFE, 0x7FFF7,
+    0x7FFF8, 0x7FFFB, 0x7FFF9, 0x3FFE4, 0x7FFFA, 0x3FFE3, 0x1FFEF, 0x1FFF0,
+    0xFFF5, 0x1FFEE, 0xFFF2, 0xFFF3, 0xFFF4, 0xFFF1, 0x7FF6, 0x7FF7,
+    0x3FF9, 0x3FF5, 0x3FF7, 0x3FF3, 0x3FF6, 0x3FF2, 0x1FF7, 0x1FF5,
+    0xFF9, 0xFF7, 0xFF6, 0x7F9, 0xFF4, 0x7F8, 0x3F9, 0x3F7,
+    0x3F5, 0x1F8, 0x1F7, 0xFA, 0xF8, 0xF6, 0x79, 0x3A,
+    0x38, 0x1A, 0xB, 0x4, 0x0, 0xA, 0xC, 0x1B,
+    0x39, 0x3B, 0x78, 0x7A, 0xF7, 0xF9, 0x1F6, 0x1F9,
+    0x3F4, 0x3F6, 0x3F8, 0x7F5, 0x7F4, 0x7F6, 0x7F7, 0xFF5,
+    0xFF8, 0x1FF4, 0x1FF6, 0x1FF8, 0x3FF8, 0x3FF4, 0xFFF0, 0x7FF4,
+    0xFFF6, 0x7FF5, 0x3FFE2, 0x7FFD9, 0x7FFDA, 0x7FFDB, 0x7FFDC, 0x7FFDD,
+    0x7FFDE, 0x7FFD8, 0x7FFD2, 0x7FFD3, 0x7FFD4, 0x7FFD5, 0x7FFD6, 0x7FFF2,
+    0x7FFDF, 0x7FFE7, 0x7FFE8, 0x7FFE9, 0x7FFEA, 0x7FFEB, 0x7FFE6, 0x7FFE0,
+    0x7FFE1, 0x7FFE2, 0x7FFE3, 0x7FFE4, 0x7FFE5, 0x7FFD7, 0x7FFEC, 0x7FFF4,
+    0x7FFF3,
+];
+pub(super) const SCALEFACTOR_LENS: [u8; 121] = [
+    18, 18, 18, 18, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19,
+    19, 19, 19, 18, 19, 18, 17, 17, 16, 17, 16, 16, 16, 16, 15, 15,
+    14, 14, 14, 14, 14, 14, 13, 13, 12, 12, 12, 11, 12, 11, 10, 10,
+    10, 9, 9, 8, 8, 8, 7, 6, 6, 5, 4, 3, 1, 4, 4, 5,
+    6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 10, 11, 11, 11, 11, 12,
+    12, 13, 13, 13, 14, 14, 16, 15, 16, 15, 18, 19, 19, 19, 19, 19,
+    19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19,
+    19, 19, 19, 19, 19, 19, 19, 19, 19,
+];
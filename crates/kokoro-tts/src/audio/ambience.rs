@@ -0,0 +1,322 @@
+//! Synthesized ambient soundscapes (rain, ocean, wind, fire, crickets) built
+//! from filtered noise and slow modulation, so no sample files are needed.
+//!
+//! Output is deterministic for a given seed.
+
+use std::f32::consts::PI;
+
+use super::synth::Rng;
+
+/// Named ambient beds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Soundscape {
+    /// Steady hiss with scattered drops
+    Rain,
+    /// Slow swells of surf
+    Ocean,
+    /// Gusting, whistling air
+    Wind,
+    /// Low rumble with crackles and pops
+    Fire,
+    /// Chirping crickets over quiet night air
+    Crickets,
+}
+
+impl Soundscape {
+    pub const ALL: [Soundscape; 5] = [
+        Soundscape::Rain,
+        Soundscape::Ocean,
+        Soundscape::Wind,
+        Soundscape::Fire,
+        Soundscape::Crickets,
+    ];
+
+    /// Parse a soundscape name (`rain`, `ocean`, `wind`, `fire`, `crickets`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rain => "rain",
+            Self::Ocean => "ocean",
+            Self::Wind => "wind",
+            Self::Fire => "fire",
+            Self::Crickets => "crickets",
+        }
+    }
+}
+
+/// Render `len` samples of `soundscape`, peak-normalised to 0.8.
+pub fn render_ambience(soundscape: Soundscape, seed: u64, len: usize, sample_rate: u32) -> Vec<f32> {
+    let mut rng = Rng::new(seed);
+    let sr = sample_rate as f32;
+    let mut out = match soundscape {
+        Soundscape::Rain => rain(&mut rng, len, sr),
+        Soundscape::Ocean => ocean(&mut rng, len, sr),
+        Soundscape::Wind => wind(&mut rng, len, sr),
+        Soundscape::Fire => fire(&mut rng, len, sr),
+        Soundscape::Crickets => crickets(&mut rng, len, sr),
+    };
+
+    let peak = out.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        let gain = 0.8 / peak;
+        out.iter_mut().for_each(|s| *s *= gain);
+    }
+    out
+}
+
+fn rain(rng: &mut Rng, len: usize, sr: f32) -> Vec<f32> {
+    let mut hiss_hp = OnePole::new(sr, 400.0);
+    let mut hiss_lp = OnePole::new(sr, 7000.0);
+    let mut drop_lp = OnePole::new(sr, 4000.0);
+    let drop_rate = 40.0 / sr;
+    let drop_decay = (-1.0 / (0.004 * sr)).exp();
+    let mut drop_env = 0.0f32;
+
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / sr;
+            let noise = rng.signed();
+            let hiss = hiss_lp.lowpass(hiss_hp.highpass(noise));
+
+            if rng.chance(drop_rate) {
+                drop_env = drop_env.max(0.2 + 0.8 * rng.unit());
+            }
+            drop_env *= drop_decay;
+            let drop = drop_lp.lowpass(rng.signed()) * drop_env;
+
+            // Showers come and go slowly
+            let intensity = 0.8 + 0.2 * (2.0 * PI * t / 17.0).sin();
+            intensity * (0.5 * hiss + drop)
+        })
+        .collect()
+}
+
+fn ocean(rng: &mut Rng, len: usize, sr: f32) -> Vec<f32> {
+    let mut surf = OnePole::new(sr, 300.0);
+    let mut brown = 0.0f32;
+    let mut wave_len = (6.0 + 4.0 * rng.unit()) * sr;
+    let mut wave_pos = rng.unit() * wave_len;
+
+    (0..len)
+        .map(|_| {
+            wave_pos += 1.0;
+            if wave_pos >= wave_len {
+                wave_pos = 0.0;
+                wave_len = (6.0 + 4.0 * rng.unit()) * sr;
+            }
+            // Quick build as the wave breaks, long wash back out
+            let p = wave_pos / wave_len;
+            let swell = if p < 0.35 {
+                (p / 0.35).powi(2)
+            } else {
+                ((1.0 - p) / 0.65).powf(1.5)
+            };
+
+            brown = (0.98 * brown + 0.02 * rng.signed() * 8.0).clamp(-1.0, 1.0);
+            surf.set_cutoff(sr, 300.0 + 1500.0 * swell);
+            let wash = surf.lowpass(0.6 * rng.signed() + 0.4 * brown);
+            (0.25 + 0.75 * swell) * wash
+        })
+        .collect()
+}
+
+fn wind(rng: &mut Rng, len: usize, sr: f32) -> Vec<f32> {
+    let mut whistle = Svf::default();
+    let mut rumble = OnePole::new(sr, 150.0);
+    let mut gust = 0.3f32;
+    let mut target = 0.5f32;
+    let smoothing = 1.0 - (-1.0 / (1.5 * sr)).exp();
+
+    (0..len)
+        .map(|n| {
+            // Pick a new gust strength roughly every two seconds
+            if n % (2.0 * sr) as usize == 0 {
+                target = rng.unit();
+            }
+            gust += smoothing * (target - gust);
+
+            let noise = rng.signed();
+            let band = whistle.bandpass(noise, sr, 250.0 + 500.0 * gust, 3.0);
+            let low = rumble.lowpass(noise);
+            (0.3 + 0.7 * gust) * (band + 1.5 * low)
+        })
+        .collect()
+}
+
+fn fire(rng: &mut Rng, len: usize, sr: f32) -> Vec<f32> {
+    let mut rumble = OnePole::new(sr, 200.0);
+    let mut crackle_hp = OnePole::new(sr, 2000.0);
+    let mut pop_lp = OnePole::new(sr, 2000.0);
+    let (crackle_rate, pop_rate) = (12.0 / sr, 0.5 / sr);
+    let crackle_decay = (-1.0 / (0.003 * sr)).exp();
+    let pop_decay = (-1.0 / (0.02 * sr)).exp();
+    let (mut crackle_env, mut pop_env) = (0.0f32, 0.0f32);
+    let flicker_rate = 0.3 + 0.2 * rng.unit();
+
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / sr;
+            if rng.chance(crackle_rate) {
+                crackle_env = rng.unit().powi(2);
+            }
+            if rng.chance(pop_rate) {
+                pop_env = 0.6 + 0.4 * rng.unit();
+            }
+            crackle_env *= crackle_decay;
+            pop_env *= pop_decay;
+
+            let noise = rng.signed();
+            let flicker = 0.7 + 0.3 * (2.0 * PI * flicker_rate * t).sin();
+            let base = 1.5 * flicker * rumble.lowpass(noise);
+            let crackle = crackle_hp.highpass(rng.signed()) * crackle_env;
+            let pop = pop_lp.lowpass(rng.signed()) * pop_env * 2.0;
+            base + crackle + pop
+        })
+        .collect()
+}
+
+fn crickets(rng: &mut Rng, len: usize, sr: f32) -> Vec<f32> {
+    struct Cricket {
+        freq: f32,
+        chirp_period: f32,
+        pulses: f32,
+        pulse_rate: f32,
+        offset: f32,
+        level: f32,
+    }
+    let chorus: Vec<Cricket> = (0..3)
+        .map(|i| Cricket {
+            freq: 4200.0 + 1000.0 * rng.unit(),
+            chirp_period: 0.6 + 0.4 * rng.unit(),
+            pulses: (3 + rng.below(3)) as f32,
+            pulse_rate: 25.0 + 10.0 * rng.unit(),
+            offset: rng.unit(),
+            // One near cricket, the others further away
+            level: if i == 0 { 1.0 } else { 0.4 + 0.2 * rng.unit() },
+        })
+        .collect();
+    let mut air = OnePole::new(sr, 500.0);
+
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / sr;
+            let song: f32 = chorus
+                .iter()
+                .map(|c| {
+                    let chirp_t = (t / c.chirp_period + c.offset).fract() * c.chirp_period;
+                    let pulse = chirp_t * c.pulse_rate;
+                    if pulse >= c.pulses {
+                        return 0.0;
+                    }
+                    // Each pulse is a smooth 60%-duty burst
+                    let phase = pulse.fract() / 0.6;
+                    let env = if phase < 1.0 { (PI * phase).sin().powi(2) } else { 0.0 };
+                    c.level * env * (2.0 * PI * c.freq * t).sin()
+                })
+                .sum();
+            0.3 * song + 0.3 * air.lowpass(rng.signed())
+        })
+        .collect()
+}
+
+/// One-pole smoothing filter; `highpass` is the input minus the lowpass.
+struct OnePole {
+    coeff: f32,
+    state: f32,
+}
+
+impl OnePole {
+    fn new(sr: f32, cutoff: f32) -> Self {
+        let mut filter = Self { coeff: 0.0, state: 0.0 };
+        filter.set_cutoff(sr, cutoff);
+        filter
+    }
+
+    fn set_cutoff(&mut self, sr: f32, cutoff: f32) {
+        self.coeff = 1.0 - (-2.0 * PI * cutoff / sr).exp();
+    }
+
+    fn lowpass(&mut self, x: f32) -> f32 {
+        self.state += self.coeff * (x - self.state);
+        self.state
+    }
+
+    fn highpass(&mut self, x: f32) -> f32 {
+        x - self.lowpass(x)
+    }
+}
+
+/// Chamberlin state-variable filter, cheap to retune every sample.
+#[derive(Default)]
+struct Svf {
+    low: f32,
+    band: f32,
+}
+
+impl Svf {
+    fn bandpass(&mut self, x: f32, sr: f32, cutoff: f32, q: f32) -> f32 {
+        let f = 2.0 * (PI * cutoff / sr).sin();
+        let high = x - self.low - self.band / q;
+        self.band += f * high;
+        self.low += f * self.band;
+        self.band
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: u32 = 24000;
+
+    fn zero_crossing_rate(samples: &[f32]) -> f32 {
+        let crossings = samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+        crossings as f32 / samples.len() as f32
+    }
+
+    fn window_rms(samples: &[f32], window: usize) -> Vec<f32> {
+        samples
+            .chunks(window)
+            .map(|c| (c.iter().map(|s| s * s).sum::<f32>() / c.len() as f32).sqrt())
+            .collect()
+    }
+
+    #[test]
+    fn names_roundtrip() {
+        for scape in Soundscape::ALL {
+            assert_eq!(Soundscape::from_name(scape.name()), Some(scape));
+        }
+        assert_eq!(Soundscape::from_name(" Rain "), Some(Soundscape::Rain));
+        assert_eq!(Soundscape::from_name("lava"), None);
+    }
+
+    #[test]
+    fn deterministic_and_in_range() {
+        for scape in Soundscape::ALL {
+            let a = render_ambience(scape, 3, SR as usize * 2, SR);
+            assert_eq!(a.len(), SR as usize * 2);
+            assert_eq!(a, render_ambience(scape, 3, SR as usize * 2, SR), "{:?}", scape);
+            assert!(a.iter().all(|s| s.is_finite() && s.abs() <= 0.8 + 1e-6));
+        }
+    }
+
+    #[test]
+    fn rain_is_brighter_than_ocean() {
+        let rain = render_ambience(Soundscape::Rain, 1, SR as usize * 2, SR);
+        let ocean = render_ambience(Soundscape::Ocean, 1, SR as usize * 2, SR);
+        assert!(zero_crossing_rate(&rain) > 2.0 * zero_crossing_rate(&ocean));
+    }
+
+    #[test]
+    fn crickets_chirp_with_gaps() {
+        let night = render_ambience(Soundscape::Crickets, 5, SR as usize * 3, SR);
+        let levels = window_rms(&night, SR as usize / 100);
+        let loudest = levels.iter().cloned().fold(0.0f32, f32::max);
+        let quietest = levels.iter().cloned().fold(f32::MAX, f32::min);
+        assert!(loudest > 4.0 * quietest);
+    }
+}
@@ -0,0 +1,467 @@
+//! Character voice effects: duration-preserving pitch shift and formant shift.
+//!
+//! Both effects run on a short-time Fourier transform (1024-sample Hann
+//! frames, 75% overlap). Pitch shifting is a phase vocoder time stretch
+//! followed by resampling back to the original length; formant shifting
+//! warps each frame's smoothed spectral envelope while keeping its harmonics.
+
+use std::f32::consts::PI;
+
+use super::limit_peak;
+
+/// STFT frame length in samples (~43 ms at 24 kHz).
+const FRAME: usize = 1024;
+
+/// Analysis hop between frames.
+const HOP: usize = FRAME / 4;
+
+/// Half-width, in bins, of the moving average used as the spectral envelope.
+/// Wide enough to smooth over the harmonics of a low speaking voice.
+const ENVELOPE_RADIUS: usize = 12;
+
+/// Upper bound on the per-bin gain of a formant shift, so bins where the
+/// envelope is near silence don't get amplified into noise.
+const MAX_FORMANT_GAIN: f32 = 4.0;
+
+/// Pitch and formant parameters for an effect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectSettings {
+    /// Pitch change in semitones (clamped to ±12).
+    pub pitch_semitones: f32,
+    /// Overall formant scale relative to the original voice: below 1.0
+    /// sounds larger, above 1.0 sounds smaller.
+    pub formant_shift: f32,
+}
+
+/// Named character presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceEffect {
+    None,
+    Giant,
+    Wolf,
+    Mouse,
+    Child,
+    Fairy,
+}
+
+impl VoiceEffect {
+    /// All presets, in the order they're offered to users.
+    pub const ALL: [VoiceEffect; 6] = [
+        VoiceEffect::None,
+        VoiceEffect::Giant,
+        VoiceEffect::Wolf,
+        VoiceEffect::Mouse,
+        VoiceEffect::Child,
+        VoiceEffect::Fairy,
+    ];
+
+    /// Parse a preset name as stored in settings (`giant`, `mouse`, ...).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|effect| effect.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Stable name used in settings and the database.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Giant => "giant",
+            Self::Wolf => "wolf",
+            Self::Mouse => "mouse",
+            Self::Child => "child",
+            Self::Fairy => "fairy",
+        }
+    }
+
+    pub fn settings(&self) -> EffectSettings {
+        let (pitch_semitones, formant_shift) = match self {
+            Self::None => (0.0, 1.0),
+            Self::Giant => (-6.0, 0.8),
+            Self::Wolf => (-4.0, 0.9),
+            Self::Mouse => (8.0, 1.3),
+            Self::Child => (4.0, 1.15),
+            Self::Fairy => (6.0, 1.1),
+        };
+        EffectSettings {
+            pitch_semitones,
+            formant_shift,
+        }
+    }
+}
+
+/// Apply a preset to mono samples. The output has the same length.
+pub fn apply_effect(samples: &[f32], effect: VoiceEffect) -> Vec<f32> {
+    if effect == VoiceEffect::None {
+        return samples.to_vec();
+    }
+    apply_settings(samples, &effect.settings())
+}
+
+/// Apply explicit pitch/formant settings. The output has the same length.
+pub fn apply_settings(samples: &[f32], settings: &EffectSettings) -> Vec<f32> {
+    let semitones = settings.pitch_semitones.clamp(-12.0, 12.0);
+    let ratio = 2f32.powf(semitones / 12.0);
+
+    let mut out = if semitones.abs() > 0.01 {
+        pitch_shift(samples, semitones)
+    } else {
+        samples.to_vec()
+    };
+
+    // Resampling moved the formants along with the pitch; correct them to
+    // the requested overall shift.
+    let formant_correction = settings.formant_shift.max(0.1) / ratio;
+    if (formant_correction - 1.0).abs() > 0.01 {
+        out = formant_shift(&out, formant_correction);
+    }
+
+    limit_peak(&mut out, 0.99);
+    out
+}
+
+/// Shift pitch by `semitones` without changing duration.
+///
+/// Formants move with the pitch (the "chipmunk" effect); pair with
+/// [`formant_shift`] to control them separately.
+pub fn pitch_shift(samples: &[f32], semitones: f32) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let ratio = 2f32.powf(semitones.clamp(-12.0, 12.0) / 12.0);
+    let stretched = time_stretch(samples, ratio);
+    resample_to(&stretched, samples.len())
+}
+
+/// Change duration by `ratio` (2.0 = twice as long) without changing pitch.
+pub fn time_stretch(samples: &[f32], ratio: f32) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let ratio = ratio.clamp(0.25, 4.0);
+    let syn_hop = ((HOP as f32 * ratio).round() as usize).clamp(1, FRAME / 2);
+    let actual_ratio = syn_hop as f32 / HOP as f32;
+    let out_len = (samples.len() as f32 * actual_ratio).round() as usize;
+
+    let bins = FRAME / 2 + 1;
+    let mut prev_phase = vec![0.0f32; bins];
+    let mut sum_phase = vec![0.0f32; bins];
+    let mut first = true;
+
+    stft_resynth(samples, syn_hop, out_len, |spectrum| {
+        for (k, bin) in spectrum.iter_mut().enumerate() {
+            let mag = bin.norm();
+            let phase = bin.arg();
+            if first {
+                sum_phase[k] = phase;
+            } else {
+                // Deviation from the bin's nominal phase advance gives its
+                // true frequency; advance by that over the synthesis hop.
+                let expected = 2.0 * PI * k as f32 * HOP as f32 / FRAME as f32;
+                let delta = wrap_phase(phase - prev_phase[k] - expected);
+                sum_phase[k] += (expected + delta) * actual_ratio;
+            }
+            prev_phase[k] = phase;
+            *bin = Complex::from_polar(mag, sum_phase[k]);
+        }
+        first = false;
+    })
+}
+
+/// Scale formants by `factor` (above 1.0 raises them) keeping pitch and duration.
+pub fn formant_shift(samples: &[f32], factor: f32) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let factor = factor.clamp(0.5, 2.0);
+
+    stft_resynth(samples, HOP, samples.len(), |spectrum| {
+        let mags: Vec<f32> = spectrum.iter().map(|c| c.norm()).collect();
+        let envelope = smooth(&mags, ENVELOPE_RADIUS);
+
+        for (k, bin) in spectrum.iter_mut().enumerate() {
+            let source = k as f32 / factor;
+            let target = interpolate(&envelope, source);
+            let gain = if envelope[k] > 1e-9 {
+                (target / envelope[k]).min(MAX_FORMANT_GAIN)
+            } else {
+                0.0
+            };
+            *bin = bin.scale(gain);
+        }
+    })
+}
+
+/// Analyse `samples` frame by frame, let `process` edit each frame's
+/// non-negative-frequency bins, and overlap-add the result with `syn_hop`.
+fn stft_resynth(
+    samples: &[f32],
+    syn_hop: usize,
+    out_len: usize,
+    mut process: impl FnMut(&mut [Complex]),
+) -> Vec<f32> {
+    let window = hann(FRAME);
+    let half = FRAME / 2;
+    // Frames are centred on multiples of HOP; one extra covers the tail.
+    let n_frames = samples.len().div_ceil(HOP) + 1;
+
+    let buffer_len = n_frames * syn_hop + FRAME;
+    let mut out = vec![0.0f32; buffer_len];
+    let mut norm = vec![0.0f32; buffer_len];
+    let mut frame = vec![Complex::default(); FRAME];
+
+    for m in 0..n_frames {
+        let start = (m * HOP) as isize - half as isize;
+        for (n, slot) in frame.iter_mut().enumerate() {
+            let idx = start + n as isize;
+            let sample = if idx >= 0 && (idx as usize) < samples.len() {
+                samples[idx as usize]
+            } else {
+                0.0
+            };
+            *slot = Complex::new(sample * window[n], 0.0);
+        }
+
+        fft(&mut frame, false);
+        process(&mut frame[..=half]);
+        // Restore conjugate symmetry so the inverse transform is real.
+        frame[0].im = 0.0;
+        frame[half].im = 0.0;
+        for k in 1..half {
+            frame[FRAME - k] = frame[k].conj();
+        }
+        fft(&mut frame, true);
+
+        let offset = m * syn_hop;
+        for n in 0..FRAME {
+            out[offset + n] += frame[n].re * window[n];
+            norm[offset + n] += window[n] * window[n];
+        }
+    }
+
+    // Output index `half` corresponds to input sample 0.
+    (0..out_len)
+        .map(|i| {
+            let idx = i + half;
+            match (out.get(idx), norm.get(idx)) {
+                (Some(&v), Some(&w)) if w > 1e-3 => v / w,
+                _ => 0.0,
+            }
+        })
+        .collect()
+}
+
+/// Linear-interpolation resample to exactly `len` samples.
+fn resample_to(samples: &[f32], len: usize) -> Vec<f32> {
+    if samples.is_empty() || len == 0 {
+        return vec![0.0; len];
+    }
+    let step = samples.len() as f32 / len as f32;
+    (0..len).map(|i| interpolate(samples, i as f32 * step)).collect()
+}
+
+/// Value of `values` at fractional index `pos`, clamped to the ends.
+fn interpolate(values: &[f32], pos: f32) -> f32 {
+    let last = values.len() - 1;
+    let i = (pos.max(0.0) as usize).min(last);
+    let frac = pos - i as f32;
+    if i == last {
+        values[last]
+    } else {
+        values[i] * (1.0 - frac) + values[i + 1] * frac
+    }
+}
+
+/// Centred moving average with the given half-width.
+fn smooth(values: &[f32], radius: usize) -> Vec<f32> {
+    let mut prefix = Vec::with_capacity(values.len() + 1);
+    prefix.push(0.0f32);
+    for &v in values {
+        prefix.push(prefix.last().unwrap() + v);
+    }
+    (0..values.len())
+        .map(|i| {
+            let lo = i.saturating_sub(radius);
+            let hi = (i + radius + 1).min(values.len());
+            (prefix[hi] - prefix[lo]) / (hi - lo) as f32
+        })
+        .collect()
+}
+
+fn wrap_phase(phase: f32) -> f32 {
+    phase - 2.0 * PI * (phase / (2.0 * PI)).round()
+}
+
+/// Periodic Hann window.
+fn hann(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / len as f32).cos())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Complex {
+    re: f32,
+    im: f32,
+}
+
+impl Complex {
+    fn new(re: f32, im: f32) -> Self {
+        Self { re, im }
+    }
+
+    fn from_polar(mag: f32, phase: f32) -> Self {
+        Self::new(mag * phase.cos(), mag * phase.sin())
+    }
+
+    fn norm(&self) -> f32 {
+        self.re.hypot(self.im)
+    }
+
+    fn arg(&self) -> f32 {
+        self.im.atan2(self.re)
+    }
+
+    fn conj(&self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    fn scale(&self, s: f32) -> Self {
+        Self::new(self.re * s, self.im * s)
+    }
+}
+
+/// In-place iterative radix-2 FFT. `buf.len()` must be a power of two.
+/// The inverse transform is scaled by 1/N.
+fn fft(buf: &mut [Complex], inverse: bool) {
+    let n = buf.len();
+    debug_assert!(n.is_power_of_two());
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * PI / len as f32;
+        let step = Complex::from_polar(1.0, angle);
+        for chunk in buf.chunks_mut(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let a = chunk[k];
+                let b = chunk[k + len / 2];
+                let t = Complex::new(b.re * w.re - b.im * w.im, b.re * w.im + b.im * w.re);
+                chunk[k] = Complex::new(a.re + t.re, a.im + t.im);
+                chunk[k + len / 2] = Complex::new(a.re - t.re, a.im - t.im);
+                w = Complex::new(w.re * step.re - w.im * step.im, w.re * step.im + w.im * step.re);
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        let scale = 1.0 / n as f32;
+        buf.iter_mut().for_each(|c| *c = c.scale(scale));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: f32 = 24000.0;
+
+    fn sine(freq: f32, secs: f32) -> Vec<f32> {
+        (0..(SR * secs) as usize)
+            .map(|i| 0.5 * (2.0 * PI * freq * i as f32 / SR).sin())
+            .collect()
+    }
+
+    /// Estimate frequency from upward zero crossings in the middle half.
+    fn estimate_freq(samples: &[f32]) -> f32 {
+        let mid = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        let crossings = mid.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        crossings as f32 * SR / mid.len() as f32
+    }
+
+    #[test]
+    fn fft_roundtrip() {
+        let original: Vec<Complex> = (0..64).map(|i| Complex::new((i as f32).sin(), 0.0)).collect();
+        let mut buf = original.clone();
+        fft(&mut buf, false);
+        fft(&mut buf, true);
+        for (a, b) in original.iter().zip(&buf) {
+            assert!((a.re - b.re).abs() < 1e-4 && b.im.abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn pitch_shift_keeps_length_and_moves_frequency() {
+        let input = sine(220.0, 1.0);
+        let up = pitch_shift(&input, 12.0);
+        assert_eq!(up.len(), input.len());
+        let freq = estimate_freq(&up);
+        assert!((freq - 440.0).abs() < 440.0 * 0.05, "got {} Hz", freq);
+
+        let down = pitch_shift(&input, -12.0);
+        let freq = estimate_freq(&down);
+        assert!((freq - 110.0).abs() < 110.0 * 0.05, "got {} Hz", freq);
+    }
+
+    #[test]
+    fn time_stretch_changes_length_not_pitch() {
+        let input = sine(300.0, 0.5);
+        let stretched = time_stretch(&input, 1.5);
+        assert_eq!(stretched.len(), input.len() * 3 / 2);
+        let freq = estimate_freq(&stretched);
+        assert!((freq - 300.0).abs() < 300.0 * 0.05, "got {} Hz", freq);
+    }
+
+    #[test]
+    fn formant_shift_keeps_pitch_and_level() {
+        let input = sine(200.0, 0.5);
+        let shifted = formant_shift(&input, 1.0);
+        assert_eq!(shifted.len(), input.len());
+        // Identity shift reconstructs the input away from the edges.
+        for i in FRAME..input.len() - FRAME {
+            assert!((shifted[i] - input[i]).abs() < 1e-3);
+        }
+
+        let raised = formant_shift(&input, 1.3);
+        let freq = estimate_freq(&raised);
+        assert!((freq - 200.0).abs() < 200.0 * 0.05, "got {} Hz", freq);
+        assert!(raised.iter().all(|s| s.is_finite()));
+    }
+
+    #[test]
+    fn presets_roundtrip_by_name() {
+        for effect in VoiceEffect::ALL {
+            assert_eq!(VoiceEffect::from_name(effect.name()), Some(effect));
+        }
+        assert_eq!(VoiceEffect::from_name(" Giant "), Some(VoiceEffect::Giant));
+        assert_eq!(VoiceEffect::from_name("dragon"), None);
+    }
+
+    #[test]
+    fn apply_effect_preserves_length_and_stays_in_range() {
+        let input = sine(180.0, 0.4);
+        assert_eq!(apply_effect(&input, VoiceEffect::None), input);
+        for effect in VoiceEffect::ALL {
+            let out = apply_effect(&input, effect);
+            assert_eq!(out.len(), input.len());
+            assert!(out.iter().all(|s| s.abs() <= 1.0));
+        }
+        assert!(apply_effect(&[], VoiceEffect::Mouse).is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! ID3v2.4 tags for MP3 files, including chapters (CHAP/CTOC frames).
+
+use super::tags::{is_jpeg, Chapter, Tags};
+
+/// Build an ID3v2.4 tag to put in front of MP3 frames. Returns an empty
+/// vector when there is nothing to write.
+pub fn id3v2(tags: &Tags, chapters: &[Chapter]) -> Vec<u8> {
+    let mut frames = Vec::new();
+    for (id, value) in [
+        (b"TIT2", &tags.title),
+        (b"TPE1", &tags.artist),
+        (b"TALB", &tags.album),
+        (b"TCON", &tags.genre),
+    ] {
+        if let Some(value) = value {
+            frames.extend(text_frame(id, value));
+        }
+    }
+    if let Some(cover) = &tags.cover {
+        let mime: &[u8] = if is_jpeg(cover) { b"image/jpeg" } else { b"image/png" };
+        let mut apic = vec![0]; // Latin-1 description
+        apic.extend(mime);
+        apic.push(0);
+        apic.push(3); // front cover
+        apic.push(0); // no description
+        apic.extend(cover);
+        frames.extend(frame(b"APIC", &apic));
+    }
+
+    if !chapters.is_empty() {
+        let mut toc = b"toc\0".to_vec();
+        toc.push(0b11); // top level, ordered
+        toc.push(chapters.len().min(255) as u8);
+        for i in 0..chapters.len().min(255) {
+            toc.extend(format!("ch{}\0", i).as_bytes());
+        }
+        frames.extend(frame(b"CTOC", &toc));
+
+        for (i, chapter) in chapters.iter().enumerate().take(255) {
+            let mut chap = format!("ch{}\0", i).into_bytes();
+            chap.extend((chapter.start_ms as u32).to_be_bytes());
+            chap.extend((chapter.end_ms as u32).to_be_bytes());
+            // Byte offsets unknown: use the times
+            chap.extend([0xff; 8]);
+            chap.extend(text_frame(b"TIT2", &chapter.title));
+            frames.extend(frame(b"CHAP", &chap));
+        }
+    }
+
+    if frames.is_empty() {
+        return frames;
+    }
+    let mut tag = b"ID3\x04\x00\x00".to_vec();
+    tag.extend(syncsafe(frames.len() as u32));
+    tag.extend(frames);
+    tag
+}
+
+fn text_frame(id: &[u8; 4], text: &str) -> Vec<u8> {
+    let mut body = vec![3]; // UTF-8
+    body.extend(text.as_bytes());
+    frame(id, &body)
+}
+
+fn frame(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut out = id.to_vec();
+    out.extend(syncsafe(body.len() as u32));
+    out.extend([0, 0]);
+    out.extend(body);
+    out
+}
+
+/// Sizes in ID3v2.4 headers use seven bits per byte.
+fn syncsafe(value: u32) -> [u8; 4] {
+    [
+        (value >> 21 & 0x7f) as u8,
+        (value >> 14 & 0x7f) as u8,
+        (value >> 7 & 0x7f) as u8,
+        (value & 0x7f) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_tags_write_nothing() {
+        assert!(id3v2(&Tags::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn chapters_are_listed_in_order() {
+        let tags = Tags {
+            title: Some("The Brave Fox".to_string()),
+            ..Default::default()
+        };
+        let chapters = vec![
+            Chapter {
+                title: "Part 1".to_string(),
+                start_ms: 0,
+                end_ms: 1500,
+            },
+            Chapter {
+                title: "Part 2".to_string(),
+                start_ms: 1500,
+                end_ms: 3000,
+            },
+        ];
+        let tag = id3v2(&tags, &chapters);
+        assert_eq!(&tag[..4], b"ID3\x04");
+        let size = tag[6..10].iter().fold(0usize, |acc, b| acc << 7 | *b as usize);
+        assert_eq!(size, tag.len() - 10);
+
+        let find = |needle: &[u8]| tag.windows(needle.len()).position(|w| w == needle).unwrap();
+        assert!(find(b"CTOC") < find(b"CHAP"));
+        let chap = find(b"CHAP") + 10;
+        assert_eq!(&tag[chap..chap + 4], b"ch0\0");
+        assert_eq!(&tag[chap + 8..chap + 12], &1500u32.to_be_bytes());
+        assert!(find(b"Part 2") > find(b"Part 1"));
+    }
+
+    #[test]
+    fn cover_is_a_front_cover_picture() {
+        let tags = Tags {
+            cover: Some(b"\x89PNG\r\n\x1a\nimage".to_vec()),
+            ..Default::default()
+        };
+        let tag = id3v2(&tags, &[]);
+        let apic = tag.windows(4).position(|w| w == b"APIC").unwrap() + 10;
+        assert_eq!(&tag[apic..apic + 12], b"\0image/png\0\x03");
+        assert!(tag.ends_with(b"\x89PNG\r\n\x1a\nimage"));
+    }
+
+    #[test]
+    fn sizes_are_syncsafe() {
+        assert_eq!(syncsafe(0x7f), [0, 0, 0, 0x7f]);
+        assert_eq!(syncsafe(0x80), [0, 0, 1, 0]);
+    }
+}
@@ -0,0 +1,461 @@
+//! Post-processing chain that makes dry TTS output sound like someone reading
+//! aloud in a room: high-pass, de-esser, tone shelf, compressor and a small
+//! algorithmic reverb.
+
+use std::f32::consts::PI;
+
+use super::limit_peak;
+
+/// Named mastering presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasteringPreset {
+    /// Leave the audio untouched.
+    None,
+    /// Cleanup and levelling only, no reverb.
+    Clean,
+    /// Small, warm living room.
+    CozyRoom,
+    /// Darker tone with a wider, sparser space.
+    Campfire,
+}
+
+/// Parameters for [`apply_mastering`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MasteringSettings {
+    /// High-pass cutoff removing rumble and plosive thumps.
+    pub highpass_hz: f32,
+    /// Sibilance level (dBFS) above which the de-esser starts ducking the voice.
+    pub deess_threshold_db: f32,
+    /// High shelf gain at 6 kHz; negative values darken the voice.
+    pub high_shelf_db: f32,
+    pub compressor: CompressorSettings,
+    pub reverb: Option<ReverbSettings>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressorSettings {
+    pub threshold_db: f32,
+    pub ratio: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub makeup_db: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverbSettings {
+    /// Comb feedback, 0..1; larger rooms ring longer.
+    pub room_size: f32,
+    /// High-frequency damping of the tail, 0..1.
+    pub damping: f32,
+    /// Wet level mixed with the dry signal.
+    pub wet: f32,
+    pub pre_delay_ms: f32,
+    /// Seconds of tail appended after the last sample.
+    pub tail_secs: f32,
+}
+
+impl MasteringPreset {
+    pub const ALL: [MasteringPreset; 4] = [
+        MasteringPreset::None,
+        MasteringPreset::Clean,
+        MasteringPreset::CozyRoom,
+        MasteringPreset::Campfire,
+    ];
+
+    /// Parse a preset name as stored in settings (`cozy_room`, `campfire`, ...).
+    /// Spaces and dashes are accepted in place of underscores.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+        Self::ALL.into_iter().find(|p| p.name() == normalized)
+    }
+
+    /// Stable name used in settings.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Clean => "clean",
+            Self::CozyRoom => "cozy_room",
+            Self::Campfire => "campfire",
+        }
+    }
+
+    /// Chain parameters, or `None` for the bypass preset.
+    pub fn settings(&self) -> Option<MasteringSettings> {
+        let compressor = CompressorSettings {
+            threshold_db: -20.0,
+            ratio: 3.0,
+            attack_ms: 8.0,
+            release_ms: 120.0,
+            makeup_db: 3.0,
+        };
+        match self {
+            Self::None => None,
+            Self::Clean => Some(MasteringSettings {
+                highpass_hz: 80.0,
+                deess_threshold_db: -30.0,
+                high_shelf_db: 0.0,
+                compressor,
+                reverb: None,
+            }),
+            Self::CozyRoom => Some(MasteringSettings {
+                highpass_hz: 80.0,
+                deess_threshold_db: -30.0,
+                high_shelf_db: -1.5,
+                compressor,
+                reverb: Some(ReverbSettings {
+                    room_size: 0.72,
+                    damping: 0.5,
+                    wet: 0.14,
+                    pre_delay_ms: 12.0,
+                    tail_secs: 0.8,
+                }),
+            }),
+            Self::Campfire => Some(MasteringSettings {
+                highpass_hz: 90.0,
+                deess_threshold_db: -32.0,
+                high_shelf_db: -4.0,
+                compressor,
+                reverb: Some(ReverbSettings {
+                    room_size: 0.82,
+                    damping: 0.7,
+                    wet: 0.18,
+                    pre_delay_ms: 30.0,
+                    tail_secs: 1.5,
+                }),
+            }),
+        }
+    }
+}
+
+/// Apply a preset. `None` returns the samples unchanged; presets with reverb
+/// append a short tail, so the output may be longer than the input.
+pub fn master(samples: &[f32], sample_rate: u32, preset: MasteringPreset) -> Vec<f32> {
+    match preset.settings() {
+        Some(settings) => apply_mastering(samples, sample_rate, &settings),
+        None => samples.to_vec(),
+    }
+}
+
+/// Run the full chain with explicit settings.
+pub fn apply_mastering(samples: &[f32], sample_rate: u32, settings: &MasteringSettings) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let sr = sample_rate as f32;
+
+    let mut out = samples.to_vec();
+    let mut highpass = Biquad::highpass(sr, settings.highpass_hz, 0.707);
+    out.iter_mut().for_each(|s| *s = highpass.process(*s));
+
+    deess(&mut out, sr, settings.deess_threshold_db);
+
+    if settings.high_shelf_db.abs() > 0.05 {
+        let mut shelf = Biquad::high_shelf(sr, 6000.0, settings.high_shelf_db);
+        out.iter_mut().for_each(|s| *s = shelf.process(*s));
+    }
+
+    compress(&mut out, sr, &settings.compressor);
+
+    if let Some(reverb) = &settings.reverb {
+        out = apply_reverb(&out, sr, reverb);
+    }
+
+    limit_peak(&mut out, 0.98);
+    out
+}
+
+/// Wideband de-esser: watch the 5 kHz+ band and briefly turn the voice
+/// down (by up to 6 dB) while sibilance exceeds the threshold.
+fn deess(samples: &mut [f32], sr: f32, threshold_db: f32) {
+    let mut band = Biquad::highpass(sr, 5000.0, 0.707);
+    let mut env = EnvelopeFollower::new(sr, 1.0, 60.0);
+    let threshold = db_to_gain(threshold_db);
+
+    for s in samples.iter_mut() {
+        let level = env.process(band.process(*s).abs());
+        if level > threshold {
+            // 2:1 above threshold
+            *s *= (threshold / level).sqrt().max(0.5);
+        }
+    }
+}
+
+/// Feed-forward compressor with make-up gain.
+fn compress(samples: &mut [f32], sr: f32, settings: &CompressorSettings) {
+    let mut env = EnvelopeFollower::new(sr, settings.attack_ms, settings.release_ms);
+    let makeup = db_to_gain(settings.makeup_db);
+    let ratio = settings.ratio.max(1.0);
+
+    for s in samples.iter_mut() {
+        let level_db = gain_to_db(env.process(s.abs()));
+        let over = level_db - settings.threshold_db;
+        let reduction_db = if over > 0.0 { over - over / ratio } else { 0.0 };
+        *s *= db_to_gain(-reduction_db) * makeup;
+    }
+}
+
+/// Freeverb-style mono reverb: parallel damped combs into series allpasses.
+fn apply_reverb(samples: &[f32], sr: f32, settings: &ReverbSettings) -> Vec<f32> {
+    // Freeverb tunings at 44.1 kHz, scaled to the actual rate.
+    const COMBS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+    const ALLPASSES: [usize; 4] = [556, 441, 341, 225];
+    let scale = sr / 44100.0;
+    let scaled = |len: usize| ((len as f32 * scale) as usize).max(1);
+
+    let mut combs: Vec<Comb> = COMBS
+        .iter()
+        .map(|&len| Comb::new(scaled(len), settings.room_size, settings.damping))
+        .collect();
+    let mut allpasses: Vec<Allpass> = ALLPASSES.iter().map(|&len| Allpass::new(scaled(len))).collect();
+
+    let pre_delay = (settings.pre_delay_ms * sr / 1000.0) as usize;
+    let tail = (settings.tail_secs * sr) as usize;
+    let total = samples.len() + tail;
+    // Freeverb's fixed input gain keeps the comb bank from clipping.
+    let input_gain = 0.015;
+
+    (0..total)
+        .map(|i| {
+            let dry = samples.get(i).copied().unwrap_or(0.0);
+            let input = i
+                .checked_sub(pre_delay)
+                .and_then(|j| samples.get(j))
+                .copied()
+                .unwrap_or(0.0)
+                * input_gain;
+
+            let mut wet: f32 = combs.iter_mut().map(|c| c.process(input)).sum();
+            for ap in allpasses.iter_mut() {
+                wet = ap.process(wet);
+            }
+            dry + wet * settings.wet * 4.0
+        })
+        .collect()
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1e-9).log10()
+}
+
+/// Peak envelope with separate attack and release times.
+struct EnvelopeFollower {
+    attack: f32,
+    release: f32,
+    level: f32,
+}
+
+impl EnvelopeFollower {
+    fn new(sr: f32, attack_ms: f32, release_ms: f32) -> Self {
+        let coeff = |ms: f32| (-1.0 / (ms.max(0.01) * 0.001 * sr)).exp();
+        Self {
+            attack: coeff(attack_ms),
+            release: coeff(release_ms),
+            level: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let coeff = if input > self.level { self.attack } else { self.release };
+        self.level = input + coeff * (self.level - input);
+        self.level
+    }
+}
+
+/// Second-order IIR section (RBJ cookbook), transposed direct form II.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn new(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn highpass(sr: f32, freq: f32, q: f32) -> Self {
+        let w0 = 2.0 * PI * freq.min(sr * 0.45) / sr;
+        let (cos, alpha) = (w0.cos(), w0.sin() / (2.0 * q));
+        Self::new(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn high_shelf(sr: f32, freq: f32, gain_db: f32) -> Self {
+        let a = 10f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * freq.min(sr * 0.45) / sr;
+        let cos = w0.cos();
+        // Shelf slope S = 1
+        let alpha = w0.sin() / 2.0 * 2f32.sqrt();
+        let sqrt_a = 2.0 * a.sqrt() * alpha;
+        Self::new(
+            [
+                a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a),
+            ],
+            [
+                (a + 1.0) - (a - 1.0) * cos + sqrt_a,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - sqrt_a,
+            ],
+        )
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// Feedback comb filter with a one-pole lowpass in the loop.
+struct Comb {
+    buffer: Vec<f32>,
+    pos: usize,
+    feedback: f32,
+    damping: f32,
+    filter_state: f32,
+}
+
+impl Comb {
+    fn new(len: usize, feedback: f32, damping: f32) -> Self {
+        Self {
+            buffer: vec![0.0; len],
+            pos: 0,
+            feedback: feedback.clamp(0.0, 0.98),
+            damping: damping.clamp(0.0, 1.0),
+            filter_state: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.buffer[self.pos];
+        self.filter_state = output * (1.0 - self.damping) + self.filter_state * self.damping;
+        self.buffer[self.pos] = input + self.filter_state * self.feedback;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Schroeder allpass diffuser.
+struct Allpass {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl Allpass {
+    const FEEDBACK: f32 = 0.5;
+
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len],
+            pos: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.pos];
+        let output = delayed - input;
+        self.buffer[self.pos] = input + delayed * Self::FEEDBACK;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: u32 = 24000;
+
+    fn tone(freq: f32, secs: f32, amp: f32) -> Vec<f32> {
+        (0..(SR as f32 * secs) as usize)
+            .map(|i| amp * (2.0 * PI * freq * i as f32 / SR as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn preset_names_roundtrip() {
+        for preset in MasteringPreset::ALL {
+            assert_eq!(MasteringPreset::from_name(preset.name()), Some(preset));
+        }
+        assert_eq!(MasteringPreset::from_name("Cozy Room"), Some(MasteringPreset::CozyRoom));
+        assert_eq!(MasteringPreset::from_name("cathedral"), None);
+    }
+
+    #[test]
+    fn none_is_bypass() {
+        let input = tone(440.0, 0.1, 0.5);
+        assert_eq!(master(&input, SR, MasteringPreset::None), input);
+    }
+
+    #[test]
+    fn highpass_removes_rumble() {
+        let mut filter = Biquad::highpass(SR as f32, 80.0, 0.707);
+        let out: Vec<f32> = tone(20.0, 1.0, 0.5).iter().map(|&s| filter.process(s)).collect();
+        assert!(rms(&out[SR as usize / 2..]) < 0.05);
+    }
+
+    #[test]
+    fn compressor_narrows_dynamics() {
+        let settings = MasteringPreset::Clean.settings().unwrap().compressor;
+        let mut loud = tone(300.0, 0.5, 0.9);
+        let mut quiet = tone(300.0, 0.5, 0.05);
+        let before = rms(&loud) / rms(&quiet);
+        compress(&mut loud, SR as f32, &settings);
+        compress(&mut quiet, SR as f32, &settings);
+        let after = rms(&loud[SR as usize / 4..]) / rms(&quiet[SR as usize / 4..]);
+        assert!(after < before * 0.6, "ratio {} -> {}", before, after);
+    }
+
+    #[test]
+    fn deesser_reduces_loud_sibilance_only() {
+        let original_hiss = tone(7000.0, 0.3, 0.5);
+        let mut hiss = original_hiss.clone();
+        deess(&mut hiss, SR as f32, -30.0);
+        assert!(rms(&hiss[1000..]) < rms(&original_hiss) * 0.6);
+
+        let original = tone(300.0, 0.3, 0.5);
+        let mut voice = original.clone();
+        deess(&mut voice, SR as f32, -30.0);
+        assert!((rms(&voice) - rms(&original)).abs() < 0.01);
+    }
+
+    #[test]
+    fn reverb_presets_add_tail_and_stay_in_range() {
+        let input = tone(220.0, 0.2, 0.8);
+        for preset in [MasteringPreset::CozyRoom, MasteringPreset::Campfire] {
+            let out = master(&input, SR, preset);
+            assert!(out.len() > input.len());
+            assert!(out.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+            // The tail rings after the input stops
+            assert!(rms(&out[input.len()..input.len() + 2400]) > 1e-4);
+        }
+        let clean = master(&input, SR, MasteringPreset::Clean);
+        assert_eq!(clean.len(), input.len());
+        assert!(master(&[], SR, MasteringPreset::CozyRoom).is_empty());
+    }
+}
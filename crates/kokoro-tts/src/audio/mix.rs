@@ -0,0 +1,117 @@
+//! Building blocks for laying music and ambience under narration.
+
+/// Repeat `samples` until they fill `len`, crossfading each loop seam over
+/// `crossfade` samples. Longer input is trimmed.
+pub fn loop_to_length(samples: &[f32], len: usize, crossfade: usize) -> Vec<f32> {
+    if samples.is_empty() {
+        return vec![0.0; len];
+    }
+    if samples.len() >= len {
+        return samples[..len].to_vec();
+    }
+
+    let crossfade = crossfade.min(samples.len() / 2);
+    let mut out = Vec::with_capacity(len + samples.len());
+    out.extend_from_slice(samples);
+    while out.len() < len {
+        let seam = out.len() - crossfade;
+        for j in 0..crossfade {
+            let t = j as f32 / crossfade as f32;
+            out[seam + j] = out[seam + j] * (1.0 - t) + samples[j] * t;
+        }
+        out.extend_from_slice(&samples[crossfade..]);
+    }
+    out.truncate(len);
+    out
+}
+
+/// Linear fade in over the first `fade_in` samples and out over the last `fade_out`.
+pub fn apply_fades(samples: &mut [f32], fade_in: usize, fade_out: usize) {
+    let len = samples.len();
+    let fade_in = fade_in.min(len);
+    for (i, s) in samples[..fade_in].iter_mut().enumerate() {
+        *s *= i as f32 / fade_in as f32;
+    }
+    let fade_out = fade_out.min(len);
+    for (i, s) in samples[len - fade_out..].iter_mut().enumerate() {
+        *s *= 1.0 - (i + 1) as f32 / fade_out as f32;
+    }
+}
+
+/// Gain curve that drops to `ducked_gain` during `regions` (sample ranges
+/// `start..end`) and sits at 1.0 elsewhere.
+///
+/// The curve starts falling `attack` samples before each region so the duck
+/// is in place when speech begins, and recovers over roughly `release`
+/// samples afterwards.
+pub fn duck_envelope(
+    len: usize,
+    regions: &[(usize, usize)],
+    ducked_gain: f32,
+    attack: usize,
+    release: usize,
+) -> Vec<f32> {
+    let mut target = vec![1.0f32; len];
+    for &(start, end) in regions {
+        let start = start.saturating_sub(attack).min(len);
+        let end = end.min(len);
+        if start < end {
+            target[start..end].fill(ducked_gain);
+        }
+    }
+
+    let coeff = |samples: usize| (-1.0 / samples.max(1) as f32).exp();
+    let (down, up) = (coeff(attack / 3), coeff(release / 3));
+    let mut level = target.first().copied().unwrap_or(1.0);
+    target
+        .into_iter()
+        .map(|t| {
+            let c = if t < level { down } else { up };
+            level = t + c * (level - t);
+            level
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loop_trims_and_repeats() {
+        assert_eq!(loop_to_length(&[1.0, 2.0, 3.0], 2, 0), vec![1.0, 2.0]);
+        assert_eq!(
+            loop_to_length(&[1.0, 2.0, 3.0], 7, 0),
+            vec![1.0, 2.0, 3.0, 1.0, 2.0, 3.0, 1.0]
+        );
+        assert_eq!(loop_to_length(&[], 3, 10), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn loop_crossfades_seams() {
+        let looped = loop_to_length(&[1.0; 8], 20, 4);
+        assert_eq!(looped.len(), 20);
+        // Equal-level material crossfades without dips
+        assert!(looped.iter().all(|&s| (s - 1.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn fades_ramp_edges() {
+        let mut samples = vec![1.0; 10];
+        apply_fades(&mut samples, 4, 2);
+        assert_eq!(&samples[..4], &[0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(samples[5], 1.0);
+        assert_eq!(&samples[8..], &[0.5, 0.0]);
+    }
+
+    #[test]
+    fn duck_envelope_dips_under_regions() {
+        let env = duck_envelope(10_000, &[(4000, 6000)], 0.2, 300, 900);
+        assert!((env[1000] - 1.0).abs() < 1e-3);
+        assert!((env[5000] - 0.2).abs() < 1e-3);
+        // Already most of the way down when the region starts
+        assert!(env[4000] < 0.3);
+        // Recovered well after the region
+        assert!(env[9500] > 0.95);
+    }
+}
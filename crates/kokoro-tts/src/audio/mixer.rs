@@ -0,0 +1,316 @@
+//! In-process multi-track mixer. Clips are placed on tracks at an offset with
+//! their own gain and fades, converted to the timeline's sample rate and
+//! channel layout, and summed into one buffer that can be written in any
+//! [`AudioFormat`].
+
+use std::path::Path;
+
+use super::{encode_interleaved, resample_linear, AudioFormat};
+use crate::KokoroError;
+
+/// Interleaved f32 audio with its layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBuffer {
+    pub samples: Vec<f32>,
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+impl AudioBuffer {
+    pub fn new(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
+        Self {
+            samples,
+            channels: channels.max(1),
+            sample_rate,
+        }
+    }
+
+    pub fn mono(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self::new(samples, 1, sample_rate)
+    }
+
+    /// Load a WAV file keeping all of its channels.
+    pub fn load(path: &Path) -> Result<Self, KokoroError> {
+        let (samples, channels, sample_rate) = super::read_wav(path)?;
+        Ok(Self::new(samples, channels, sample_rate))
+    }
+
+    /// Number of sample frames (samples per channel).
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.frames() as f64 / self.sample_rate as f64
+    }
+
+    /// Convert to another channel count and sample rate.
+    ///
+    /// Fewer output channels average the source channels that fold onto
+    /// them (stereo → mono averages L and R); more output channels repeat
+    /// the source channels (mono → stereo duplicates).
+    pub fn convert(&self, channels: u16, sample_rate: u32) -> AudioBuffer {
+        let (from, to) = (self.channels as usize, channels.max(1) as usize);
+        let planes: Vec<Vec<f32>> = (0..from)
+            .map(|c| self.samples.iter().skip(c).step_by(from).copied().collect())
+            .collect();
+
+        let mapped: Vec<Vec<f32>> = if to == from {
+            planes
+        } else if to < from {
+            (0..to)
+                .map(|c| {
+                    let sources: Vec<&Vec<f32>> = planes.iter().skip(c).step_by(to).collect();
+                    let scale = 1.0 / sources.len() as f32;
+                    (0..self.frames())
+                        .map(|i| sources.iter().map(|p| p[i]).sum::<f32>() * scale)
+                        .collect()
+                })
+                .collect()
+        } else {
+            (0..to).map(|c| planes[c % from].clone()).collect()
+        };
+
+        let resampled: Vec<Vec<f32>> = mapped
+            .iter()
+            .map(|p| resample_linear(p, self.sample_rate, sample_rate))
+            .collect();
+        let frames = resampled.first().map_or(0, |p| p.len());
+        let mut samples = Vec::with_capacity(frames * to);
+        for i in 0..frames {
+            samples.extend(resampled.iter().map(|p| p[i]));
+        }
+        AudioBuffer::new(samples, to as u16, sample_rate)
+    }
+
+    /// Encode in `format`.
+    pub fn encode(&self, format: AudioFormat) -> Result<Vec<u8>, KokoroError> {
+        encode_interleaved(&self.samples, self.channels, self.sample_rate, format)
+    }
+
+    /// Write to `path` in `format`.
+    pub fn save(&self, path: &Path, format: AudioFormat) -> Result<(), KokoroError> {
+        let bytes = self.encode(format)?;
+        std::fs::write(path, bytes).map_err(|e| {
+            KokoroError::Audio(format!("Failed to write audio file {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// A piece of audio placed on a track.
+#[derive(Debug, Clone)]
+pub struct Clip {
+    pub buffer: AudioBuffer,
+    /// Start time on the timeline, in seconds
+    pub offset_secs: f64,
+    pub gain: f32,
+    pub fade_in_secs: f32,
+    pub fade_out_secs: f32,
+}
+
+impl Clip {
+    /// A clip at the start of the timeline at unity gain with no fades.
+    pub fn new(buffer: AudioBuffer) -> Self {
+        Self {
+            buffer,
+            offset_secs: 0.0,
+            gain: 1.0,
+            fade_in_secs: 0.0,
+            fade_out_secs: 0.0,
+        }
+    }
+
+    pub fn at(mut self, offset_secs: f64) -> Self {
+        self.offset_secs = offset_secs.max(0.0);
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_fades(mut self, fade_in_secs: f32, fade_out_secs: f32) -> Self {
+        self.fade_in_secs = fade_in_secs.max(0.0);
+        self.fade_out_secs = fade_out_secs.max(0.0);
+        self
+    }
+
+    pub fn end_secs(&self) -> f64 {
+        self.offset_secs + self.buffer.duration_secs()
+    }
+}
+
+/// A lane of clips sharing one gain.
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub gain: f32,
+    pub clips: Vec<Clip>,
+}
+
+impl Track {
+    pub fn new(gain: f32) -> Self {
+        Self {
+            gain,
+            clips: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, clip: Clip) -> &mut Self {
+        self.clips.push(clip);
+        self
+    }
+
+    /// Place `clip` right after the last clip on this track.
+    pub fn append(&mut self, clip: Clip) -> &mut Self {
+        let end = self.end_secs();
+        self.add(clip.at(end))
+    }
+
+    pub fn end_secs(&self) -> f64 {
+        self.clips.iter().map(Clip::end_secs).fold(0.0, f64::max)
+    }
+}
+
+/// Tracks mixed down to one sample rate and channel layout.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub tracks: Vec<Track>,
+}
+
+impl Timeline {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            tracks: Vec::new(),
+        }
+    }
+
+    /// Add an empty track and return it for filling.
+    pub fn add_track(&mut self, gain: f32) -> &mut Track {
+        self.tracks.push(Track::new(gain));
+        self.tracks.last_mut().expect("track was just pushed")
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.tracks.iter().map(Track::end_secs).fold(0.0, f64::max)
+    }
+
+    /// Mix every clip into one buffer. Samples are summed without limiting;
+    /// integer encoders clamp anything outside [-1, 1].
+    pub fn render(&self) -> AudioBuffer {
+        let channels = self.channels as usize;
+        let mut out: Vec<f32> = Vec::new();
+
+        for track in &self.tracks {
+            for clip in &track.clips {
+                let buffer = clip.buffer.convert(self.channels, self.sample_rate);
+                let frames = buffer.frames();
+                let start = (clip.offset_secs * self.sample_rate as f64).round() as usize;
+                let end = (start + frames) * channels;
+                if out.len() < end {
+                    out.resize(end, 0.0);
+                }
+
+                let fade = |secs: f32| ((secs * self.sample_rate as f32) as usize).min(frames);
+                let (fade_in, fade_out) = (fade(clip.fade_in_secs), fade(clip.fade_out_secs));
+                let gain = clip.gain * track.gain;
+
+                for (i, frame) in buffer.samples.chunks(channels).enumerate() {
+                    let mut g = gain;
+                    if i < fade_in {
+                        g *= i as f32 / fade_in as f32;
+                    }
+                    if i + fade_out >= frames && fade_out > 0 {
+                        g *= (frames - i - 1) as f32 / fade_out as f32;
+                    }
+                    let at = (start + i) * channels;
+                    for (o, s) in out[at..at + channels].iter_mut().zip(frame) {
+                        *o += s * g;
+                    }
+                }
+            }
+        }
+
+        AudioBuffer::new(out, self.channels, self.sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_channel_layouts() {
+        let mono = AudioBuffer::mono(vec![0.1, 0.2], 8000);
+        assert_eq!(mono.convert(2, 8000).samples, vec![0.1, 0.1, 0.2, 0.2]);
+
+        let stereo = AudioBuffer::new(vec![1.0, 0.0, 0.5, 0.5], 2, 8000);
+        assert_eq!(stereo.convert(1, 8000).samples, vec![0.5, 0.5]);
+        assert_eq!(stereo.frames(), 2);
+    }
+
+    #[test]
+    fn converts_sample_rate() {
+        let buffer = AudioBuffer::new(vec![0.0; 2 * 12000], 2, 12000);
+        let converted = buffer.convert(2, 24000);
+        assert_eq!(converted.frames(), 24000);
+        assert_eq!(converted.channels, 2);
+        assert!((converted.duration_secs() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn append_concatenates_clips() {
+        let mut timeline = Timeline::new(4, 1);
+        timeline
+            .add_track(1.0)
+            .append(Clip::new(AudioBuffer::mono(vec![1.0; 4], 4)))
+            .append(Clip::new(AudioBuffer::mono(vec![2.0; 2], 4)));
+        assert_eq!(timeline.duration_secs(), 1.5);
+        assert_eq!(timeline.render().samples, vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn clips_apply_offset_gain_and_fades() {
+        let mut timeline = Timeline::new(10, 1);
+        timeline.add_track(0.5).add(
+            Clip::new(AudioBuffer::mono(vec![1.0; 10], 10))
+                .at(0.5)
+                .with_gain(2.0)
+                .with_fades(0.4, 0.2),
+        );
+        timeline.add_track(1.0).add(Clip::new(AudioBuffer::mono(vec![0.25; 2], 10)));
+
+        let out = timeline.render().samples;
+        assert_eq!(out.len(), 15);
+        assert_eq!(&out[..2], &[0.25, 0.25]);
+        assert_eq!(&out[2..5], &[0.0, 0.0, 0.0]);
+        // Fade in over four frames, hold, then fade out over two
+        assert_eq!(&out[5..9], &[0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(out[10], 1.0);
+        assert_eq!(&out[13..], &[0.5, 0.0]);
+    }
+
+    #[test]
+    fn saves_stereo_in_requested_format() {
+        let dir = std::env::temp_dir().join(format!("kokoro_mixer_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stereo.wav");
+
+        let mut timeline = Timeline::new(8000, 2);
+        timeline.add_track(1.0).add(Clip::new(AudioBuffer::mono(vec![0.5; 800], 16000)));
+        timeline.render().save(&path, AudioFormat::Wav16).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        assert_eq!(reader.len(), 800);
+
+        let loaded = AudioBuffer::load(&path).unwrap();
+        assert_eq!(loaded.frames(), 400);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,443 @@
+pub mod aac;
+pub mod ambience;
+pub mod effects;
+pub mod id3;
+pub mod mastering;
+pub mod mix;
+pub mod mixer;
+pub mod mp3;
+pub mod mp4;
+pub mod synth;
+pub mod tags;
+
+use std::io::{Cursor, Seek, Write};
+use std::path::Path;
+
+use crate::KokoroError;
+
+/// Sample rate for Kokoro-82M output audio.
+pub const SAMPLE_RATE: u32 = 24000;
+
+/// Output encodings the crate can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    /// 32-bit float WAV (the app's native format)
+    Wav,
+    /// 16-bit PCM WAV, for players that don't handle float WAV
+    Wav16,
+    /// Headerless 16-bit signed little-endian PCM
+    Pcm,
+    /// MPEG-1/2 Layer III at the default bitrate for the channel count
+    Mp3,
+    /// AAC-LC in an MP4 container
+    M4a,
+}
+
+impl AudioFormat {
+    /// Parse a format name (`wav`, `wav16`, `pcm`, `mp3`, `m4a`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "wav16" => Some(Self::Wav16),
+            "pcm" | "raw" => Some(Self::Pcm),
+            "mp3" => Some(Self::Mp3),
+            "m4a" | "aac" => Some(Self::M4a),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from a file extension, defaulting to float WAV.
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::Wav)
+    }
+
+    /// The format a file extension (`wav`, `pcm`, `mp3`, `m4b`, ...)
+    /// stands for, if any.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "pcm" | "raw" => Some(Self::Pcm),
+            "mp3" => Some(Self::Mp3),
+            "m4a" | "m4b" | "aac" => Some(Self::M4a),
+            _ => None,
+        }
+    }
+
+    /// File extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav | Self::Wav16 => "wav",
+            Self::Pcm => "pcm",
+            Self::Mp3 => "mp3",
+            Self::M4a => "m4a",
+        }
+    }
+}
+
+/// Encode samples in the given format into an in-memory buffer.
+pub fn encode_audio(
+    samples: &[f32],
+    sample_rate: u32,
+    format: AudioFormat,
+) -> Result<Vec<u8>, KokoroError> {
+    encode_interleaved(samples, 1, sample_rate, format)
+}
+
+/// Encode interleaved multi-channel samples in the given format.
+pub fn encode_interleaved(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    format: AudioFormat,
+) -> Result<Vec<u8>, KokoroError> {
+    match format {
+        AudioFormat::Wav => {
+            let mut cursor = Cursor::new(Vec::new());
+            write_wav(&mut cursor, samples, channels, sample_rate, 32, hound::SampleFormat::Float)?;
+            Ok(cursor.into_inner())
+        }
+        AudioFormat::Wav16 => {
+            let mut cursor = Cursor::new(Vec::new());
+            write_wav(&mut cursor, samples, channels, sample_rate, 16, hound::SampleFormat::Int)?;
+            Ok(cursor.into_inner())
+        }
+        AudioFormat::Pcm => Ok(encode_pcm16(samples)),
+        AudioFormat::Mp3 => mp3::encode_mp3(
+            samples,
+            channels,
+            sample_rate,
+            mp3::default_bitrate(sample_rate, channels),
+        ),
+        AudioFormat::M4a => mp4::encode_m4a(samples, channels, sample_rate, &Default::default()),
+    }
+}
+
+/// Save samples to a file in the given format.
+pub fn save_audio(
+    path: &Path,
+    samples: &[f32],
+    sample_rate: u32,
+    format: AudioFormat,
+) -> Result<(), KokoroError> {
+    let bytes = encode_audio(samples, sample_rate, format)?;
+    std::fs::write(path, bytes).map_err(|e| {
+        KokoroError::Audio(format!("Failed to write audio file {}: {}", path.display(), e))
+    })
+}
+
+/// Scale samples down if their peak exceeds `ceiling`, keeping quieter
+/// audio as it is.
+pub fn limit_peak(samples: &mut [f32], ceiling: f32) {
+    let peak = samples.iter().fold(0.0f32, |m, &s| m.max(s.abs()));
+    if peak > ceiling {
+        let gain = ceiling / peak;
+        samples.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+/// Convert f32 samples to 16-bit signed little-endian PCM bytes.
+pub fn encode_pcm16(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|&s| to_i16(s).to_le_bytes())
+        .collect()
+}
+
+/// Convert one f32 sample in [-1, 1] to i16, clamping out-of-range values.
+pub fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+/// Write an interleaved WAV stream with the given sample encoding.
+fn write_wav<W: Write + Seek>(
+    writer: W,
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    sample_format: hound::SampleFormat,
+) -> Result<(), KokoroError> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+
+    let mut writer = hound::WavWriter::new(writer, spec)
+        .map_err(|e| KokoroError::Audio(format!("Failed to start WAV stream: {}", e)))?;
+
+    for &sample in samples {
+        let result = if sample_format == hound::SampleFormat::Float {
+            writer.write_sample(sample)
+        } else {
+            writer.write_sample(to_i16(sample))
+        };
+        result.map_err(|e| KokoroError::Audio(format!("Failed to write WAV sample: {}", e)))?;
+    }
+
+    writer
+        .finalize()
+        .map_err(|e| KokoroError::Audio(format!("Failed to finalize WAV stream: {}", e)))
+}
+
+/// Save f32 audio samples as a WAV file (24kHz mono).
+pub fn save_wav(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), KokoroError> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec).map_err(|e| {
+        KokoroError::Audio(format!("Failed to create WAV file {}: {}", path.display(), e))
+    })?;
+
+    for &sample in samples {
+        writer.write_sample(sample).map_err(|e| {
+            KokoroError::Audio(format!("Failed to write WAV sample: {}", e))
+        })?;
+    }
+
+    writer.finalize().map_err(|e| {
+        KokoroError::Audio(format!("Failed to finalize WAV file: {}", e))
+    })?;
+
+    Ok(())
+}
+
+/// Load a WAV file as mono f32 samples, returning them with the file's sample rate.
+/// Integer and float encodings are accepted; multi-channel audio is averaged.
+pub fn load_wav(path: &Path) -> Result<(Vec<f32>, u32), KokoroError> {
+    let (interleaved, channels, sample_rate) = read_wav(path)?;
+    let channels = channels.max(1) as usize;
+    let samples = if channels == 1 {
+        interleaved
+    } else {
+        interleaved
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    };
+    Ok((samples, sample_rate))
+}
+
+/// Length of a WAV file in seconds, read from its header without decoding it.
+pub fn wav_duration(path: &Path) -> Result<f64, KokoroError> {
+    let reader = hound::WavReader::open(path).map_err(|e| {
+        KokoroError::Audio(format!("Failed to open WAV file {}: {}", path.display(), e))
+    })?;
+    Ok(reader.duration() as f64 / reader.spec().sample_rate as f64)
+}
+
+/// Decode a WAV file to interleaved f32 samples with its channel count and rate.
+pub(crate) fn read_wav(path: &Path) -> Result<(Vec<f32>, u16, u32), KokoroError> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| {
+        KokoroError::Audio(format!("Failed to open WAV file {}: {}", path.display(), e))
+    })?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 * scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| KokoroError::Audio(format!("Failed to read WAV file {}: {}", path.display(), e)))?;
+
+    Ok((interleaved, spec.channels, spec.sample_rate))
+}
+
+/// Resample with linear interpolation. Adequate for background beds and
+/// effects; not intended for the voice itself.
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let out_len = (samples.len() as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(samples.len() - 1)];
+            let b = samples[(idx + 1).min(samples.len() - 1)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Normalize audio samples so the peak amplitude reaches the target level.
+/// This boosts quiet audio without clipping.
+pub fn normalize(samples: &mut [f32], target_peak: f32) {
+    let peak = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
+    if peak > 0.0 && peak < target_peak {
+        let gain = target_peak / peak;
+        for s in samples.iter_mut() {
+            *s *= gain;
+        }
+    }
+}
+
+/// Concatenate multiple audio chunks into a single sample vector.
+pub fn concat_samples(chunks: &[Vec<f32>]) -> Vec<f32> {
+    let total_len: usize = chunks.iter().map(|c| c.len()).sum();
+    let mut result = Vec::with_capacity(total_len);
+    for chunk in chunks {
+        result.extend_from_slice(chunk);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_peak_only_scales_loud_audio() {
+        let mut loud = vec![0.5, -2.0, 1.0];
+        limit_peak(&mut loud, 0.98);
+        assert_eq!(loud, vec![0.245, -0.98, 0.49]);
+
+        let mut quiet = vec![0.5, -0.25];
+        limit_peak(&mut quiet, 0.98);
+        assert_eq!(quiet, vec![0.5, -0.25]);
+    }
+
+    #[test]
+    fn test_concat_samples() {
+        let chunks = vec![vec![1.0, 2.0], vec![3.0, 4.0, 5.0]];
+        let result = concat_samples(&chunks);
+        assert_eq!(result, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_concat_empty() {
+        let chunks: Vec<Vec<f32>> = vec![];
+        let result = concat_samples(&chunks);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_boosts_quiet_audio() {
+        let mut samples = vec![0.1, -0.2, 0.15, -0.05];
+        normalize(&mut samples, 0.95);
+        // Peak was 0.2, should now be 0.95
+        let peak = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
+        assert!((peak - 0.95).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_normalize_no_clipping_loud_audio() {
+        let mut samples = vec![0.5, -0.98, 0.3];
+        let original = samples.clone();
+        normalize(&mut samples, 0.95);
+        // Peak 0.98 > 0.95, should not change
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_normalize_silence() {
+        let mut samples = vec![0.0, 0.0, 0.0];
+        normalize(&mut samples, 0.95);
+        assert_eq!(samples, vec![0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_audio_format_names() {
+        assert_eq!(AudioFormat::from_name("WAV"), Some(AudioFormat::Wav));
+        assert_eq!(AudioFormat::from_name("wav16"), Some(AudioFormat::Wav16));
+        assert_eq!(AudioFormat::from_name("pcm"), Some(AudioFormat::Pcm));
+        assert_eq!(AudioFormat::from_name("ogg"), None);
+        assert_eq!(AudioFormat::from_path(Path::new("a/b.pcm")), AudioFormat::Pcm);
+        assert_eq!(AudioFormat::from_path(Path::new("a/b.wav")), AudioFormat::Wav);
+        assert_eq!(AudioFormat::from_path(Path::new("a/b.MP3")), AudioFormat::Mp3);
+        assert_eq!(AudioFormat::from_path(Path::new("a/b.m4b")), AudioFormat::M4a);
+        assert_eq!(AudioFormat::from_name("aac"), Some(AudioFormat::M4a));
+        assert_eq!(AudioFormat::from_extension("ogg"), None);
+    }
+
+    #[test]
+    fn test_encode_pcm16() {
+        let bytes = encode_pcm16(&[0.0, 1.0, -1.0, 2.0]);
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(i16::from_le_bytes([bytes[2], bytes[3]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([bytes[4], bytes[5]]), -i16::MAX);
+        // Out-of-range samples are clamped
+        assert_eq!(i16::from_le_bytes([bytes[6], bytes[7]]), i16::MAX);
+    }
+
+    #[test]
+    fn test_encode_wav16_roundtrip() {
+        let samples = vec![0.0, 0.5, -0.5];
+        let bytes = encode_audio(&samples, SAMPLE_RATE, AudioFormat::Wav16).unwrap();
+        let reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        assert_eq!(reader.spec().sample_format, hound::SampleFormat::Int);
+        assert_eq!(reader.len(), 3);
+    }
+
+    #[test]
+    fn test_save_wav() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("kokoro_test_output.wav");
+
+        // Generate a simple sine wave
+        let samples: Vec<f32> = (0..24000)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 24000.0).sin() * 0.5)
+            .collect();
+
+        save_wav(&path, &samples, SAMPLE_RATE).unwrap();
+        assert!(path.exists());
+
+        // Read it back and verify
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 24000);
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.len(), 24000);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_wav_downmixes_and_scales() {
+        let path = std::env::temp_dir().join("kokoro_test_stereo16.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for (l, r) in [(16384i16, 0i16), (-32768, -32768)] {
+            writer.write_sample(l).unwrap();
+            writer.write_sample(r).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (samples, rate) = load_wav(&path).unwrap();
+        assert_eq!(rate, 44100);
+        assert_eq!(samples, vec![0.25, -1.0]);
+        assert_eq!(wav_duration(&path).unwrap(), 2.0 / 44100.0);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_resample_linear() {
+        let samples = vec![0.0, 1.0, 0.0, -1.0];
+        assert_eq!(resample_linear(&samples, 24000, 24000), samples);
+        let up = resample_linear(&samples, 12000, 24000);
+        assert_eq!(up.len(), 8);
+        assert_eq!(up[1], 0.5);
+        assert_eq!(resample_linear(&samples, 24000, 12000), vec![0.0, 0.0]);
+    }
+}
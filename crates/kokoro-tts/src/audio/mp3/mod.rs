@@ -0,0 +1,797 @@
+//! MPEG-1/2 Layer III (MP3) encoder.
+//!
+//! Deliberately simple: long blocks only, one global gain per granule with
+//! flat scalefactors, and no bit reservoir. That keeps every frame
+//! self-contained and is plenty for narration at 64 kbps and up, which is
+//! all the app and the speech server need.
+
+mod tables;
+
+use std::f64::consts::PI;
+
+use super::resample_linear;
+use crate::KokoroError;
+use tables::{LINBITS, PAIR_TABLES, QUAD_CODES, QUAD_LENS, WINDOW_D};
+
+/// Spectral lines per granule.
+const GRANULE: usize = 576;
+/// Largest quantized magnitude (15 plus 13 escape bits).
+const MAX_QUANT: u32 = 15 + (1 << 13) - 1;
+/// `part2_3_length` is a 12-bit field.
+const MAX_GRANULE_BITS: usize = 4095;
+
+/// Sample rates Layer III carries natively: (rate, MPEG-1, header index, sfb table).
+const RATES: [(u32, bool, u32, usize); 6] = [
+    (44100, true, 0, 0),
+    (48000, true, 1, 1),
+    (32000, true, 2, 2),
+    (22050, false, 0, 3),
+    (24000, false, 1, 4),
+    (16000, false, 2, 5),
+];
+
+const MPEG1_BITRATES: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Long-block scalefactor band edges, in `RATES` order.
+const SFB_LONG: [[usize; 23]; 6] = [
+    [
+        0, 4, 8, 12, 16, 20, 24, 30, 36, 44, 52, 62, 74, 90, 110, 134, 162, 196, 238, 288, 342,
+        418, 576,
+    ],
+    [
+        0, 4, 8, 12, 16, 20, 24, 30, 36, 42, 50, 60, 72, 88, 106, 128, 156, 190, 230, 276, 330,
+        384, 576,
+    ],
+    [
+        0, 4, 8, 12, 16, 20, 24, 30, 36, 44, 54, 66, 82, 102, 126, 156, 194, 240, 296, 364, 448,
+        550, 576,
+    ],
+    [
+        0, 6, 12, 18, 24, 30, 36, 44, 54, 66, 80, 96, 116, 140, 168, 200, 238, 284, 336, 396, 464,
+        522, 576,
+    ],
+    [
+        0, 6, 12, 18, 24, 30, 36, 44, 54, 66, 80, 96, 114, 136, 162, 194, 232, 278, 332, 394, 464,
+        540, 576,
+    ],
+    [
+        0, 6, 12, 18, 24, 30, 36, 44, 54, 66, 80, 96, 116, 140, 168, 200, 238, 284, 336, 396, 464,
+        522, 576,
+    ],
+];
+
+/// Region0/region1 band counts by the number of bands in big_values
+/// (ISO/IEC 11172-3 reference encoder).
+const SUBDIVISION: [(usize, usize); 23] = [
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 1),
+    (1, 1),
+    (1, 1),
+    (1, 2),
+    (2, 2),
+    (2, 3),
+    (2, 3),
+    (3, 4),
+    (3, 4),
+    (3, 4),
+    (4, 5),
+    (4, 5),
+    (4, 6),
+    (5, 6),
+    (5, 6),
+    (5, 7),
+    (6, 7),
+    (6, 7),
+];
+
+/// Pair tables without escape bits, with the largest value each can code.
+const SMALL_TABLES: [(usize, u32); 13] = [
+    (1, 1),
+    (2, 2),
+    (3, 2),
+    (5, 3),
+    (6, 3),
+    (7, 5),
+    (8, 5),
+    (9, 5),
+    (10, 7),
+    (11, 7),
+    (12, 7),
+    (13, 15),
+    (15, 15),
+];
+
+/// Nearest sample rate an MP3 can carry; anything else is resampled to it.
+pub fn supported_rate(sample_rate: u32) -> u32 {
+    RATES
+        .iter()
+        .map(|r| r.0)
+        .min_by_key(|&r| (r as i64 - sample_rate as i64).abs())
+        .unwrap_or(24000)
+}
+
+/// A sensible constant bitrate in kbps for speech at this rate and layout.
+pub fn default_bitrate(sample_rate: u32, channels: u16) -> u32 {
+    let per_channel = if sample_rate >= 32000 { 80 } else { 64 };
+    let max = if sample_rate >= 32000 { 320 } else { 160 };
+    (per_channel * channels.clamp(1, 2) as u32).min(max)
+}
+
+/// Encode interleaved samples as a constant bitrate MP3, resampling if the
+/// rate isn't one MP3 supports.
+pub fn encode_mp3(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    bitrate_kbps: u32,
+) -> Result<Vec<u8>, KokoroError> {
+    let rate = supported_rate(sample_rate);
+    let resampled;
+    let samples = if rate == sample_rate {
+        samples
+    } else {
+        resampled = resample_interleaved(samples, channels, sample_rate, rate);
+        &resampled
+    };
+    let mut encoder = Mp3Encoder::new(rate, channels, bitrate_kbps)?;
+    let mut out = encoder.encode(samples);
+    out.extend(encoder.finish());
+    Ok(out)
+}
+
+fn resample_interleaved(samples: &[f32], channels: u16, from: u32, to: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let split: Vec<Vec<f32>> = (0..channels)
+        .map(|c| {
+            let channel: Vec<f32> = samples.iter().skip(c).step_by(channels).copied().collect();
+            resample_linear(&channel, from, to)
+        })
+        .collect();
+    let frames = split.iter().map(Vec::len).min().unwrap_or(0);
+    (0..frames)
+        .flat_map(|i| split.iter().map(move |c| c[i]))
+        .collect()
+}
+
+/// Streaming constant bitrate MP3 encoder for mono or stereo input.
+pub struct Mp3Encoder {
+    channels: usize,
+    mpeg1: bool,
+    rate: u32,
+    rate_index: u32,
+    bitrate: u32,
+    bitrate_index: u32,
+    sfb: &'static [usize; 23],
+    /// Remainder used to spread padding slots so the average bitrate is exact.
+    slot_rest: u32,
+    pending: Vec<f32>,
+    state: Vec<ChannelState>,
+    filter: Box<[[f64; 64]; 32]>,
+    mdct: Box<[[f64; 36]; 18]>,
+}
+
+/// Per-channel analysis history.
+struct ChannelState {
+    /// Last 512 input samples, newest first.
+    history: [f64; 512],
+    /// Previous granule's subband samples, the first half of each MDCT block.
+    previous: [[f64; 18]; 32],
+}
+
+/// One quantized granule of one channel, ready to write.
+struct Granule {
+    ix: [u32; GRANULE],
+    negative: [bool; GRANULE],
+    global_gain: u32,
+    big_values: usize,
+    count1_end: usize,
+    table_select: [usize; 3],
+    region0_count: usize,
+    region1_count: usize,
+    region_ends: [usize; 3],
+    count1_table: usize,
+    bits: usize,
+}
+
+impl Mp3Encoder {
+    /// `sample_rate` must be one of 16, 22.05, 24, 32, 44.1 or 48 kHz, and
+    /// `bitrate_kbps` one the MPEG version allows (8-160 or 32-320).
+    pub fn new(sample_rate: u32, channels: u16, bitrate_kbps: u32) -> Result<Self, KokoroError> {
+        let &(rate, mpeg1, rate_index, sfb) = RATES
+            .iter()
+            .find(|r| r.0 == sample_rate)
+            .ok_or_else(|| KokoroError::Audio(format!("MP3 can't carry {} Hz audio", sample_rate)))?;
+        if !(1..=2).contains(&channels) {
+            return Err(KokoroError::Audio(format!(
+                "MP3 supports mono or stereo, not {} channels",
+                channels
+            )));
+        }
+        let bitrates = if mpeg1 { &MPEG1_BITRATES } else { &MPEG2_BITRATES };
+        let bitrate_index = bitrates
+            .iter()
+            .skip(1)
+            .position(|&b| b == bitrate_kbps)
+            .ok_or_else(|| {
+                KokoroError::Audio(format!("{} kbps isn't an MP3 bitrate at {} Hz", bitrate_kbps, rate))
+            })? as u32
+            + 1;
+
+        let mut filter = Box::new([[0.0; 64]; 32]);
+        for (i, row) in filter.iter_mut().enumerate() {
+            for (k, m) in row.iter_mut().enumerate() {
+                *m = ((2 * i + 1) as f64 * (k as f64 - 16.0) * PI / 64.0).cos();
+            }
+        }
+        // Sine-windowed MDCT, scaled by 1/9 so the decoder's unscaled IMDCT
+        // with overlap-add gives back the subband samples.
+        let mut mdct = Box::new([[0.0; 36]; 18]);
+        for (k, row) in mdct.iter_mut().enumerate() {
+            for (n, m) in row.iter_mut().enumerate() {
+                let window = (PI / 36.0 * (n as f64 + 0.5)).sin();
+                let basis = (PI / 72.0 * (2 * n + 19) as f64 * (2 * k + 1) as f64).cos();
+                *m = window * basis / 9.0;
+            }
+        }
+
+        Ok(Self {
+            channels: channels as usize,
+            mpeg1,
+            rate,
+            rate_index,
+            bitrate: bitrate_kbps,
+            bitrate_index,
+            sfb: &SFB_LONG[sfb],
+            slot_rest: 0,
+            pending: Vec::new(),
+            state: (0..channels)
+                .map(|_| ChannelState {
+                    history: [0.0; 512],
+                    previous: [[0.0; 18]; 32],
+                })
+                .collect(),
+            filter,
+            mdct,
+        })
+    }
+
+    /// Samples per channel in one frame.
+    pub fn frame_samples(&self) -> usize {
+        self.granules() * GRANULE
+    }
+
+    fn granules(&self) -> usize {
+        if self.mpeg1 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Feed interleaved samples and return any complete frames.
+    pub fn encode(&mut self, samples: &[f32]) -> Vec<u8> {
+        self.pending.extend_from_slice(samples);
+        let frame_len = self.frame_samples() * self.channels;
+        let mut out = Vec::new();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            let frame: Vec<f32> = self.pending[start..start + frame_len].to_vec();
+            self.encode_frame(&frame, &mut out);
+            start += frame_len;
+        }
+        self.pending.drain(..start);
+        out
+    }
+
+    /// Pad out the last frame and flush the filterbank delay.
+    pub fn finish(mut self) -> Vec<u8> {
+        let frame_len = self.frame_samples() * self.channels;
+        // The analysis filterbank and MDCT hold back just over one granule.
+        let flush = frame_len - self.pending.len() % frame_len + frame_len;
+        let silence = vec![0.0; flush];
+        self.encode(&silence)
+    }
+
+    fn encode_frame(&mut self, frame: &[f32], out: &mut Vec<u8>) {
+        let granules = self.granules();
+        let coefficient = if self.mpeg1 { 144 } else { 72 };
+        let numerator = coefficient * self.bitrate * 1000;
+        self.slot_rest += numerator % self.rate;
+        let padding = self.slot_rest >= self.rate;
+        if padding {
+            self.slot_rest -= self.rate;
+        }
+        let frame_bytes = (numerator / self.rate) as usize + padding as usize;
+        let side_bytes = match (self.mpeg1, self.channels) {
+            (true, 1) => 17,
+            (true, _) => 32,
+            (false, 1) => 9,
+            (false, _) => 17,
+        };
+        let main_bits = (frame_bytes - 4 - side_bytes) * 8;
+
+        let mut coded = Vec::with_capacity(granules * self.channels);
+        let mut remaining = main_bits;
+        for gr in 0..granules {
+            for ch in 0..self.channels {
+                let pcm: Vec<f64> = frame
+                    .iter()
+                    .skip(gr * GRANULE * self.channels + ch)
+                    .step_by(self.channels)
+                    .take(GRANULE)
+                    .map(|&s| s as f64)
+                    .collect();
+                let xr = self.transform(ch, &pcm);
+                let units_left = granules * self.channels - coded.len();
+                let budget = (remaining / units_left).min(MAX_GRANULE_BITS);
+                let granule = quantize(&xr, self.sfb, budget);
+                remaining -= granule.bits;
+                coded.push(granule);
+            }
+        }
+
+        let mut bits = BitWriter::default();
+        bits.put(0x7ff, 11);
+        bits.put(if self.mpeg1 { 3 } else { 2 }, 2);
+        bits.put(1, 2); // layer III
+        bits.put(1, 1); // no CRC
+        bits.put(self.bitrate_index, 4);
+        bits.put(self.rate_index, 2);
+        bits.put(padding as u32, 1);
+        bits.put(0, 1);
+        bits.put(if self.channels == 1 { 3 } else { 0 }, 2);
+        bits.put(0, 2);
+        bits.put(0, 1);
+        bits.put(1, 1); // original
+        bits.put(0, 2);
+
+        // Side information; main_data_begin is always 0 (no reservoir).
+        if self.mpeg1 {
+            bits.put(0, 9);
+            bits.put(0, if self.channels == 1 { 5 } else { 3 });
+            bits.put(0, 4 * self.channels as u32); // scfsi
+        } else {
+            bits.put(0, 8);
+            bits.put(0, if self.channels == 1 { 1 } else { 2 });
+        }
+        for granule in &coded {
+            bits.put(granule.bits as u32, 12);
+            bits.put(granule.big_values as u32, 9);
+            bits.put(granule.global_gain, 8);
+            bits.put(0, if self.mpeg1 { 4 } else { 9 }); // scalefac_compress
+            bits.put(0, 1); // window switching
+            for &table in &granule.table_select {
+                bits.put(table as u32, 5);
+            }
+            bits.put(granule.region0_count as u32, 4);
+            bits.put(granule.region1_count as u32, 3);
+            if self.mpeg1 {
+                bits.put(0, 1); // preflag
+            }
+            bits.put(0, 1); // scalefac_scale
+            bits.put(granule.count1_table as u32, 1);
+        }
+        for granule in &coded {
+            write_granule(&mut bits, granule);
+        }
+
+        let mut bytes = bits.into_bytes();
+        bytes.resize(frame_bytes, 0);
+        out.extend_from_slice(&bytes);
+    }
+
+    /// Analysis filterbank, MDCT and alias reduction for one granule.
+    fn transform(&mut self, ch: usize, pcm: &[f64]) -> [f64; GRANULE] {
+        let state = &mut self.state[ch];
+        let mut subbands = [[0.0f64; 18]; 32];
+        for t in 0..18 {
+            state.history.copy_within(0..480, 32);
+            for i in 0..32 {
+                state.history[31 - i] = pcm[t * 32 + i];
+            }
+            let mut y = [0.0f64; 64];
+            for (k, y) in y.iter_mut().enumerate() {
+                *y = (0..8)
+                    .map(|j| WINDOW_D[k + 64 * j] / 32.0 * state.history[k + 64 * j])
+                    .sum();
+            }
+            for (sb, row) in self.filter.iter().enumerate() {
+                let mut s: f64 = row.iter().zip(&y).map(|(m, y)| m * y).sum();
+                // Frequency inversion: odd samples of odd subbands
+                if sb % 2 == 1 && t % 2 == 1 {
+                    s = -s;
+                }
+                subbands[sb][t] = s;
+            }
+        }
+
+        let mut xr = [0.0f64; GRANULE];
+        for sb in 0..32 {
+            let mut block = [0.0f64; 36];
+            block[..18].copy_from_slice(&state.previous[sb]);
+            block[18..].copy_from_slice(&subbands[sb]);
+            for (k, row) in self.mdct.iter().enumerate() {
+                xr[18 * sb + k] = row.iter().zip(&block).map(|(m, z)| m * z).sum();
+            }
+        }
+        state.previous = subbands;
+
+        // Undo the decoder's alias-reduction butterflies.
+        for sb in 1..32 {
+            for (i, (cs, ca)) in alias_coefficients().into_iter().enumerate() {
+                let lower = 18 * sb - 1 - i;
+                let upper = 18 * sb + i;
+                let (l, u) = (xr[lower], xr[upper]);
+                xr[lower] = l * cs + u * ca;
+                xr[upper] = u * cs - l * ca;
+            }
+        }
+        xr
+    }
+}
+
+fn alias_coefficients() -> [(f64, f64); 8] {
+    const C: [f64; 8] = [-0.6, -0.535, -0.33, -0.185, -0.095, -0.041, -0.0142, -0.0037];
+    C.map(|c| {
+        let norm = (1.0 + c * c).sqrt();
+        (1.0 / norm, c / norm)
+    })
+}
+
+/// Find the smallest global gain whose quantized spectrum fits the budget.
+fn quantize(xr: &[f64; GRANULE], sfb: &[usize; 23], budget: usize) -> Granule {
+    let xr34: Vec<f64> = xr.iter().map(|x| x.abs().powf(0.75)).collect();
+    let negative: Vec<bool> = xr.iter().map(|&x| x < 0.0).collect();
+    let attempt = |gain: u32| -> Option<Granule> {
+        let step = 2f64.powf(-0.1875 * (gain as f64 - 210.0));
+        let mut ix = [0u32; GRANULE];
+        for (q, x) in ix.iter_mut().zip(&xr34) {
+            let value = x * step + 0.4054;
+            if value > MAX_QUANT as f64 {
+                return None;
+            }
+            *q = value as u32;
+        }
+        let mut granule = layout(ix, sfb);
+        granule.global_gain = gain;
+        for (n, (&neg, &q)) in granule.negative.iter_mut().zip(negative.iter().zip(&ix)) {
+            *n = neg && q != 0;
+        }
+        (granule.bits <= budget).then_some(granule)
+    };
+
+    let (mut low, mut high) = (0u32, 255u32);
+    while low < high {
+        let mid = (low + high) / 2;
+        if attempt(mid).is_some() {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    (low..=255)
+        .find_map(attempt)
+        .unwrap_or_else(|| layout([0; GRANULE], sfb))
+}
+
+/// Split a quantized spectrum into big_values, count1 and zero regions and
+/// pick the cheapest Huffman tables.
+fn layout(ix: [u32; GRANULE], sfb: &[usize; 23]) -> Granule {
+    let mut end = GRANULE;
+    while end >= 2 && ix[end - 1] == 0 && ix[end - 2] == 0 {
+        end -= 2;
+    }
+    let count1_end = end;
+    while end >= 4 && ix[end - 4..end].iter().all(|&q| q <= 1) {
+        end -= 4;
+    }
+    let big_end = end;
+
+    let mut bands = 0;
+    while bands < 22 && sfb[bands] < big_end {
+        bands += 1;
+    }
+    let (mut region0, mut region1) = SUBDIVISION[bands];
+    while region0 > 0 && sfb[region0 + 1] > big_end {
+        region0 -= 1;
+    }
+    while region1 > 0 && sfb[region0 + region1 + 2] > big_end {
+        region1 -= 1;
+    }
+    let region_ends = [
+        sfb[region0 + 1].min(big_end),
+        sfb[region0 + region1 + 2].min(big_end),
+        big_end,
+    ];
+
+    let mut bits = 0;
+    let mut table_select = [0; 3];
+    let mut start = 0;
+    for (region, &region_end) in region_ends.iter().enumerate() {
+        let (table, cost) = best_table(&ix[start..region_end]);
+        table_select[region] = table;
+        bits += cost;
+        start = region_end;
+    }
+    let quads = &ix[big_end..count1_end];
+    let (count1_table, cost) = (0..2)
+        .map(|t| (t, quad_bits(quads, t)))
+        .min_by_key(|&(_, cost)| cost)
+        .unwrap_or((0, 0));
+    bits += cost;
+
+    Granule {
+        ix,
+        negative: [false; GRANULE],
+        global_gain: 0,
+        big_values: big_end / 2,
+        count1_end,
+        table_select,
+        region0_count: region0,
+        region1_count: region1,
+        region_ends,
+        count1_table,
+        bits,
+    }
+}
+
+fn best_table(values: &[u32]) -> (usize, usize) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return (0, 0);
+    }
+    let mut candidates: Vec<usize> = SMALL_TABLES
+        .iter()
+        .filter(|&&(_, limit)| max <= limit)
+        .map(|&(table, _)| table)
+        .collect();
+    if max > 15 {
+        for range in [16..24, 24..32] {
+            if let Some(table) = range.into_iter().find(|&t| max < 15 + (1 << LINBITS[t])) {
+                candidates.push(table);
+            }
+        }
+    }
+    candidates
+        .into_iter()
+        .map(|table| (table, pair_bits(values, table)))
+        .min_by_key(|&(_, cost)| cost)
+        .expect("MAX_QUANT fits table 23 and 31")
+}
+
+fn pair_index(x: u32, y: u32, table: usize) -> (usize, u32) {
+    let linbits = LINBITS[table];
+    let (x, y) = if linbits > 0 { (x.min(15), y.min(15)) } else { (x, y) };
+    let wrap = PAIR_TABLES[table].as_ref().map_or(0, |t| t.wrap);
+    (x as usize * wrap + y as usize, linbits)
+}
+
+fn pair_bits(values: &[u32], table: usize) -> usize {
+    let Some(codes) = PAIR_TABLES[table].as_ref() else {
+        return 0;
+    };
+    values
+        .chunks(2)
+        .map(|pair| {
+            let (x, y) = (pair[0], pair[1]);
+            let (index, linbits) = pair_index(x, y, table);
+            let mut bits = codes.lens[index] as usize;
+            for v in [x, y] {
+                if v != 0 {
+                    bits += 1;
+                }
+                if linbits > 0 && v >= 15 {
+                    bits += linbits as usize;
+                }
+            }
+            bits
+        })
+        .sum()
+}
+
+fn quad_index(quad: &[u32]) -> usize {
+    quad.iter().fold(0, |acc, &q| acc << 1 | (q != 0) as usize)
+}
+
+fn quad_bits(values: &[u32], table: usize) -> usize {
+    values
+        .chunks(4)
+        .map(|quad| {
+            QUAD_LENS[table][quad_index(quad)] as usize + quad.iter().filter(|&&q| q != 0).count()
+        })
+        .sum()
+}
+
+fn write_granule(bits: &mut BitWriter, granule: &Granule) {
+    let mut start = 0;
+    for (region, &end) in granule.region_ends.iter().enumerate() {
+        let table = granule.table_select[region];
+        if let Some(codes) = PAIR_TABLES[table].as_ref() {
+            for i in (start..end).step_by(2) {
+                let (x, y) = (granule.ix[i], granule.ix[i + 1]);
+                let (index, linbits) = pair_index(x, y, table);
+                bits.put(codes.codes[index] as u32, codes.lens[index] as u32);
+                for (v, negative) in [(x, granule.negative[i]), (y, granule.negative[i + 1])] {
+                    if v == 0 {
+                        continue;
+                    }
+                    if linbits > 0 && v >= 15 {
+                        bits.put(v - 15, linbits);
+                    }
+                    bits.put(negative as u32, 1);
+                }
+            }
+        }
+        start = end;
+    }
+    for i in (start..granule.count1_end).step_by(4) {
+        let quad = &granule.ix[i..i + 4];
+        let index = quad_index(quad);
+        let table = granule.count1_table;
+        bits.put(QUAD_CODES[table][index] as u32, QUAD_LENS[table][index] as u32);
+        for (j, &q) in quad.iter().enumerate() {
+            if q != 0 {
+                bits.put(granule.negative[i + j] as u32, 1);
+            }
+        }
+    }
+}
+
+/// MSB-first bit writer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    len: u32,
+}
+
+impl BitWriter {
+    fn put(&mut self, value: u32, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (value as u64 & ((1u64 << bits) - 1));
+        self.len += bits;
+        while self.len >= 8 {
+            self.len -= 8;
+            self.bytes.push((self.acc >> self.len) as u8);
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push((self.acc << (8 - self.len)) as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    /// Decode with symphonia to (interleaved samples, channels, rate).
+    fn decode(bytes: Vec<u8>) -> (Vec<f32>, usize, u32) {
+        let stream = MediaSourceStream::new(Box::new(std::io::Cursor::new(bytes)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+        let probed = symphonia::default::get_probe()
+            .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+            .unwrap();
+        let mut format = probed.format;
+        let track = format.default_track().unwrap();
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .unwrap();
+        let mut samples = Vec::new();
+        let (mut channels, mut rate) = (0, 0);
+        while let Ok(packet) = format.next_packet() {
+            let decoded = decoder.decode(&packet).unwrap();
+            let spec = *decoded.spec();
+            channels = spec.channels.count();
+            rate = spec.rate;
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+            samples.extend_from_slice(buffer.samples());
+        }
+        (samples, channels, rate)
+    }
+
+    fn tone(freq: f32, rate: u32, seconds: f32, amplitude: f32) -> Vec<f32> {
+        (0..(rate as f32 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    /// Best signal-to-noise ratio in dB over small alignments of the decoded
+    /// audio against the input (the codec adds a fixed delay).
+    fn snr(input: &[f32], decoded: &[f32]) -> f32 {
+        (0..2000)
+            .map(|delay| {
+                let len = input.len().min(decoded.len().saturating_sub(delay));
+                let noise: Vec<f32> =
+                    (0..len).map(|i| decoded[i + delay] - input[i]).collect();
+                20.0 * (rms(&input[..len]) / rms(&noise).max(1e-9)).log10()
+            })
+            .fold(f32::MIN, f32::max)
+    }
+
+    #[test]
+    fn mono_24k_round_trips_through_a_decoder() {
+        let input = tone(440.0, 24000, 1.0, 0.5);
+        let bytes = encode_mp3(&input, 1, 24000, 64).unwrap();
+        assert_eq!(bytes.len() % 192, 0, "64 kbps at 24 kHz is 192 bytes a frame");
+        let (decoded, channels, rate) = decode(bytes);
+        assert_eq!((channels, rate), (1, 24000));
+        assert!(decoded.len() >= input.len());
+        let snr = snr(&input, &decoded);
+        assert!(snr > 20.0, "SNR {:.1} dB", snr);
+    }
+
+    #[test]
+    fn stereo_44k_keeps_channels_apart() {
+        let left = tone(300.0, 44100, 0.5, 0.4);
+        let right = tone(1000.0, 44100, 0.5, 0.2);
+        let input: Vec<f32> = left.iter().zip(&right).flat_map(|(l, r)| [*l, *r]).collect();
+        let (decoded, channels, rate) = decode(encode_mp3(&input, 2, 44100, 128).unwrap());
+        assert_eq!((channels, rate), (2, 44100));
+        let decoded_left: Vec<f32> = decoded.iter().step_by(2).copied().collect();
+        let decoded_right: Vec<f32> = decoded.iter().skip(1).step_by(2).copied().collect();
+        assert!(snr(&left, &decoded_left) > 20.0);
+        assert!(snr(&right, &decoded_right) > 20.0);
+    }
+
+    #[test]
+    fn loud_noise_stays_within_the_frame_budget() {
+        // White noise at full scale needs the escape tables and forces the
+        // rate loop well above the minimum gain.
+        let mut seed = 1u32;
+        let input: Vec<f32> = (0..48000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+            })
+            .collect();
+        let bytes = encode_mp3(&input, 1, 22050, 32).unwrap();
+        let (decoded, _, rate) = decode(bytes);
+        assert_eq!(rate, 22050);
+        assert!(rms(&decoded) > 0.1);
+    }
+
+    #[test]
+    fn unsupported_rates_are_resampled_and_silence_is_cheap() {
+        let bytes = encode_mp3(&vec![0.0; 8000], 1, 8000, 32).unwrap();
+        let (decoded, _, rate) = decode(bytes);
+        assert_eq!(rate, 16000);
+        assert!(decoded.iter().all(|s| s.abs() < 1e-4));
+        assert!(Mp3Encoder::new(8000, 1, 32).is_err());
+        assert!(Mp3Encoder::new(24000, 1, 320).is_err());
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let input = tone(220.0, 24000, 0.3, 0.3);
+        let mut encoder = Mp3Encoder::new(24000, 1, 48).unwrap();
+        let mut streamed = Vec::new();
+        for chunk in input.chunks(1000) {
+            streamed.extend(encoder.encode(chunk));
+        }
+        streamed.extend(encoder.finish());
+        assert_eq!(streamed, encode_mp3(&input, 1, 24000, 48).unwrap());
+    }
+}
@@ -0,0 +1,593 @@
+//! M4A/M4B writer: AAC-LC audio in an MP4 container with iTunes tags and
+//! chapters. Chapters are written both as a Nero `chpl` list and as a
+//! QuickTime text track, so Apple and non-Apple players find them.
+
+use super::aac::{self, AacEncoder, FRAME_SAMPLES};
+use super::tags::{is_jpeg, Chapter, Tags};
+use crate::KokoroError;
+
+/// Options for [`encode_m4a`].
+#[derive(Debug, Clone, Default)]
+pub struct M4aOptions {
+    /// Defaults to [`aac::default_bitrate`]
+    pub bitrate_kbps: Option<u32>,
+    /// Brand the file as an audiobook (`M4B `) rather than music (`M4A `)
+    pub audiobook: bool,
+    pub tags: Tags,
+    pub chapters: Vec<Chapter>,
+}
+
+/// Encode interleaved samples as AAC and wrap them in an MP4 file.
+/// `sample_rate` must be one AAC supports (8-48 kHz).
+pub fn encode_m4a(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    options: &M4aOptions,
+) -> Result<Vec<u8>, KokoroError> {
+    let bitrate = options.bitrate_kbps.unwrap_or_else(|| aac::default_bitrate(channels));
+    let mut encoder = AacEncoder::new(sample_rate, channels, bitrate)?;
+    let config = encoder.audio_specific_config();
+    let mut units = encoder.encode(samples);
+    units.extend(encoder.finish());
+
+    let frames = samples.len() / channels.max(1) as usize;
+    let duration_ms = frames as u64 * 1000 / sample_rate as u64;
+    let chapter_samples: Vec<Vec<u8>> = options.chapters.iter().map(chapter_sample).collect();
+
+    let ftyp = ftyp(options.audiobook);
+    // The moov box records the offset of the media data that follows it, so
+    // build it once to learn its size and again with the real offsets.
+    let layout = Layout {
+        sample_rate,
+        channels,
+        bitrate,
+        config,
+        units: &units,
+        duration_ms,
+        chapters: &options.chapters,
+        chapter_samples: &chapter_samples,
+    };
+    let probe = layout.moov(&options.tags, 0);
+    let audio_offset = (ftyp.len() + probe.len() + 8) as u32;
+    let moov = layout.moov(&options.tags, audio_offset);
+
+    let mut mdat = Vec::new();
+    for unit in units.iter().chain(&chapter_samples) {
+        mdat.extend_from_slice(unit);
+    }
+    let mut out = ftyp;
+    out.extend(moov);
+    out.extend(sized(b"mdat", &mdat));
+    Ok(out)
+}
+
+struct Layout<'a> {
+    sample_rate: u32,
+    channels: u16,
+    bitrate: u32,
+    config: [u8; 2],
+    units: &'a [Vec<u8>],
+    duration_ms: u64,
+    chapters: &'a [Chapter],
+    chapter_samples: &'a [Vec<u8>],
+}
+
+impl Layout<'_> {
+    fn moov(&self, tags: &Tags, audio_offset: u32) -> Vec<u8> {
+        let has_chapters = !self.chapters.is_empty();
+        let mut body = mvhd(self.duration_ms, if has_chapters { 3 } else { 2 });
+        body.extend(self.audio_trak(audio_offset, has_chapters));
+        if has_chapters {
+            let audio_len: usize = self.units.iter().map(Vec::len).sum();
+            body.extend(self.chapter_trak(audio_offset + audio_len as u32));
+        }
+        body.extend(udta(tags, self.chapters));
+        sized(b"moov", &body)
+    }
+
+    fn audio_trak(&self, offset: u32, has_chapters: bool) -> Vec<u8> {
+        let media_duration = (self.units.len() * FRAME_SAMPLES) as u32;
+        let mut trak = tkhd(1, self.duration_ms as u32, true);
+        // Skip the encoder's priming frame
+        let mut elst = full(0, 0);
+        elst.extend(1u32.to_be_bytes());
+        elst.extend((self.duration_ms as u32).to_be_bytes());
+        elst.extend((FRAME_SAMPLES as u32).to_be_bytes());
+        elst.extend(0x0001_0000u32.to_be_bytes());
+        trak.extend(sized(b"edts", &sized(b"elst", &elst)));
+        if has_chapters {
+            trak.extend(sized(b"tref", &sized(b"chap", &2u32.to_be_bytes())));
+        }
+
+        let sizes: Vec<u32> = self.units.iter().map(|u| u.len() as u32).collect();
+        let mut stbl = stsd(&self.mp4a());
+        stbl.extend(stts(&[(sizes.len() as u32, FRAME_SAMPLES as u32)]));
+        stbl.extend(stsc(sizes.len() as u32));
+        stbl.extend(stsz(&sizes));
+        stbl.extend(stco(offset));
+
+        let mut smhd = full(0, 0);
+        smhd.extend([0; 4]);
+        let mut minf = sized(b"smhd", &smhd);
+        minf.extend(dinf());
+        minf.extend(sized(b"stbl", &stbl));
+
+        let mut mdia = mdhd(self.sample_rate, media_duration);
+        mdia.extend(hdlr(b"soun", "SoundHandler"));
+        mdia.extend(sized(b"minf", &minf));
+        trak.extend(sized(b"mdia", &mdia));
+        sized(b"trak", &trak)
+    }
+
+    fn mp4a(&self) -> Vec<u8> {
+        let max_unit = self.units.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        let mut decoder_config = vec![0x40, 0x15];
+        decoder_config.extend(&max_unit.to_be_bytes()[1..]);
+        decoder_config.extend((self.bitrate * 1000).to_be_bytes());
+        decoder_config.extend((self.bitrate * 1000).to_be_bytes());
+        decoder_config.extend(descriptor(0x05, &self.config));
+        let mut es = vec![0, 1, 0]; // ES_ID, flags
+        es.extend(descriptor(0x04, &decoder_config));
+        es.extend(descriptor(0x06, &[0x02]));
+        let mut esds = full(0, 0);
+        esds.extend(descriptor(0x03, &es));
+
+        let mut entry = sample_entry_header();
+        entry.extend([0; 8]);
+        entry.extend(self.channels.to_be_bytes());
+        entry.extend(16u16.to_be_bytes());
+        entry.extend([0; 4]);
+        entry.extend((self.sample_rate << 16).to_be_bytes());
+        entry.extend(sized(b"esds", &esds));
+        sized(b"mp4a", &entry)
+    }
+
+    fn chapter_trak(&self, offset: u32) -> Vec<u8> {
+        let mut trak = tkhd(2, self.duration_ms as u32, false);
+        let durations: Vec<(u32, u32)> = self
+            .chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                let end = self
+                    .chapters
+                    .get(i + 1)
+                    .map_or(self.duration_ms.max(chapter.end_ms), |next| next.start_ms);
+                let start = if i == 0 { 0 } else { chapter.start_ms };
+                (1, end.saturating_sub(start) as u32)
+            })
+            .collect();
+        let sizes: Vec<u32> = self.chapter_samples.iter().map(|s| s.len() as u32).collect();
+
+        let mut stbl = stsd(&text_sample_entry());
+        stbl.extend(stts(&durations));
+        stbl.extend(stsc(sizes.len() as u32));
+        stbl.extend(stsz(&sizes));
+        stbl.extend(stco(offset));
+
+        let mut minf = gmhd();
+        minf.extend(dinf());
+        minf.extend(sized(b"stbl", &stbl));
+
+        let mut mdia = mdhd(1000, self.duration_ms as u32);
+        mdia.extend(hdlr(b"text", "ChapterHandler"));
+        mdia.extend(sized(b"minf", &minf));
+        trak.extend(sized(b"mdia", &mdia));
+        sized(b"trak", &trak)
+    }
+}
+
+fn ftyp(audiobook: bool) -> Vec<u8> {
+    let brand = if audiobook { b"M4B " } else { b"M4A " };
+    let mut body = brand.to_vec();
+    body.extend(0u32.to_be_bytes());
+    for compatible in [brand, b"mp42", b"isom"] {
+        body.extend(compatible);
+    }
+    sized(b"ftyp", &body)
+}
+
+fn mvhd(duration_ms: u64, next_track: u32) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend([0; 8]); // creation, modification
+    body.extend(1000u32.to_be_bytes());
+    body.extend((duration_ms as u32).to_be_bytes());
+    body.extend(0x0001_0000u32.to_be_bytes()); // rate
+    body.extend(0x0100u16.to_be_bytes()); // volume
+    body.extend([0; 10]);
+    body.extend(matrix());
+    body.extend([0; 24]);
+    body.extend(next_track.to_be_bytes());
+    sized(b"mvhd", &body)
+}
+
+fn tkhd(track_id: u32, duration_ms: u32, audio: bool) -> Vec<u8> {
+    // Chapter tracks are referenced, not played
+    let mut body = full(0, if audio { 7 } else { 0 });
+    body.extend([0; 8]);
+    body.extend(track_id.to_be_bytes());
+    body.extend([0; 4]);
+    body.extend(duration_ms.to_be_bytes());
+    body.extend([0; 8]);
+    body.extend([0; 4]); // layer, alternate group
+    body.extend(if audio { 0x0100u16 } else { 0 }.to_be_bytes());
+    body.extend([0; 2]);
+    body.extend(matrix());
+    body.extend([0; 8]); // width, height
+    sized(b"tkhd", &body)
+}
+
+fn mdhd(timescale: u32, duration: u32) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend([0; 8]);
+    body.extend(timescale.to_be_bytes());
+    body.extend(duration.to_be_bytes());
+    body.extend(0x55c4u16.to_be_bytes()); // "und"
+    body.extend([0; 2]);
+    sized(b"mdhd", &body)
+}
+
+fn hdlr(handler: &[u8; 4], name: &str) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend([0; 4]);
+    body.extend(handler);
+    body.extend([0; 12]);
+    body.extend(name.as_bytes());
+    body.push(0);
+    sized(b"hdlr", &body)
+}
+
+fn dinf() -> Vec<u8> {
+    let mut dref = full(0, 0);
+    dref.extend(1u32.to_be_bytes());
+    dref.extend(sized(b"url ", &full(0, 1)));
+    sized(b"dinf", &sized(b"dref", &dref))
+}
+
+/// QuickTime base media header for the text track.
+fn gmhd() -> Vec<u8> {
+    let mut gmin = full(0, 0);
+    gmin.extend(0x0040u16.to_be_bytes()); // graphics mode: copy
+    gmin.extend([0x80, 0x00, 0x80, 0x00, 0x80, 0x00]); // op colour
+    gmin.extend([0; 4]); // balance, reserved
+    let mut text = 1u16.to_be_bytes().to_vec();
+    for word in [0u32, 0, 0, 1, 0, 0, 0, 0x0000_4000] {
+        text.extend(word.to_be_bytes());
+    }
+    text.extend([0; 2]);
+    let mut body = sized(b"gmin", &gmin);
+    body.extend(sized(b"text", &text));
+    sized(b"gmhd", &body)
+}
+
+fn stsd(entry: &[u8]) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend(1u32.to_be_bytes());
+    body.extend(entry);
+    sized(b"stsd", &body)
+}
+
+fn stts(runs: &[(u32, u32)]) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend((runs.len() as u32).to_be_bytes());
+    for (count, delta) in runs {
+        body.extend(count.to_be_bytes());
+        body.extend(delta.to_be_bytes());
+    }
+    sized(b"stts", &body)
+}
+
+/// Everything in one chunk.
+fn stsc(samples: u32) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend(1u32.to_be_bytes());
+    for value in [1, samples, 1] {
+        body.extend(value.to_be_bytes());
+    }
+    sized(b"stsc", &body)
+}
+
+fn stsz(sizes: &[u32]) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend(0u32.to_be_bytes());
+    body.extend((sizes.len() as u32).to_be_bytes());
+    for size in sizes {
+        body.extend(size.to_be_bytes());
+    }
+    sized(b"stsz", &body)
+}
+
+fn stco(offset: u32) -> Vec<u8> {
+    let mut body = full(0, 0);
+    body.extend(1u32.to_be_bytes());
+    body.extend(offset.to_be_bytes());
+    sized(b"stco", &body)
+}
+
+fn sample_entry_header() -> Vec<u8> {
+    let mut entry = vec![0; 6];
+    entry.extend(1u16.to_be_bytes()); // data reference index
+    entry
+}
+
+/// Timed-text sample description with a default style and one font.
+fn text_sample_entry() -> Vec<u8> {
+    let mut entry = sample_entry_header();
+    entry.extend(1u32.to_be_bytes()); // display flags
+    entry.extend([0; 2]); // justification
+    entry.extend([0; 4]); // background colour
+    entry.extend([0; 8]); // default text box
+    entry.extend([0; 4]); // style: start, end char
+    entry.extend(1u16.to_be_bytes()); // font id
+    entry.extend([0; 2]); // face, size
+    entry.extend([0; 4]); // text colour
+    let mut ftab = 1u16.to_be_bytes().to_vec();
+    ftab.extend(1u16.to_be_bytes());
+    ftab.push(0);
+    entry.extend(sized(b"ftab", &ftab));
+    sized(b"text", &entry)
+}
+
+/// A chapter title as a text sample: length-prefixed UTF-8 and an
+/// encoding marker.
+fn chapter_sample(chapter: &Chapter) -> Vec<u8> {
+    let title = truncate(&chapter.title, u16::MAX as usize);
+    let mut sample = (title.len() as u16).to_be_bytes().to_vec();
+    sample.extend(title.as_bytes());
+    sample.extend(sized(b"encd", &0x0000_0100u32.to_be_bytes()));
+    sample
+}
+
+fn udta(tags: &Tags, chapters: &[Chapter]) -> Vec<u8> {
+    let mut body = Vec::new();
+    if !chapters.is_empty() {
+        let mut chpl = full(1, 0);
+        chpl.extend([0; 4]);
+        chpl.push(chapters.len().min(255) as u8);
+        for chapter in chapters.iter().take(255) {
+            // 100 ns units
+            chpl.extend((chapter.start_ms * 10_000).to_be_bytes());
+            let title = truncate(&chapter.title, 255);
+            chpl.push(title.len() as u8);
+            chpl.extend(title.as_bytes());
+        }
+        body.extend(sized(b"chpl", &chpl));
+    }
+
+    let mut ilst = Vec::new();
+    for (key, value) in [
+        (b"\xa9nam", &tags.title),
+        (b"\xa9ART", &tags.artist),
+        (b"\xa9alb", &tags.album),
+        (b"\xa9gen", &tags.genre),
+    ] {
+        if let Some(value) = value {
+            let mut data = 1u32.to_be_bytes().to_vec(); // UTF-8
+            data.extend([0; 4]);
+            data.extend(value.as_bytes());
+            ilst.extend(sized(key, &sized(b"data", &data)));
+        }
+    }
+    if let Some(cover) = &tags.cover {
+        let kind: u32 = if is_jpeg(cover) { 13 } else { 14 };
+        let mut data = kind.to_be_bytes().to_vec();
+        data.extend([0; 4]);
+        data.extend(cover);
+        ilst.extend(sized(b"covr", &sized(b"data", &data)));
+    }
+    if !ilst.is_empty() {
+        let mut meta = full(0, 0);
+        let mut handler = full(0, 0);
+        handler.extend([0; 4]);
+        handler.extend(b"mdirappl");
+        handler.extend([0; 9]);
+        meta.extend(sized(b"hdlr", &handler));
+        meta.extend(sized(b"ilst", &ilst));
+        body.extend(sized(b"meta", &meta));
+    }
+    sized(b"udta", &body)
+}
+
+fn matrix() -> Vec<u8> {
+    [0x0001_0000u32, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect()
+}
+
+fn full(version: u8, flags: u32) -> Vec<u8> {
+    let mut header = vec![version];
+    header.extend(&flags.to_be_bytes()[1..]);
+    header
+}
+
+fn sized(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    out.extend(kind);
+    out.extend(body);
+    out
+}
+
+/// An MPEG-4 descriptor with a four-byte length.
+fn descriptor(tag: u8, body: &[u8]) -> Vec<u8> {
+    let len = body.len() as u32;
+    let mut out = vec![
+        tag,
+        0x80 | (len >> 21 & 0x7f) as u8,
+        0x80 | (len >> 14 & 0x7f) as u8,
+        0x80 | (len >> 7 & 0x7f) as u8,
+        (len & 0x7f) as u8,
+    ];
+    out.extend(body);
+    out
+}
+
+/// Cut to at most `max` bytes on a character boundary.
+fn truncate(text: &str, max: usize) -> &str {
+    let mut end = text.len().min(max);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::{MetadataOptions, StandardTagKey};
+    use symphonia::core::probe::Hint;
+
+    struct Decoded {
+        samples: Vec<f32>,
+        channels: usize,
+        rate: u32,
+        title: Option<String>,
+    }
+
+    fn decode(bytes: Vec<u8>) -> Decoded {
+        let stream = MediaSourceStream::new(Box::new(std::io::Cursor::new(bytes)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("m4a");
+        let probed = symphonia::default::get_probe()
+            .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+            .unwrap();
+        let mut format = probed.format;
+        let title = format.metadata().current().and_then(|revision| {
+            revision
+                .tags()
+                .iter()
+                .find(|tag| tag.std_key == Some(StandardTagKey::TrackTitle))
+                .map(|tag| tag.value.to_string())
+        });
+        let track = format.default_track().unwrap();
+        let track_id = track.id;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .unwrap();
+        let mut decoded = Decoded {
+            samples: Vec::new(),
+            channels: 0,
+            rate: 0,
+            title,
+        };
+        while let Ok(packet) = format.next_packet() {
+            if packet.track_id() != track_id {
+                continue;
+            }
+            let audio = decoder.decode(&packet).unwrap();
+            let spec = *audio.spec();
+            decoded.channels = spec.channels.count();
+            decoded.rate = spec.rate;
+            let mut buffer = SampleBuffer::<f32>::new(audio.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(audio);
+            decoded.samples.extend_from_slice(buffer.samples());
+        }
+        decoded
+    }
+
+    fn tone(freq: f32, rate: u32, seconds: f32, amplitude: f32) -> Vec<f32> {
+        (0..(rate as f32 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    /// SNR in dB once the priming frame is skipped.
+    fn snr(input: &[f32], decoded: &[f32], delay: usize) -> f32 {
+        let len = input.len().min(decoded.len().saturating_sub(delay));
+        let noise: Vec<f32> = (0..len).map(|i| decoded[i + delay] - input[i]).collect();
+        20.0 * (rms(&input[..len]) / rms(&noise).max(1e-9)).log10()
+    }
+
+    #[test]
+    fn mono_aac_round_trips_through_a_decoder() {
+        let input = tone(440.0, 24000, 1.0, 0.5);
+        let options = M4aOptions {
+            tags: Tags {
+                title: Some("The Brave Fox".to_string()),
+                cover: Some(b"\xff\xd8\xff\xe0cover".to_vec()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let bytes = encode_m4a(&input, 1, 24000, &options).unwrap();
+        let covr = bytes.windows(4).position(|w| w == b"covr").unwrap();
+        // A JPEG `data` atom
+        assert_eq!(&bytes[covr + 8..covr + 16], b"data\0\0\0\x0d");
+        let decoded = decode(bytes);
+        assert_eq!((decoded.channels, decoded.rate), (1, 24000));
+        assert_eq!(decoded.title.as_deref(), Some("The Brave Fox"));
+        let snr = snr(&input, &decoded.samples, FRAME_SAMPLES);
+        assert!(snr > 20.0, "SNR {:.1} dB", snr);
+    }
+
+    #[test]
+    fn stereo_aac_keeps_channels_apart() {
+        let left = tone(300.0, 44100, 0.5, 0.4);
+        let right = tone(2000.0, 44100, 0.5, 0.2);
+        let input: Vec<f32> = left.iter().zip(&right).flat_map(|(l, r)| [*l, *r]).collect();
+        let decoded = decode(encode_m4a(&input, 2, 44100, &M4aOptions::default()).unwrap());
+        assert_eq!((decoded.channels, decoded.rate), (2, 44100));
+        let decoded_left: Vec<f32> = decoded.samples.iter().step_by(2).copied().collect();
+        let decoded_right: Vec<f32> = decoded.samples.iter().skip(1).step_by(2).copied().collect();
+        assert!(snr(&left, &decoded_left, FRAME_SAMPLES) > 20.0);
+        assert!(snr(&right, &decoded_right, FRAME_SAMPLES) > 20.0);
+    }
+
+    #[test]
+    fn audiobook_with_chapters_still_decodes() {
+        let input = tone(220.0, 24000, 2.0, 0.3);
+        let options = M4aOptions {
+            audiobook: true,
+            chapters: vec![
+                Chapter {
+                    title: "Part 1".to_string(),
+                    start_ms: 0,
+                    end_ms: 1000,
+                },
+                Chapter {
+                    title: "Part 2 — the end".to_string(),
+                    start_ms: 1000,
+                    end_ms: 2000,
+                },
+            ],
+            ..Default::default()
+        };
+        let bytes = encode_m4a(&input, 1, 24000, &options).unwrap();
+        assert_eq!(&bytes[4..12], b"ftypM4B ");
+        let chpl = bytes.windows(4).position(|w| w == b"chpl").unwrap();
+        assert_eq!(bytes[chpl + 12], 2);
+        let decoded = decode(bytes);
+        assert!(decoded.samples.len() >= input.len());
+        assert!(snr(&input, &decoded.samples, FRAME_SAMPLES) > 20.0);
+    }
+
+    #[test]
+    fn loud_noise_fits_the_bitrate() {
+        let mut seed = 7u32;
+        let input: Vec<f32> = (0..24000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+            })
+            .collect();
+        let bytes = encode_m4a(&input, 1, 24000, &M4aOptions::default()).unwrap();
+        // 64 kbps for one second, plus container overhead
+        assert!(bytes.len() < 8000 + 2000, "{} bytes", bytes.len());
+        assert!(rms(&decode(bytes).samples) > 0.1);
+    }
+
+    #[test]
+    fn descriptors_use_four_byte_lengths() {
+        assert_eq!(descriptor(0x05, &[0x13, 0x08]), vec![0x05, 0x80, 0x80, 0x80, 0x02, 0x13, 0x08]);
+        assert_eq!(truncate("ab—c", 3), "ab");
+    }
+}
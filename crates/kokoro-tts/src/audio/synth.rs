@@ -0,0 +1,499 @@
+//! Procedural theme music: short melodies over chord progressions played by
+//! soft synthesized instruments (sine pads, an FM music box, plucked strings).
+//!
+//! Everything is derived from a seed, so the same seed and style always
+//! render the same tune. The output is a seamless loop of sixteen bars.
+
+use std::f32::consts::PI;
+
+/// Bars in a rendered theme: four 4-bar phrases in an A A' B A form.
+const BARS: usize = 16;
+const BARS_PER_PHRASE: usize = 4;
+
+/// Overall musical character, chosen per genre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicStyle {
+    /// Slow 3/4 music box over pads
+    Lullaby,
+    /// Bouncy plucked strings with bass
+    Adventure,
+    /// Minor-key music box over pads
+    Fantasy,
+    /// Gentle plucked melody over pads
+    Gentle,
+    /// Quick music box over staccato plucks
+    Playful,
+    /// Lydian bells over pads with bass
+    Curious,
+}
+
+impl MusicStyle {
+    /// Style for a genre ID from the genres table.
+    pub fn for_genre(genre_id: &str) -> Self {
+        match genre_id {
+            "bedtime" => Self::Lullaby,
+            "adventure" => Self::Adventure,
+            "fantasy" => Self::Fantasy,
+            "animal" => Self::Playful,
+            "science" => Self::Curious,
+            _ => Self::Gentle,
+        }
+    }
+
+    fn params(&self) -> StyleParams {
+        const MAJOR: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+        const MINOR: [i32; 7] = [0, 2, 3, 5, 7, 8, 10];
+        const LYDIAN: [i32; 7] = [0, 2, 4, 6, 7, 9, 11];
+
+        match self {
+            Self::Lullaby => StyleParams {
+                tempo: 66.0,
+                beats_per_bar: 3,
+                scale: MAJOR,
+                progressions: &[[0, 5, 3, 4], [0, 3, 0, 4], [0, 3, 5, 4]],
+                melody: Instrument::MusicBox,
+                chords: ChordStyle::Sustained(Instrument::Pad),
+                bass: false,
+                steps_per_beat: 1,
+                rest_chance: 0.25,
+            },
+            Self::Adventure => StyleParams {
+                tempo: 112.0,
+                beats_per_bar: 4,
+                scale: MAJOR,
+                progressions: &[[0, 4, 5, 3], [0, 3, 4, 3], [5, 3, 0, 4]],
+                melody: Instrument::Pluck,
+                chords: ChordStyle::Staccato(Instrument::Pluck),
+                bass: true,
+                steps_per_beat: 2,
+                rest_chance: 0.15,
+            },
+            Self::Fantasy => StyleParams {
+                tempo: 80.0,
+                beats_per_bar: 4,
+                scale: MINOR,
+                progressions: &[[0, 5, 2, 6], [0, 3, 5, 4], [0, 6, 5, 6]],
+                melody: Instrument::MusicBox,
+                chords: ChordStyle::Sustained(Instrument::Pad),
+                bass: false,
+                steps_per_beat: 1,
+                rest_chance: 0.2,
+            },
+            Self::Gentle => StyleParams {
+                tempo: 76.0,
+                beats_per_bar: 4,
+                scale: MAJOR,
+                progressions: &[[0, 3, 5, 4], [0, 5, 3, 4], [3, 4, 0, 0]],
+                melody: Instrument::Pluck,
+                chords: ChordStyle::Sustained(Instrument::Pad),
+                bass: false,
+                steps_per_beat: 1,
+                rest_chance: 0.2,
+            },
+            Self::Playful => StyleParams {
+                tempo: 120.0,
+                beats_per_bar: 4,
+                scale: MAJOR,
+                progressions: &[[0, 3, 4, 0], [0, 5, 3, 4], [0, 4, 0, 4]],
+                melody: Instrument::MusicBox,
+                chords: ChordStyle::Staccato(Instrument::Pluck),
+                bass: true,
+                steps_per_beat: 2,
+                rest_chance: 0.3,
+            },
+            Self::Curious => StyleParams {
+                tempo: 96.0,
+                beats_per_bar: 4,
+                scale: LYDIAN,
+                progressions: &[[0, 1, 0, 1], [0, 1, 5, 4], [0, 4, 1, 0]],
+                melody: Instrument::MusicBox,
+                chords: ChordStyle::Sustained(Instrument::Pad),
+                bass: true,
+                steps_per_beat: 2,
+                rest_chance: 0.25,
+            },
+        }
+    }
+}
+
+struct StyleParams {
+    tempo: f32,
+    beats_per_bar: usize,
+    scale: [i32; 7],
+    /// Scale degrees (0 = tonic), one chord per bar
+    progressions: &'static [[i32; 4]],
+    melody: Instrument,
+    chords: ChordStyle,
+    bass: bool,
+    steps_per_beat: usize,
+    rest_chance: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instrument {
+    Pad,
+    MusicBox,
+    Pluck,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ChordStyle {
+    /// Whole-bar pad chord
+    Sustained(Instrument),
+    /// Short chord on every beat
+    Staccato(Instrument),
+}
+
+/// One scheduled note.
+#[derive(Debug, Clone, Copy)]
+struct Note {
+    start: f32,
+    duration: f32,
+    midi: i32,
+    velocity: f32,
+    instrument: Instrument,
+}
+
+/// Render a theme for `style` from `seed` as a seamless 16-bar loop.
+pub fn render_theme(style: MusicStyle, seed: u64, sample_rate: u32) -> Vec<f32> {
+    let params = style.params();
+    let mut rng = Rng::new(seed);
+    let notes = compose(&params, &mut rng);
+
+    let beat_secs = 60.0 / params.tempo;
+    let loop_secs = (BARS * params.beats_per_bar) as f32 * beat_secs;
+    let loop_len = (loop_secs * sample_rate as f32) as usize;
+    // Room for the last notes to ring out before folding them back in
+    let mut buffer = vec![0.0f32; loop_len + 3 * sample_rate as usize];
+
+    for note in &notes {
+        render_note(&mut buffer, note, sample_rate, &mut rng);
+    }
+
+    // Fold the tail onto the start so the loop point is seamless
+    let (head, tail) = buffer.split_at_mut(loop_len);
+    for (h, t) in head.iter_mut().zip(tail.iter()) {
+        *h += t;
+    }
+    buffer.truncate(loop_len);
+
+    let peak = buffer.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        let gain = 0.8 / peak;
+        buffer.iter_mut().for_each(|s| *s *= gain);
+    }
+    buffer
+}
+
+/// Pick a key and progression, then write chords, bass and an A A' B A melody.
+fn compose(params: &StyleParams, rng: &mut Rng) -> Vec<Note> {
+    // Keys from C to A keep the melody in a comfortable register
+    let root = 60 + [0, 2, 5, 7, 9][rng.below(5)];
+    let progression = params.progressions[rng.below(params.progressions.len())];
+    let beat = 60.0 / params.tempo;
+    let bar = beat * params.beats_per_bar as f32;
+    let to_midi = |degree: i32| {
+        root + 12 * degree.div_euclid(7) + params.scale[degree.rem_euclid(7) as usize]
+    };
+
+    let mut notes = Vec::new();
+
+    for bar_index in 0..BARS {
+        let start = bar_index as f32 * bar;
+        let chord_root = progression[bar_index % BARS_PER_PHRASE];
+        let chord: Vec<i32> = [0, 2, 4].iter().map(|i| to_midi(chord_root + i) - 12).collect();
+
+        match params.chords {
+            ChordStyle::Sustained(instrument) => {
+                for &midi in &chord {
+                    notes.push(Note {
+                        start,
+                        duration: bar,
+                        midi,
+                        velocity: 0.3,
+                        instrument,
+                    });
+                }
+            }
+            ChordStyle::Staccato(instrument) => {
+                // Off-beat chords bounce against the bass
+                for b in 0..params.beats_per_bar {
+                    let offset = if params.bass { 0.5 * beat } else { 0.0 };
+                    for &midi in &chord {
+                        notes.push(Note {
+                            start: start + b as f32 * beat + offset,
+                            duration: 0.4 * beat,
+                            midi,
+                            velocity: 0.22,
+                            instrument,
+                        });
+                    }
+                }
+            }
+        }
+
+        if params.bass {
+            for b in (0..params.beats_per_bar).step_by(2) {
+                notes.push(Note {
+                    start: start + b as f32 * beat,
+                    duration: beat,
+                    midi: to_midi(chord_root) - 24,
+                    velocity: 0.5,
+                    instrument: Instrument::Pluck,
+                });
+            }
+        }
+    }
+
+    let phrase_a = compose_phrase(params, &progression, rng);
+    let mut phrase_a2 = phrase_a.clone();
+    let steps_per_bar = params.beats_per_bar * params.steps_per_beat;
+    let varied = compose_phrase(params, &progression, rng);
+    let last_bar = (BARS_PER_PHRASE - 1) * steps_per_bar;
+    phrase_a2[last_bar..].copy_from_slice(&varied[last_bar..]);
+    let phrase_b = compose_phrase(params, &progression, rng);
+
+    let step = beat / params.steps_per_beat as f32;
+    let phrase_secs = bar * BARS_PER_PHRASE as f32;
+    for (p, phrase) in [&phrase_a, &phrase_a2, &phrase_b, &phrase_a].iter().enumerate() {
+        let phrase_start = p as f32 * phrase_secs;
+        let mut i = 0;
+        while i < phrase.len() {
+            let Some(degree) = phrase[i] else {
+                i += 1;
+                continue;
+            };
+            // Consecutive identical steps are one held note
+            let mut len = 1;
+            while i + len < phrase.len() && phrase[i + len] == Some(degree) {
+                len += 1;
+            }
+            notes.push(Note {
+                start: phrase_start + i as f32 * step,
+                duration: len as f32 * step,
+                midi: to_midi(degree),
+                velocity: if i % steps_per_bar == 0 { 0.7 } else { 0.55 },
+                instrument: params.melody,
+            });
+            i += len;
+        }
+    }
+
+    notes
+}
+
+/// Melody for one phrase as scale degrees per step (`None` = rest).
+/// Strong beats snap to chord tones; the phrase ends on the tonic.
+fn compose_phrase(params: &StyleParams, progression: &[i32; 4], rng: &mut Rng) -> Vec<Option<i32>> {
+    let steps_per_bar = params.beats_per_bar * params.steps_per_beat;
+    let total = steps_per_bar * BARS_PER_PHRASE;
+    let (lowest, highest) = (0, 11);
+
+    let mut melody = Vec::with_capacity(total);
+    let mut degree = 2 + rng.below(3) as i32;
+    let mut i = 0;
+    while i < total {
+        let bar = i / steps_per_bar;
+        let on_beat = i % params.steps_per_beat == 0;
+        let bar_start = i % steps_per_bar == 0;
+
+        if i + steps_per_bar >= total && bar_start {
+            // Final bar: resolve to the tonic and hold
+            let tonic = if degree >= 4 { 7 } else { 0 };
+            melody.extend(std::iter::repeat_n(Some(tonic), total - i));
+            break;
+        }
+
+        if !bar_start && rng.chance(params.rest_chance) {
+            melody.push(None);
+            i += 1;
+            continue;
+        }
+
+        if bar_start || (on_beat && rng.chance(0.5)) {
+            let chord_root = progression[bar];
+            degree = nearest_chord_tone(degree, chord_root);
+        } else {
+            const STEPS: [i32; 7] = [-2, -1, -1, 0, 1, 1, 2];
+            degree += STEPS[rng.below(STEPS.len())];
+        }
+        degree = degree.clamp(lowest, highest);
+
+        let hold = if on_beat && rng.chance(0.3) { 2 } else { 1 };
+        for _ in 0..hold.min(total - i) {
+            melody.push(Some(degree));
+            i += 1;
+        }
+    }
+    melody
+}
+
+/// Closest degree to `degree` that belongs to the triad on `chord_root`.
+fn nearest_chord_tone(degree: i32, chord_root: i32) -> i32 {
+    (degree - 3..=degree + 3)
+        .filter(|d| [0, 2, 4].contains(&(d - chord_root).rem_euclid(7)))
+        .min_by_key(|d| (d - degree).abs())
+        .unwrap_or(degree)
+}
+
+fn render_note(buffer: &mut [f32], note: &Note, sample_rate: u32, rng: &mut Rng) {
+    let sr = sample_rate as f32;
+    let freq = 440.0 * 2f32.powf((note.midi - 69) as f32 / 12.0);
+    let start = (note.start * sr) as usize;
+    if start >= buffer.len() {
+        return;
+    }
+    let available = buffer.len() - start;
+
+    match note.instrument {
+        Instrument::Pad => {
+            // Two slightly detuned sines with a soft attack and release
+            let attack = 0.4 * sr;
+            let release = 0.8 * sr;
+            let held = note.duration * sr;
+            let len = ((held + release) as usize).min(available);
+            let detune = 2f32.powf(4.0 / 1200.0);
+            for (n, out) in buffer[start..start + len].iter_mut().enumerate() {
+                let t = n as f32 / sr;
+                let n = n as f32;
+                let env = if n < attack {
+                    n / attack
+                } else if n < held {
+                    1.0
+                } else {
+                    1.0 - (n - held) / release
+                };
+                let tone = (2.0 * PI * freq * t).sin() + (2.0 * PI * freq * detune * t).sin()
+                    + 0.2 * (4.0 * PI * freq * t).sin();
+                *out += 0.08 * note.velocity * env * tone;
+            }
+        }
+        Instrument::MusicBox => {
+            // FM bell: inharmonic modulator whose depth decays faster than the tone
+            let len = ((1.8 * sr) as usize).min(available);
+            for (n, out) in buffer[start..start + len].iter_mut().enumerate() {
+                let t = n as f32 / sr;
+                let index = 2.5 * (-t * 6.0).exp();
+                let modulator = (2.0 * PI * freq * 3.5 * t).sin();
+                let env = (-t * 2.5).exp() * (n as f32 / 40.0).min(1.0);
+                *out += 0.25 * note.velocity * env * (2.0 * PI * freq * t + index * modulator).sin();
+            }
+        }
+        Instrument::Pluck => {
+            // Karplus-Strong: a noise burst recirculating through an averaging delay
+            let period = ((sr / freq) as usize).max(2);
+            let mut line: Vec<f32> = (0..period).map(|_| rng.signed()).collect();
+            let held = (note.duration * sr) as usize;
+            let len = (held + (0.3 * sr) as usize).min(available);
+            let mut pos = 0;
+            for (n, out) in buffer[start..start + len].iter_mut().enumerate() {
+                let next = (pos + 1) % period;
+                let sample = line[pos];
+                // Damp faster once the note is released
+                let decay = if n < held { 0.996 } else { 0.97 };
+                line[pos] = decay * 0.5 * (line[pos] + line[next]);
+                pos = next;
+                *out += 0.3 * note.velocity * sample;
+            }
+        }
+    }
+}
+
+/// SplitMix64: tiny, fast, and stable across platforms and releases.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub(crate) fn unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `[-1, 1)`.
+    pub(crate) fn signed(&mut self) -> f32 {
+        self.unit() * 2.0 - 1.0
+    }
+
+    pub(crate) fn chance(&mut self, p: f32) -> bool {
+        self.unit() < p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: u32 = 24000;
+
+    #[test]
+    fn same_seed_same_theme() {
+        let a = render_theme(MusicStyle::Lullaby, 42, SR);
+        let b = render_theme(MusicStyle::Lullaby, 42, SR);
+        assert_eq!(a, b);
+        assert_ne!(a, render_theme(MusicStyle::Lullaby, 43, SR));
+    }
+
+    #[test]
+    fn theme_is_sixteen_bars_and_in_range() {
+        for style in [
+            MusicStyle::Lullaby,
+            MusicStyle::Adventure,
+            MusicStyle::Fantasy,
+            MusicStyle::Gentle,
+            MusicStyle::Playful,
+            MusicStyle::Curious,
+        ] {
+            let params = style.params();
+            let secs = (BARS * params.beats_per_bar) as f32 * 60.0 / params.tempo;
+            let theme = render_theme(style, 7, SR);
+            assert_eq!(theme.len(), (secs * SR as f32) as usize, "{:?}", style);
+            let peak = theme.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!(theme.iter().all(|s| s.is_finite()));
+            assert!((peak - 0.8).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn genres_map_to_styles() {
+        assert_eq!(MusicStyle::for_genre("bedtime"), MusicStyle::Lullaby);
+        assert_eq!(MusicStyle::for_genre("adventure"), MusicStyle::Adventure);
+        assert_eq!(MusicStyle::for_genre("unknown"), MusicStyle::Gentle);
+    }
+
+    #[test]
+    fn phrases_fill_their_bars_and_end_on_tonic() {
+        let params = MusicStyle::Adventure.params();
+        let mut rng = Rng::new(1);
+        for _ in 0..20 {
+            let phrase = compose_phrase(&params, &[0, 4, 5, 3], &mut rng);
+            assert_eq!(phrase.len(), BARS_PER_PHRASE * params.beats_per_bar * params.steps_per_beat);
+            let last = phrase.last().unwrap().unwrap();
+            assert_eq!(last.rem_euclid(7), 0);
+        }
+    }
+
+    #[test]
+    fn nearest_chord_tone_snaps_to_triad() {
+        // Triad on the tonic is degrees 0, 2, 4 (and 7, 9, 11)
+        assert_eq!(nearest_chord_tone(3, 0), 2);
+        assert_eq!(nearest_chord_tone(6, 0), 7);
+        // Triad on the dominant is 4, 6, 8
+        assert_eq!(nearest_chord_tone(5, 4), 4);
+    }
+}
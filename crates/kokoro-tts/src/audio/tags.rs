@@ -0,0 +1,25 @@
+//! Titles and chapters written into MP3 and M4A/M4B files.
+
+/// Descriptive tags; empty fields are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    /// Front cover image, PNG or JPEG
+    pub cover: Option<Vec<u8>>,
+}
+
+/// Whether an image is a JPEG (otherwise it is taken to be a PNG).
+pub(crate) fn is_jpeg(image: &[u8]) -> bool {
+    image.starts_with(&[0xff, 0xd8, 0xff])
+}
+
+/// A named span of the audio, in milliseconds from the start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
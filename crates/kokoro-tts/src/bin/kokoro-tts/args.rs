@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+/// Parsed command-line arguments for a subcommand.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Parse `args` (without the program and subcommand names).
+    ///
+    /// - `value_opts`: long option names that take a value (`--voice af_nova`
+    ///   or `--voice=af_nova`)
+    /// - `flag_opts`: long option names that are boolean switches
+    ///
+    /// `-o` is accepted as shorthand for `--output`. A lone `-` is kept as a
+    /// positional argument (meaning stdin/stdout).
+    pub fn parse(args: &[String], value_opts: &[&str], flag_opts: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let long = if arg == "-o" {
+                Some("output".to_string())
+            } else {
+                arg.strip_prefix("--").map(|s| s.to_string())
+            };
+
+            let Some(long) = long else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+
+            let (name, inline_value) = match long.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (long, None),
+            };
+
+            if value_opts.contains(&name.as_str()) {
+                let value = match inline_value {
+                    Some(v) => v,
+                    None => iter
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| format!("Option --{} needs a value", name))?,
+                };
+                parsed.options.insert(name, value);
+            } else if flag_opts.contains(&name.as_str()) && inline_value.is_none() {
+                parsed.flags.push(name);
+            } else {
+                return Err(format!("Unknown option: {}", arg));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Get an option value.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|s| s.as_str())
+    }
+
+    /// Get an option parsed as a number.
+    pub fn get_parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.get(name) {
+            Some(v) => v
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("Invalid value for --{}: {}", name, v)),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether a boolean switch was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_options_flags_and_positionals() {
+        let args = Args::parse(
+            &strings(&["hello there", "--voice", "bf_emma", "--speed=1.2", "-o", "out.wav", "--json"]),
+            &["voice", "speed", "output"],
+            &["json"],
+        )
+        .unwrap();
+
+        assert_eq!(args.positional, vec!["hello there"]);
+        assert_eq!(args.get("voice"), Some("bf_emma"));
+        assert_eq!(args.get_parsed::<f32>("speed").unwrap(), Some(1.2));
+        assert_eq!(args.get("output"), Some("out.wav"));
+        assert!(args.flag("json"));
+        assert!(!args.flag("other"));
+    }
+
+    #[test]
+    fn dash_is_positional() {
+        let args = Args::parse(&strings(&["-"]), &[], &[]).unwrap();
+        assert_eq!(args.positional, vec!["-"]);
+    }
+
+    #[test]
+    fn rejects_unknown_and_incomplete_options() {
+        assert!(Args::parse(&strings(&["--bogus"]), &["voice"], &[]).is_err());
+        assert!(Args::parse(&strings(&["--voice"]), &["voice"], &[]).is_err());
+    }
+
+    #[test]
+    fn invalid_number_is_an_error() {
+        let args = Args::parse(&strings(&["--speed", "fast"]), &["speed"], &[]).unwrap();
+        assert!(args.get_parsed::<f32>("speed").is_err());
+    }
+}
@@ -0,0 +1,396 @@
+//! `kokoro-tts` command-line tool: synthesize speech, inspect voices and
+//! phonemes, and batch-render files without launching the desktop app.
+
+mod args;
+mod server;
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use kokoro_tts::audio::{self, AudioFormat};
+use kokoro_tts::voices::VoiceStore;
+use kokoro_tts::{phonemize, tokenize, Kokoro};
+
+use args::Args;
+
+const USAGE: &str = "\
+Usage: kokoro-tts <command> [options]
+
+Commands:
+  say [TEXT|-]        Synthesize TEXT (or stdin) to an audio file
+  voices              List installed voices
+  phonemize [TEXT|-]  Print IPA phonemes and token IDs
+  batch INPUT         Synthesize every line of a .txt file, or every
+                      object of a .jsonl file, into separate outputs
+  serve               Run an OpenAI-compatible /v1/audio/speech server
+
+Options:
+  -o, --output PATH   Output file for `say` (default: output.wav, `-` for stdout)
+  --out-dir DIR       Output directory for `batch` (default: current dir)
+  --voice NAME        Voice preset (default: af_nova)
+  --speed N           Speech rate, 1.0 = normal (default: 1.0)
+  --lang CODE         espeak-ng language code (default: en-us)
+  --format FMT        wav, wav16, pcm, mp3 or m4a (default: from output extension)
+  --model PATH        ONNX model (default: ~/.pattikadhai/models/kokoro/model_quantized.onnx)
+  --voices PATH       Voices directory or NPZ (default: ~/.pattikadhai/models/kokoro/voices)
+  --skip-existing     `batch`: don't re-render outputs that already exist
+  --host ADDR         `serve`: interface to bind (default: 127.0.0.1, 0.0.0.0 for LAN)
+  --port N            `serve`: port to listen on (default: 8880)
+
+JSONL lines look like {\"text\": \"...\", \"output\": \"name.wav\", \"voice\": \"bf_emma\", \"speed\": 0.9}
+where everything except `text` is optional. Each output is written in the
+format its extension names; with --format, the extensions must agree.";
+
+const MODEL_OPTS: [&str; 2] = ["model", "voices"];
+const SYNTH_OPTS: [&str; 4] = ["voice", "speed", "lang", "format"];
+
+fn main() -> ExitCode {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = argv.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let result = match command.as_str() {
+        "say" => cmd_say(rest),
+        "voices" => cmd_voices(rest),
+        "phonemize" => cmd_phonemize(rest),
+        "batch" => cmd_batch(rest),
+        "serve" => cmd_serve(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Default Kokoro directory shared with the desktop app.
+fn default_kokoro_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".pattikadhai")
+        .join("models")
+        .join("kokoro")
+}
+
+fn model_path(args: &Args) -> PathBuf {
+    args.get("model")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_kokoro_dir().join("model_quantized.onnx"))
+}
+
+fn voices_path(args: &Args) -> PathBuf {
+    args.get("voices")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_kokoro_dir().join("voices"))
+}
+
+fn load_kokoro(args: &Args) -> Result<Kokoro, String> {
+    Kokoro::new(&model_path(args), &voices_path(args)).map_err(|e| e.to_string())
+}
+
+/// Synthesis settings shared by `say` and `batch`.
+struct SynthOptions {
+    voice: String,
+    speed: f32,
+    lang: String,
+    format: Option<AudioFormat>,
+}
+
+impl SynthOptions {
+    fn from_args(args: &Args) -> Result<Self, String> {
+        let format = match args.get("format") {
+            Some(name) => Some(
+                AudioFormat::from_name(name).ok_or_else(|| format!("Unknown format: {}", name))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            voice: args.get("voice").unwrap_or("af_nova").to_string(),
+            speed: args.get_parsed("speed")?.unwrap_or(1.0),
+            lang: args.get("lang").unwrap_or("en-us").to_string(),
+            format,
+        })
+    }
+}
+
+/// Read the text argument, or stdin when it is missing or `-`.
+fn read_text(args: &Args) -> Result<String, String> {
+    match args.positional.first().map(|s| s.as_str()) {
+        Some("-") | None => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read stdin: {}", e))?;
+            Ok(text)
+        }
+        Some(_) => Ok(args.positional.join(" ")),
+    }
+}
+
+fn cmd_say(argv: &[String]) -> Result<(), String> {
+    let value_opts = [&MODEL_OPTS[..], &SYNTH_OPTS[..], &["output"]].concat();
+    let args = Args::parse(argv, &value_opts, &[])?;
+    let opts = SynthOptions::from_args(&args)?;
+
+    let text = read_text(&args)?;
+    if text.trim().is_empty() {
+        return Err("No text to synthesize".to_string());
+    }
+    // Checked before synthesis, as `batch` does, so a mismatch fails fast
+    let output = args.get("output").unwrap_or("output.wav");
+    let format = if output == "-" {
+        opts.format.unwrap_or(AudioFormat::Wav)
+    } else {
+        output_format(Path::new(output), opts.format)?
+    };
+
+    let mut kokoro = load_kokoro(&args)?;
+    let audio = kokoro
+        .create(text.trim(), &opts.voice, opts.speed, &opts.lang)
+        .map_err(|e| e.to_string())?;
+
+    if output == "-" {
+        let bytes = audio::encode_audio(&audio.samples, audio.sample_rate, format)
+            .map_err(|e| e.to_string())?;
+        std::io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| format!("Failed to write stdout: {}", e))?;
+    } else {
+        let path = Path::new(output);
+        audio::save_audio(path, &audio.samples, audio.sample_rate, format)
+            .map_err(|e| e.to_string())?;
+        eprintln!(
+            "Wrote {} ({:.2}s)",
+            path.display(),
+            audio.samples.len() as f64 / audio.sample_rate as f64
+        );
+    }
+    Ok(())
+}
+
+fn cmd_voices(argv: &[String]) -> Result<(), String> {
+    let args = Args::parse(argv, &MODEL_OPTS, &[])?;
+    let path = voices_path(&args);
+    let store = if path.is_dir() {
+        VoiceStore::load_dir(&path)
+    } else {
+        VoiceStore::load_npz(&path)
+    }
+    .map_err(|e| e.to_string())?;
+
+    for name in store.names() {
+        println!("{}", name);
+    }
+    Ok(())
+}
+
+fn cmd_phonemize(argv: &[String]) -> Result<(), String> {
+    let args = Args::parse(argv, &["lang"], &[])?;
+    let lang = args.get("lang").unwrap_or("en-us");
+    let text = read_text(&args)?;
+
+    let phonemes = phonemize::phonemize(text.trim(), lang).map_err(|e| e.to_string())?;
+    let tokens = tokenize::tokenize(&phonemes);
+
+    println!("{}", phonemes);
+    println!(
+        "{}",
+        tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")
+    );
+    eprintln!("{} tokens (limit {})", tokens.len(), tokenize::MAX_PHONEME_LEN);
+    Ok(())
+}
+
+/// One unit of work for `batch`.
+#[derive(Debug, serde::Deserialize)]
+struct BatchItem {
+    text: String,
+    output: Option<String>,
+    voice: Option<String>,
+    speed: Option<f32>,
+    lang: Option<String>,
+}
+
+/// Parse a batch input file: one item per non-empty line for plain text,
+/// one JSON object per line for `.jsonl`.
+fn parse_batch(content: &str, jsonl: bool) -> Result<Vec<BatchItem>, String> {
+    let mut items = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if jsonl {
+            let item: BatchItem = serde_json::from_str(line)
+                .map_err(|e| format!("Line {}: invalid JSON: {}", line_no + 1, e))?;
+            items.push(item);
+        } else {
+            items.push(BatchItem {
+                text: line.to_string(),
+                output: None,
+                voice: None,
+                speed: None,
+                lang: None,
+            });
+        }
+    }
+    Ok(items)
+}
+
+fn cmd_batch(argv: &[String]) -> Result<(), String> {
+    let value_opts = [&MODEL_OPTS[..], &SYNTH_OPTS[..], &["out-dir"]].concat();
+    let args = Args::parse(argv, &value_opts, &["skip-existing"])?;
+    let opts = SynthOptions::from_args(&args)?;
+    // For generated names; named outputs go by their own extension
+    let default_format = opts.format.unwrap_or(AudioFormat::Wav);
+
+    let input = args
+        .positional
+        .first()
+        .map(PathBuf::from)
+        .ok_or("batch needs an input file")?;
+    let content = std::fs::read_to_string(&input)
+        .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let jsonl = input.extension().and_then(|e| e.to_str()) == Some("jsonl");
+    let items = parse_batch(&content, jsonl)?;
+
+    let out_dir = PathBuf::from(args.get("out-dir").unwrap_or("."));
+    std::fs::create_dir_all(&out_dir)
+        .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("batch");
+
+    let mut kokoro = load_kokoro(&args)?;
+    let mut failed = 0;
+
+    for (i, item) in items.iter().enumerate() {
+        let name = item
+            .output
+            .clone()
+            .unwrap_or_else(|| format!("{}_{:04}.{}", stem, i + 1, default_format.extension()));
+        let path = out_dir.join(name);
+        let format = match output_format(&path, opts.format) {
+            Ok(format) => format,
+            Err(e) => {
+                failed += 1;
+                eprintln!("[{}/{}] FAILED {}: {}", i + 1, items.len(), path.display(), e);
+                continue;
+            }
+        };
+
+        if args.flag("skip-existing") && path.exists() {
+            eprintln!("[{}/{}] skip {}", i + 1, items.len(), path.display());
+            continue;
+        }
+
+        let voice = item.voice.as_deref().unwrap_or(&opts.voice);
+        let lang = item.lang.as_deref().unwrap_or(&opts.lang);
+        let speed = item.speed.unwrap_or(opts.speed);
+
+        let result = kokoro
+            .create(&item.text, voice, speed, lang)
+            .and_then(|a| audio::save_audio(&path, &a.samples, a.sample_rate, format));
+
+        match result {
+            Ok(()) => eprintln!("[{}/{}] {}", i + 1, items.len(), path.display()),
+            Err(e) => {
+                failed += 1;
+                eprintln!("[{}/{}] FAILED {}: {}", i + 1, items.len(), path.display(), e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} items failed", failed, items.len()));
+    }
+    Ok(())
+}
+
+/// The format to write `path` in: its extension decides, and `--format`,
+/// if given, has to agree with it.
+fn output_format(path: &Path, forced: Option<AudioFormat>) -> Result<AudioFormat, String> {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return Ok(forced.unwrap_or(AudioFormat::Wav));
+    };
+    let by_extension = AudioFormat::from_extension(ext)
+        .ok_or_else(|| format!("Unknown audio extension .{}", ext))?;
+    match forced {
+        Some(format) if format.extension() != by_extension.extension() => Err(format!(
+            ".{} output doesn't match --format (which writes .{})",
+            ext,
+            format.extension()
+        )),
+        Some(format) => Ok(format),
+        None => Ok(by_extension),
+    }
+}
+
+fn cmd_serve(argv: &[String]) -> Result<(), String> {
+    let value_opts = [&MODEL_OPTS[..], &["host", "port", "voice"]].concat();
+    let args = Args::parse(argv, &value_opts, &[])?;
+
+    let host = args.get("host").unwrap_or("127.0.0.1");
+    let port: u16 = args.get_parsed("port")?.unwrap_or(8880);
+    let kokoro = load_kokoro(&args)?;
+
+    server::serve(
+        kokoro,
+        server::ServerConfig {
+            addr: format!("{}:{}", host, port),
+            default_voice: args.get("voice").unwrap_or("af_nova").to_string(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_batch_text_skips_blank_lines() {
+        let items = parse_batch("First line.\n\n  Second line.  \n", false).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].text, "Second line.");
+        assert!(items[0].output.is_none());
+    }
+
+    #[test]
+    fn parse_batch_jsonl_reads_overrides() {
+        let content = r#"{"text": "Hi.", "output": "hi.wav", "voice": "bf_emma", "speed": 0.8}
+{"text": "Bye."}"#;
+        let items = parse_batch(content, true).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].output.as_deref(), Some("hi.wav"));
+        assert_eq!(items[0].voice.as_deref(), Some("bf_emma"));
+        assert_eq!(items[0].speed, Some(0.8));
+        assert!(items[1].voice.is_none());
+    }
+
+    #[test]
+    fn output_format_follows_the_extension() {
+        let format = |path: &str, forced| output_format(Path::new(path), forced);
+        assert_eq!(format("a.mp3", None), Ok(AudioFormat::Mp3));
+        assert_eq!(format("a.M4B", None), Ok(AudioFormat::M4a));
+        assert_eq!(format("a", None), Ok(AudioFormat::Wav));
+        assert_eq!(format("a.wav", Some(AudioFormat::Wav16)), Ok(AudioFormat::Wav16));
+        assert!(format("a.wav", Some(AudioFormat::Mp3)).is_err());
+        assert!(format("a.ogg", None).is_err());
+    }
+
+    #[test]
+    fn parse_batch_jsonl_reports_bad_line() {
+        let err = parse_batch("{\"text\": \"ok\"}\nnot json", true).unwrap_err();
+        assert!(err.starts_with("Line 2"));
+    }
+}
@@ -0,0 +1,464 @@
+//! Minimal OpenAI-compatible speech server.
+//!
+//! Implements `POST /v1/audio/speech` (plus voice and model listings) on
+//! top of `Kokoro`, so tools that speak the OpenAI TTS API can use local
+//! voices. Audio is streamed with chunked transfer encoding, one sentence
+//! at a time, so playback can start before the whole input is rendered.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use kokoro_tts::audio::mp3::{self, Mp3Encoder};
+use kokoro_tts::{audio, split_sentences, Kokoro};
+
+/// Longest request line plus headers accepted.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// Largest request body accepted (input text is capped well below this).
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// OpenAI caps `input` at 4096 characters.
+const MAX_INPUT_CHARS: usize = 4096;
+
+/// Connections handled at once; synthesis is serialized anyway, so more
+/// threads would only hold more idle sockets.
+const WORKERS: usize = 4;
+
+/// How long a client may stall while sending a request or reading audio.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// OpenAI voice names and the Kokoro voice each maps to. Every target is
+/// one of the voices the app installs.
+const OPENAI_VOICES: &[(&str, &str)] = &[
+    ("alloy", "af_sarah"),
+    ("ash", "am_adam"),
+    ("coral", "af_heart"),
+    ("echo", "am_echo"),
+    ("fable", "bm_george"),
+    ("onyx", "am_michael"),
+    ("nova", "af_nova"),
+    ("sage", "bf_emma"),
+    ("shimmer", "af_sky"),
+];
+
+pub struct ServerConfig {
+    pub addr: String,
+    pub default_voice: String,
+}
+
+/// Request body for `POST /v1/audio/speech`.
+#[derive(Debug, serde::Deserialize)]
+struct SpeechRequest {
+    #[allow(dead_code)]
+    model: Option<String>,
+    input: String,
+    voice: Option<String>,
+    speed: Option<f32>,
+    response_format: Option<String>,
+}
+
+/// A parsed HTTP request.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// Run the server until the process is killed.
+pub fn serve(kokoro: Kokoro, config: ServerConfig) -> Result<(), String> {
+    let listener = TcpListener::bind(&config.addr)
+        .map_err(|e| format!("Failed to bind {}: {}", config.addr, e))?;
+    eprintln!("Listening on http://{}", config.addr);
+
+    let kokoro = Arc::new(Mutex::new(kokoro));
+    let default_voice = Arc::new(config.default_voice);
+
+    // A fixed pool of workers; accepting blocks while they are all busy
+    // and the queue is full.
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(WORKERS);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..WORKERS {
+        let receiver = Arc::clone(&receiver);
+        let kokoro = Arc::clone(&kokoro);
+        let default_voice = Arc::clone(&default_voice);
+        std::thread::spawn(move || loop {
+            let next = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => break,
+            };
+            let Ok(stream) = next else {
+                break;
+            };
+            if let Err(e) = handle_connection(stream, &kokoro, &default_voice) {
+                eprintln!("Request failed: {}", e);
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Connection failed: {}", e);
+                continue;
+            }
+        };
+        if sender.send(stream).is_err() {
+            return Err("Every server worker has stopped".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    kokoro: &Mutex<Kokoro>,
+    default_voice: &str,
+) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)))
+        .map_err(|e| format!("Failed to set socket timeouts: {}", e))?;
+    let request = match read_request(&mut BufReader::new(&stream)) {
+        Ok(r) => r,
+        Err((status, e)) => return send_error(&mut stream, status, &e),
+    };
+    eprintln!("{} {}", request.method, request.path);
+
+    let path = request.path.split('?').next().unwrap_or("");
+    match (request.method.as_str(), path) {
+        ("POST", "/v1/audio/speech") => handle_speech(&mut stream, &request, kokoro, default_voice),
+        ("GET", "/v1/audio/voices") | ("GET", "/v1/voices") => {
+            let voices = lock(kokoro)?.voices();
+            let body = serde_json::json!({ "voices": voices });
+            send_json(&mut stream, 200, &body)
+        }
+        ("GET", "/v1/models") => {
+            let models: Vec<_> = ["kokoro", "tts-1", "tts-1-hd", "gpt-4o-mini-tts"]
+                .iter()
+                .map(|id| serde_json::json!({ "id": id, "object": "model", "owned_by": "pattikadhai" }))
+                .collect();
+            send_json(
+                &mut stream,
+                200,
+                &serde_json::json!({ "object": "list", "data": models }),
+            )
+        }
+        _ => send_error(
+            &mut stream,
+            404,
+            &format!("No route for {} {}", request.method, path),
+        ),
+    }
+}
+
+fn handle_speech(
+    stream: &mut TcpStream,
+    request: &Request,
+    kokoro: &Mutex<Kokoro>,
+    default_voice: &str,
+) -> Result<(), String> {
+    let speech: SpeechRequest = match serde_json::from_slice(&request.body) {
+        Ok(s) => s,
+        Err(e) => return send_error(stream, 400, &format!("Invalid JSON body: {}", e)),
+    };
+
+    if speech.input.trim().is_empty() {
+        return send_error(stream, 400, "`input` must not be empty");
+    }
+    if speech.input.chars().count() > MAX_INPUT_CHARS {
+        return send_error(stream, 400, "`input` is longer than 4096 characters");
+    }
+
+    // OpenAI defaults to MP3
+    let format = speech.response_format.as_deref().unwrap_or("mp3");
+    let (content_type, mut mp3) = match format {
+        "mp3" => {
+            let bitrate = mp3::default_bitrate(audio::SAMPLE_RATE, 1);
+            let encoder =
+                Mp3Encoder::new(audio::SAMPLE_RATE, 1, bitrate).map_err(|e| e.to_string())?;
+            ("audio/mpeg", Some(encoder))
+        }
+        "wav" => ("audio/wav", None),
+        "pcm" => ("audio/pcm", None),
+        other => {
+            return send_error(
+                stream,
+                400,
+                &format!("Unsupported response_format '{}': use mp3, wav or pcm", other),
+            )
+        }
+    };
+
+    let available = lock(kokoro)?.voices();
+    let requested = speech.voice.as_deref().unwrap_or(default_voice);
+    let Some(voice) = resolve_voice(requested, &available) else {
+        return send_error(stream, 400, &format!("Unknown voice '{}'", requested));
+    };
+    let speed = speech.speed.unwrap_or(1.0).clamp(0.25, 4.0);
+
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+        content_type
+    );
+    stream
+        .write_all(head.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    if format == "wav" {
+        write_chunk(stream, &wav_stream_header(audio::SAMPLE_RATE))?;
+    }
+
+    for sentence in split_sentences(&speech.input) {
+        let result = lock(kokoro)?.create(&sentence, &voice, speed, "en-us");
+        match result {
+            Ok(audio) => {
+                let bytes = match &mut mp3 {
+                    Some(encoder) => encoder.encode(&audio.samples),
+                    None => audio::encode_pcm16(&audio.samples),
+                };
+                write_chunk(stream, &bytes)?;
+            }
+            Err(e) => {
+                // Headers are already sent. Drop the connection without the
+                // final chunk so the client sees the response as truncated,
+                // not complete.
+                let _ = stream.shutdown(Shutdown::Both);
+                return Err(format!("Synthesis failed for {:?}: {}", sentence, e));
+            }
+        }
+    }
+    if let Some(encoder) = mp3 {
+        write_chunk(stream, &encoder.finish())?;
+    }
+
+    stream.write_all(b"0\r\n\r\n").map_err(|e| e.to_string())?;
+    stream.flush().map_err(|e| e.to_string())
+}
+
+fn lock(kokoro: &Mutex<Kokoro>) -> Result<std::sync::MutexGuard<'_, Kokoro>, String> {
+    kokoro
+        .lock()
+        .map_err(|e| format!("Failed to lock Kokoro: {}", e))
+}
+
+/// Map a requested voice to an installed Kokoro voice.
+///
+/// Accepts exact Kokoro names, OpenAI names (`nova`, `alloy`, ...), and bare
+/// Kokoro names without the accent/gender prefix (`emma` → `bf_emma`).
+fn resolve_voice(requested: &str, available: &[String]) -> Option<String> {
+    let requested = requested.trim().to_ascii_lowercase();
+    if available.contains(&requested) {
+        return Some(requested);
+    }
+
+    if let Some((_, mapped)) = OPENAI_VOICES.iter().find(|(name, _)| *name == requested) {
+        if available.iter().any(|v| v == mapped) {
+            return Some(mapped.to_string());
+        }
+    }
+
+    available
+        .iter()
+        .find(|v| v.split_once('_').map(|(_, name)| name) == Some(requested.as_str()))
+        .cloned()
+}
+
+/// Read one HTTP/1.1 request (request line, headers and body). On failure,
+/// returns the status to answer with and why.
+fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, (u16, String)> {
+    let bad = |message: String| (400, message);
+    // A client cannot grow the head past the limit by never ending a line
+    let mut head = reader.by_ref().take(MAX_HEAD_BYTES as u64);
+    let mut read_line = |what: &str| {
+        let mut line = String::new();
+        head.read_line(&mut line)
+            .map_err(|e| bad(format!("Failed to read {}: {}", what, e)))?;
+        if !line.ends_with('\n') && head.limit() == 0 {
+            return Err((431, "Request head too large".to_string()));
+        }
+        Ok(line)
+    };
+
+    let line = read_line("request")?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| bad("Empty request".into()))?.to_string();
+    let path = parts.next().ok_or_else(|| bad("Missing request path".into()))?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let header = read_line("header")?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = match headers.get("content-length") {
+        Some(v) => v.parse().map_err(|_| bad("Invalid Content-Length".into()))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(bad("Request body too large".to_string()));
+    }
+
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| bad(format!("Failed to read body: {}", e)))?;
+
+    Ok(Request { method, path, body })
+}
+
+/// WAV header for a 16-bit mono stream of unknown length.
+/// Sizes are set to the maximum, which players treat as "read until EOF".
+fn wav_stream_header(sample_rate: u32) -> Vec<u8> {
+    let mut h = Vec::with_capacity(44);
+    h.extend_from_slice(b"RIFF");
+    h.extend_from_slice(&u32::MAX.to_le_bytes());
+    h.extend_from_slice(b"WAVEfmt ");
+    h.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    h.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    h.extend_from_slice(&1u16.to_le_bytes()); // mono
+    h.extend_from_slice(&sample_rate.to_le_bytes());
+    h.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    h.extend_from_slice(&2u16.to_le_bytes()); // block align
+    h.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    h.extend_from_slice(b"data");
+    h.extend_from_slice(&(u32::MAX - 36).to_le_bytes());
+    h
+}
+
+fn write_chunk<W: Write>(writer: &mut W, data: &[u8]) -> Result<(), String> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    write!(writer, "{:X}\r\n", data.len()).map_err(|e| e.to_string())?;
+    writer.write_all(data).map_err(|e| e.to_string())?;
+    writer.write_all(b"\r\n").map_err(|e| e.to_string())?;
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn send_json(stream: &mut TcpStream, status: u16, body: &serde_json::Value) -> Result<(), String> {
+    let body = body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .map_err(|e| e.to_string())?;
+    stream.write_all(body.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Send an error in the OpenAI error envelope.
+fn send_error(stream: &mut TcpStream, status: u16, message: &str) -> Result<(), String> {
+    let body = serde_json::json!({
+        "error": { "message": message, "type": "invalid_request_error" }
+    });
+    send_json(stream, status, &body)
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        431 => "Request Header Fields Too Large",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The voices the app downloads.
+    fn voices() -> Vec<String> {
+        [
+            "af_nova", "bf_emma", "af_heart", "af_bella", "af_jessica", "af_sarah", "af_sky",
+            "am_adam", "am_michael", "bm_george", "bf_lily", "am_echo",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn every_openai_voice_maps_to_an_installed_voice() {
+        for (name, mapped) in OPENAI_VOICES {
+            assert_eq!(resolve_voice(name, &voices()).as_deref(), Some(*mapped), "{}", name);
+        }
+    }
+
+    #[test]
+    fn resolve_voice_accepts_kokoro_openai_and_short_names() {
+        assert_eq!(
+            resolve_voice("bf_emma", &voices()).as_deref(),
+            Some("bf_emma")
+        );
+        assert_eq!(resolve_voice("Nova", &voices()).as_deref(), Some("af_nova"));
+        assert_eq!(
+            resolve_voice("onyx", &voices()).as_deref(),
+            Some("am_michael")
+        );
+        assert_eq!(resolve_voice("emma", &voices()).as_deref(), Some("bf_emma"));
+        assert_eq!(resolve_voice("af_alloy", &voices()), None);
+    }
+
+    #[test]
+    fn read_request_parses_body() {
+        let raw = "POST /v1/audio/speech HTTP/1.1\r\nHost: x\r\nContent-Length: 15\r\n\r\n{\"input\":\"hi\"}\n";
+        let request = read_request(&mut BufReader::new(raw.as_bytes())).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/audio/speech");
+        let speech: SpeechRequest = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(speech.input, "hi");
+        assert!(speech.voice.is_none());
+    }
+
+    #[test]
+    fn read_request_rejects_huge_body() {
+        let raw = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(read_request(&mut BufReader::new(raw.as_bytes())).is_err());
+    }
+
+    #[test]
+    fn read_request_stops_at_an_endless_header() {
+        let raw = format!("GET / HTTP/1.1\r\nX-Long: {}", "a".repeat(MAX_HEAD_BYTES * 4));
+        let err = read_request(&mut BufReader::new(raw.as_bytes())).err().unwrap();
+        assert_eq!(err.0, 431);
+    }
+
+    #[test]
+    fn chunk_encoding() {
+        let mut out = Vec::new();
+        write_chunk(&mut out, b"hello world!").unwrap();
+        write_chunk(&mut out, b"").unwrap();
+        assert_eq!(out, b"C\r\nhello world!\r\n");
+    }
+
+    #[test]
+    fn wav_stream_header_layout() {
+        let header = wav_stream_header(24000);
+        assert_eq!(header.len(), 44);
+        assert_eq!(&header[0..4], b"RIFF");
+        assert_eq!(&header[36..40], b"data");
+        assert_eq!(
+            u32::from_le_bytes([header[24], header[25], header[26], header[27]]),
+            24000
+        );
+    }
+}
@@ -5,9 +5,11 @@ pub mod tokenize;
 pub mod voices;
 
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use model::KokoroModel;
-use voices::VoiceStore;
+use voices::{Voice, VoiceStore};
 
 /// Errors from the Kokoro TTS pipeline.
 #[derive(Debug, thiserror::Error)]
@@ -26,6 +28,35 @@ pub enum KokoroError {
 
     #[error("Audio error: {0}")]
     Audio(String),
+
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+/// Shared flag for stopping a long synthesis early. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// `Err(KokoroError::Cancelled)` once [`cancel`](Self::cancel) was called.
+    pub fn check(&self) -> Result<(), KokoroError> {
+        if self.is_cancelled() {
+            return Err(KokoroError::Cancelled);
+        }
+        Ok(())
+    }
 }
 
 /// Generated audio data.
@@ -76,6 +107,21 @@ impl Kokoro {
         speed: f32,
         lang: &str,
     ) -> Result<AudioData, KokoroError> {
+        self.create_cancellable(text, voice, speed, lang, &CancelToken::new())
+    }
+
+    /// Like [`create`](Self::create), but stops with
+    /// [`KokoroError::Cancelled`] between chunks once `cancel` is triggered.
+    pub fn create_cancellable(
+        &mut self,
+        text: &str,
+        voice: &str,
+        speed: f32,
+        lang: &str,
+        cancel: &CancelToken,
+    ) -> Result<AudioData, KokoroError> {
+        cancel.check()?;
+
         // Step 1: Text → IPA phonemes
         let phonemes = phonemize::phonemize(text, lang)?;
         log::info!("Phonemized {} chars → {} phoneme chars", text.len(), phonemes.len());
@@ -91,7 +137,7 @@ impl Kokoro {
 
         // Step 3: Handle long text by chunking at sentence boundaries
         if tokens.len() >= tokenize::MAX_PHONEME_LEN {
-            return self.create_chunked(text, voice, speed, lang);
+            return self.create_chunked(text, voice, speed, lang, cancel);
         }
 
         // Step 4: Get voice embedding for this token length
@@ -122,6 +168,7 @@ impl Kokoro {
         voice: &str,
         speed: f32,
         lang: &str,
+        cancel: &CancelToken,
     ) -> Result<AudioData, KokoroError> {
         let sentences = split_sentences(text);
         let mut chunks: Vec<Vec<f32>> = Vec::new();
@@ -140,6 +187,7 @@ impl Kokoro {
 
             if test_tokens.len() >= tokenize::MAX_PHONEME_LEN && !batch.is_empty() {
                 // Process current batch
+                cancel.check()?;
                 let audio = self.create_single(&batch, voice, speed, lang)?;
                 chunks.push(audio);
                 batch = sentence.to_string();
@@ -150,6 +198,7 @@ impl Kokoro {
 
         // Process remaining batch
         if !batch.is_empty() {
+            cancel.check()?;
             let audio = self.create_single(&batch, voice, speed, lang)?;
             chunks.push(audio);
         }
@@ -177,9 +226,60 @@ impl Kokoro {
             return Ok(Vec::new());
         }
 
+        self.infer_tokens(&tokens, voice, speed)
+    }
+
+    /// Generate speech audio directly from IPA phonemes, skipping
+    /// phonemization. Useful for hand-tuning pronunciation.
+    ///
+    /// - `phonemes`: IPA phoneme string (characters outside the Kokoro
+    ///   vocabulary are skipped)
+    /// - `voice`: voice preset name (e.g., "af_nova")
+    /// - `speed`: speech rate (1.0 = normal)
+    ///
+    /// Input longer than the 510-token limit is split at sentence
+    /// boundaries, the same way `create` splits long text.
+    pub fn create_from_phonemes(
+        &mut self,
+        phonemes: &str,
+        voice: &str,
+        speed: f32,
+    ) -> Result<AudioData, KokoroError> {
+        let tokens = tokenize::tokenize(phonemes);
+        if tokens.is_empty() {
+            return Err(KokoroError::Phonemize(
+                "No valid tokens produced from phonemes".to_string(),
+            ));
+        }
+
+        let mut chunks: Vec<Vec<f32>> = Vec::new();
+        for batch in batch_phoneme_chunks(phonemes) {
+            let tokens = tokenize::tokenize(&batch);
+            if tokens.is_empty() {
+                continue;
+            }
+            chunks.push(self.infer_tokens(&tokens, voice, speed)?);
+        }
+
+        let mut samples = audio::concat_samples(&chunks);
+        log::info!("Generated {} audio samples from phonemes", samples.len());
+        audio::normalize(&mut samples, 0.95);
+        Ok(AudioData {
+            samples,
+            sample_rate: audio::SAMPLE_RATE,
+        })
+    }
+
+    /// Run the model on a token sequence that fits within the 510-token limit.
+    fn infer_tokens(
+        &mut self,
+        tokens: &[i64],
+        voice: &str,
+        speed: f32,
+    ) -> Result<Vec<f32>, KokoroError> {
         let voice_data = self.voices.get(voice)?;
         let style = voice_data.embedding(tokens.len())?;
-        let padded = tokenize::pad_tokens(&tokens);
+        let padded = tokenize::pad_tokens(tokens);
 
         self.model.infer(&padded, style, speed)
     }
@@ -188,10 +288,41 @@ impl Kokoro {
     pub fn voices(&self) -> Vec<String> {
         self.voices.names()
     }
+
+    /// Register an additional voice (e.g. a freshly imported voice pack)
+    /// without reloading the model.
+    pub fn add_voice(&mut self, name: &str, voice: Voice) {
+        self.voices.insert(name, voice);
+    }
+}
+
+/// Group phoneme sentences into batches that each stay under the token limit.
+fn batch_phoneme_chunks(phonemes: &str) -> Vec<String> {
+    let mut batches = Vec::new();
+    let mut batch = String::new();
+
+    for sentence in split_sentences(phonemes) {
+        let test = if batch.is_empty() {
+            sentence.clone()
+        } else {
+            format!("{} {}", batch, sentence)
+        };
+
+        if tokenize::tokenize(&test).len() >= tokenize::MAX_PHONEME_LEN && !batch.is_empty() {
+            batches.push(std::mem::replace(&mut batch, sentence));
+        } else {
+            batch = test;
+        }
+    }
+
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
 }
 
 /// Split text at sentence boundaries (. ! ? followed by space or end of string).
-fn split_sentences(text: &str) -> Vec<String> {
+pub fn split_sentences(text: &str) -> Vec<String> {
     let mut result = Vec::new();
     let mut current = String::new();
 
@@ -252,6 +383,34 @@ mod tests {
         assert!(sentences.is_empty());
     }
 
+    #[test]
+    fn test_batch_phoneme_chunks_short_input() {
+        let batches = batch_phoneme_chunks("həlˈoʊ wˈɜːld. haʊ ɑːɹ juː?");
+        assert_eq!(batches, vec!["həlˈoʊ wˈɜːld. haʊ ɑːɹ juː?"]);
+    }
+
+    #[test]
+    fn test_batch_phoneme_chunks_respects_token_limit() {
+        let sentence = format!("{}.", "a".repeat(200));
+        let input = [sentence.as_str(); 5].join(" ");
+        let batches = batch_phoneme_chunks(&input);
+        assert!(batches.len() > 1);
+        for batch in &batches {
+            assert!(tokenize::tokenize(batch).len() < tokenize::MAX_PHONEME_LEN);
+        }
+    }
+
+    #[test]
+    fn test_cancel_token_is_shared_between_clones() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(token.check().is_ok());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(KokoroError::Cancelled)));
+    }
+
     #[test]
     fn test_integration() {
         // Full integration test - requires model files and espeak-ng
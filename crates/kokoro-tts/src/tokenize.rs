@@ -175,7 +175,7 @@ mod tests {
 
     #[test]
     fn test_max_length() {
-        let long_input: String = std::iter::repeat('a').take(600).collect();
+        let long_input = "a".repeat(600);
         let tokens = tokenize(&long_input);
         assert_eq!(tokens.len(), MAX_PHONEME_LEN);
     }
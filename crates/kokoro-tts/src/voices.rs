@@ -15,6 +15,23 @@ pub struct Voice {
 }
 
 impl Voice {
+    /// Build a voice from a flat f32 embedding, checking the (510, 256) layout.
+    pub fn from_data(data: Vec<f32>) -> Result<Self, KokoroError> {
+        if data.len() != EXPECTED_FLOATS {
+            return Err(KokoroError::Voice(format!(
+                "Voice embedding has {} values (expected {} = 510 x 256)",
+                data.len(),
+                EXPECTED_FLOATS
+            )));
+        }
+        Ok(Self { data })
+    }
+
+    /// Serialize the embedding as raw little-endian f32 bytes (the `.bin` format).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
     /// Get the style embedding for a given token count (before padding).
     /// Returns a slice of 256 f32 values.
     pub fn embedding(&self, token_len: usize) -> Result<&[f32], KokoroError> {
@@ -140,6 +157,11 @@ impl VoiceStore {
         Ok(Self { voices })
     }
 
+    /// Add or replace a voice.
+    pub fn insert(&mut self, name: &str, voice: Voice) {
+        self.voices.insert(name.to_string(), voice);
+    }
+
     /// Get a voice by name.
     pub fn get(&self, name: &str) -> Result<&Voice, KokoroError> {
         self.voices
@@ -155,6 +177,119 @@ impl VoiceStore {
     }
 }
 
+/// Read voice embeddings from a `.bin` file, a `.npy` file, or a zip archive
+/// of them (which also covers NPZ voice packs).
+///
+/// Returns `(name, voice)` pairs where the name is the file or entry stem.
+/// Every embedding is validated against the (510, 256) layout.
+pub fn read_voice_file(path: &Path) -> Result<Vec<(String, Voice)>, KokoroError> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("bin") | Some("npy") => {
+            let raw = std::fs::read(path).map_err(|e| {
+                KokoroError::Voice(format!("Failed to read voice file {}: {}", path.display(), e))
+            })?;
+            // Kokoro's NPZ pack also uses the .bin extension, so sniff for a zip header
+            if raw.starts_with(b"PK\x03\x04") {
+                return read_voice_archive(path);
+            }
+            let voice = parse_voice_bytes(&stem, ext.as_deref(), &raw)?;
+            Ok(vec![(stem, voice)])
+        }
+        Some("zip") | Some("npz") => read_voice_archive(path),
+        _ => Err(KokoroError::Voice(format!(
+            "Unsupported voice file {}: expected .bin, .npy or .zip",
+            path.display()
+        ))),
+    }
+}
+
+/// Read every `.bin` / `.npy` entry from a zip archive.
+fn read_voice_archive(path: &Path) -> Result<Vec<(String, Voice)>, KokoroError> {
+    let file = std::fs::File::open(path).map_err(|e| {
+        KokoroError::Voice(format!("Failed to open voice archive {}: {}", path.display(), e))
+    })?;
+
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        KokoroError::Voice(format!("Failed to read voice archive: {}", e))
+    })?;
+
+    let mut voices = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| {
+            KokoroError::Voice(format!("Failed to read archive entry {}: {}", i, e))
+        })?;
+
+        if entry.is_dir() || entry.name().starts_with("__MACOSX") {
+            continue;
+        }
+
+        let entry_path = Path::new(entry.name()).to_path_buf();
+        let ext = entry_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        if !matches!(ext.as_deref(), Some("bin") | Some("npy")) {
+            continue;
+        }
+
+        let name = entry_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+        if name.is_empty() {
+            continue;
+        }
+
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf).map_err(|e| {
+            KokoroError::Voice(format!("Failed to read archive entry data: {}", e))
+        })?;
+
+        let voice = parse_voice_bytes(&name, ext.as_deref(), &buf)?;
+        voices.push((name, voice));
+    }
+
+    if voices.is_empty() {
+        return Err(KokoroError::Voice(format!(
+            "No .bin or .npy voices found in {}",
+            path.display()
+        )));
+    }
+
+    Ok(voices)
+}
+
+/// Decode a single voice embedding from raw `.bin` or `.npy` bytes.
+fn parse_voice_bytes(name: &str, ext: Option<&str>, raw: &[u8]) -> Result<Voice, KokoroError> {
+    let data = if ext == Some("npy") {
+        parse_npy_f32(raw)?
+    } else {
+        if raw.len() != EXPECTED_BYTES {
+            return Err(KokoroError::Voice(format!(
+                "Voice '{}' has unexpected size {} bytes (expected {})",
+                name,
+                raw.len(),
+                EXPECTED_BYTES
+            )));
+        }
+        parse_raw_f32(raw)
+    };
+
+    Voice::from_data(data).map_err(|e| KokoroError::Voice(format!("Voice '{}': {}", name, e)))
+}
+
 /// Parse raw little-endian f32 bytes into a Vec<f32>.
 fn parse_raw_f32(data: &[u8]) -> Vec<f32> {
     data.chunks_exact(4)
@@ -178,9 +313,21 @@ fn parse_npy_f32(data: &[u8]) -> Result<Vec<f32>, KokoroError> {
         return Err(KokoroError::Voice(format!("Unsupported .npy version: {}", major)));
     };
 
+    if data.len() < header_start + header_len {
+        return Err(KokoroError::Voice("Truncated .npy header".to_string()));
+    }
+
+    let header = String::from_utf8_lossy(&data[header_start..header_start + header_len]);
+    if header.contains("descr") && !header.contains("<f4") {
+        return Err(KokoroError::Voice(format!(
+            "Unsupported .npy dtype (expected little-endian float32): {}",
+            header.trim()
+        )));
+    }
+
     let raw = &data[header_start + header_len..];
 
-    if raw.len() % 4 != 0 {
+    if !raw.len().is_multiple_of(4) {
         return Err(KokoroError::Voice("NPY data length not aligned to f32".to_string()));
     }
 
@@ -230,6 +377,103 @@ mod tests {
         assert_eq!(emb.len(), 256);
     }
 
+    /// Build a minimal v1.0 .npy file holding `values` as float32.
+    fn make_npy(values: &[f32], descr: &str) -> Vec<u8> {
+        let header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': ({},), }}\n",
+            descr,
+            values.len()
+        );
+        let mut out = b"\x93NUMPY\x01\x00".to_vec();
+        out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        out.extend_from_slice(header.as_bytes());
+        for v in values {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn test_voice_from_data_validates_layout() {
+        assert!(Voice::from_data(vec![0.0; EXPECTED_FLOATS]).is_ok());
+        assert!(Voice::from_data(vec![0.0; 256]).is_err());
+    }
+
+    #[test]
+    fn test_voice_bytes_roundtrip() {
+        let data: Vec<f32> = (0..EXPECTED_FLOATS).map(|i| i as f32).collect();
+        let voice = Voice::from_data(data.clone()).unwrap();
+        let bytes = voice.to_bytes();
+        assert_eq!(bytes.len(), EXPECTED_BYTES);
+        assert_eq!(parse_raw_f32(&bytes), data);
+    }
+
+    #[test]
+    fn test_read_voice_file_bin_and_npy() {
+        let dir = std::env::temp_dir().join("kokoro_test_read_voice_file");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let values: Vec<f32> = vec![0.5; EXPECTED_FLOATS];
+        let bin_path = dir.join("custom.bin");
+        std::fs::write(&bin_path, Voice::from_data(values.clone()).unwrap().to_bytes()).unwrap();
+        let voices = read_voice_file(&bin_path).unwrap();
+        assert_eq!(voices.len(), 1);
+        assert_eq!(voices[0].0, "custom");
+
+        let npy_path = dir.join("other.npy");
+        std::fs::write(&npy_path, make_npy(&values, "<f4")).unwrap();
+        let voices = read_voice_file(&npy_path).unwrap();
+        assert_eq!(voices[0].0, "other");
+        assert_eq!(voices[0].1.embedding(3).unwrap()[0], 0.5);
+
+        let bad_path = dir.join("short.bin");
+        std::fs::write(&bad_path, [0u8; 1024]).unwrap();
+        assert!(read_voice_file(&bad_path).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_read_voice_file_zip() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join("kokoro_test_read_voice_zip");
+        std::fs::create_dir_all(&dir).unwrap();
+        let zip_path = dir.join("pack.zip");
+
+        let values: Vec<f32> = vec![0.25; EXPECTED_FLOATS];
+        {
+            let file = std::fs::File::create(&zip_path).unwrap();
+            let mut writer = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("voices/wolf.npy", options).unwrap();
+            writer.write_all(&make_npy(&values, "<f4")).unwrap();
+            writer.start_file("voices/mouse.bin", options).unwrap();
+            writer.write_all(&Voice::from_data(values.clone()).unwrap().to_bytes()).unwrap();
+            writer.start_file("README.txt", options).unwrap();
+            writer.write_all(b"not a voice").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut names: Vec<String> = read_voice_file(&zip_path)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["mouse", "wolf"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_npy_rejects_wrong_dtype() {
+        let data = make_npy(&[1.0, 2.0], "<f8");
+        assert!(parse_npy_f32(&data).is_err());
+        let data = make_npy(&[1.0, 2.0], "<f4");
+        assert_eq!(parse_npy_f32(&data).unwrap(), vec![1.0, 2.0]);
+    }
+
     #[test]
     fn test_parse_raw_f32() {
         let bytes: Vec<u8> = vec![0, 0, 128, 63, 0, 0, 0, 64]; // 1.0f32, 2.0f32
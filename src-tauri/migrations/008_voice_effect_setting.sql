@@ -0,0 +1,2 @@
+-- Character voice effect applied to narration (see kokoro_tts::audio::effects)
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('tts_effect', 'none');
@@ -0,0 +1,2 @@
+-- Mastering preset applied to finished narration (none, clean, cozy_room, campfire)
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('audio_mastering', 'none');
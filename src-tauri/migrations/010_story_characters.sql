@@ -0,0 +1,10 @@
+-- Per-story cast: which Kokoro voice and effect each speaking character uses
+CREATE TABLE IF NOT EXISTS story_characters (
+    story_id TEXT NOT NULL REFERENCES stories(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    voice TEXT NOT NULL,
+    effect TEXT NOT NULL DEFAULT 'none',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (story_id, name)
+);
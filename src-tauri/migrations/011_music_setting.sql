@@ -0,0 +1,2 @@
+-- Mix a genre music bed under narration once turned on in Settings
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('music_enabled', 'false');
@@ -0,0 +1,7 @@
+-- Seed for each story's procedurally generated theme tune, so every part
+-- of a story (and every regeneration) plays the same melody
+CREATE TABLE IF NOT EXISTS story_music (
+    story_id TEXT PRIMARY KEY REFERENCES stories(id) ON DELETE CASCADE,
+    seed INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
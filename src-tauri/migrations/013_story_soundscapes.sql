@@ -0,0 +1,10 @@
+-- Ambient soundscape for each story: detected from the story text the first
+-- time audio is generated, or picked by the user ('none' turns it off)
+CREATE TABLE IF NOT EXISTS story_soundscapes (
+    story_id TEXT PRIMARY KEY REFERENCES stories(id) ON DELETE CASCADE,
+    soundscape TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Ambience stays off until turned on in Settings
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('ambience_enabled', 'false');
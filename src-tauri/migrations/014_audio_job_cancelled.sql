@@ -0,0 +1,57 @@
+-- Allow audio jobs to be cancelled. SQLite cannot alter a CHECK constraint,
+-- so the table is rebuilt.
+--
+-- The app applies this at startup and the SQL plugin may apply it again
+-- later, inside a transaction with foreign keys on, so it must be safe to
+-- run twice. Dropping audio_jobs deletes the rows that reference it, so the
+-- queue, attempt and sentence tables (created here if 015, 018 and 019 have
+-- not run yet) are set aside and restored around the rebuild.
+
+CREATE TABLE IF NOT EXISTS audio_job_queue (
+    job_id TEXT PRIMARY KEY REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    priority INTEGER NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE TABLE IF NOT EXISTS audio_job_attempts (
+    job_id TEXT PRIMARY KEY REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS audio_job_sentences (
+    job_id TEXT NOT NULL REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    sentence_index INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    wav_path TEXT NOT NULL,
+    duration_secs REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (job_id, sentence_index)
+);
+CREATE TEMP TABLE audio_job_queue_saved AS SELECT * FROM audio_job_queue;
+CREATE TEMP TABLE audio_job_attempts_saved AS SELECT * FROM audio_job_attempts;
+CREATE TEMP TABLE audio_job_sentences_saved AS SELECT * FROM audio_job_sentences;
+
+CREATE TABLE audio_jobs_new (
+    id TEXT PRIMARY KEY,
+    story_part_id TEXT NOT NULL REFERENCES story_parts(id) ON DELETE CASCADE,
+    voice_path TEXT,
+    music_path TEXT,
+    final_path TEXT,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK(status IN ('pending','voice_generating','music_generating','mixing','complete','failed','cancelled')),
+    error_message TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO audio_jobs_new
+    SELECT id, story_part_id, voice_path, music_path, final_path, status, error_message, created_at, updated_at
+    FROM audio_jobs;
+DROP TABLE audio_jobs;
+ALTER TABLE audio_jobs_new RENAME TO audio_jobs;
+
+INSERT OR IGNORE INTO audio_job_queue SELECT * FROM temp.audio_job_queue_saved;
+INSERT OR IGNORE INTO audio_job_attempts SELECT * FROM temp.audio_job_attempts_saved;
+INSERT OR IGNORE INTO audio_job_sentences SELECT * FROM temp.audio_job_sentences_saved;
+DROP TABLE temp.audio_job_queue_saved;
+DROP TABLE temp.audio_job_attempts_saved;
+DROP TABLE temp.audio_job_sentences_saved;
@@ -0,0 +1,10 @@
+-- Order and priority for pending audio jobs. The scheduler starts pending
+-- jobs by position; a pending job without a row here runs after those with one.
+CREATE TABLE IF NOT EXISTS audio_job_queue (
+    job_id TEXT PRIMARY KEY REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    priority INTEGER NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('audio_job_concurrency', '1');
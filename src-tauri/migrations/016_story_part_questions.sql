@@ -0,0 +1,9 @@
+-- Discussion questions suggested for a story part, printed after the part in
+-- the PDF export. Regenerating replaces a part's questions.
+CREATE TABLE IF NOT EXISTS story_part_questions (
+    story_part_id TEXT NOT NULL REFERENCES story_parts(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    question TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (story_part_id, position)
+);
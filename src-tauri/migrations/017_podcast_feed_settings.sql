@@ -0,0 +1,5 @@
+-- Local podcast feed of the library: off until turned on, and bound to one
+-- network address (this computer only by default)
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('podcast_feed_enabled', 'false');
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('podcast_feed_address', '127.0.0.1');
+INSERT OR IGNORE INTO app_settings (key, value) VALUES ('podcast_feed_port', '8765');
@@ -0,0 +1,6 @@
+-- How many times each audio job has been started, so a job that keeps being
+-- interrupted (say, by a crash while it runs) is not resumed forever
+CREATE TABLE IF NOT EXISTS audio_job_attempts (
+    job_id TEXT PRIMARY KEY REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
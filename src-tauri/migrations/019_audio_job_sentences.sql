@@ -0,0 +1,11 @@
+-- Sentences the audio pipeline has finished for a job, so an interrupted job
+-- resumes after the last finished sentence
+CREATE TABLE IF NOT EXISTS audio_job_sentences (
+    job_id TEXT NOT NULL REFERENCES audio_jobs(id) ON DELETE CASCADE,
+    sentence_index INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    wav_path TEXT NOT NULL,
+    duration_secs REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (job_id, sentence_index)
+);
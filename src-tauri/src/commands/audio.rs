@@ -1,7 +1,18 @@
-use crate::db::models::AudioJob;
-use crate::db::queries;
-use crate::services::pipeline::{AudioPipeline, PipelineProgress};
+use crate::db::models::{AudioJob, QueuedAudioJob, StoryCharacter};
+use crate::db::queries::{self, VoiceSettings};
+use crate::db::open_db;
+use crate::services::ollama::{OllamaClient, StoryToken};
+use crate::services::{ambience, dialogue, prompts};
+use crate::services::pipeline::{
+    parse_effect, plan_sentences, settled_len, AudioPipeline, PipelineProgress, StoryContext,
+};
+use crate::services::scheduler::{AudioScheduler, ClaimedJob, JobPriority};
+use crate::services::stream::PcmStream;
+use kokoro_tts::audio::ambience::Soundscape;
+use kokoro_tts::audio::effects::VoiceEffect;
+use kokoro_tts::CancelToken;
 use rusqlite::Connection;
+use tauri::ipc::{Channel, InvokeResponseBody};
 use tauri::{Emitter, Manager};
 use log;
 
@@ -11,72 +22,458 @@ pub struct AudioJobInfo {
     pub status: String,
 }
 
+/// Queue a pending audio job. `priority` is `play_now` (the default) or
+/// `background`; the job starts as soon as a slot is free. With `on_audio`,
+/// the narration is streamed to the caller as binary PCM frames (see
+/// [`PcmStream`]) while it is generated.
 #[tauri::command]
 pub async fn start_audio_generation(
     job_id: String,
     part_id: String,
-    text: String,
+    priority: Option<String>,
+    on_audio: Option<Channel<InvokeResponseBody>>,
     app: tauri::AppHandle,
 ) -> Result<AudioJobInfo, String> {
-    let app_clone = app.clone();
-    let job_id_clone = job_id.clone();
-    let part_id_clone = part_id.clone();
+    let priority = match priority.as_deref() {
+        Some(name) => {
+            JobPriority::from_name(name).ok_or_else(|| format!("Unknown priority '{}'", name))?
+        }
+        None => JobPriority::PlayNow,
+    };
+    let scheduler = AudioScheduler::global();
+    // Attached before the job is queued so it cannot start without it
+    if let Some(channel) = on_audio {
+        scheduler.attach_stream(&job_id, PcmStream::new(channel));
+    }
+    let queued = open_db(&app).and_then(|conn| {
+        queries::enqueue_audio_job(&conn, &job_id, priority.value())
+            .map_err(|e| format!("Failed to queue audio job: {}", e))?;
+        Ok(conn)
+    });
+    let conn = match queued {
+        Ok(conn) => conn,
+        Err(e) => {
+            if let Some(stream) = scheduler.detach_stream(&job_id) {
+                stream.fail(&e);
+            }
+            return Err(e);
+        }
+    };
+    log::info!("Queued audio job {} for part {} ({:?})", job_id, part_id, priority);
+    pump_queue(&app, &conn)?;
+
+    Ok(AudioJobInfo {
+        job_id,
+        status: "pending".to_string(),
+    })
+}
+
+/// Stop a job. A running job stops at the next sentence boundary and reports
+/// a `cancelled` stage, keeping the sentences it finished for a retry; a
+/// queued job is cancelled straight away.
+#[tauri::command]
+pub async fn cancel_audio_generation(job_id: String, app: tauri::AppHandle) -> Result<(), String> {
+    if AudioScheduler::global().cancel(&job_id) {
+        log::info!("Cancelling audio job {}", job_id);
+        return Ok(());
+    }
+
+    let conn = open_db(&app)?;
+    let was_queued = queries::finish_pending_audio_job(&conn, &job_id, "cancelled")
+        .map_err(|e| format!("Failed to cancel audio job: {}", e))?;
+    if !was_queued {
+        return Err(format!("Audio job {} is not queued or running", job_id));
+    }
+    let scheduler = AudioScheduler::global();
+    // Cancelling is not an error for a player waiting on the job
+    if let Some(stream) = scheduler.detach_stream(&job_id) {
+        stream.end();
+    }
+    scheduler.finish(&job_id);
+    log::info!("Cancelled queued audio job {}", job_id);
+    let _ = app.emit(
+        "audio-progress",
+        PipelineProgress {
+            job_id,
+            stage: "cancelled".to_string(),
+            progress: 0.0,
+            error: None,
+        },
+    );
+    Ok(())
+}
+
+/// Jobs that are running, then those waiting in the order they will start.
+#[tauri::command]
+pub async fn list_audio_queue(app: tauri::AppHandle) -> Result<Vec<QueuedAudioJob>, String> {
+    let conn = open_db(&app)?;
+    queries::get_audio_queue(&conn).map_err(|e| format!("Failed to read audio queue: {}", e))
+}
+
+/// Move a waiting job to `position` among the waiting jobs (0 is next).
+#[tauri::command]
+pub async fn reorder_audio_job(
+    job_id: String,
+    position: usize,
+    app: tauri::AppHandle,
+) -> Result<Vec<QueuedAudioJob>, String> {
+    let conn = open_db(&app)?;
+    queries::move_audio_job(&conn, &job_id, position)
+        .map_err(|_| format!("Audio job {} is not waiting in the queue", job_id))?;
+    queries::get_audio_queue(&conn).map_err(|e| format!("Failed to read audio queue: {}", e))
+}
+
+/// Queue a failed or cancelled job again. A failed job resumes after its
+/// last finished sentence.
+#[tauri::command]
+pub async fn retry_audio_job(job_id: String, app: tauri::AppHandle) -> Result<AudioJobInfo, String> {
+    let conn = open_db(&app)?;
+    let reset = queries::reset_audio_job(&conn, &job_id)
+        .map_err(|e| format!("Failed to reset audio job: {}", e))?;
+    if !reset {
+        return Err(format!("Audio job {} has not failed or been cancelled", job_id));
+    }
+    queries::enqueue_audio_job(&conn, &job_id, JobPriority::Background.value())
+        .map_err(|e| format!("Failed to queue audio job: {}", e))?;
+    pump_queue(&app, &conn)?;
 
-    // Get paths
+    Ok(AudioJobInfo {
+        job_id,
+        status: "pending".to_string(),
+    })
+}
+
+/// Write a new story with Ollama and narrate it as it is written: each
+/// sentence is spoken to `on_audio` as soon as it is complete, while the
+/// tokens stream to `on_token` as with `generate_story_text`. The story is
+/// saved as part 1 of `story_id` with audio job `job_id`, which is queued
+/// once the text is finished to master and mix the sentences already
+/// spoken. Returns the story text. Cancelling `job_id` while the story is
+/// being written stops Ollama and discards the story.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn narrate_new_story(
+    story_id: String,
+    part_id: String,
+    job_id: String,
+    title: String,
+    genre_id: String,
+    genre_name: String,
+    genre_description: String,
+    title_hint: Option<String>,
+    on_token: Channel<StoryToken>,
+    on_audio: Channel<InvokeResponseBody>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    log::info!("narrate_new_story: genre={}, hint={:?}", genre_name, title_hint);
+    let conn = open_db(&app)?;
+    queries::insert_story(&conn, &story_id, &title, &genre_id)
+        .and_then(|_| queries::insert_story_part(&conn, &part_id, &story_id, 1, "", "audio_processing"))
+        .and_then(|_| queries::insert_audio_job(&conn, &job_id, &part_id))
+        .and_then(|_| queries::update_audio_job_status(&conn, &job_id, "voice_generating", None))
+        .map_err(|e| format!("Failed to create story: {}", e))?;
+
+    let voice_settings = queries::get_voice_settings(&conn).ok();
+    let narrator = voice_settings
+        .as_ref()
+        .map_or("af_nova", |vs| vs.voice.as_str())
+        .to_string();
+    let scheduler = AudioScheduler::global();
+    let cancel = scheduler.register(&job_id);
+    let pipeline = audio_pipeline(&app)?;
+    let mut narration = pipeline.narrate_live(
+        &part_id,
+        voice_settings.as_ref(),
+        PcmStream::new(on_audio),
+        cancel.clone(),
+    );
+
+    let client = OllamaClient::new();
+    let system = prompts::build_system_prompt(&genre_name, &genre_description);
+    let prompt = prompts::build_story_prompt(title_hint.as_deref());
+    let (tokens, mut arrived) = tokio::sync::mpsc::unbounded_channel::<String>();
+    // Cancelling the job also drops the Ollama request, which stops the writing
+    let write_cancel = cancel.clone();
+    let write = async move {
+        tokio::select! {
+            written = client.generate_streaming_with("gemma3:4b", &system, &prompt, &on_token, |token| {
+                let _ = tokens.send(token.to_string());
+            }) => written,
+            _ = cancelled(&write_cancel) => Err("Story writing was cancelled".to_string()),
+        }
+    };
+    let narrate = async {
+        // Its own connection: a borrowed one would be held across awaits
+        let db = open_db(&app)?;
+        let mut text = String::new();
+        let mut settled = 0;
+        let mut narrating = true;
+        while let Some(token) = arrived.recv().await {
+            text.push_str(&token);
+            let now_settled = settled_len(&text);
+            if !narrating || now_settled == settled {
+                continue;
+            }
+            settled = now_settled;
+            // Keep the partial text so an interrupted job has something to resume
+            let _ = queries::update_story_part_content(&db, &part_id, &text[..settled]);
+            let cast = load_cast(&db, &story_id, &text[..settled], &narrator).unwrap_or_default();
+            if let Err(e) = narration.speak_ready(&text, &cast, false).await {
+                if !cancel.is_cancelled() {
+                    log::warn!("Live narration stopped: {}", e);
+                    narration.fail(&e);
+                }
+                narrating = false;
+            }
+        }
+        Ok::<_, String>(narrating)
+    };
+    let (written, narrating) = tokio::join!(write, narrate);
+    let narrating = narrating.unwrap_or_else(|e| {
+        log::warn!("Live narration could not start: {}", e);
+        false
+    });
+
+    let text = match written {
+        Ok(text) if !text.trim().is_empty() => text,
+        result => {
+            let err = result.err().unwrap_or_else(|| "Ollama wrote an empty story".to_string());
+            scheduler.finish(&job_id);
+            if cancel.is_cancelled() {
+                narration.end();
+            } else {
+                narration.fail(&err);
+            }
+            pipeline.discard_segments(&part_id).await;
+            let _ = queries::delete_story(&conn, &story_id);
+            return Err(err);
+        }
+    };
+    if let Err(e) = queries::update_story_part_content(&conn, &part_id, &text) {
+        let err = format!("Failed to save story: {}", e);
+        scheduler.finish(&job_id);
+        narration.fail(&err);
+        return Err(err);
+    }
+
+    if narrating {
+        let cast = load_cast(&conn, &story_id, &text, &narrator).unwrap_or_default();
+        if let Err(e) = narration.speak_ready(&text, &cast, true).await {
+            if !cancel.is_cancelled() {
+                log::warn!("Live narration stopped: {}", e);
+                narration.fail(&e);
+            }
+        }
+    }
+    narration.end();
+    scheduler.finish(&job_id);
+
+    if cancel.is_cancelled() {
+        log::info!("Audio job {} cancelled", job_id);
+        let _ = queries::update_audio_job_status(&conn, &job_id, "cancelled", None);
+        let _ = queries::update_story_part_audio(&conn, &part_id, "text_ready", None);
+        let _ = app.emit(
+            "audio-progress",
+            PipelineProgress {
+                job_id,
+                stage: "cancelled".to_string(),
+                progress: 0.0,
+                error: None,
+            },
+        );
+        return Ok(text);
+    }
+
+    // The queued job reuses every sentence already spoken
+    queries::update_audio_job_status(&conn, &job_id, "pending", None)
+        .and_then(|_| queries::enqueue_audio_job(&conn, &job_id, JobPriority::PlayNow.value()))
+        .map_err(|e| format!("Failed to queue audio job: {}", e))?;
+    pump_queue(&app, &conn)?;
+    Ok(text)
+}
+
+/// Resolves once `cancel` is triggered.
+async fn cancelled(cancel: &CancelToken) {
+    while !cancel.is_cancelled() {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
+
+/// Queue the jobs left running when the app last closed and start the
+/// queue. Each interrupted job reuses the sentences it finished whose text,
+/// voice, speed and effect are unchanged (see [`crate::services::pipeline::segment_key`]); a job
+/// interrupted [`queries::MAX_AUDIO_JOB_ATTEMPTS`] times fails instead.
+pub fn resume_interrupted_jobs(app: &tauri::AppHandle, conn: &Connection) -> Result<usize, String> {
+    let count = queries::requeue_interrupted_audio_jobs(conn)
+        .map_err(|e| format!("Failed to requeue interrupted jobs: {}", e))?;
+    pump_queue(app, conn)?;
+    Ok(count)
+}
+
+fn audio_pipeline(app: &tauri::AppHandle) -> Result<AudioPipeline, String> {
     let app_data_dir = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    let audio_dir = app_data_dir.join("audio");
-    let db_path = app_data_dir.join("pattikadhai.db");
-
     let models_dir = super::resolve_models_dir();
     log::info!("Audio pipeline models dir: {}", models_dir.display());
+    Ok(AudioPipeline::new(
+        app_data_dir.join("audio"),
+        models_dir,
+        super::resolve_music_dir(),
+        super::resolve_sfx_dir(),
+        app_data_dir.join("pattikadhai.db"),
+    ))
+}
+
+/// Start queued jobs until the concurrency limit is reached or the queue
+/// is empty.
+fn pump_queue(app: &tauri::AppHandle, conn: &Connection) -> Result<(), String> {
+    let scheduler = AudioScheduler::global();
+    let limit = queries::get_audio_job_concurrency(conn)
+        .map_err(|e| format!("Failed to read concurrency setting: {}", e))?;
+
+    loop {
+        let claimed = scheduler.claim_next(conn, limit)?;
+        if claimed.is_empty() {
+            return Ok(());
+        }
+        for job in claimed {
+            match queries::get_story_part_content(conn, &job.part_id) {
+                Ok(text) => {
+                    log::info!("Starting audio job {} for part {}", job.job_id, job.part_id);
+                    spawn_audio_job(app.clone(), job, text)?;
+                }
+                Err(e) => {
+                    log::warn!("Cannot start job {}: {}", job.job_id, e);
+                    let _ = queries::update_audio_job_status(
+                        conn,
+                        &job.job_id,
+                        "failed",
+                        Some("Story part no longer exists"),
+                    );
+                    scheduler.finish(&job.job_id);
+                }
+            }
+        }
+    }
+}
+
+/// Run the audio pipeline for a claimed job in the background, recording
+/// the result and starting the next queued job when it stops.
+fn spawn_audio_job(app: tauri::AppHandle, job: ClaimedJob, text: String) -> Result<(), String> {
+    let ClaimedJob {
+        job_id,
+        part_id,
+        cancel,
+        stream,
+    } = job;
+
+    let db_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("pattikadhai.db");
+    let pipeline = audio_pipeline(&app)?;
 
     // Spawn background task
-    tokio::spawn(async move {
-        let pipeline = AudioPipeline::new(audio_dir, models_dir);
-
-        // Read voice settings and update job status
-        let voice_settings = if let Ok(conn) = Connection::open(&db_path) {
-            let _ = queries::update_audio_job_status(&conn, &job_id_clone, "voice_generating", None);
-            let _ = queries::update_story_part_audio(&conn, &part_id_clone, "audio_processing", None);
-            queries::get_voice_settings(&conn).ok()
+    tauri::async_runtime::spawn(async move {
+
+        // Read voice settings and the story's cast, and update job status
+        let (voice_settings, story) = if let Ok(conn) = Connection::open(&db_path) {
+            let _ = queries::update_audio_job_status(&conn, &job_id, "voice_generating", None);
+            let _ = queries::update_story_part_audio(&conn, &part_id, "audio_processing", None);
+            let voice_settings = queries::get_voice_settings(&conn).ok();
+            let narrator = voice_settings.as_ref().map_or("af_nova", |vs| vs.voice.as_str());
+            let story = load_story_context(&conn, &part_id, &text, narrator)
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load story details: {}", e);
+                    StoryContext::default()
+                });
+            (voice_settings, story)
         } else {
-            None
+            (None, StoryContext::default())
         };
 
         match pipeline
-            .process(&job_id_clone, &part_id_clone, &text, &app_clone, voice_settings.as_ref())
+            .process(
+                &job_id,
+                &part_id,
+                &text,
+                &app,
+                voice_settings.as_ref(),
+                &story,
+                &cancel,
+                stream.as_ref(),
+            )
             .await
         {
             Ok(result) => {
                 if let Ok(conn) = Connection::open(&db_path) {
                     let _ = queries::update_audio_job_status(
                         &conn,
-                        &job_id_clone,
+                        &job_id,
                         "complete",
                         None,
                     );
                     let _ = queries::update_audio_job_paths(
                         &conn,
-                        &job_id_clone,
-                        None,
+                        &job_id,
+                        Some(&result.voice_path),
                         Some(&result.audio_path),
                     );
+                    let _ = queries::update_audio_job_music_path(
+                        &conn,
+                        &job_id,
+                        result.music_path.as_deref(),
+                    );
                     let _ = queries::update_story_part_audio(
                         &conn,
-                        &part_id_clone,
+                        &part_id,
                         "audio_ready",
                         Some(&result.audio_path),
                     );
                 }
             }
+            Err(_) if cancel.is_cancelled() => {
+                log::info!("Audio job {} cancelled", job_id);
+                if let Some(stream) = &stream {
+                    stream.end();
+                }
+                if let Ok(conn) = Connection::open(&db_path) {
+                    let _ = queries::update_audio_job_status(&conn, &job_id, "cancelled", None);
+                    // A cancelled run never replaces the part's final file, so
+                    // audio from an earlier run is still playable
+                    let earlier = pipeline.final_path(&part_id);
+                    let _ = if earlier.exists() {
+                        queries::update_story_part_audio(
+                            &conn,
+                            &part_id,
+                            "audio_ready",
+                            Some(&earlier.to_string_lossy()),
+                        )
+                    } else {
+                        queries::update_story_part_audio(&conn, &part_id, "text_ready", None)
+                    };
+                }
+                let _ = app.emit(
+                    "audio-progress",
+                    PipelineProgress {
+                        job_id: job_id.clone(),
+                        stage: "cancelled".to_string(),
+                        progress: 0.0,
+                        error: None,
+                    },
+                );
+            }
             Err(err) => {
-                let _ = app_clone.emit(
+                if let Some(stream) = &stream {
+                    stream.fail(&err);
+                }
+                let _ = app.emit(
                     "audio-progress",
                     PipelineProgress {
-                        job_id: job_id_clone.clone(),
+                        job_id: job_id.clone(),
                         stage: "failed".to_string(),
                         progress: 0.0,
                         error: Some(err.clone()),
@@ -85,27 +482,83 @@ pub async fn start_audio_generation(
                 if let Ok(conn) = Connection::open(&db_path) {
                     let _ = queries::update_audio_job_status(
                         &conn,
-                        &job_id_clone,
+                        &job_id,
                         "failed",
                         Some(&err),
                     );
                     let _ = queries::update_story_part_audio(
                         &conn,
-                        &part_id_clone,
+                        &part_id,
                         "audio_failed",
                         None,
                     );
                 }
             }
         }
+
+        AudioScheduler::global().finish(&job_id);
+        match Connection::open(&db_path) {
+            Ok(conn) => {
+                if let Err(e) = pump_queue(&app, &conn) {
+                    log::warn!("Failed to start the next audio job: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to open DB to start the next audio job: {}", e),
+        }
     });
 
-    Ok(AudioJobInfo {
-        job_id,
-        status: "voice_generating".to_string(),
+    Ok(())
+}
+
+/// Look up the part's story, genre, theme seed and soundscape (detecting one
+/// from `text` the first time), attribute the dialogue in `text`, and give
+/// any new speakers a voice.
+fn load_story_context(
+    conn: &Connection,
+    part_id: &str,
+    text: &str,
+    narrator_voice: &str,
+) -> rusqlite::Result<StoryContext> {
+    let story_id = queries::get_story_id_for_part(conn, part_id)?;
+    let genre_id = queries::get_story_genre(conn, &story_id)?;
+    let characters = load_cast(conn, &story_id, text, narrator_voice)?;
+    let music_seed = queries::get_or_create_music_seed(conn, &story_id)?;
+    let soundscape = match queries::get_story_soundscape(conn, &story_id)? {
+        // A stored choice wins, including 'none'
+        Some(name) => Soundscape::from_name(&name),
+        None => {
+            let detected = ambience::detect_soundscape(text);
+            if let Some(scape) = detected {
+                queries::set_story_soundscape(conn, &story_id, scape.name())?;
+            }
+            detected
+        }
+    };
+    Ok(StoryContext {
+        story_id,
+        genre_id,
+        characters,
+        music_seed: Some(music_seed),
+        soundscape,
     })
 }
 
+/// Attribute the dialogue in `text`, give any new speakers a voice, and
+/// return the story's cast.
+fn load_cast(
+    conn: &Connection,
+    story_id: &str,
+    text: &str,
+    narrator_voice: &str,
+) -> rusqlite::Result<Vec<StoryCharacter>> {
+    let existing = queries::get_story_characters(conn, story_id)?;
+    let names = dialogue::speakers(&dialogue::segment_dialogue(text));
+    for character in dialogue::assign_voices(story_id, &names, &existing, narrator_voice) {
+        queries::insert_story_character(conn, &character)?;
+    }
+    queries::get_story_characters(conn, story_id)
+}
+
 #[tauri::command]
 pub async fn get_audio_job_status(
     job_id: String,
@@ -139,3 +592,112 @@ pub async fn get_audio_job_status(
 
     Ok(result)
 }
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SentencePhonemes {
+    pub index: usize,
+    pub text: String,
+    pub phonemes: String,
+}
+
+/// Phonemize the text of part `part_id` sentence by sentence, planned the
+/// way the audio pipeline plans it (dialogue split out, cues removed), so the
+/// indices match the sentences [`synthesize_phonemes`] replaces.
+#[tauri::command]
+pub async fn phonemize_text(
+    part_id: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<SentencePhonemes>, String> {
+    let conn = open_db(&app)?;
+    let (text, characters, voice_settings) = load_part_reading(&conn, &part_id)?;
+    tokio::task::spawn_blocking(move || {
+        let narrator = voice_settings.as_ref().map(|vs| vs.voice.as_str());
+        let narrator_effect = narrator_effect(voice_settings.as_ref());
+        plan_sentences(&text, &characters, narrator, narrator_effect)
+            .into_iter()
+            .enumerate()
+            .map(|(index, sentence)| {
+                let phonemes = kokoro_tts::phonemize::phonemize(&sentence.text, "en-us")
+                    .map_err(|e| format!("Phonemization failed: {}", e))?;
+                Ok(SentencePhonemes {
+                    index,
+                    text: sentence.text,
+                    phonemes,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Phonemize task panicked: {}", e))?
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PhonemeAudio {
+    pub wav_path: String,
+    pub duration_secs: f64,
+    /// Job queued to re-render the part with the new sentence
+    pub job_id: String,
+}
+
+/// Replace sentence `index` of part `part_id` (as numbered by
+/// [`phonemize_text`]) with audio synthesized from user-edited IPA, in the
+/// sentence's voice and effect. The sentence goes into the part's segment
+/// cache and a job is queued to master, mix and re-time the part with it.
+#[tauri::command]
+pub async fn synthesize_phonemes(
+    part_id: String,
+    index: usize,
+    phonemes: String,
+    app: tauri::AppHandle,
+) -> Result<PhonemeAudio, String> {
+    let conn = open_db(&app)?;
+    let (text, characters, voice_settings) = load_part_reading(&conn, &part_id)?;
+    let narrator = voice_settings.as_ref().map(|vs| vs.voice.as_str());
+    let speed = voice_settings.as_ref().and_then(|vs| vs.speed);
+    let planned = plan_sentences(
+        &text,
+        &characters,
+        narrator,
+        narrator_effect(voice_settings.as_ref()),
+    );
+    let sentence = planned
+        .get(index)
+        .ok_or_else(|| format!("Part {} has no sentence {}", part_id, index))?;
+
+    let pipeline = audio_pipeline(&app)?;
+    let (wav_path, duration_secs) = pipeline
+        .replace_sentence(&part_id, sentence, &phonemes, speed)
+        .await?;
+
+    let job_id = uuid::Uuid::new_v4().to_string();
+    queries::insert_audio_job(&conn, &job_id, &part_id)
+        .and_then(|_| queries::enqueue_audio_job(&conn, &job_id, JobPriority::PlayNow.value()))
+        .map_err(|e| format!("Failed to queue audio job: {}", e))?;
+    pump_queue(&app, &conn)?;
+
+    Ok(PhonemeAudio {
+        wav_path,
+        duration_secs,
+        job_id,
+    })
+}
+
+/// A part's text with the story's cast and the voice settings, everything
+/// that decides who reads each sentence.
+fn load_part_reading(
+    conn: &Connection,
+    part_id: &str,
+) -> Result<(String, Vec<StoryCharacter>, Option<VoiceSettings>), String> {
+    let text = queries::get_story_part_content(conn, part_id)
+        .map_err(|_| format!("Story part {} not found", part_id))?;
+    let characters = queries::get_story_id_for_part(conn, part_id)
+        .and_then(|story_id| queries::get_story_characters(conn, &story_id))
+        .map_err(|e| format!("Failed to read the story's cast: {}", e))?;
+    Ok((text, characters, queries::get_voice_settings(conn).ok()))
+}
+
+fn narrator_effect(voice_settings: Option<&VoiceSettings>) -> VoiceEffect {
+    voice_settings
+        .map(|vs| parse_effect(&vs.effect))
+        .unwrap_or(VoiceEffect::None)
+}
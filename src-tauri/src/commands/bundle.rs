@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::db::models::{AudioJob, Story, StoryCharacter, StoryPart};
+use crate::db::{open_db, queries};
+use crate::services::bundle::{self, BundleCharacter, BundlePart, Manifest};
+use crate::services::pipeline;
+use rusqlite::Connection;
+use tauri::Manager;
+
+/// Package a story's rows with its parts' final audio and timing into a
+/// `.pattikadhai` archive at `path`, for moving it to another computer.
+#[tauri::command]
+pub async fn export_story_bundle(
+    story_id: String,
+    path: String,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let (mut manifest, audio_paths) = read_rows(&open_db(&app)?, &story_id)?;
+    let audio_dir = audio_dir(&app)?;
+
+    let mut files = Vec::new();
+    for (part, (audio_path, produced)) in manifest.parts.iter_mut().zip(audio_paths) {
+        // Audio that has gone missing on disk is left out; the part imports
+        // as text only
+        if let Some(bytes) = read_optional(audio_path.map(PathBuf::from)).await? {
+            let name = format!("audio/{}.wav", part.id);
+            files.push((name.clone(), bytes));
+            part.audio_file = Some(name);
+            for (job, produced) in manifest.audio_jobs.iter_mut().zip(produced) {
+                job.produced_audio |= produced;
+            }
+        }
+        let timing = pipeline::timing_path(&audio_dir, &part.id);
+        if let Some(bytes) = read_optional(Some(timing)).await? {
+            let name = format!("timing/{}.json", part.id);
+            files.push((name.clone(), bytes));
+            part.timing_file = Some(name);
+        }
+    }
+
+    log::info!("Exporting story {} as a bundle with {} files to {}", story_id, files.len(), path);
+    let bytes = tokio::task::spawn_blocking(move || bundle::pack(manifest, files))
+        .await
+        .map_err(|e| format!("Bundle task panicked: {}", e))??;
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Import a `.pattikadhai` archive and return the new story's ID. Files are
+/// checked against their checksums first. The story, its parts and jobs get
+/// new IDs, so importing twice makes two copies, and a genre this computer
+/// lacks is added.
+#[tauri::command]
+pub async fn import_story_bundle(path: String, app: tauri::AppHandle) -> Result<String, String> {
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let unpacked = tokio::task::spawn_blocking(move || bundle::unpack(&bytes))
+        .await
+        .map_err(|e| format!("Bundle task panicked: {}", e))??;
+    let mut manifest = unpacked.manifest;
+    if manifest.schema_version > bundle::SCHEMA_VERSION {
+        log::warn!(
+            "Importing a bundle from schema {} into schema {}; newer fields are dropped",
+            manifest.schema_version,
+            bundle::SCHEMA_VERSION
+        );
+    }
+
+    // Part IDs name files on disk, so none from the archive are kept
+    manifest.reassign_ids();
+
+    // Files go under this computer's audio directory, named for the part
+    let audio_dir = audio_dir(&app)?;
+    tokio::fs::create_dir_all(&audio_dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", audio_dir.display(), e))?;
+    let mut written = Vec::new();
+    let mut audio_paths = Vec::new();
+    let mut result = Ok(());
+    for part in &manifest.parts {
+        match write_part_files(&audio_dir, part, &unpacked.files, &mut written).await {
+            Ok(audio_path) => audio_paths.push(audio_path),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    if result.is_ok() {
+        result = open_db(&app).and_then(|mut conn| insert_rows(&mut conn, &manifest, &audio_paths));
+    }
+    if let Err(e) = result {
+        for path in written {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        return Err(e);
+    }
+    log::info!(
+        "Imported story {} with {} parts from {}",
+        manifest.story.id,
+        manifest.parts.len(),
+        path
+    );
+    Ok(manifest.story.id)
+}
+
+/// A part's audio path, and for each of the story's jobs whether it wrote
+/// that audio.
+type PartAudio = (Option<String>, Vec<bool>);
+
+/// A manifest of the story's rows, with each part's audio.
+fn read_rows(conn: &Connection, story_id: &str) -> Result<(Manifest, Vec<PartAudio>), String> {
+    let story = queries::get_story(conn, story_id)
+        .map_err(|e| format!("Story not found: {}", e))?;
+    let genre = queries::get_genre(conn, &story.genre_id)
+        .map_err(|e| format!("Failed to read genre: {}", e))?;
+    let parts = queries::get_story_parts(conn, story_id)
+        .map_err(|e| format!("Failed to read story parts: {}", e))?;
+    let jobs = queries::get_story_audio_jobs(conn, story_id)
+        .map_err(|e| format!("Failed to read audio jobs: {}", e))?;
+
+    let mut manifest = Manifest::new(&story, genre.as_ref(), &parts, &jobs);
+    for part in &mut manifest.parts {
+        part.questions = queries::get_part_questions(conn, &part.id)
+            .map_err(|e| format!("Failed to read discussion questions: {}", e))?;
+    }
+    manifest.characters = queries::get_story_characters(conn, story_id)
+        .map_err(|e| format!("Failed to read story characters: {}", e))?
+        .into_iter()
+        .map(|c| BundleCharacter {
+            name: c.name,
+            voice: c.voice,
+            effect: c.effect,
+        })
+        .collect();
+    manifest.music_seed = queries::get_music_seed(conn, story_id)
+        .map_err(|e| format!("Failed to read story music: {}", e))?;
+    manifest.soundscape = queries::get_story_soundscape(conn, story_id)
+        .map_err(|e| format!("Failed to read story soundscape: {}", e))?;
+
+    let audio_paths = parts
+        .iter()
+        .map(|part| {
+            // Which of the part's jobs wrote its current audio
+            let produced = jobs
+                .iter()
+                .map(|job| {
+                    job.story_part_id == part.id
+                        && job.final_path.is_some()
+                        && job.final_path == part.audio_path
+                })
+                .collect();
+            (part.audio_path.clone(), produced)
+        })
+        .collect();
+    Ok((manifest, audio_paths))
+}
+
+/// Add the genre if needed, then the story, parts, jobs and the story's
+/// cast, music and soundscape, all or nothing. `audio_paths` is where each
+/// part's audio was written.
+fn insert_rows(
+    conn: &mut Connection,
+    manifest: &Manifest,
+    audio_paths: &[Option<String>],
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start import: {}", e))?;
+    let genre_id = &manifest.story.genre_id;
+    let has_genre = queries::get_genre(&tx, genre_id)
+        .map_err(|e| format!("Failed to read genre: {}", e))?
+        .is_some();
+    if !has_genre {
+        let (name, description, icon) = match &manifest.genre {
+            Some(g) => (g.name.as_str(), g.description.as_str(), g.icon.as_deref()),
+            None => (genre_id.as_str(), "", None),
+        };
+        queries::insert_genre(&tx, genre_id, name, description, icon)
+            .map_err(|e| format!("Failed to add genre {}: {}", genre_id, e))?;
+    }
+
+    let story = &manifest.story;
+    queries::restore_story(
+        &tx,
+        &Story {
+            id: story.id.clone(),
+            title: story.title.clone(),
+            genre_id: story.genre_id.clone(),
+            status: story.status.clone(),
+            is_sample: 0,
+            created_at: story.created_at.clone(),
+            updated_at: story.updated_at.clone(),
+        },
+    )
+    .map_err(|e| format!("Failed to import story: {}", e))?;
+    for (part, audio_path) in manifest.parts.iter().zip(audio_paths) {
+        queries::restore_story_part(
+            &tx,
+            &StoryPart {
+                id: part.id.clone(),
+                story_id: story.id.clone(),
+                part_number: part.part_number,
+                content: part.content.clone(),
+                audio_path: audio_path.clone(),
+                status: part.status.clone(),
+                created_at: part.created_at.clone(),
+                updated_at: part.updated_at.clone(),
+            },
+        )
+        .map_err(|e| format!("Failed to import part {}: {}", part.part_number, e))?;
+        queries::set_part_questions(&tx, &part.id, &part.questions)
+            .map_err(|e| format!("Failed to import discussion questions: {}", e))?;
+    }
+    for job in &manifest.audio_jobs {
+        let final_path = manifest
+            .parts
+            .iter()
+            .zip(audio_paths)
+            .find(|(part, _)| part.id == job.story_part_id)
+            .and_then(|(_, path)| path.clone())
+            .filter(|_| job.produced_audio);
+        queries::restore_audio_job(
+            &tx,
+            &AudioJob {
+                id: job.id.clone(),
+                story_part_id: job.story_part_id.clone(),
+                voice_path: None,
+                final_path,
+                status: job.status.clone(),
+                error_message: job.error_message.clone(),
+                created_at: job.created_at.clone(),
+                updated_at: job.updated_at.clone(),
+            },
+        )
+        .map_err(|e| format!("Failed to import audio job: {}", e))?;
+    }
+
+    for character in &manifest.characters {
+        queries::insert_story_character(
+            &tx,
+            &StoryCharacter {
+                story_id: story.id.clone(),
+                name: character.name.clone(),
+                voice: character.voice.clone(),
+                effect: character.effect.clone(),
+            },
+        )
+        .map_err(|e| format!("Failed to import character {}: {}", character.name, e))?;
+    }
+    if let Some(seed) = manifest.music_seed {
+        queries::restore_music_seed(&tx, &story.id, seed)
+            .map_err(|e| format!("Failed to import story music: {}", e))?;
+    }
+    if let Some(soundscape) = &manifest.soundscape {
+        queries::set_story_soundscape(&tx, &story.id, soundscape)
+            .map_err(|e| format!("Failed to import story soundscape: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("Failed to finish import: {}", e))
+}
+
+/// Write a part's bundled audio and timing where the pipeline keeps them,
+/// adding each path to `written`. Returns where the audio went.
+async fn write_part_files(
+    audio_dir: &Path,
+    part: &BundlePart,
+    files: &HashMap<String, Vec<u8>>,
+    written: &mut Vec<PathBuf>,
+) -> Result<Option<String>, String> {
+    let mut audio_path = None;
+    if let Some(bytes) = part.audio_file.as_ref().and_then(|f| files.get(f)) {
+        let dest = pipeline::final_path(audio_dir, &part.id);
+        write_file(&dest, bytes).await?;
+        audio_path = Some(dest.to_string_lossy().to_string());
+        written.push(dest);
+    }
+    if let Some(bytes) = part.timing_file.as_ref().and_then(|f| files.get(f)) {
+        let dest = pipeline::timing_path(audio_dir, &part.id);
+        write_file(&dest, bytes).await?;
+        written.push(dest);
+    }
+    Ok(audio_path)
+}
+
+async fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    tokio::fs::write(path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// A file's bytes, or `None` if there is no path or nothing at it.
+async fn read_optional(path: Option<PathBuf>) -> Result<Option<Vec<u8>>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn audio_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("audio"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        queries::apply_rusqlite_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn bundle_round_trips_cast_music_soundscape_and_questions() {
+        let source = library();
+        source
+            .execute_batch(
+                "INSERT INTO genres (id, name, description) VALUES ('g', 'Fables', 'd');
+                 INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'The Fox', 'g');
+                 INSERT INTO story_parts (id, story_id, part_number, content, status)
+                     VALUES ('p1', 's1', 1, 'Once.', 'text_ready');",
+            )
+            .unwrap();
+        queries::insert_story_character(
+            &source,
+            &StoryCharacter {
+                story_id: "s1".to_string(),
+                name: "Fox".to_string(),
+                voice: "bm_george".to_string(),
+                effect: "giant".to_string(),
+            },
+        )
+        .unwrap();
+        let seed = queries::get_or_create_music_seed(&source, "s1").unwrap();
+        queries::set_story_soundscape(&source, "s1", "forest").unwrap();
+        let questions = vec!["Why did the fox run?".to_string(), "What next?".to_string()];
+        queries::set_part_questions(&source, "p1", &questions).unwrap();
+
+        let (manifest, _) = read_rows(&source, "s1").unwrap();
+        let bytes = bundle::pack(manifest, Vec::new()).unwrap();
+        let mut manifest = bundle::unpack(&bytes).unwrap().manifest;
+        manifest.reassign_ids();
+
+        let mut target = library();
+        insert_rows(&mut target, &manifest, &[None]).unwrap();
+        let story_id = &manifest.story.id;
+        let characters = queries::get_story_characters(&target, story_id).unwrap();
+        assert_eq!(characters.len(), 1);
+        assert_eq!(
+            (characters[0].name.as_str(), characters[0].voice.as_str(), characters[0].effect.as_str()),
+            ("Fox", "bm_george", "giant")
+        );
+        assert_eq!(queries::get_music_seed(&target, story_id).unwrap(), Some(seed));
+        assert_eq!(
+            queries::get_story_soundscape(&target, story_id).unwrap().as_deref(),
+            Some("forest")
+        );
+        let part_id = &manifest.parts[0].id;
+        assert_ne!(part_id, "p1");
+        assert_eq!(queries::get_part_questions(&target, part_id).unwrap(), questions);
+    }
+}
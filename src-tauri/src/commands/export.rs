@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use crate::db::{open_db, queries};
+use crate::services::audiobook::{self, AudiobookFormat, AudiobookTags, PART_GAP_SECS};
+use crate::services::captions::{self, CaptionFormat};
+use crate::services::epub::{self, EpubBook, EpubPart};
+use crate::services::pdf::{self, PdfBook, PdfPart};
+use crate::services::pipeline::{self, TimingSegment};
+use crate::services::tts::TtsService;
+use tauri::Manager;
+
+/// A story with the parts that have finished audio, ready to export.
+struct ExportStory {
+    title: String,
+    genre_id: String,
+    parts: Vec<ExportPart>,
+}
+
+struct ExportPart {
+    audio_path: PathBuf,
+    /// Sentence timing; empty for audio made before timing was recorded
+    timing: Vec<TimingSegment>,
+}
+
+/// Write captions for a story's narrated parts to `path`. `format` is `vtt`,
+/// `srt` or `lrc`; the parts are joined into one timeline as if played back
+/// to back.
+#[tauri::command]
+pub async fn export_story_captions(
+    story_id: String,
+    format: String,
+    path: String,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let format = CaptionFormat::from_name(&format)
+        .ok_or_else(|| format!("Unknown caption format '{}'", format))?;
+    let story = load_export_story(&app, &story_id)?;
+
+    let mut timed = Vec::new();
+    for part in story.parts {
+        let duration = audio_duration(&part.audio_path)?;
+        timed.push((part.timing, duration));
+    }
+    let segments = captions::combine_parts(&timed);
+    if segments.is_empty() {
+        return Err("The story's audio has no sentence timing to export".to_string());
+    }
+
+    log::info!("Exporting {} captions for story {} to {}", format.extension(), story_id, path);
+    tokio::fs::write(&path, captions::render(format, &segments, &story.title))
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Join the final audio of every part of a story, in order, into one
+/// audiobook at `path`. `format` is `m4b` (AAC with a chapter per part) or
+/// `mp3` (with ID3v2 chapter frames). With `spoken_title`, the narrator
+/// reads the title and genre first.
+#[tauri::command]
+pub async fn export_audiobook(
+    story_id: String,
+    format: String,
+    path: String,
+    spoken_title: bool,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let format = AudiobookFormat::from_name(&format)
+        .ok_or_else(|| format!("Unknown audiobook format '{}'", format))?;
+    let conn = open_db(&app)?;
+    let missing = queries::get_story_parts_without_audio(&conn, &story_id)
+        .map_err(|e| format!("Failed to read story parts: {}", e))?;
+    if let Some(part) = missing.first() {
+        return Err(format!("Part {} has no audio yet", part));
+    }
+    let story = load_export_story(&app, &story_id)?;
+    let genre = queries::get_genre_name(&conn, &story.genre_id)
+        .map_err(|e| format!("Genre not found: {}", e))?;
+    let voice_settings = queries::get_voice_settings(&conn).ok();
+    drop(conn);
+
+    // Every part at the first part's rate
+    let mut parts = Vec::new();
+    let mut sample_rate = 0;
+    for part in &story.parts {
+        let (samples, rate) = load_wav(&part.audio_path).await?;
+        if sample_rate == 0 {
+            sample_rate = rate;
+        }
+        parts.push(kokoro_tts::audio::resample_linear(&samples, rate, sample_rate));
+    }
+
+    let gap = vec![0.0; (PART_GAP_SECS * sample_rate as f32) as usize];
+    let mut samples = Vec::new();
+    if spoken_title {
+        let tts = TtsService::new(super::resolve_models_dir());
+        let intro = tts
+            .generate_raw(
+                &format!("{}. {}.", story.title, genre),
+                voice_settings.as_ref().map(|vs| vs.voice.as_str()),
+                voice_settings.as_ref().and_then(|vs| vs.speed),
+            )
+            .await?;
+        samples.extend(kokoro_tts::audio::resample_linear(
+            &intro.samples,
+            intro.sample_rate,
+            sample_rate,
+        ));
+        samples.extend_from_slice(&gap);
+    }
+    let intro_secs = samples.len() as f64 / sample_rate as f64;
+    let part_secs: Vec<f64> = parts
+        .iter()
+        .map(|p| p.len() as f64 / sample_rate as f64)
+        .collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            samples.extend_from_slice(&gap);
+        }
+        samples.extend_from_slice(part);
+    }
+
+    let tags = AudiobookTags {
+        title: story.title,
+        genre,
+    };
+    let chapters = audiobook::chapters(&part_secs, intro_secs, PART_GAP_SECS as f64);
+    log::info!(
+        "Exporting story {} as {} with {} chapters to {}",
+        story_id,
+        format.extension(),
+        chapters.len(),
+        path
+    );
+    let bytes = tokio::task::spawn_blocking(move || {
+        audiobook::encode(format, &samples, sample_rate, &tags, &chapters)
+    })
+    .await
+    .map_err(|e| format!("Audiobook task panicked: {}", e))??;
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Package a story as an EPUB 3 read-along at `path`. Every part's text is
+/// included; narrated parts embed their audio with a media overlay, so
+/// readers that support overlays highlight each sentence as it is read.
+#[tauri::command]
+pub async fn export_story_epub(
+    story_id: String,
+    path: String,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let conn = open_db(&app)?;
+    let title = queries::get_story_title(&conn, &story_id)
+        .map_err(|e| format!("Story not found: {}", e))?;
+    let genre = queries::get_story_genre(&conn, &story_id)
+        .and_then(|genre_id| queries::get_genre_name(&conn, &genre_id))
+        .map_err(|e| format!("Genre not found: {}", e))?;
+    let story_parts = queries::get_story_parts(&conn, &story_id)
+        .map_err(|e| format!("Failed to read story parts: {}", e))?;
+    drop(conn);
+    let audio_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("audio");
+
+    let mut parts = Vec::new();
+    for part in story_parts {
+        let timing = load_timing(&pipeline::timing_path(&audio_dir, &part.id));
+        let narration = match &part.audio_path {
+            Some(audio_path) if part.status == "audio_ready" && !timing.is_empty() => {
+                let (samples, sample_rate) = load_wav(Path::new(audio_path)).await?;
+                let mp3 = tokio::task::spawn_blocking(move || {
+                    kokoro_tts::audio::encode_audio(
+                        &samples,
+                        sample_rate,
+                        kokoro_tts::audio::AudioFormat::Mp3,
+                    )
+                })
+                .await
+                .map_err(|e| format!("Audio task panicked: {}", e))?
+                .map_err(|e| format!("Failed to encode part {}: {}", part.part_number, e))?;
+                Some((mp3, timing))
+            }
+            _ => None,
+        };
+        parts.push(EpubPart {
+            content: part.content,
+            narration,
+        });
+    }
+
+    let book = EpubBook {
+        id: story_id.clone(),
+        title,
+        genre,
+        modified: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        parts,
+    };
+    log::info!("Exporting story {} as EPUB to {}", story_id, path);
+    let bytes = tokio::task::spawn_blocking(move || epub::build(&book))
+        .await
+        .map_err(|e| format!("EPUB task panicked: {}", e))??;
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Lay a story out as a printable picture book at `path`. With `qr_text`,
+/// the title page carries a QR code for it (the audiobook's file name);
+/// a part's discussion questions follow it when they have been generated.
+#[tauri::command]
+pub async fn export_story_pdf(
+    story_id: String,
+    path: String,
+    qr_text: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let book = {
+        let conn = open_db(&app)?;
+        let title = queries::get_story_title(&conn, &story_id)
+            .map_err(|e| format!("Story not found: {}", e))?;
+        let genre_id = queries::get_story_genre(&conn, &story_id)
+            .map_err(|e| format!("Story not found: {}", e))?;
+        let genre = queries::get_genre_name(&conn, &genre_id)
+            .map_err(|e| format!("Genre not found: {}", e))?;
+        let icon = queries::get_genre_icon(&conn, &genre_id)
+            .map_err(|e| format!("Genre not found: {}", e))?;
+        let mut parts = Vec::new();
+        for part in queries::get_story_parts(&conn, &story_id)
+            .map_err(|e| format!("Failed to read story parts: {}", e))?
+        {
+            let questions = queries::get_part_questions(&conn, &part.id)
+                .map_err(|e| format!("Failed to read discussion questions: {}", e))?;
+            parts.push(PdfPart {
+                content: part.content,
+                questions,
+            });
+        }
+        PdfBook {
+            title,
+            genre,
+            genre_id,
+            icon,
+            qr_text: qr_text.filter(|text| !text.trim().is_empty()),
+            parts,
+        }
+    };
+
+    log::info!("Exporting story {} as PDF to {}", story_id, path);
+    let bytes = tokio::task::spawn_blocking(move || pdf::build(&book))
+        .await
+        .map_err(|e| format!("PDF task panicked: {}", e))??;
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// The story's title, genre and parts that have audio, in order.
+fn load_export_story(app: &tauri::AppHandle, story_id: &str) -> Result<ExportStory, String> {
+    let conn = open_db(app)?;
+    let title = queries::get_story_title(&conn, story_id)
+        .map_err(|e| format!("Story not found: {}", e))?;
+    let genre_id = queries::get_story_genre(&conn, story_id)
+        .map_err(|e| format!("Story not found: {}", e))?;
+    let audio = queries::get_story_part_audio(&conn, story_id)
+        .map_err(|e| format!("Failed to read story parts: {}", e))?;
+    if audio.is_empty() {
+        return Err("No part of this story has audio yet".to_string());
+    }
+
+    let audio_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("audio");
+    let parts = audio
+        .into_iter()
+        .map(|(part_id, audio_path)| ExportPart {
+            audio_path: PathBuf::from(audio_path),
+            timing: load_timing(&pipeline::timing_path(&audio_dir, &part_id)),
+        })
+        .collect();
+    Ok(ExportStory {
+        title,
+        genre_id,
+        parts,
+    })
+}
+
+fn load_timing(path: &Path) -> Vec<TimingSegment> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+async fn load_wav(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        kokoro_tts::audio::load_wav(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    })
+    .await
+    .map_err(|e| format!("Audio task panicked: {}", e))?
+}
+
+/// Length of a WAV file in seconds, from its header.
+fn audio_duration(path: &Path) -> Result<f64, String> {
+    kokoro_tts::audio::wav_duration(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
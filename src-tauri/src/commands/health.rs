@@ -8,7 +8,6 @@ pub async fn check_dependency(name: String, _app: tauri::AppHandle) -> Result<De
     match name.as_str() {
         "ollama" => Ok(health::check_ollama().await),
         "gemma3:4b" => Ok(health::check_gemma3().await),
-        "ffmpeg" => Ok(health::check_ffmpeg()),
         "espeak_ng" => Ok(health::check_espeak_ng()),
         "tts_model" => {
             let models_dir = super::resolve_models_dir();
@@ -31,7 +30,6 @@ pub async fn install_dependency(name: String, _app: tauri::AppHandle) -> Result<
     let (program, args): (&str, Vec<String>) = match name.as_str() {
         "ollama" => ("brew", vec!["install".into(), "ollama".into()]),
         "gemma3:4b" => ("ollama", vec!["pull".into(), "gemma3:4b".into()]),
-        "ffmpeg" => ("brew", vec!["install".into(), "ffmpeg".into()]),
         "espeak_ng" => ("brew", vec!["install".into(), "espeak-ng".into()]),
         "tts_model" => {
             let models_dir = super::resolve_models_dir();
@@ -1,6 +1,10 @@
 pub mod audio;
+pub mod bundle;
+pub mod export;
 pub mod health;
+pub mod podcast;
 pub mod stories;
+pub mod voices;
 
 /// Resolve the shared models directory at `~/.pattikadhai/models/`.
 pub fn resolve_models_dir() -> std::path::PathBuf {
@@ -9,3 +13,19 @@ pub fn resolve_models_dir() -> std::path::PathBuf {
         .join(".pattikadhai")
         .join("models")
 }
+
+/// Resolve the background music library at `~/.pattikadhai/music/`.
+pub fn resolve_music_dir() -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join(".pattikadhai")
+        .join("music")
+}
+
+/// Resolve the sound-effect library at `~/.pattikadhai/sfx/`.
+pub fn resolve_sfx_dir() -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join(".pattikadhai")
+        .join("sfx")
+}
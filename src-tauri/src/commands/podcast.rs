@@ -0,0 +1,79 @@
+use std::net::{IpAddr, SocketAddr};
+
+use crate::db::queries;
+use crate::services::podcast::{FeedSource, PodcastServer};
+use rusqlite::Connection;
+use tauri::Manager;
+
+/// A network interface the podcast feed can be bound to.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub address: String,
+}
+
+/// Whether the podcast feed is being served, and where to subscribe.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PodcastFeedStatus {
+    pub running: bool,
+    pub url: Option<String>,
+}
+
+/// IPv4 interfaces the feed can listen on, loopback first.
+#[tauri::command]
+pub fn list_network_interfaces() -> Result<Vec<NetworkInterface>, String> {
+    let mut interfaces: Vec<NetworkInterface> = local_ip_address::list_afinet_netifas()
+        .map_err(|e| format!("Failed to list network interfaces: {}", e))?
+        .into_iter()
+        .filter(|(_, ip)| ip.is_ipv4())
+        .map(|(name, ip)| NetworkInterface {
+            name,
+            address: ip.to_string(),
+        })
+        .collect();
+    interfaces.sort_by_key(|i| !i.address.starts_with("127."));
+    Ok(interfaces)
+}
+
+/// Start, restart or stop the feed server to match the saved settings.
+#[tauri::command]
+pub async fn apply_podcast_feed(app: tauri::AppHandle) -> Result<PodcastFeedStatus, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let db_path = app_data_dir.join("pattikadhai.db");
+    let settings = {
+        let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open DB: {}", e))?;
+        queries::get_podcast_feed_settings(&conn)
+            .map_err(|e| format!("Failed to read podcast feed settings: {}", e))?
+    };
+
+    let server = PodcastServer::global();
+    if !settings.enabled {
+        server.stop().await;
+        return Ok(status(None));
+    }
+    let ip: IpAddr = settings
+        .address
+        .parse()
+        .map_err(|_| format!("Not a network address: {}", settings.address))?;
+    let source = FeedSource {
+        db_path,
+        audio_dir: app_data_dir.join("audio"),
+    };
+    let addr = server.start(SocketAddr::new(ip, settings.port), source).await?;
+    Ok(status(Some(addr)))
+}
+
+#[tauri::command]
+pub async fn get_podcast_feed_status() -> Result<PodcastFeedStatus, String> {
+    Ok(status(PodcastServer::global().address().await))
+}
+
+fn status(addr: Option<SocketAddr>) -> PodcastFeedStatus {
+    PodcastFeedStatus {
+        running: addr.is_some(),
+        url: addr.map(|addr| format!("http://{}/feed.xml", addr)),
+    }
+}
@@ -1,6 +1,8 @@
 use crate::db::models::{Genre, Story, StoryPart};
+use crate::db::queries;
 use crate::services::ollama::{OllamaClient, StoryToken};
 use crate::services::prompts;
+use crate::services::sfx;
 use rusqlite::Connection;
 use tauri::ipc::Channel;
 use tauri::Manager;
@@ -64,6 +66,44 @@ pub async fn continue_story(
         .await
 }
 
+/// Ask the model for discussion questions about a story part and store them,
+/// replacing any earlier ones.
+#[tauri::command]
+pub async fn generate_discussion_questions(
+    part_id: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<String>, String> {
+    let db_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("pattikadhai.db");
+    let content = {
+        let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open DB: {}", e))?;
+        queries::get_story_part_content(&conn, &part_id)
+            .map_err(|e| format!("Story part not found: {}", e))?
+    };
+
+    log::info!("generate_discussion_questions: part={}", part_id);
+    let client = OllamaClient::new();
+    let reply = client
+        .generate(
+            "gemma3:4b",
+            "You are a helpful assistant for parents and teachers reading with children.",
+            &prompts::build_discussion_questions_prompt(&sfx::strip_cues(&content)),
+        )
+        .await?;
+    let questions = prompts::parse_questions(&reply);
+    if questions.is_empty() {
+        return Err("The model did not suggest any questions".to_string());
+    }
+
+    let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open DB: {}", e))?;
+    queries::set_part_questions(&conn, &part_id, &questions)
+        .map_err(|e| format!("Failed to save questions: {}", e))?;
+    Ok(questions)
+}
+
 #[derive(serde::Serialize)]
 pub struct StoryWithParts {
     pub story: Story,
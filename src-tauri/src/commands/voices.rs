@@ -0,0 +1,118 @@
+use crate::services::tts::TtsService;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImportedVoice {
+    pub name: String,
+    pub path: String,
+}
+
+/// Resolve the Kokoro voices directory at `~/.pattikadhai/models/kokoro/voices`.
+fn voices_dir() -> std::path::PathBuf {
+    super::resolve_models_dir().join("kokoro").join("voices")
+}
+
+/// Turn a user-chosen display name into a safe voice file stem.
+fn sanitize_voice_name(name: &str) -> String {
+    let mut out = String::new();
+    for ch in name.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+/// List the voices installed in the voices directory.
+#[tauri::command]
+pub async fn list_voices() -> Result<Vec<String>, String> {
+    let dir = voices_dir();
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read voices dir: {}", e)),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("bin"))
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(String::from))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Import a custom Kokoro voice from a `.bin`, `.npy`, or zip of them.
+///
+/// A single voice is stored as `<name>.bin`; multiple voices from an
+/// archive are stored as `<name>_<entry>.bin`. Existing voices are never
+/// overwritten. Imported voices are usable immediately.
+#[tauri::command]
+pub async fn import_voice(path: String, name: String) -> Result<Vec<ImportedVoice>, String> {
+    log::info!("import_voice: path={}, name={}", path, name);
+
+    let base_name = sanitize_voice_name(&name);
+    if base_name.is_empty() {
+        return Err("Voice name must contain letters or digits".to_string());
+    }
+
+    let source = std::path::PathBuf::from(&path);
+    let voices = tokio::task::spawn_blocking(move || kokoro_tts::voices::read_voice_file(&source))
+        .await
+        .map_err(|e| format!("Voice import task panicked: {}", e))?
+        .map_err(|e| format!("Invalid voice file: {}", e))?;
+
+    let dir = voices_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create voices dir: {}", e))?;
+
+    let single = voices.len() == 1;
+    let named: Vec<(String, kokoro_tts::voices::Voice)> = voices
+        .into_iter()
+        .map(|(entry, voice)| {
+            let voice_name = if single {
+                base_name.clone()
+            } else {
+                format!("{}_{}", base_name, sanitize_voice_name(&entry))
+            };
+            (voice_name, voice)
+        })
+        .collect();
+
+    // Check every target first so a collision doesn't leave a half-imported pack
+    for (voice_name, _) in &named {
+        if dir.join(format!("{}.bin", voice_name)).exists() {
+            return Err(format!("A voice named '{}' already exists", voice_name));
+        }
+    }
+
+    let mut imported = Vec::new();
+    for (voice_name, voice) in named {
+        let target = dir.join(format!("{}.bin", voice_name));
+        std::fs::write(&target, voice.to_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        TtsService::register_voice(&voice_name, voice)?;
+
+        log::info!("Imported voice '{}' to {}", voice_name, target.display());
+        imported.push(ImportedVoice {
+            name: voice_name,
+            path: target.to_string_lossy().to_string(),
+        });
+    }
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_voice_name_makes_safe_stems() {
+        assert_eq!(sanitize_voice_name("Grandma Patti"), "grandma_patti");
+        assert_eq!(sanitize_voice_name("  wolf!!voice  "), "wolf_voice");
+        assert_eq!(sanitize_voice_name("../../etc"), "etc");
+        assert_eq!(sanitize_voice_name("***"), "");
+    }
+}
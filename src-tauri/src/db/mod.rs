@@ -1,2 +1,18 @@
 pub mod models;
 pub mod queries;
+
+use rusqlite::Connection;
+use tauri::Manager;
+
+/// Open the app's database, with foreign keys enforced so deletes cascade.
+pub fn open_db(app: &tauri::AppHandle) -> Result<Connection, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let conn = Connection::open(app_data_dir.join("pattikadhai.db"))
+        .map_err(|e| format!("Failed to open DB: {}", e))?;
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+    Ok(conn)
+}
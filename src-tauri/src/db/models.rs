@@ -45,6 +45,14 @@ pub struct AudioJob {
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoryCharacter {
+    pub story_id: String,
+    pub name: String,
+    pub voice: String,
+    pub effect: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,3 +144,36 @@ mod tests {
         }
     }
 }
+
+/// A sentence the audio pipeline has synthesized and saved for a job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioJobSentence {
+    pub job_id: String,
+    pub sentence_index: usize,
+    pub text: String,
+    pub wav_path: String,
+    pub duration_secs: f64,
+}
+
+/// An audio job waiting in or running from the scheduler's queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedAudioJob {
+    pub job_id: String,
+    pub story_part_id: String,
+    pub status: String,
+    /// Higher runs first: 1 for "play now", 0 for background pre-rendering
+    pub priority: i64,
+}
+
+/// A story part with finished audio, as published in the podcast feed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PodcastEpisode {
+    pub part_id: String,
+    pub story_id: String,
+    pub story_title: String,
+    pub genre_name: String,
+    pub part_number: i32,
+    pub content: String,
+    pub audio_path: String,
+    pub updated_at: String,
+}
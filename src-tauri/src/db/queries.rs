@@ -1,4 +1,9 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+use super::models::{
+    AudioJob, AudioJobSentence, Genre, PodcastEpisode, QueuedAudioJob, Story, StoryCharacter,
+    StoryPart,
+};
 
 /// Apply rusqlite-side migrations that may not be covered by tauri-plugin-sql.
 /// Each migration is idempotent so safe to re-run.
@@ -9,21 +14,64 @@ pub fn apply_rusqlite_migrations(conn: &Connection) -> Result<()> {
     conn.execute_batch(include_str!("../../migrations/006_kittentts_voice_settings.sql"))?;
     // Migration 7: Back to Kokoro ONNX voice settings
     conn.execute_batch(include_str!("../../migrations/007_kokoro_onnx_voice_settings.sql"))?;
+    // Migration 8: Voice effect setting
+    conn.execute_batch(include_str!("../../migrations/008_voice_effect_setting.sql"))?;
+    // Migration 9: Audio mastering setting
+    conn.execute_batch(include_str!("../../migrations/009_audio_mastering_setting.sql"))?;
+    // Migration 10: Story characters table
+    conn.execute_batch(include_str!("../../migrations/010_story_characters.sql"))?;
+    // Migration 11: Music bed setting
+    conn.execute_batch(include_str!("../../migrations/011_music_setting.sql"))?;
+    // Migration 12: Story theme music seeds
+    conn.execute_batch(include_str!("../../migrations/012_story_music.sql"))?;
+    // Migration 13: Story soundscapes and ambience setting
+    conn.execute_batch(include_str!("../../migrations/013_story_soundscapes.sql"))?;
+    // Migration 14: Cancelled audio job status (rebuilds audio_jobs, so only once)
+    let audio_jobs_sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'audio_jobs'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !audio_jobs_sql.contains("'cancelled'") {
+        conn.execute_batch(include_str!("../../migrations/014_audio_job_cancelled.sql"))?;
+    }
+    // Migration 15: Audio job queue and concurrency setting
+    conn.execute_batch(include_str!("../../migrations/015_audio_job_queue.sql"))?;
+    // Migration 16: Story part discussion questions
+    conn.execute_batch(include_str!("../../migrations/016_story_part_questions.sql"))?;
+    // Migration 17: Podcast feed settings
+    conn.execute_batch(include_str!("../../migrations/017_podcast_feed_settings.sql"))?;
+    // Migration 18: Audio job attempts
+    conn.execute_batch(include_str!("../../migrations/018_audio_job_attempts.sql"))?;
+    // Migration 19: Audio job sentence progress
+    conn.execute_batch(include_str!("../../migrations/019_audio_job_sentences.sql"))?;
     Ok(())
 }
 
 pub struct VoiceSettings {
     pub voice: String,
     pub speed: Option<f32>,
+    /// Effect preset name (`none`, `giant`, `mouse`, ...)
+    pub effect: String,
+    /// Mastering preset name (`none`, `clean`, `cozy_room`, `campfire`)
+    pub mastering: String,
+    /// Mix a music bed from the genre's library under the narration
+    pub music_enabled: bool,
+    /// Mix the story's ambient soundscape under the narration
+    pub ambience_enabled: bool,
 }
 
 /// Read TTS voice settings from app_settings table
 pub fn get_voice_settings(conn: &Connection) -> Result<VoiceSettings> {
     let mut voice = "af_nova".to_string();
     let mut speed: Option<f32> = None;
+    let mut effect = "none".to_string();
+    let mut mastering = "none".to_string();
+    let mut music_enabled = false;
+    let mut ambience_enabled = false;
 
     let mut stmt = conn.prepare(
-        "SELECT key, value FROM app_settings WHERE key IN ('tts_voice', 'tts_speed')",
+        "SELECT key, value FROM app_settings WHERE key IN ('tts_voice', 'tts_speed', 'tts_effect', 'audio_mastering', 'music_enabled', 'ambience_enabled')",
     )?;
     let rows = stmt.query_map([], |row| {
         Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
@@ -34,14 +82,29 @@ pub fn get_voice_settings(conn: &Connection) -> Result<VoiceSettings> {
         match key.as_str() {
             "tts_voice" => voice = value,
             "tts_speed" => speed = value.parse().ok(),
+            "tts_effect" => effect = value,
+            "audio_mastering" => mastering = value,
+            "music_enabled" => music_enabled = value == "true",
+            "ambience_enabled" => ambience_enabled = value == "true",
             _ => {}
         }
     }
 
-    Ok(VoiceSettings { voice, speed })
+    Ok(VoiceSettings {
+        voice,
+        speed,
+        effect,
+        mastering,
+        music_enabled,
+        ambience_enabled,
+    })
 }
 
-/// Update audio job status (used from background Tokio tasks)
+/// Statuses a job does not leave unless it is retried
+const FINISHED_AUDIO_JOB_STATUSES: [&str; 3] = ["complete", "failed", "cancelled"];
+
+/// Update audio job status (used from background Tokio tasks). A job that
+/// has finished leaves the queue.
 pub fn update_audio_job_status(
     conn: &Connection,
     job_id: &str,
@@ -52,6 +115,9 @@ pub fn update_audio_job_status(
         "UPDATE audio_jobs SET status = ?1, error_message = ?2, updated_at = datetime('now') WHERE id = ?3",
         params![status, error_message, job_id],
     )?;
+    if FINISHED_AUDIO_JOB_STATUSES.contains(&status) {
+        conn.execute("DELETE FROM audio_job_queue WHERE job_id = ?1", [job_id])?;
+    }
     Ok(())
 }
 
@@ -69,20 +135,339 @@ pub fn update_audio_job_paths(
     Ok(())
 }
 
-/// Reset any in-progress audio jobs to failed on app restart.
-/// This handles the case where the app was closed or crashed mid-generation.
-pub fn reset_stale_audio_jobs(conn: &Connection) -> Result<()> {
+/// Record the music track mixed into an audio job
+pub fn update_audio_job_music_path(conn: &Connection, job_id: &str, music_path: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE audio_jobs SET music_path = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![music_path, job_id],
+    )?;
+    Ok(())
+}
+
+/// Record a finished sentence so the job can resume after a restart
+pub fn insert_audio_job_sentence(conn: &Connection, sentence: &AudioJobSentence) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO audio_job_sentences (job_id, sentence_index, text, wav_path, duration_secs) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            sentence.job_id,
+            sentence.sentence_index as i64,
+            sentence.text,
+            sentence.wav_path,
+            sentence.duration_secs
+        ],
+    )?;
+    Ok(())
+}
+
+/// Finished sentences for a job, in order
+pub fn get_audio_job_sentences(conn: &Connection, job_id: &str) -> Result<Vec<AudioJobSentence>> {
+    let mut stmt = conn.prepare(
+        "SELECT job_id, sentence_index, text, wav_path, duration_secs FROM audio_job_sentences WHERE job_id = ?1 ORDER BY sentence_index",
+    )?;
+    let rows = stmt.query_map([job_id], |row| {
+        Ok(AudioJobSentence {
+            job_id: row.get(0)?,
+            sentence_index: row.get::<_, i64>(1)? as usize,
+            text: row.get(2)?,
+            wav_path: row.get(3)?,
+            duration_secs: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Forget a job's sentence progress once its audio is assembled
+pub fn delete_audio_job_sentences(conn: &Connection, job_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM audio_job_sentences WHERE job_id = ?1", [job_id])?;
+    Ok(())
+}
+
+/// Times a job may be started before an interruption fails it instead of
+/// queueing it again.
+pub const MAX_AUDIO_JOB_ATTEMPTS: i64 = 3;
+
+/// Count a start of the job towards [`MAX_AUDIO_JOB_ATTEMPTS`]
+pub fn record_audio_job_attempt(conn: &Connection, job_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audio_job_attempts (job_id, attempts) VALUES (?1, 1)
+         ON CONFLICT(job_id) DO UPDATE SET attempts = attempts + 1",
+        [job_id],
+    )?;
+    Ok(())
+}
+
+/// Put jobs that were still running when the app last closed back in the
+/// queue, failing those already started [`MAX_AUDIO_JOB_ATTEMPTS`] times.
+/// Returns how many jobs are now pending.
+pub fn requeue_interrupted_audio_jobs(conn: &Connection) -> Result<usize> {
+    const INTERRUPTED: &str = "status IN ('voice_generating', 'music_generating', 'mixing')";
+    const EXHAUSTED: &str = "id IN (SELECT job_id FROM audio_job_attempts WHERE attempts >= ?1)";
     conn.execute(
-        "UPDATE audio_jobs SET status = 'failed', error_message = 'Interrupted by app restart', updated_at = datetime('now') WHERE status IN ('pending', 'voice_generating')",
+        &format!(
+            "UPDATE story_parts SET status = 'audio_failed', updated_at = datetime('now')
+             WHERE id IN (SELECT story_part_id FROM audio_jobs WHERE {} AND {})",
+            INTERRUPTED, EXHAUSTED
+        ),
+        [MAX_AUDIO_JOB_ATTEMPTS],
+    )?;
+    conn.execute(
+        &format!(
+            "UPDATE audio_jobs SET status = 'failed', error_message = ?2, updated_at = datetime('now')
+             WHERE {} AND {}",
+            INTERRUPTED, EXHAUSTED
+        ),
+        params![
+            MAX_AUDIO_JOB_ATTEMPTS,
+            format!("Interrupted {} times", MAX_AUDIO_JOB_ATTEMPTS)
+        ],
+    )?;
+    conn.execute(
+        &format!(
+            "UPDATE audio_jobs SET status = 'pending', updated_at = datetime('now') WHERE {}",
+            INTERRUPTED
+        ),
         [],
     )?;
+    // Also clears rows left by jobs that finished before the queue forgot them
     conn.execute(
-        "UPDATE story_parts SET status = 'audio_failed', updated_at = datetime('now') WHERE status = 'audio_processing'",
+        "DELETE FROM audio_job_queue WHERE job_id IN
+             (SELECT id FROM audio_jobs WHERE status IN ('complete', 'failed', 'cancelled'))",
+        [],
+    )?;
+    conn.query_row(
+        "SELECT COUNT(*) FROM audio_jobs WHERE status = 'pending'",
         [],
+        |row| row.get(0),
+    )
+}
+
+/// Running jobs first, then pending jobs in the order they will start.
+const AUDIO_QUEUE_SQL: &str = "SELECT j.id, j.story_part_id, j.status, COALESCE(q.priority, 0)
+     FROM audio_jobs j LEFT JOIN audio_job_queue q ON q.job_id = j.id
+     WHERE j.status IN ('pending', 'voice_generating', 'music_generating', 'mixing')
+     ORDER BY j.status = 'pending', q.position IS NULL, q.position, j.created_at, j.rowid";
+
+/// Jobs that are running or waiting to run
+pub fn get_audio_queue(conn: &Connection) -> Result<Vec<QueuedAudioJob>> {
+    let mut stmt = conn.prepare(AUDIO_QUEUE_SQL)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(QueuedAudioJob {
+            job_id: row.get(0)?,
+            story_part_id: row.get(1)?,
+            status: row.get(2)?,
+            priority: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn pending_audio_jobs(conn: &Connection) -> Result<Vec<QueuedAudioJob>> {
+    Ok(get_audio_queue(conn)?
+        .into_iter()
+        .filter(|job| job.status == "pending")
+        .collect())
+}
+
+/// Number the queue rows of `job_ids` in order
+fn write_audio_queue_order(conn: &Connection, job_ids: &[String]) -> Result<()> {
+    for (position, job_id) in job_ids.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO audio_job_queue (job_id, position) VALUES (?1, ?2)
+             ON CONFLICT(job_id) DO UPDATE SET position = excluded.position",
+            params![job_id, position as i64],
+        )?;
+    }
+    Ok(())
+}
+
+/// Queue a pending job behind every pending job of the same or higher priority
+pub fn enqueue_audio_job(conn: &Connection, job_id: &str, priority: i64) -> Result<()> {
+    let mut order: Vec<QueuedAudioJob> = pending_audio_jobs(conn)?
+        .into_iter()
+        .filter(|job| job.job_id != job_id)
+        .collect();
+    let at = order.iter().take_while(|job| job.priority >= priority).count();
+    conn.execute(
+        "INSERT INTO audio_job_queue (job_id, priority, position) VALUES (?1, ?2, 0)
+         ON CONFLICT(job_id) DO UPDATE SET priority = excluded.priority",
+        params![job_id, priority],
+    )?;
+    order.insert(
+        at,
+        QueuedAudioJob {
+            job_id: job_id.to_string(),
+            story_part_id: String::new(),
+            status: "pending".to_string(),
+            priority,
+        },
+    );
+    let ids: Vec<String> = order.into_iter().map(|job| job.job_id).collect();
+    write_audio_queue_order(conn, &ids)
+}
+
+/// Move a pending job to `index` among the pending jobs (clamped to the end)
+pub fn move_audio_job(conn: &Connection, job_id: &str, index: usize) -> Result<()> {
+    let mut ids: Vec<String> = pending_audio_jobs(conn)?
+        .into_iter()
+        .map(|job| job.job_id)
+        .collect();
+    let Some(from) = ids.iter().position(|id| id == job_id) else {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    };
+    let id = ids.remove(from);
+    ids.insert(index.min(ids.len()), id);
+    write_audio_queue_order(conn, &ids)
+}
+
+/// The pending job that should start next, as (job ID, part ID)
+pub fn next_queued_audio_job(conn: &Connection) -> Result<Option<(String, String)>> {
+    Ok(pending_audio_jobs(conn)?
+        .into_iter()
+        .next()
+        .map(|job| (job.job_id, job.story_part_id)))
+}
+
+/// Move a job that has not started yet straight to `status`. Returns whether
+/// the job was still pending.
+pub fn finish_pending_audio_job(conn: &Connection, job_id: &str, status: &str) -> Result<bool> {
+    let changed = conn.execute(
+        "UPDATE audio_jobs SET status = ?1, updated_at = datetime('now') WHERE id = ?2 AND status = 'pending'",
+        params![status, job_id],
+    )?;
+    if changed > 0 {
+        conn.execute("DELETE FROM audio_job_queue WHERE job_id = ?1", [job_id])?;
+    }
+    Ok(changed > 0)
+}
+
+/// Make a failed or cancelled job pending again, with its attempts counted
+/// afresh. Returns whether it was reset.
+pub fn reset_audio_job(conn: &Connection, job_id: &str) -> Result<bool> {
+    let changed = conn.execute(
+        "UPDATE audio_jobs SET status = 'pending', error_message = NULL, updated_at = datetime('now')
+         WHERE id = ?1 AND status IN ('failed', 'cancelled')",
+        [job_id],
+    )?;
+    if changed > 0 {
+        conn.execute("DELETE FROM audio_job_attempts WHERE job_id = ?1", [job_id])?;
+    }
+    Ok(changed > 0)
+}
+
+/// Local podcast feed settings from the app_settings table
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodcastFeedSettings {
+    pub enabled: bool,
+    /// The one interface address the feed server binds to
+    pub address: String,
+    pub port: u16,
+}
+
+/// Read the podcast feed settings, falling back to off on 127.0.0.1:8765
+pub fn get_podcast_feed_settings(conn: &Connection) -> Result<PodcastFeedSettings> {
+    let mut settings = PodcastFeedSettings {
+        enabled: false,
+        address: "127.0.0.1".to_string(),
+        port: 8765,
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT key, value FROM app_settings
+         WHERE key IN ('podcast_feed_enabled', 'podcast_feed_address', 'podcast_feed_port')",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    for row in rows {
+        let (key, value) = row?;
+        match key.as_str() {
+            "podcast_feed_enabled" => settings.enabled = value == "true",
+            "podcast_feed_address" if !value.is_empty() => settings.address = value,
+            "podcast_feed_port" => {
+                if let Ok(port) = value.parse() {
+                    settings.port = port;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(settings)
+}
+
+/// How many audio jobs may run at once (at least one)
+pub fn get_audio_job_concurrency(conn: &Connection) -> Result<usize> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = 'audio_job_concurrency'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.and_then(|v| v.parse().ok()).unwrap_or(1).max(1))
+}
+
+/// Create a story with no parts
+pub fn insert_story(conn: &Connection, id: &str, title: &str, genre_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO stories (id, title, genre_id) VALUES (?1, ?2, ?3)",
+        params![id, title, genre_id],
     )?;
     Ok(())
 }
 
+/// Delete a story along with its parts, jobs and everything else that
+/// references it. The cascade needs foreign keys on, as [`super::open_db`]
+/// leaves them.
+pub fn delete_story(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute("DELETE FROM stories WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Add a part to a story
+pub fn insert_story_part(
+    conn: &Connection,
+    id: &str,
+    story_id: &str,
+    part_number: i32,
+    content: &str,
+    status: &str,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO story_parts (id, story_id, part_number, content, status) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, story_id, part_number, content, status],
+    )?;
+    Ok(())
+}
+
+/// Replace the text of a story part
+pub fn update_story_part_content(conn: &Connection, part_id: &str, content: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE story_parts SET content = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![content, part_id],
+    )?;
+    Ok(())
+}
+
+/// Create a pending audio job for a story part
+pub fn insert_audio_job(conn: &Connection, id: &str, part_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audio_jobs (id, story_part_id) VALUES (?1, ?2)",
+        params![id, part_id],
+    )?;
+    Ok(())
+}
+
+/// Text of a story part, used to resume its audio job
+pub fn get_story_part_content(conn: &Connection, part_id: &str) -> Result<String> {
+    conn.query_row(
+        "SELECT content FROM story_parts WHERE id = ?1",
+        [part_id],
+        |row| row.get(0),
+    )
+}
+
 /// Update story part status and audio path
 pub fn update_story_part_audio(
     conn: &Connection,
@@ -97,6 +482,372 @@ pub fn update_story_part_audio(
     Ok(())
 }
 
+/// Look up the story a part belongs to
+pub fn get_story_id_for_part(conn: &Connection, part_id: &str) -> Result<String> {
+    conn.query_row(
+        "SELECT story_id FROM story_parts WHERE id = ?1",
+        [part_id],
+        |row| row.get(0),
+    )
+}
+
+/// Look up a story's genre
+pub fn get_story_genre(conn: &Connection, story_id: &str) -> Result<String> {
+    conn.query_row(
+        "SELECT genre_id FROM stories WHERE id = ?1",
+        [story_id],
+        |row| row.get(0),
+    )
+}
+
+/// Look up a story's title
+pub fn get_story_title(conn: &Connection, story_id: &str) -> Result<String> {
+    conn.query_row(
+        "SELECT title FROM stories WHERE id = ?1",
+        [story_id],
+        |row| row.get(0),
+    )
+}
+
+/// All parts of a story, in order
+pub fn get_story_parts(conn: &Connection, story_id: &str) -> Result<Vec<StoryPart>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, story_id, part_number, content, audio_path, status, created_at, updated_at
+         FROM story_parts WHERE story_id = ?1 ORDER BY part_number",
+    )?;
+    let rows = stmt.query_map([story_id], |row| {
+        Ok(StoryPart {
+            id: row.get(0)?,
+            story_id: row.get(1)?,
+            part_number: row.get(2)?,
+            content: row.get(3)?,
+            audio_path: row.get(4)?,
+            status: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// A story's parts that have finished audio, in order, as (part ID, audio path)
+pub fn get_story_part_audio(conn: &Connection, story_id: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, audio_path FROM story_parts
+         WHERE story_id = ?1 AND status = 'audio_ready' AND audio_path IS NOT NULL
+         ORDER BY part_number",
+    )?;
+    let rows = stmt.query_map([story_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Look up a genre's display name
+pub fn get_genre_name(conn: &Connection, genre_id: &str) -> Result<String> {
+    conn.query_row(
+        "SELECT name FROM genres WHERE id = ?1",
+        [genre_id],
+        |row| row.get(0),
+    )
+}
+
+/// Look up a genre's icon name, if it has one
+pub fn get_genre_icon(conn: &Connection, genre_id: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT icon FROM genres WHERE id = ?1",
+        [genre_id],
+        |row| row.get(0),
+    )
+}
+
+/// Discussion questions for a story part, in order
+pub fn get_part_questions(conn: &Connection, part_id: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT question FROM story_part_questions WHERE story_part_id = ?1 ORDER BY position",
+    )?;
+    let rows = stmt.query_map([part_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Replace a story part's discussion questions
+pub fn set_part_questions(conn: &Connection, part_id: &str, questions: &[String]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM story_part_questions WHERE story_part_id = ?1",
+        [part_id],
+    )?;
+    for (position, question) in questions.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO story_part_questions (story_part_id, position, question) VALUES (?1, ?2, ?3)",
+            params![part_id, position as i64, question],
+        )?;
+    }
+    Ok(())
+}
+
+/// Part numbers of a story's parts that have no finished audio
+pub fn get_story_parts_without_audio(conn: &Connection, story_id: &str) -> Result<Vec<i32>> {
+    let mut stmt = conn.prepare(
+        "SELECT part_number FROM story_parts
+         WHERE story_id = ?1 AND (status != 'audio_ready' OR audio_path IS NULL)
+         ORDER BY part_number",
+    )?;
+    let rows = stmt.query_map([story_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+const PODCAST_EPISODE_SELECT: &str =
+    "SELECT p.id, s.id, s.title, COALESCE(g.name, s.genre_id), p.part_number, p.content,
+            p.audio_path, p.updated_at
+     FROM story_parts p
+     JOIN stories s ON s.id = p.story_id
+     LEFT JOIN genres g ON g.id = s.genre_id
+     WHERE p.status = 'audio_ready' AND p.audio_path IS NOT NULL";
+
+fn podcast_episode_from_row(row: &rusqlite::Row) -> Result<PodcastEpisode> {
+    Ok(PodcastEpisode {
+        part_id: row.get(0)?,
+        story_id: row.get(1)?,
+        story_title: row.get(2)?,
+        genre_name: row.get(3)?,
+        part_number: row.get(4)?,
+        content: row.get(5)?,
+        audio_path: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Every part in the library with finished audio, newest first
+pub fn get_podcast_episodes(conn: &Connection) -> Result<Vec<PodcastEpisode>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} ORDER BY p.updated_at DESC, s.title, p.part_number",
+        PODCAST_EPISODE_SELECT
+    ))?;
+    let rows = stmt.query_map([], podcast_episode_from_row)?;
+    rows.collect()
+}
+
+/// One part with finished audio, or `None` if it has none
+pub fn get_podcast_episode(conn: &Connection, part_id: &str) -> Result<Option<PodcastEpisode>> {
+    conn.query_row(
+        &format!("{} AND p.id = ?1", PODCAST_EPISODE_SELECT),
+        [part_id],
+        podcast_episode_from_row,
+    )
+    .optional()
+}
+
+/// A story's row
+pub fn get_story(conn: &Connection, story_id: &str) -> Result<Story> {
+    conn.query_row(
+        "SELECT id, title, genre_id, status, is_sample, created_at, updated_at FROM stories WHERE id = ?1",
+        [story_id],
+        |row| {
+            Ok(Story {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                genre_id: row.get(2)?,
+                status: row.get(3)?,
+                is_sample: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        },
+    )
+}
+
+/// A genre's row, if it exists
+pub fn get_genre(conn: &Connection, genre_id: &str) -> Result<Option<Genre>> {
+    conn.query_row(
+        "SELECT id, name, description, icon, display_order, created_at FROM genres WHERE id = ?1",
+        [genre_id],
+        |row| {
+            Ok(Genre {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                icon: row.get(3)?,
+                display_order: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Add a genre after the existing ones
+pub fn insert_genre(
+    conn: &Connection,
+    id: &str,
+    name: &str,
+    description: &str,
+    icon: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO genres (id, name, description, icon, display_order)
+         VALUES (?1, ?2, ?3, ?4, (SELECT COALESCE(MAX(display_order), -1) + 1 FROM genres))",
+        params![id, name, description, icon],
+    )?;
+    Ok(())
+}
+
+/// Audio jobs for all parts of a story, oldest first
+pub fn get_story_audio_jobs(conn: &Connection, story_id: &str) -> Result<Vec<AudioJob>> {
+    let mut stmt = conn.prepare(
+        "SELECT j.id, j.story_part_id, j.voice_path, j.final_path, j.status, j.error_message, j.created_at, j.updated_at
+         FROM audio_jobs j JOIN story_parts p ON p.id = j.story_part_id
+         WHERE p.story_id = ?1 ORDER BY j.created_at, j.id",
+    )?;
+    let rows = stmt.query_map([story_id], |row| {
+        Ok(AudioJob {
+            id: row.get(0)?,
+            story_part_id: row.get(1)?,
+            voice_path: row.get(2)?,
+            final_path: row.get(3)?,
+            status: row.get(4)?,
+            error_message: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Insert a story row as it was on another computer. Empty timestamps
+/// become now.
+pub fn restore_story(conn: &Connection, story: &Story) -> Result<()> {
+    conn.execute(
+        "INSERT INTO stories (id, title, genre_id, status, is_sample, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(NULLIF(?6, ''), datetime('now')), COALESCE(NULLIF(?7, ''), datetime('now')))",
+        params![
+            story.id,
+            story.title,
+            story.genre_id,
+            story.status,
+            story.is_sample,
+            story.created_at,
+            story.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Insert a story part row as it was on another computer
+pub fn restore_story_part(conn: &Connection, part: &StoryPart) -> Result<()> {
+    conn.execute(
+        "INSERT INTO story_parts (id, story_id, part_number, content, audio_path, status, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(NULLIF(?7, ''), datetime('now')), COALESCE(NULLIF(?8, ''), datetime('now')))",
+        params![
+            part.id,
+            part.story_id,
+            part.part_number,
+            part.content,
+            part.audio_path,
+            part.status,
+            part.created_at,
+            part.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Insert an audio job row as it was on another computer
+pub fn restore_audio_job(conn: &Connection, job: &AudioJob) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audio_jobs (id, story_part_id, voice_path, final_path, status, error_message, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(NULLIF(?7, ''), datetime('now')), COALESCE(NULLIF(?8, ''), datetime('now')))",
+        params![
+            job.id,
+            job.story_part_id,
+            job.voice_path,
+            job.final_path,
+            job.status,
+            job.error_message,
+            job.created_at,
+            job.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Read the character → voice map for a story
+pub fn get_story_characters(conn: &Connection, story_id: &str) -> Result<Vec<StoryCharacter>> {
+    let mut stmt = conn.prepare(
+        "SELECT story_id, name, voice, effect FROM story_characters WHERE story_id = ?1 ORDER BY created_at, name",
+    )?;
+    let rows = stmt.query_map([story_id], |row| {
+        Ok(StoryCharacter {
+            story_id: row.get(0)?,
+            name: row.get(1)?,
+            voice: row.get(2)?,
+            effect: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Add a character without overwriting a voice the user already picked
+pub fn insert_story_character(conn: &Connection, character: &StoryCharacter) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO story_characters (story_id, name, voice, effect) VALUES (?1, ?2, ?3, ?4)",
+        params![character.story_id, character.name, character.voice, character.effect],
+    )?;
+    Ok(())
+}
+
+/// Seed for a story's procedural theme tune, created on first use
+pub fn get_or_create_music_seed(conn: &Connection, story_id: &str) -> Result<u64> {
+    let (seed, _) = uuid::Uuid::new_v4().as_u64_pair();
+    conn.execute(
+        "INSERT OR IGNORE INTO story_music (story_id, seed) VALUES (?1, ?2)",
+        params![story_id, seed as i64],
+    )?;
+    let seed: i64 = conn.query_row(
+        "SELECT seed FROM story_music WHERE story_id = ?1",
+        [story_id],
+        |row| row.get(0),
+    )?;
+    Ok(seed as u64)
+}
+
+/// Seed of a story's theme tune, if it has one yet
+pub fn get_music_seed(conn: &Connection, story_id: &str) -> Result<Option<u64>> {
+    conn.query_row(
+        "SELECT seed FROM story_music WHERE story_id = ?1",
+        [story_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|seed| seed.map(|seed| seed as u64))
+}
+
+/// Store a story's theme tune seed as it was on another computer
+pub fn restore_music_seed(conn: &Connection, story_id: &str, seed: u64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO story_music (story_id, seed) VALUES (?1, ?2)",
+        params![story_id, seed as i64],
+    )?;
+    Ok(())
+}
+
+/// Soundscape name stored for a story, if one was detected or chosen
+pub fn get_story_soundscape(conn: &Connection, story_id: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT soundscape FROM story_soundscapes WHERE story_id = ?1",
+        [story_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Store the soundscape for a story, replacing any earlier choice
+pub fn set_story_soundscape(conn: &Connection, story_id: &str, soundscape: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO story_soundscapes (story_id, soundscape) VALUES (?1, ?2)
+         ON CONFLICT(story_id) DO UPDATE SET soundscape = excluded.soundscape, updated_at = datetime('now')",
+        params![story_id, soundscape],
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,94 +917,303 @@ mod tests {
         assert_eq!(final_p.unwrap(), "/final.wav");
     }
 
-    #[test]
-    fn test_reset_stale_audio_jobs() {
-        let conn = Connection::open_in_memory().unwrap();
+    /// Audio jobs j1..jN for parts p1..pN, all pending, with the queue tables
+    fn setup_audio_queue(conn: &Connection, jobs: usize) {
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/015_audio_job_queue.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/018_audio_job_attempts.sql")).unwrap();
         conn.execute_batch(
-            "CREATE TABLE audio_jobs (
-                id TEXT PRIMARY KEY,
-                story_part_id TEXT NOT NULL,
-                voice_path TEXT,
-                final_path TEXT,
-                status TEXT NOT NULL DEFAULT 'pending',
-                error_message TEXT,
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            CREATE TABLE story_parts (
-                id TEXT PRIMARY KEY,
-                status TEXT NOT NULL DEFAULT 'text_ready',
-                audio_path TEXT,
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            INSERT INTO audio_jobs (id, story_part_id, status) VALUES ('j1', 'p1', 'pending');
-            INSERT INTO audio_jobs (id, story_part_id, status) VALUES ('j2', 'p2', 'voice_generating');
-            INSERT INTO audio_jobs (id, story_part_id, status) VALUES ('j3', 'p3', 'complete');
-            INSERT INTO audio_jobs (id, story_part_id, status) VALUES ('j4', 'p4', 'failed');
-            INSERT INTO story_parts (id, status) VALUES ('p1', 'audio_processing');
-            INSERT INTO story_parts (id, status) VALUES ('p2', 'audio_processing');
-            INSERT INTO story_parts (id, status) VALUES ('p3', 'audio_ready');
-            INSERT INTO story_parts (id, status) VALUES ('p4', 'text_ready');",
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');",
         )
         .unwrap();
+        for i in 1..=jobs {
+            conn.execute(
+                "INSERT INTO story_parts (id, story_id, part_number, content) VALUES (?1, 's1', ?2, 'Text.')",
+                params![format!("p{}", i), i as i64],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO audio_jobs (id, story_part_id) VALUES (?1, ?2)",
+                params![format!("j{}", i), format!("p{}", i)],
+            )
+            .unwrap();
+        }
+    }
 
-        reset_stale_audio_jobs(&conn).unwrap();
+    fn queue_ids(conn: &Connection) -> Vec<String> {
+        get_audio_queue(conn).unwrap().into_iter().map(|job| job.job_id).collect()
+    }
 
-        // In-progress jobs (pending, voice_generating) should be failed
-        for job_id in &["j1", "j2"] {
-            let (status, error): (String, Option<String>) = conn
-                .query_row(
-                    "SELECT status, error_message FROM audio_jobs WHERE id = ?1",
-                    params![job_id],
-                    |row| Ok((row.get(0)?, row.get(1)?)),
-                )
-                .unwrap();
-            assert_eq!(status, "failed", "job {} should be failed", job_id);
-            assert_eq!(error.unwrap(), "Interrupted by app restart");
+    #[test]
+    fn test_requeue_interrupted_audio_jobs() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 5);
+        update_audio_job_status(&conn, "j2", "voice_generating", None).unwrap();
+        update_audio_job_status(&conn, "j3", "complete", None).unwrap();
+        update_audio_job_status(&conn, "j4", "failed", Some("boom")).unwrap();
+        update_audio_job_status(&conn, "j5", "mixing", None).unwrap();
+
+        assert_eq!(requeue_interrupted_audio_jobs(&conn).unwrap(), 3);
+        let queue = get_audio_queue(&conn).unwrap();
+        assert!(queue.iter().all(|job| job.status == "pending"));
+        assert_eq!(queue_ids(&conn), vec!["j1", "j2", "j5"]);
+    }
+
+    #[test]
+    fn test_interrupted_audio_jobs_give_up() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 2);
+        for _ in 0..MAX_AUDIO_JOB_ATTEMPTS {
+            record_audio_job_attempt(&conn, "j1").unwrap();
         }
+        record_audio_job_attempt(&conn, "j2").unwrap();
+        update_audio_job_status(&conn, "j1", "voice_generating", None).unwrap();
+        update_audio_job_status(&conn, "j2", "voice_generating", None).unwrap();
 
-        // Complete job should be untouched
-        let status: String = conn
-            .query_row("SELECT status FROM audio_jobs WHERE id = 'j3'", [], |row| {
-                row.get(0)
+        // j1 was started as often as allowed, so it fails instead
+        assert_eq!(requeue_interrupted_audio_jobs(&conn).unwrap(), 1);
+        assert_eq!(queue_ids(&conn), vec!["j2"]);
+        let (status, error): (String, Option<String>) = conn
+            .query_row("SELECT status, error_message FROM audio_jobs WHERE id = 'j1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
             })
             .unwrap();
-        assert_eq!(status, "complete");
+        assert_eq!(status, "failed");
+        assert!(error.unwrap().contains("Interrupted"));
+        let part: String = conn
+            .query_row("SELECT status FROM story_parts WHERE id = 'p1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(part, "audio_failed");
 
-        // Already-failed job should be untouched (error_message stays null)
-        let status: String = conn
-            .query_row("SELECT status FROM audio_jobs WHERE id = 'j4'", [], |row| {
-                row.get(0)
-            })
+        // Retrying by hand starts the count again
+        assert!(reset_audio_job(&conn, "j1").unwrap());
+        update_audio_job_status(&conn, "j1", "voice_generating", None).unwrap();
+        assert_eq!(requeue_interrupted_audio_jobs(&conn).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_audio_queue_priorities_and_reordering() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 4);
+        enqueue_audio_job(&conn, "j1", 0).unwrap();
+        enqueue_audio_job(&conn, "j2", 0).unwrap();
+        enqueue_audio_job(&conn, "j3", 0).unwrap();
+        // "Play now" jumps ahead of background jobs
+        enqueue_audio_job(&conn, "j4", 1).unwrap();
+        assert_eq!(queue_ids(&conn), vec!["j4", "j1", "j2", "j3"]);
+        assert_eq!(next_queued_audio_job(&conn).unwrap(), Some(("j4".to_string(), "p4".to_string())));
+
+        move_audio_job(&conn, "j3", 1).unwrap();
+        assert_eq!(queue_ids(&conn), vec!["j4", "j3", "j1", "j2"]);
+        move_audio_job(&conn, "j4", 99).unwrap();
+        assert_eq!(queue_ids(&conn), vec!["j3", "j1", "j2", "j4"]);
+        assert!(move_audio_job(&conn, "missing", 0).is_err());
+
+        // Running jobs are listed first and never picked again
+        update_audio_job_status(&conn, "j1", "voice_generating", None).unwrap();
+        assert_eq!(queue_ids(&conn), vec!["j1", "j3", "j2", "j4"]);
+        assert_eq!(next_queued_audio_job(&conn).unwrap().unwrap().0, "j3");
+    }
+
+    #[test]
+    fn test_cancel_and_retry_audio_jobs() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 2);
+        conn.execute_batch(include_str!("../../migrations/014_audio_job_cancelled.sql")).unwrap();
+        assert_eq!(get_audio_job_concurrency(&conn).unwrap(), 1);
+
+        assert!(finish_pending_audio_job(&conn, "j1", "cancelled").unwrap());
+        assert!(!finish_pending_audio_job(&conn, "j1", "cancelled").unwrap());
+        assert_eq!(queue_ids(&conn), vec!["j2"]);
+
+        // Only failed or cancelled jobs can be retried
+        assert!(!reset_audio_job(&conn, "j2").unwrap());
+        assert!(reset_audio_job(&conn, "j1").unwrap());
+        assert_eq!(queue_ids(&conn), vec!["j1", "j2"]);
+    }
+
+    #[test]
+    fn test_finished_audio_jobs_leave_the_queue() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 3);
+        conn.execute_batch(include_str!("../../migrations/014_audio_job_cancelled.sql")).unwrap();
+        for job in ["j1", "j2", "j3"] {
+            enqueue_audio_job(&conn, job, 0).unwrap();
+        }
+        let queued = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM audio_job_queue", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        assert!(finish_pending_audio_job(&conn, "j1", "cancelled").unwrap());
+        update_audio_job_status(&conn, "j2", "voice_generating", None).unwrap();
+        assert_eq!(queued(&conn), 2);
+        update_audio_job_status(&conn, "j2", "complete", None).unwrap();
+        assert_eq!(queued(&conn), 1);
+
+        // Rows a finished job left behind are cleared on the next start
+        conn.execute("INSERT INTO audio_job_queue (job_id, position) VALUES ('j2', 9)", [])
             .unwrap();
-        assert_eq!(status, "failed");
+        requeue_interrupted_audio_jobs(&conn).unwrap();
+        assert_eq!(queued(&conn), 1);
+    }
+
+    #[test]
+    fn test_cancelled_status_migration_keeps_jobs() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');
+             INSERT INTO story_parts (id, story_id, part_number, content) VALUES ('p1', 's1', 1, 'Hi.');
+             INSERT INTO audio_jobs (id, story_part_id, status) VALUES ('j1', 'p1', 'voice_generating');",
+        )
+        .unwrap();
+        assert!(update_audio_job_status(&conn, "j1", "cancelled", None).is_err());
+
+        conn.execute_batch(include_str!("../../migrations/014_audio_job_cancelled.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/015_audio_job_queue.sql")).unwrap();
+
+        update_audio_job_status(&conn, "j1", "cancelled", None).unwrap();
+        // The rebuilt table still cascades from story parts
+        conn.execute("DELETE FROM story_parts WHERE id = 'p1'", []).unwrap();
+        let jobs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audio_jobs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(jobs, 0);
+    }
 
-        // audio_processing story parts should be audio_failed
-        for part_id in &["p1", "p2"] {
-            let status: String = conn
-                .query_row(
-                    "SELECT status FROM story_parts WHERE id = ?1",
-                    params![part_id],
-                    |row| row.get(0),
-                )
+    #[test]
+    fn test_cancelled_status_migration_runs_twice() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        apply_rusqlite_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');",
+        )
+        .unwrap();
+        insert_story_part(&conn, "p1", "s1", 1, "Hi.", "text_ready").unwrap();
+        insert_audio_job(&conn, "j1", "p1").unwrap();
+        enqueue_audio_job(&conn, "j1", 1).unwrap();
+        record_audio_job_attempt(&conn, "j1").unwrap();
+        insert_audio_job_sentence(
+            &conn,
+            &AudioJobSentence {
+                job_id: "j1".to_string(),
+                sentence_index: 0,
+                text: "Hi.".to_string(),
+                wav_path: "/tmp/p1/hi.wav".to_string(),
+                duration_secs: 0.5,
+            },
+        )
+        .unwrap();
+
+        // Once from Rust at startup, again when the SQL plugin loads
+        for _ in 0..2 {
+            conn.execute_batch(include_str!("../../migrations/014_audio_job_cancelled.sql"))
                 .unwrap();
-            assert_eq!(status, "audio_failed", "part {} should be audio_failed", part_id);
         }
 
-        // Other story parts should be untouched
-        let status: String = conn
-            .query_row("SELECT status FROM story_parts WHERE id = 'p3'", [], |row| {
-                row.get(0)
-            })
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("audio_jobs"), 1);
+        assert_eq!(count("audio_job_queue"), 1);
+        assert_eq!(count("audio_job_attempts"), 1);
+        assert_eq!(get_audio_job_sentences(&conn, "j1").unwrap().len(), 1);
+        // The restored rows still cascade from the rebuilt table
+        update_audio_job_status(&conn, "j1", "cancelled", None).unwrap();
+        assert_eq!(count("audio_job_queue"), 0);
+        conn.execute("DELETE FROM audio_jobs WHERE id = 'j1'", []).unwrap();
+        assert_eq!(count("audio_job_attempts"), 0);
+        assert_eq!(count("audio_job_sentences"), 0);
+    }
+
+    #[test]
+    fn test_audio_job_sentence_progress() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/019_audio_job_sentences.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');
+             INSERT INTO story_parts (id, story_id, part_number, content) VALUES ('p1', 's1', 1, 'Once upon a time.');
+             INSERT INTO audio_jobs (id, story_part_id) VALUES ('j1', 'p1');",
+        )
+        .unwrap();
+
+        let sentence = |i: usize| AudioJobSentence {
+            job_id: "j1".to_string(),
+            sentence_index: i,
+            text: format!("Sentence {}.", i),
+            wav_path: format!("/tmp/segments/p1/{}.wav", i),
+            duration_secs: 1.5,
+        };
+        insert_audio_job_sentence(&conn, &sentence(1)).unwrap();
+        insert_audio_job_sentence(&conn, &sentence(0)).unwrap();
+        // Re-recording a sentence replaces it
+        insert_audio_job_sentence(&conn, &sentence(1)).unwrap();
+        assert_eq!(get_audio_job_sentences(&conn, "j1").unwrap(), vec![sentence(0), sentence(1)]);
+
+        delete_audio_job_sentences(&conn, "j1").unwrap();
+        assert!(get_audio_job_sentences(&conn, "j1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_story_written_from_rust() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch("INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');")
             .unwrap();
-        assert_eq!(status, "audio_ready");
 
-        let status: String = conn
-            .query_row("SELECT status FROM story_parts WHERE id = 'p4'", [], |row| {
-                row.get(0)
-            })
+        insert_story(&conn, "s1", "Story", "g").unwrap();
+        insert_story_part(&conn, "p1", "s1", 1, "", "audio_processing").unwrap();
+        insert_audio_job(&conn, "j1", "p1").unwrap();
+        update_story_part_content(&conn, "p1", "Once upon a time.").unwrap();
+        assert_eq!(get_story_part_content(&conn, "p1").unwrap(), "Once upon a time.");
+        assert_eq!(get_story_id_for_part(&conn, "p1").unwrap(), "s1");
+
+        delete_story(&conn, "s1").unwrap();
+        let jobs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audio_jobs", [], |row| row.get(0))
             .unwrap();
-        assert_eq!(status, "text_ready");
+        assert_eq!(jobs, 0);
+        assert!(get_story_part_content(&conn, "p1").is_err());
+    }
+
+    #[test]
+    fn test_get_story_part_audio_in_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_audio_queue(&conn, 3);
+        update_story_part_audio(&conn, "p3", "audio_ready", Some("/a/p3_final.wav")).unwrap();
+        update_story_part_audio(&conn, "p1", "audio_ready", Some("/a/p1_final.wav")).unwrap();
+        update_story_part_audio(&conn, "p2", "audio_failed", None).unwrap();
+
+        assert_eq!(get_story_title(&conn, "s1").unwrap(), "Story");
+        assert_eq!(get_genre_name(&conn, "g").unwrap(), "G");
+        assert_eq!(get_story_parts_without_audio(&conn, "s1").unwrap(), vec![2]);
+        let numbers: Vec<i32> = get_story_parts(&conn, "s1")
+            .unwrap()
+            .iter()
+            .map(|part| part.part_number)
+            .collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert_eq!(
+            get_story_part_audio(&conn, "s1").unwrap(),
+            vec![
+                ("p1".to_string(), "/a/p1_final.wav".to_string()),
+                ("p3".to_string(), "/a/p3_final.wav".to_string()),
+            ]
+        );
     }
 
     /// Apply all migrations to set up app_settings in test DB
@@ -262,6 +1222,10 @@ mod tests {
         conn.execute_batch(include_str!("../../migrations/005_kokoro_voice_settings.sql")).unwrap();
         conn.execute_batch(include_str!("../../migrations/006_kittentts_voice_settings.sql")).unwrap();
         conn.execute_batch(include_str!("../../migrations/007_kokoro_onnx_voice_settings.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/008_voice_effect_setting.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/009_audio_mastering_setting.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/011_music_setting.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/013_story_soundscapes.sql")).unwrap();
     }
 
     #[test]
@@ -272,6 +1236,10 @@ mod tests {
         let settings = get_voice_settings(&conn).unwrap();
         assert_eq!(settings.voice, "af_nova");
         assert_eq!(settings.speed, Some(1.0));
+        assert_eq!(settings.effect, "none");
+        assert_eq!(settings.mastering, "none");
+        assert!(!settings.music_enabled);
+        assert!(!settings.ambience_enabled);
     }
 
     #[test]
@@ -287,4 +1255,189 @@ mod tests {
         let settings = get_voice_settings(&conn).unwrap();
         assert_eq!(settings.voice, "bf_emma");
     }
+
+    #[test]
+    fn test_story_characters_insert_keeps_existing_voice() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/010_story_characters.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');
+             INSERT INTO story_parts (id, story_id, part_number, content) VALUES ('p1', 's1', 1, 'x');",
+        )
+        .unwrap();
+
+        let wolf = StoryCharacter {
+            story_id: "s1".to_string(),
+            name: "Wolf".to_string(),
+            voice: "bm_george".to_string(),
+            effect: "wolf".to_string(),
+        };
+        insert_story_character(&conn, &wolf).unwrap();
+        insert_story_character(
+            &conn,
+            &StoryCharacter {
+                voice: "af_sky".to_string(),
+                ..wolf.clone()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(get_story_id_for_part(&conn, "p1").unwrap(), "s1");
+        assert_eq!(get_story_genre(&conn, "s1").unwrap(), "g");
+        assert_eq!(get_story_characters(&conn, "s1").unwrap(), vec![wolf]);
+    }
+
+    #[test]
+    fn test_music_seed_is_stable_per_story() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/012_story_music.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s2', 'Other', 'g');",
+        )
+        .unwrap();
+
+        let seed = get_or_create_music_seed(&conn, "s1").unwrap();
+        assert_eq!(get_or_create_music_seed(&conn, "s1").unwrap(), seed);
+        assert_ne!(get_or_create_music_seed(&conn, "s2").unwrap(), seed);
+    }
+
+    #[test]
+    fn test_story_soundscape_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/013_story_soundscapes.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');",
+        )
+        .unwrap();
+
+        assert_eq!(get_story_soundscape(&conn, "s1").unwrap(), None);
+        set_story_soundscape(&conn, "s1", "rain").unwrap();
+        set_story_soundscape(&conn, "s1", "ocean").unwrap();
+        assert_eq!(get_story_soundscape(&conn, "s1").unwrap().as_deref(), Some("ocean"));
+    }
+
+    #[test]
+    fn test_restore_story_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        assert_eq!(get_genre(&conn, "pets").unwrap().map(|g| g.id), None);
+        insert_genre(&conn, "pets", "Pets", "Furry friends", Some("paw-print")).unwrap();
+        assert_eq!(get_genre(&conn, "pets").unwrap().unwrap().display_order, 0);
+
+        let story = Story {
+            id: "s1".to_string(),
+            title: "Fox".to_string(),
+            genre_id: "pets".to_string(),
+            status: "complete".to_string(),
+            is_sample: 0,
+            created_at: "2024-01-01 10:00:00".to_string(),
+            updated_at: String::new(),
+        };
+        restore_story(&conn, &story).unwrap();
+        restore_story_part(
+            &conn,
+            &StoryPart {
+                id: "p1".to_string(),
+                story_id: "s1".to_string(),
+                part_number: 1,
+                content: "Text".to_string(),
+                audio_path: Some("/a/p1_final.wav".to_string()),
+                status: "audio_ready".to_string(),
+                created_at: String::new(),
+                updated_at: String::new(),
+            },
+        )
+        .unwrap();
+        restore_audio_job(
+            &conn,
+            &AudioJob {
+                id: "j1".to_string(),
+                story_part_id: "p1".to_string(),
+                voice_path: None,
+                final_path: Some("/a/p1_final.wav".to_string()),
+                status: "complete".to_string(),
+                error_message: None,
+                created_at: String::new(),
+                updated_at: String::new(),
+            },
+        )
+        .unwrap();
+
+        let restored = get_story(&conn, "s1").unwrap();
+        assert_eq!(restored.created_at, "2024-01-01 10:00:00");
+        assert!(!restored.updated_at.is_empty());
+        let jobs = get_story_audio_jobs(&conn, "s1").unwrap();
+        assert_eq!(jobs[0].final_path.as_deref(), Some("/a/p1_final.wav"));
+    }
+
+    #[test]
+    fn test_part_questions_replace() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/016_story_part_questions.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');
+             INSERT INTO story_parts (id, story_id, part_number, content) VALUES ('p1', 's1', 1, 'Text');",
+        )
+        .unwrap();
+
+        assert!(get_part_questions(&conn, "p1").unwrap().is_empty());
+        let first = vec!["Why?".to_string(), "Who?".to_string(), "Where?".to_string()];
+        set_part_questions(&conn, "p1", &first).unwrap();
+        assert_eq!(get_part_questions(&conn, "p1").unwrap(), first);
+        set_part_questions(&conn, "p1", &["How?".to_string()]).unwrap();
+        assert_eq!(get_part_questions(&conn, "p1").unwrap(), vec!["How?".to_string()]);
+    }
+
+    #[test]
+    fn test_podcast_feed_episodes_and_settings() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/017_podcast_feed_settings.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('pets', 'Pets', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Fox', 'pets');
+             INSERT INTO story_parts (id, story_id, part_number, content, audio_path, status, updated_at)
+             VALUES ('p1', 's1', 1, 'One', '/a/p1.wav', 'audio_ready', '2026-01-01 10:00:00'),
+                    ('p2', 's1', 2, 'Two', '/a/p2.wav', 'audio_ready', '2026-01-02 10:00:00'),
+                    ('p3', 's1', 3, 'Three', NULL, 'text_ready', '2026-01-03 10:00:00');",
+        )
+        .unwrap();
+
+        let episodes = get_podcast_episodes(&conn).unwrap();
+        let ids: Vec<&str> = episodes.iter().map(|e| e.part_id.as_str()).collect();
+        assert_eq!(ids, vec!["p2", "p1"]);
+        assert_eq!(episodes[0].genre_name, "Pets");
+        assert_eq!(episodes[0].story_title, "Fox");
+        assert_eq!(get_podcast_episode(&conn, "p1").unwrap().unwrap().audio_path, "/a/p1.wav");
+        assert_eq!(get_podcast_episode(&conn, "p3").unwrap(), None);
+
+        let settings = get_podcast_feed_settings(&conn).unwrap();
+        assert_eq!(
+            settings,
+            PodcastFeedSettings {
+                enabled: false,
+                address: "127.0.0.1".to_string(),
+                port: 8765,
+            }
+        );
+        conn.execute_batch(
+            "UPDATE app_settings SET value = 'true' WHERE key = 'podcast_feed_enabled';
+             UPDATE app_settings SET value = 'nope' WHERE key = 'podcast_feed_port';",
+        )
+        .unwrap();
+        let settings = get_podcast_feed_settings(&conn).unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.port, 8765);
+    }
 }
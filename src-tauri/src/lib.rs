@@ -49,6 +49,78 @@ fn migrations() -> Vec<Migration> {
             sql: include_str!("../migrations/007_kokoro_onnx_voice_settings.sql"),
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 8,
+            description: "Voice effect setting",
+            sql: include_str!("../migrations/008_voice_effect_setting.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "Audio mastering setting",
+            sql: include_str!("../migrations/009_audio_mastering_setting.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "Story characters table",
+            sql: include_str!("../migrations/010_story_characters.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "Music bed setting",
+            sql: include_str!("../migrations/011_music_setting.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "Story theme music seeds",
+            sql: include_str!("../migrations/012_story_music.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "Story soundscapes and ambience setting",
+            sql: include_str!("../migrations/013_story_soundscapes.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "Cancelled audio job status",
+            sql: include_str!("../migrations/014_audio_job_cancelled.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "Audio job queue",
+            sql: include_str!("../migrations/015_audio_job_queue.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "Story part discussion questions",
+            sql: include_str!("../migrations/016_story_part_questions.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "Podcast feed settings",
+            sql: include_str!("../migrations/017_podcast_feed_settings.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "Audio job attempts",
+            sql: include_str!("../migrations/018_audio_job_attempts.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "Audio job sentence progress",
+            sql: include_str!("../migrations/019_audio_job_sentences.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
@@ -77,13 +149,25 @@ pub fn run() {
                             Ok(()) => log::info!("Applied rusqlite migrations on startup"),
                             Err(e) => log::warn!("Failed to apply rusqlite migrations: {e}"),
                         }
-                        match db::queries::reset_stale_audio_jobs(&conn) {
-                            Ok(()) => log::info!("Reset stale audio jobs on startup"),
-                            Err(e) => log::warn!("Failed to reset stale audio jobs: {e}"),
+                        match commands::audio::resume_interrupted_jobs(app.handle(), &conn) {
+                            Ok(0) => {}
+                            Ok(n) => log::info!("Resuming {n} interrupted audio jobs"),
+                            Err(e) => log::warn!("Failed to resume audio jobs: {e}"),
                         }
                     }
                     Err(e) => log::warn!("Failed to open DB on startup: {e}"),
                 }
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    match commands::podcast::apply_podcast_feed(handle).await {
+                        Ok(status) => {
+                            if let Some(url) = status.url {
+                                log::info!("Podcast feed published at {url}");
+                            }
+                        }
+                        Err(e) => log::warn!("Failed to start podcast feed: {e}"),
+                    }
+                });
             }
             Ok(())
         })
@@ -94,8 +178,27 @@ pub fn run() {
             commands::stories::generate_story_text,
             commands::stories::continue_story,
             commands::stories::get_story_detail,
+            commands::stories::generate_discussion_questions,
             commands::audio::start_audio_generation,
+            commands::audio::narrate_new_story,
+            commands::audio::cancel_audio_generation,
+            commands::audio::list_audio_queue,
+            commands::audio::reorder_audio_job,
+            commands::audio::retry_audio_job,
             commands::audio::get_audio_job_status,
+            commands::audio::phonemize_text,
+            commands::audio::synthesize_phonemes,
+            commands::export::export_story_captions,
+            commands::export::export_audiobook,
+            commands::export::export_story_epub,
+            commands::export::export_story_pdf,
+            commands::bundle::export_story_bundle,
+            commands::bundle::import_story_bundle,
+            commands::voices::list_voices,
+            commands::voices::import_voice,
+            commands::podcast::list_network_interfaces,
+            commands::podcast::apply_podcast_feed,
+            commands::podcast::get_podcast_feed_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
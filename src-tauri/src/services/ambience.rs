@@ -0,0 +1,93 @@
+use kokoro_tts::audio::ambience::Soundscape;
+
+/// Words that suggest each soundscape, matched against whole lowercase words.
+const KEYWORDS: &[(Soundscape, &[&str])] = &[
+    (
+        Soundscape::Rain,
+        &["rain", "raining", "rainy", "raindrops", "storm", "stormy", "thunder", "puddle", "puddles", "drizzle", "umbrella", "monsoon"],
+    ),
+    (
+        Soundscape::Ocean,
+        &["sea", "ocean", "beach", "waves", "shore", "island", "sail", "sailed", "ship", "boat", "tide", "seashell", "harbour", "harbor"],
+    ),
+    (
+        Soundscape::Wind,
+        &["wind", "windy", "breeze", "gust", "mountain", "mountains", "desert", "blizzard", "snowstorm", "cliff", "kite"],
+    ),
+    (
+        Soundscape::Fire,
+        &["fire", "campfire", "fireplace", "hearth", "flames", "embers", "bonfire", "lantern", "cosy", "cozy"],
+    ),
+    (
+        Soundscape::Crickets,
+        &["night", "forest", "crickets", "stars", "moon", "moonlight", "owl", "fireflies", "meadow", "jungle", "woods"],
+    ),
+];
+
+/// A setting needs at least this many keyword hits before it gets ambience.
+const MIN_HITS: usize = 2;
+
+/// Guess a soundscape from the story text by counting setting keywords.
+/// Returns `None` when nothing stands out.
+pub fn detect_soundscape(text: &str) -> Option<Soundscape> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    KEYWORDS
+        .iter()
+        .map(|(scape, keywords)| {
+            let hits = words.iter().filter(|w| keywords.contains(w)).count();
+            (*scape, hits)
+        })
+        .filter(|&(_, hits)| hits >= MIN_HITS)
+        // Earlier entries win ties: rain and sea are more distinctive than night
+        .fold(None, |best: Option<(Soundscape, usize)>, (scape, hits)| match best {
+            Some((_, best_hits)) if best_hits >= hits => best,
+            _ => Some((scape, hits)),
+        })
+        .map(|(scape, _)| scape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_dominant_setting() {
+        assert_eq!(
+            detect_soundscape("The rain fell all night. Thunder rolled as Mia jumped in puddles."),
+            Some(Soundscape::Rain)
+        );
+        assert_eq!(
+            detect_soundscape("Ravi sailed his little boat across the sea to the island."),
+            Some(Soundscape::Ocean)
+        );
+        assert_eq!(
+            detect_soundscape("They sat by the campfire, watching the flames dance."),
+            Some(Soundscape::Fire)
+        );
+    }
+
+    #[test]
+    fn needs_more_than_one_mention() {
+        assert_eq!(detect_soundscape("Leo liked the moon. He also liked cake."), None);
+        assert_eq!(detect_soundscape(""), None);
+    }
+
+    #[test]
+    fn matches_whole_words_only() {
+        // "seashore" and "windows" are not "sea" or "wind"
+        assert_eq!(detect_soundscape("The seashore windows and windows."), None);
+    }
+
+    #[test]
+    fn ties_prefer_the_more_distinctive_setting() {
+        assert_eq!(
+            detect_soundscape("At night under the stars, the rain began and a storm came."),
+            Some(Soundscape::Rain)
+        );
+    }
+}
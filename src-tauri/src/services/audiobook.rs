@@ -0,0 +1,179 @@
+use kokoro_tts::audio::{self, aac, mp4::M4aOptions, tags::Tags};
+
+/// Silence between parts, and after the spoken title.
+pub const PART_GAP_SECS: f32 = 1.0;
+
+/// Cover art: the app icon, as the podcast feed uses.
+const COVER_PNG: &[u8] = include_bytes!("../../icons/256x256.png");
+
+/// AAC bitrate for mono narration.
+const M4B_BITRATE_KBPS: u32 = 64;
+
+/// Audiobook containers `export_audiobook` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudiobookFormat {
+    /// AAC in an MP4 container with a chapter per part
+    M4b,
+    /// MP3 with ID3v2 chapter frames
+    Mp3,
+}
+
+impl AudiobookFormat {
+    /// Parse `m4b` or `mp3`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "m4b" => Some(Self::M4b),
+            "mp3" => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::M4b => "m4b",
+            Self::Mp3 => "mp3",
+        }
+    }
+}
+
+/// A named span of the audiobook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+/// Tags written into the audiobook.
+#[derive(Debug, Clone)]
+pub struct AudiobookTags {
+    pub title: String,
+    pub genre: String,
+}
+
+/// Chapters for parts of the given lengths laid out one after another with
+/// `gap_secs` of silence between them, after `intro_secs` of spoken title.
+/// The title belongs to the first chapter.
+pub fn chapters(part_secs: &[f64], intro_secs: f64, gap_secs: f64) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut at = intro_secs;
+    for (i, secs) in part_secs.iter().enumerate() {
+        let start = if i == 0 { 0.0 } else { at };
+        chapters.push(Chapter {
+            title: format!("Part {}", i + 1),
+            start_secs: start,
+            end_secs: at + secs,
+        });
+        at += secs + gap_secs;
+    }
+    chapters
+}
+
+/// Encode mono `samples` as an audiobook file with tags and `chapters`.
+pub fn encode(
+    format: AudiobookFormat,
+    samples: &[f32],
+    sample_rate: u32,
+    tags: &AudiobookTags,
+    chapters: &[Chapter],
+) -> Result<Vec<u8>, String> {
+    let tags = Tags {
+        title: Some(tags.title.clone()),
+        artist: Some("Pattikadhai".to_string()),
+        album: Some(tags.title.clone()),
+        genre: Some(tags.genre.clone()),
+        cover: Some(COVER_PNG.to_vec()),
+    };
+    let chapters: Vec<audio::tags::Chapter> = chapters
+        .iter()
+        .map(|chapter| audio::tags::Chapter {
+            title: chapter.title.clone(),
+            start_ms: (chapter.start_secs * 1000.0).round() as u64,
+            end_ms: (chapter.end_secs * 1000.0).round() as u64,
+        })
+        .collect();
+
+    match format {
+        AudiobookFormat::M4b => {
+            let (samples, sample_rate) = if aac::supports_rate(sample_rate) {
+                (samples.to_vec(), sample_rate)
+            } else {
+                let resampled =
+                    audio::resample_linear(samples, sample_rate, audio::SAMPLE_RATE);
+                (resampled, audio::SAMPLE_RATE)
+            };
+            let options = M4aOptions {
+                bitrate_kbps: Some(M4B_BITRATE_KBPS),
+                audiobook: true,
+                tags,
+                chapters,
+            };
+            audio::mp4::encode_m4a(&samples, 1, sample_rate, &options)
+        }
+        AudiobookFormat::Mp3 => {
+            let bitrate = audio::mp3::default_bitrate(sample_rate, 1);
+            audio::mp3::encode_mp3(samples, 1, sample_rate, bitrate).map(|frames| {
+                let mut out = audio::id3::id3v2(&tags, &chapters);
+                out.extend(frames);
+                out
+            })
+        }
+    }
+    .map_err(|e| format!("Failed to encode audiobook: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chapters_follow_parts_with_gaps() {
+        let chapters = chapters(&[10.0, 5.0, 2.5], 3.0, 1.0);
+        let spans: Vec<(f64, f64)> = chapters.iter().map(|c| (c.start_secs, c.end_secs)).collect();
+        // The spoken title opens part 1
+        assert_eq!(spans, vec![(0.0, 13.0), (14.0, 19.0), (20.0, 22.5)]);
+        assert_eq!(chapters[2].title, "Part 3");
+    }
+
+    fn tags() -> AudiobookTags {
+        AudiobookTags {
+            title: "The Brave Fox".to_string(),
+            genre: "Animal Tales".to_string(),
+        }
+    }
+
+    fn tone(seconds: f32) -> Vec<f32> {
+        (0..(seconds * 24000.0) as usize)
+            .map(|i| 0.3 * (i as f32 * 0.05).sin())
+            .collect()
+    }
+
+    #[test]
+    fn m4b_is_tagged_with_chapters() {
+        let chapters = chapters(&[1.0, 0.5], 0.0, 1.0);
+        let bytes = encode(AudiobookFormat::M4b, &tone(2.5), 24000, &tags(), &chapters).unwrap();
+        assert_eq!(&bytes[4..12], b"ftypM4B ");
+        let find = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+        assert!(find(b"chpl"));
+        assert!(find(b"covr"));
+        assert!(find(b"The Brave Fox"));
+        assert!(find(b"Part 2"));
+    }
+
+    #[test]
+    fn mp3_starts_with_id3_chapters() {
+        let chapters = chapters(&[1.0], 0.0, 1.0);
+        let bytes = encode(AudiobookFormat::Mp3, &tone(1.0), 24000, &tags(), &chapters).unwrap();
+        assert_eq!(&bytes[..3], b"ID3");
+        let find = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+        assert!(find(b"CHAP"));
+        assert!(find(b"APIC"));
+        assert!(find(b"Animal Tales"));
+    }
+
+    #[test]
+    fn m4b_resamples_rates_aac_lacks() {
+        let bytes = encode(AudiobookFormat::M4b, &tone(0.5), 22000, &tags(), &[]).unwrap();
+        assert_eq!(&bytes[4..12], b"ftypM4B ");
+    }
+}
@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::db::models::{AudioJob, Genre, Story, StoryPart};
+
+/// Identifies a story bundle's manifest.
+pub const BUNDLE_FORMAT: &str = "pattikadhai-story";
+/// Layout of the archive; bundles with a newer layout are refused.
+pub const BUNDLE_VERSION: u32 = 1;
+/// Latest database migration, recorded so an importing app knows which
+/// columns the rows came from. Bump along with new migrations.
+pub const SCHEMA_VERSION: u32 = 18;
+
+const MANIFEST: &str = "manifest.json";
+
+/// Largest manifest read from a bundle.
+const MAX_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+/// Largest single file read from a bundle; well over an hour of a part's
+/// float WAV audio.
+const MAX_FILE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// `manifest.json`: the story's rows and a checksum for every other file in
+/// the archive. Fields a bundle lacks take defaults, so bundles from older
+/// and newer schemas still parse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub exported_at: String,
+    pub story: BundleStory,
+    /// The story's genre, recreated on import if it is missing there
+    #[serde(default)]
+    pub genre: Option<BundleGenre>,
+    #[serde(default)]
+    pub parts: Vec<BundlePart>,
+    #[serde(default)]
+    pub audio_jobs: Vec<BundleAudioJob>,
+    /// The story's cast and the voice each character speaks with
+    #[serde(default)]
+    pub characters: Vec<BundleCharacter>,
+    /// Seed of the story's theme tune
+    #[serde(default)]
+    pub music_seed: Option<u64>,
+    /// Soundscape detected or picked for the story
+    #[serde(default)]
+    pub soundscape: Option<String>,
+    #[serde(default)]
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleStory {
+    pub id: String,
+    pub title: String,
+    pub genre_id: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleGenre {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePart {
+    pub id: String,
+    pub part_number: i32,
+    pub content: String,
+    #[serde(default)]
+    pub status: String,
+    /// Archive path of the part's final audio
+    #[serde(default)]
+    pub audio_file: Option<String>,
+    /// Archive path of the part's sentence timing
+    #[serde(default)]
+    pub timing_file: Option<String>,
+    /// Discussion questions, in order
+    #[serde(default)]
+    pub questions: Vec<String>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleAudioJob {
+    pub id: String,
+    pub story_part_id: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    /// Whether the job's output is the part's bundled audio
+    #[serde(default)]
+    pub produced_audio: bool,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleCharacter {
+    pub name: String,
+    pub voice: String,
+    #[serde(default = "default_effect")]
+    pub effect: String,
+}
+
+fn default_effect() -> String {
+    "none".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl Manifest {
+    /// A manifest for the story's rows at the current schema. Part files,
+    /// questions and the story's cast, music and soundscape are filled in by
+    /// the caller.
+    pub fn new(story: &Story, genre: Option<&Genre>, parts: &[StoryPart], jobs: &[AudioJob]) -> Self {
+        Manifest {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            schema_version: SCHEMA_VERSION,
+            exported_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            story: BundleStory {
+                id: story.id.clone(),
+                title: story.title.clone(),
+                genre_id: story.genre_id.clone(),
+                status: story.status.clone(),
+                created_at: story.created_at.clone(),
+                updated_at: story.updated_at.clone(),
+            },
+            genre: genre.map(|g| BundleGenre {
+                id: g.id.clone(),
+                name: g.name.clone(),
+                description: g.description.clone(),
+                icon: g.icon.clone(),
+            }),
+            parts: parts
+                .iter()
+                .map(|p| BundlePart {
+                    id: p.id.clone(),
+                    part_number: p.part_number,
+                    content: p.content.clone(),
+                    status: p.status.clone(),
+                    audio_file: None,
+                    timing_file: None,
+                    questions: Vec::new(),
+                    created_at: p.created_at.clone(),
+                    updated_at: p.updated_at.clone(),
+                })
+                .collect(),
+            audio_jobs: jobs
+                .iter()
+                .map(|j| BundleAudioJob {
+                    id: j.id.clone(),
+                    story_part_id: j.story_part_id.clone(),
+                    status: j.status.clone(),
+                    error_message: j.error_message.clone(),
+                    produced_audio: false,
+                    created_at: j.created_at.clone(),
+                    updated_at: j.updated_at.clone(),
+                })
+                .collect(),
+            characters: Vec::new(),
+            music_seed: None,
+            soundscape: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Give the story, its parts and jobs fresh IDs, keeping the links
+    /// between them. Every import does this: IDs from the archive are
+    /// neither trusted as file names nor allowed to clash with rows here.
+    pub fn reassign_ids(&mut self) {
+        self.story.id = uuid::Uuid::new_v4().to_string();
+        let mut part_ids = HashMap::new();
+        for part in &mut self.parts {
+            let id = uuid::Uuid::new_v4().to_string();
+            part_ids.insert(std::mem::replace(&mut part.id, id.clone()), id);
+        }
+        for job in &mut self.audio_jobs {
+            job.id = uuid::Uuid::new_v4().to_string();
+            if let Some(id) = part_ids.get(&job.story_part_id) {
+                job.story_part_id = id.clone();
+            }
+        }
+    }
+}
+
+/// Zip the manifest and files, recording each file's size and SHA-256.
+pub fn pack(mut manifest: Manifest, files: Vec<(String, Vec<u8>)>) -> Result<Vec<u8>, String> {
+    manifest.files = files
+        .iter()
+        .map(|(path, bytes)| BundleFile {
+            path: path.clone(),
+            sha256: sha256_hex(bytes),
+            size: bytes.len() as u64,
+        })
+        .collect();
+    write_archive(&manifest, &files)
+}
+
+/// A bundle read back, with its files checked against the manifest.
+pub struct Unpacked {
+    pub manifest: Manifest,
+    /// File bytes by archive path
+    pub files: HashMap<String, Vec<u8>>,
+}
+
+/// Read a bundle, verify every file's size and checksum, and bring the rows
+/// in line with this schema: statuses it does not know, or that no longer
+/// hold without the bundled files, are mapped to the nearest it does.
+pub fn unpack(bytes: &[u8]) -> Result<Unpacked, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Not a story bundle: {}", e))?;
+    let manifest_bytes = read_entry(&mut archive, MANIFEST, MAX_MANIFEST_BYTES)?;
+    let mut manifest: Manifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err("Not a Pattikadhai story bundle".to_string());
+    }
+    if manifest.version > BUNDLE_VERSION {
+        return Err(format!(
+            "This bundle uses format version {}; update Pattikadhai to import it",
+            manifest.version
+        ));
+    }
+
+    let mut files = HashMap::new();
+    for file in &manifest.files {
+        if file.size > MAX_FILE_BYTES {
+            return Err(format!("{} is too large to import", file.path));
+        }
+        let bytes = read_entry(&mut archive, &file.path, file.size)?;
+        if bytes.len() as u64 != file.size || sha256_hex(&bytes) != file.sha256 {
+            return Err(format!("{} is damaged (checksum mismatch)", file.path));
+        }
+        files.insert(file.path.clone(), bytes);
+    }
+    for part in &manifest.parts {
+        for path in [&part.audio_file, &part.timing_file].into_iter().flatten() {
+            if !files.contains_key(path) {
+                return Err(format!("Part {} refers to missing file {}", part.part_number, path));
+            }
+        }
+    }
+
+    normalize(&mut manifest);
+    Ok(Unpacked { manifest, files })
+}
+
+fn normalize(manifest: &mut Manifest) {
+    if !matches!(manifest.story.status.as_str(), "draft" | "complete") {
+        manifest.story.status = "draft".to_string();
+    }
+    for part in &mut manifest.parts {
+        let has_audio = part.audio_file.is_some();
+        part.status = match part.status.as_str() {
+            "draft" | "text_ready" | "audio_failed" => part.status.clone(),
+            _ if has_audio => "audio_ready".to_string(),
+            _ => "text_ready".to_string(),
+        };
+    }
+    // Jobs whose part is not in the bundle are dropped; unfinished ones are
+    // cancelled, since nothing on this computer is running them
+    let audio_parts: HashMap<&str, bool> = manifest
+        .parts
+        .iter()
+        .map(|p| (p.id.as_str(), p.audio_file.is_some()))
+        .collect();
+    manifest.audio_jobs.retain_mut(|job| {
+        let Some(&has_audio) = audio_parts.get(job.story_part_id.as_str()) else {
+            return false;
+        };
+        job.produced_audio &= has_audio;
+        if !matches!(job.status.as_str(), "complete" | "failed" | "cancelled") {
+            job.status = "cancelled".to_string();
+        }
+        true
+    });
+}
+
+fn write_archive(manifest: &Manifest, files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to write bundle manifest: {}", e))?;
+    add(&mut zip, MANIFEST, &json, deflated)?;
+    for (path, bytes) in files {
+        // Audio gains little from compression
+        let options = if path.ends_with(".json") { deflated } else { stored };
+        add(&mut zip, path, bytes, options)?;
+    }
+
+    zip.finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| format!("Failed to finish bundle: {}", e))
+}
+
+fn add(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    bytes: &[u8],
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .and_then(|_| zip.write_all(bytes).map_err(Into::into))
+        .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))
+}
+
+/// Read an entry of at most `max` bytes. The size the archive claims is
+/// checked first, and the read itself is capped in case the claim is a lie.
+fn read_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+    max: u64,
+) -> Result<Vec<u8>, String> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|_| format!("The bundle is missing {}", name))?;
+    let too_large = || format!("{} in the bundle is larger than expected", name);
+    if entry.size() > max {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    entry
+        .take(max + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {} from bundle: {}", name, e))?;
+    if bytes.len() as u64 > max {
+        return Err(too_large());
+    }
+    Ok(bytes)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Manifest {
+        let story = Story {
+            id: "s1".to_string(),
+            title: "The Fox".to_string(),
+            genre_id: "animal".to_string(),
+            status: "complete".to_string(),
+            is_sample: 0,
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-02 00:00:00".to_string(),
+        };
+        let part = |id: &str, n: i32, status: &str| StoryPart {
+            id: id.to_string(),
+            story_id: "s1".to_string(),
+            part_number: n,
+            content: format!("Part {} text.", n),
+            audio_path: None,
+            status: status.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let job = AudioJob {
+            id: "j1".to_string(),
+            story_part_id: "p1".to_string(),
+            voice_path: None,
+            final_path: None,
+            status: "complete".to_string(),
+            error_message: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let mut manifest = Manifest::new(
+            &story,
+            None,
+            &[part("p1", 1, "audio_ready"), part("p2", 2, "text_ready")],
+            &[job],
+        );
+        manifest.parts[0].audio_file = Some("audio/p1.wav".to_string());
+        manifest.audio_jobs[0].produced_audio = true;
+        manifest
+    }
+
+    #[test]
+    fn round_trips_rows_and_files() {
+        let files = vec![("audio/p1.wav".to_string(), b"RIFF....WAVE".to_vec())];
+        let bytes = pack(manifest(), files).unwrap();
+        let unpacked = unpack(&bytes).unwrap();
+        assert_eq!(unpacked.manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(unpacked.manifest.parts.len(), 2);
+        assert_eq!(unpacked.manifest.parts[0].status, "audio_ready");
+        assert_eq!(unpacked.manifest.files[0].size, 12);
+        assert_eq!(unpacked.files["audio/p1.wav"], b"RIFF....WAVE");
+    }
+
+    #[test]
+    fn rejects_damaged_files_and_newer_formats() {
+        let mut manifest = manifest();
+        manifest.files = vec![BundleFile {
+            path: "audio/p1.wav".to_string(),
+            sha256: sha256_hex(b"RIFF....WAVE"),
+            size: 12,
+        }];
+        let tampered = write_archive(&manifest, &[("audio/p1.wav".to_string(), b"RIFF....WAVF".to_vec())]);
+        let err = unpack(&tampered.unwrap()).err().unwrap();
+        assert!(err.contains("checksum"), "{}", err);
+
+        let mut newer = self::manifest();
+        newer.parts[0].audio_file = None;
+        newer.version = BUNDLE_VERSION + 1;
+        let err = unpack(&write_archive(&newer, &[]).unwrap()).err().unwrap();
+        assert!(err.contains("update Pattikadhai"), "{}", err);
+    }
+
+    #[test]
+    fn normalizes_rows_from_other_schemas() {
+        // An older bundle: no schema version, optional fields left out, a
+        // job still running and one for a part that is not included
+        let json = r#"{
+            "format": "pattikadhai-story",
+            "version": 1,
+            "story": {"id": "s1", "title": "Fox", "genre_id": "animal", "status": "published"},
+            "parts": [{"id": "p1", "part_number": 1, "content": "Text.", "status": "audio_ready"}],
+            "audio_jobs": [
+                {"id": "j1", "story_part_id": "p1", "status": "mixing", "produced_audio": true},
+                {"id": "j2", "story_part_id": "gone", "status": "complete"}
+            ]
+        }"#;
+        let mut manifest: Manifest = serde_json::from_str(json).unwrap();
+        normalize(&mut manifest);
+        assert_eq!(manifest.schema_version, 0);
+        assert_eq!(manifest.story.status, "draft");
+        // No audio came with the part
+        assert_eq!(manifest.parts[0].status, "text_ready");
+        assert_eq!(manifest.audio_jobs.len(), 1);
+        assert_eq!(manifest.audio_jobs[0].status, "cancelled");
+        assert!(!manifest.audio_jobs[0].produced_audio);
+    }
+
+    #[test]
+    fn refuses_entries_larger_than_declared() {
+        // A highly compressible entry that inflates past its manifest size
+        let mut manifest = manifest();
+        manifest.parts[0].audio_file = None;
+        manifest.files = vec![BundleFile {
+            path: "timing/p1.json".to_string(),
+            sha256: String::new(),
+            size: 10,
+        }];
+        let bomb = write_archive(&manifest, &[("timing/p1.json".to_string(), vec![b' '; 1 << 20])]);
+        let err = unpack(&bomb.unwrap()).err().unwrap();
+        assert!(err.contains("larger than expected"), "{}", err);
+
+        manifest.files[0].size = MAX_FILE_BYTES + 1;
+        let err = unpack(&write_archive(&manifest, &[]).unwrap()).err().unwrap();
+        assert!(err.contains("too large"), "{}", err);
+    }
+
+    #[test]
+    fn reassigned_ids_keep_job_links() {
+        let mut manifest = manifest();
+        manifest.reassign_ids();
+        assert_ne!(manifest.story.id, "s1");
+        assert_ne!(manifest.parts[0].id, "p1");
+        assert_eq!(manifest.audio_jobs[0].story_part_id, manifest.parts[0].id);
+        assert_ne!(manifest.audio_jobs[0].id, "j1");
+    }
+}
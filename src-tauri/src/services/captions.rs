@@ -0,0 +1,179 @@
+use crate::services::pipeline::TimingSegment;
+
+/// Caption file formats the timing sidecar can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionFormat {
+    /// WebVTT, for browsers and most media players
+    WebVtt,
+    /// SubRip
+    Srt,
+    /// Lyrics, shown line by line by many MP3 players
+    Lrc,
+}
+
+impl CaptionFormat {
+    /// Parse `vtt`, `srt` or `lrc`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "vtt" => Some(Self::WebVtt),
+            "srt" => Some(Self::Srt),
+            "lrc" => Some(Self::Lrc),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::WebVtt => "vtt",
+            Self::Srt => "srt",
+            Self::Lrc => "lrc",
+        }
+    }
+}
+
+/// Join the timing of consecutive parts into one timeline. Each part is its
+/// segments and the length of its audio in seconds; a part's segments are
+/// shifted by the length of the parts before it.
+pub fn combine_parts(parts: &[(Vec<TimingSegment>, f64)]) -> Vec<TimingSegment> {
+    let mut combined = Vec::new();
+    let mut offset = 0.0;
+    for (segments, duration) in parts {
+        combined.extend(segments.iter().map(|s| TimingSegment {
+            text: s.text.clone(),
+            start: s.start + offset,
+            end: s.end + offset,
+        }));
+        offset += duration;
+    }
+    combined
+}
+
+/// Render segments as a caption file. `title` is used by formats with a
+/// header for it.
+pub fn render(format: CaptionFormat, segments: &[TimingSegment], title: &str) -> String {
+    let mut out = String::new();
+    match format {
+        CaptionFormat::WebVtt => {
+            out.push_str("WEBVTT\n");
+            for s in segments {
+                out.push_str(&format!(
+                    "\n{} --> {}\n{}\n",
+                    clock(s.start, '.'),
+                    clock(s.end, '.'),
+                    escape_vtt(&one_line(&s.text))
+                ));
+            }
+        }
+        CaptionFormat::Srt => {
+            for (i, s) in segments.iter().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                out.push_str(&format!(
+                    "{}\n{} --> {}\n{}\n",
+                    i + 1,
+                    clock(s.start, ','),
+                    clock(s.end, ','),
+                    one_line(&s.text)
+                ));
+            }
+        }
+        CaptionFormat::Lrc => {
+            out.push_str(&format!("[ti:{}]\n", one_line(title)));
+            for s in segments {
+                out.push_str(&format!("{}{}\n", lrc_time(s.start), one_line(&s.text)));
+            }
+        }
+    }
+    out
+}
+
+/// `HH:MM:SS.mmm`, with `separator` before the milliseconds.
+fn clock(secs: f64, separator: char) -> String {
+    let ms = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// `[mm:ss.xx]`; minutes keep counting past an hour.
+fn lrc_time(secs: f64) -> String {
+    let cs = (secs.max(0.0) * 100.0).round() as u64;
+    format!("[{:02}:{:02}.{:02}]", cs / 6000, cs / 100 % 60, cs % 100)
+}
+
+/// Caption lines are one line each; sentences can span paragraphs.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(text: &str, start: f64, end: f64) -> TimingSegment {
+        TimingSegment {
+            text: text.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn combines_parts_with_offsets() {
+        let parts = vec![
+            (vec![seg("One.", 0.0, 1.5), seg("Two.", 1.5, 3.0)], 4.0),
+            (vec![seg("Three.", 0.0, 2.0)], 2.5),
+        ];
+        let combined = combine_parts(&parts);
+        assert_eq!(combined.len(), 3);
+        assert_eq!((combined[2].start, combined[2].end), (4.0, 6.0));
+    }
+
+    #[test]
+    fn renders_webvtt() {
+        let vtt = render(
+            CaptionFormat::WebVtt,
+            &[seg("Tom & Jerry\nran.", 0.0, 1.25), seg("End.", 3661.5, 3662.0)],
+            "Story",
+        );
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.250\nTom &amp; Jerry ran.\n\n01:01:01.500 --> 01:01:02.000\nEnd.\n"
+        );
+    }
+
+    #[test]
+    fn renders_srt() {
+        let srt = render(CaptionFormat::Srt, &[seg("A.", 0.0, 1.0), seg("B.", 1.0, 2.5)], "Story");
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,000\nA.\n\n2\n00:00:01,000 --> 00:00:02,500\nB.\n"
+        );
+    }
+
+    #[test]
+    fn renders_lrc() {
+        let lrc = render(CaptionFormat::Lrc, &[seg("A.", 0.0, 1.0), seg("B.", 3725.456, 3726.0)], "The Fox");
+        assert_eq!(lrc, "[ti:The Fox]\n[00:00.00]A.\n[62:05.46]B.\n");
+    }
+
+    #[test]
+    fn parses_format_names() {
+        for name in ["vtt", "srt", "lrc"] {
+            assert_eq!(CaptionFormat::from_name(name).unwrap().extension(), name);
+        }
+        assert_eq!(CaptionFormat::from_name("ass"), None);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::db::models::StoryCharacter;
+use kokoro_tts::audio::effects::VoiceEffect;
+
+/// Verbs that introduce or tag a line of dialogue.
+const SPEECH_VERBS: &[&str] = &[
+    "said", "says", "asked", "replied", "answered", "shouted", "whispered", "cried", "called",
+    "exclaimed", "yelled", "added", "laughed", "giggled", "growled", "roared", "squeaked",
+    "muttered", "mumbled", "sang", "explained", "announced", "begged", "sighed", "chirped",
+];
+
+/// Capitalised words that start sentences but are never character names.
+const NOT_NAMES: &[&str] = &[
+    "I", "He", "She", "They", "We", "You", "It", "Then", "And", "But", "So", "Now", "When",
+    "Finally", "Suddenly", "Soon",
+];
+
+/// Words that end a name after "said the ...": "said the wolf with a grin".
+const NAME_STOP_WORDS: &[&str] = &["with", "to", "as", "and", "in", "at", "from", "while", "again"];
+
+/// Longest narration between two quotes that still counts as one exchange.
+const MAX_TAG_WORDS: usize = 6;
+
+/// Voices handed out to characters, in order, skipping the narrator's.
+const CHARACTER_VOICES: &[&str] = &[
+    "am_adam", "af_bella", "bm_george", "af_sky", "am_michael", "bf_lily", "am_echo",
+    "af_heart", "af_sarah", "af_jessica", "bf_emma", "af_nova",
+];
+
+/// A run of story text read by one voice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogueSegment {
+    pub text: String,
+    /// True for quoted speech, false for narration
+    pub is_dialogue: bool,
+    /// Attributed speaker for dialogue; `None` means the narrator reads it
+    pub speaker: Option<String>,
+}
+
+/// Split text into narration and quoted dialogue, attributing each quote.
+///
+/// Handles straight (`"`) and smart (`“ ”`) double quotes. Speakers come from
+/// tags next to the quote ("said Tom", "the wolf growled"); untagged quotes in
+/// a back-and-forth exchange alternate between the last two speakers.
+pub fn segment_dialogue(text: &str) -> Vec<DialogueSegment> {
+    let mut segments: Vec<DialogueSegment> = split_quotes(text)
+        .into_iter()
+        .map(|(text, is_dialogue)| DialogueSegment {
+            text,
+            is_dialogue,
+            speaker: None,
+        })
+        .collect();
+
+    // Speakers in order of appearance, most recent last
+    let mut recent: Vec<String> = Vec::new();
+    for i in 0..segments.len() {
+        if !segments[i].is_dialogue {
+            continue;
+        }
+
+        let after = segments.get(i + 1).filter(|s| !s.is_dialogue);
+        let before = i.checked_sub(1).map(|j| &segments[j]).filter(|s| !s.is_dialogue);
+
+        let mut speaker = after
+            .and_then(|s| speaker_after_quote(&s.text))
+            .or_else(|| before.and_then(|s| speaker_before_quote(&s.text)));
+
+        // Untagged reply in an ongoing exchange (the previous quote is at
+        // most a short tag away): alternate between the last two speakers
+        let follows_quote = match before {
+            None => i > 0,
+            Some(narration) => {
+                i >= 2 && narration.text.split_whitespace().count() <= MAX_TAG_WORDS
+            }
+        };
+        if speaker.is_none() && follows_quote && recent.len() >= 2 {
+            speaker = Some(recent[recent.len() - 2].clone());
+        }
+
+        if let Some(name) = &speaker {
+            recent.retain(|n| n != name);
+            recent.push(name.clone());
+        }
+        segments[i].speaker = speaker;
+    }
+
+    segments
+}
+
+/// Distinct attributed speakers, in order of first appearance.
+pub fn speakers(segments: &[DialogueSegment]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in segments.iter().filter_map(|s| s.speaker.as_ref()) {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+/// Pick voices for characters that don't have one yet.
+///
+/// Voices are taken from `CHARACTER_VOICES`, avoiding the narrator and voices
+/// already in use where possible. Names that match an effect preset
+/// ("Big Bad Wolf", "Mouse") get that effect by default.
+pub fn assign_voices(
+    story_id: &str,
+    names: &[String],
+    existing: &[StoryCharacter],
+    narrator_voice: &str,
+) -> Vec<StoryCharacter> {
+    let mut used: Vec<String> = existing.iter().map(|c| c.voice.clone()).collect();
+    let mut assigned = Vec::new();
+
+    for name in names {
+        if existing.iter().any(|c| &c.name == name) {
+            continue;
+        }
+        let candidates: Vec<&str> = CHARACTER_VOICES
+            .iter()
+            .copied()
+            .filter(|v| *v != narrator_voice)
+            .collect();
+        let voice = candidates
+            .iter()
+            .find(|v| !used.iter().any(|u| u == *v))
+            .copied()
+            .unwrap_or(candidates[(existing.len() + assigned.len()) % candidates.len()]);
+        used.push(voice.to_string());
+
+        let effect = name
+            .split_whitespace()
+            .rev()
+            .find_map(VoiceEffect::from_name)
+            .unwrap_or(VoiceEffect::None);
+
+        assigned.push(StoryCharacter {
+            story_id: story_id.to_string(),
+            name: name.clone(),
+            voice: voice.to_string(),
+            effect: effect.name().to_string(),
+        });
+    }
+
+    assigned
+}
+
+/// Split at double quotes into (text, is_dialogue) runs. Quote marks stay
+/// with the dialogue so Kokoro still sees them. An unclosed quote runs to
+/// the end of the text.
+fn split_quotes(text: &str) -> Vec<(String, bool)> {
+    let mut runs = Vec::new();
+    let mut current = String::new();
+    let mut in_quote = false;
+
+    for ch in text.chars() {
+        let opens = !in_quote && (ch == '"' || ch == '\u{201c}');
+        let closes = in_quote && (ch == '"' || ch == '\u{201d}');
+
+        if opens {
+            if !current.is_empty() {
+                runs.push((std::mem::take(&mut current), false));
+            }
+            current.push(ch);
+            in_quote = true;
+        } else if closes {
+            current.push(ch);
+            runs.push((std::mem::take(&mut current), true));
+            in_quote = false;
+        } else {
+            current.push(ch);
+        }
+    }
+    if !current.is_empty() {
+        runs.push((current, in_quote));
+    }
+    runs
+}
+
+/// Tag at the start of the narration following a quote:
+/// `"Hi," said Tom.` or `"Hi," the old owl hooted.`
+fn speaker_after_quote(narration: &str) -> Option<String> {
+    let clause = narration
+        .trim_start()
+        .split(['.', ',', '!', '?', ';', ':', '\n'])
+        .next()?;
+    let words: Vec<&str> = clause.split_whitespace().collect();
+
+    if words.first().is_some_and(|w| is_speech_verb(w)) {
+        return name_after_verb(&words[1..]);
+    }
+    let verb_at = words.iter().take(5).position(|w| is_speech_verb(w))?;
+    name_before_verb(&words[..verb_at])
+}
+
+/// Tag at the end of the narration preceding a quote: `Tom said, "Hi."`
+fn speaker_before_quote(narration: &str) -> Option<String> {
+    let trimmed = narration.trim_end().trim_end_matches([',', ':']);
+    let clause = trimmed.rsplit(['.', '!', '?', ';', '\n']).next()?;
+    let words: Vec<&str> = clause.split_whitespace().collect();
+    let (verb, rest) = words.split_last()?;
+    if !is_speech_verb(verb) {
+        return None;
+    }
+    name_before_verb(rest)
+}
+
+/// Name in `said the big wolf` / `said Little Red`.
+fn name_after_verb(words: &[&str]) -> Option<String> {
+    let (first, rest) = words.split_first()?;
+    if is_article(first) {
+        let name: Vec<&str> = rest
+            .iter()
+            .take_while(|w| !NAME_STOP_WORDS.contains(w) && !w.ends_with("ly"))
+            .take(3)
+            .copied()
+            .collect();
+        return format_name(&name);
+    }
+    let name: Vec<&str> = words.iter().take_while(|w| is_capitalized(w)).copied().collect();
+    format_name(&name)
+}
+
+/// Name in `the old owl said` / `Then Tom said`.
+fn name_before_verb(words: &[&str]) -> Option<String> {
+    if let Some(article) = words.iter().rposition(|w| is_article(w)) {
+        return format_name(&words[article + 1..]);
+    }
+    let start = words
+        .iter()
+        .rposition(|w| !is_capitalized(w) || NOT_NAMES.contains(w))
+        .map_or(0, |i| i + 1);
+    format_name(&words[start..])
+}
+
+/// Title-case a name, rejecting pronouns and empty or overly long matches.
+fn format_name(words: &[&str]) -> Option<String> {
+    if words.is_empty() || words.len() > 4 || NOT_NAMES.contains(&words[0]) {
+        return None;
+    }
+    let name = words
+        .iter()
+        .map(|w| {
+            let w = w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-');
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+fn is_speech_verb(word: &str) -> bool {
+    SPEECH_VERBS.contains(&word.to_lowercase().as_str())
+}
+
+fn is_article(word: &str) -> bool {
+    matches!(word.to_lowercase().as_str(), "the" | "a" | "an")
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dialogue(segments: &[DialogueSegment]) -> Vec<(&str, Option<&str>)> {
+        segments
+            .iter()
+            .filter(|s| s.is_dialogue)
+            .map(|s| (s.text.as_str(), s.speaker.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_straight_and_smart_quotes() {
+        let segments = segment_dialogue("Tom waved. \"Hi!\" He smiled. \u{201c}Bye.\u{201d}");
+        let kinds: Vec<bool> = segments.iter().map(|s| s.is_dialogue).collect();
+        assert_eq!(kinds, vec![false, true, false, true]);
+        assert_eq!(segments[1].text, "\"Hi!\"");
+        assert_eq!(segments[3].text, "\u{201c}Bye.\u{201d}");
+    }
+
+    #[test]
+    fn attributes_said_patterns() {
+        let text = "\"Come in,\" said the big wolf with a grin. \"No,\" Mouse replied. \
+                    Little Red said, \"Run!\" \"Hello,\" said Tom quietly.";
+        let segments = segment_dialogue(text);
+        assert_eq!(
+            dialogue(&segments),
+            vec![
+                ("\"Come in,\"", Some("Big Wolf")),
+                ("\"No,\"", Some("Mouse")),
+                ("\"Run!\"", Some("Little Red")),
+                ("\"Hello,\"", Some("Tom")),
+            ]
+        );
+    }
+
+    #[test]
+    fn pronoun_tags_are_not_names() {
+        let segments = segment_dialogue("\"Look!\" she said. Then he said, \"Where?\"");
+        assert_eq!(dialogue(&segments), vec![("\"Look!\"", None), ("\"Where?\"", None)]);
+    }
+
+    #[test]
+    fn untagged_replies_alternate() {
+        let text = "\"Who are you?\" asked Owl. \"I'm Mouse,\" said Mouse. \"Why?\" \"Just curious.\"";
+        let segments = segment_dialogue(text);
+        let order: Vec<Option<&str>> = dialogue(&segments).into_iter().map(|(_, s)| s).collect();
+        assert_eq!(order, vec![Some("Owl"), Some("Mouse"), Some("Owl"), Some("Mouse")]);
+        assert_eq!(speakers(&segments), vec!["Owl", "Mouse"]);
+    }
+
+    #[test]
+    fn unclosed_quote_runs_to_end() {
+        let segments = segment_dialogue("Owl said, \"Once upon a time");
+        assert_eq!(segments.len(), 2);
+        assert!(segments[1].is_dialogue);
+        assert_eq!(segments[1].speaker.as_deref(), Some("Owl"));
+    }
+
+    #[test]
+    fn assign_voices_skips_narrator_and_existing() {
+        let existing = vec![StoryCharacter {
+            story_id: "s1".to_string(),
+            name: "Tom".to_string(),
+            voice: "am_adam".to_string(),
+            effect: "none".to_string(),
+        }];
+        let names = vec!["Tom".to_string(), "Big Bad Wolf".to_string(), "Ann".to_string()];
+        let assigned = assign_voices("s1", &names, &existing, "af_bella");
+
+        assert_eq!(assigned.len(), 2);
+        assert_eq!(assigned[0].name, "Big Bad Wolf");
+        assert_eq!(assigned[0].voice, "bm_george");
+        assert_eq!(assigned[0].effect, "wolf");
+        assert_eq!(assigned[1].voice, "af_sky");
+        assert_eq!(assigned[1].effect, "none");
+    }
+}
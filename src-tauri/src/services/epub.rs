@@ -0,0 +1,378 @@
+use std::io::{Cursor, Write};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::services::pipeline::TimingSegment;
+use crate::services::sfx;
+
+/// Class EPUB readers add to the sentence being read.
+const ACTIVE_CLASS: &str = "-epub-media-overlay-active";
+
+/// A story to package as an EPUB.
+pub struct EpubBook {
+    /// Used for the book's `urn:uuid:` identifier
+    pub id: String,
+    pub title: String,
+    pub genre: String,
+    /// `dcterms:modified` timestamp, `YYYY-MM-DDThh:mm:ssZ`
+    pub modified: String,
+    pub parts: Vec<EpubPart>,
+}
+
+pub struct EpubPart {
+    /// Story text, SFX cues included
+    pub content: String,
+    /// Narration for a read-along: MP3 bytes and sentence timing
+    pub narration: Option<(Vec<u8>, Vec<TimingSegment>)>,
+}
+
+/// Build an EPUB 3 from `book`. Narrated parts get a SMIL media overlay that
+/// points each sentence span at its stretch of the part's audio.
+pub fn build(book: &EpubBook) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    // The mimetype must come first, uncompressed
+    add(&mut zip, "mimetype", b"application/epub+zip", stored)?;
+    add(&mut zip, "META-INF/container.xml", CONTAINER_XML.as_bytes(), deflated)?;
+    add(&mut zip, "OEBPS/style.css", STYLE_CSS.as_bytes(), deflated)?;
+    add(&mut zip, "OEBPS/nav.xhtml", nav_xhtml(book).as_bytes(), deflated)?;
+    add(&mut zip, "OEBPS/content.opf", package_opf(book).as_bytes(), deflated)?;
+
+    for (i, part) in book.parts.iter().enumerate() {
+        let n = i + 1;
+        let timing = part.narration.as_ref().map(|(_, timing)| timing.as_slice());
+        add(
+            &mut zip,
+            &format!("OEBPS/part{}.xhtml", n),
+            part_xhtml(n, &book.title, &part.content, timing).as_bytes(),
+            deflated,
+        )?;
+        if let Some((audio, timing)) = &part.narration {
+            add(&mut zip, &format!("OEBPS/part{}.smil", n), smil(n, timing).as_bytes(), deflated)?;
+            // Already-dense audio gains little from compression
+            add(&mut zip, &format!("OEBPS/audio/part{}.mp3", n), audio, stored)?;
+        }
+    }
+
+    zip.finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| format!("Failed to finish EPUB: {}", e))
+}
+
+fn add(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    bytes: &[u8],
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .and_then(|_| zip.write_all(bytes).map_err(Into::into))
+        .map_err(|e| format!("Failed to add {} to EPUB: {}", name, e))
+}
+
+/// A part's page. With timing, each sentence is a `<span id="sN">` for the
+/// media overlay; the text around the sentences keeps its quotes and line
+/// breaks.
+fn part_xhtml(n: usize, title: &str, content: &str, timing: Option<&[TimingSegment]>) -> String {
+    let text: String = content
+        .lines()
+        .map(sfx::strip_cues)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+
+    let mut body = String::new();
+    match timing {
+        Some(segments) => {
+            let mut rest = text;
+            for (i, segment) in segments.iter().enumerate() {
+                let (start, end) = match find_words(rest, &segment.text) {
+                    Some(span) => span,
+                    // Text edited since the audio was made: the sentence is
+                    // whatever stands before the next one that still matches
+                    None => {
+                        let next = segments[i + 1..]
+                            .iter()
+                            .find_map(|later| find_words(rest, &later.text))
+                            .map_or(rest.len(), |(at, _)| at);
+                        let skipped = rest[..next].trim_end();
+                        (skipped.len() - skipped.trim_start().len(), skipped.len())
+                    }
+                };
+                body.push_str(&escape_text(&rest[..start]));
+                body.push_str(&format!(
+                    "<span id=\"s{}\">{}</span>",
+                    i,
+                    escape_text(&rest[start..end])
+                ));
+                rest = &rest[end..];
+            }
+            body.push_str(&escape_text(rest));
+        }
+        None => body.push_str(&escape_text(text)),
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="en" lang="en">
+<head>
+<title>{title}</title>
+<link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+<section epub:type="chapter">
+<h2>Part {n}</h2>
+<p>{body}</p>
+</section>
+</body>
+</html>
+"#,
+        title = escape_text(title),
+        n = n,
+        body = body,
+    )
+}
+
+fn smil(n: usize, timing: &[TimingSegment]) -> String {
+    let pars: String = timing
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            format!(
+                "<par id=\"par{i}\"><text src=\"part{n}.xhtml#s{i}\"/><audio src=\"audio/part{n}.mp3\" clipBegin=\"{:.3}s\" clipEnd=\"{:.3}s\"/></par>\n",
+                s.start, s.end
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<smil xmlns="http://www.w3.org/ns/SMIL" xmlns:epub="http://www.idpf.org/2007/ops" version="3.0">
+<body>
+<seq id="seq{n}" epub:textref="part{n}.xhtml" epub:type="chapter">
+{pars}</seq>
+</body>
+</smil>
+"#
+    )
+}
+
+fn nav_xhtml(book: &EpubBook) -> String {
+    let items: String = (1..=book.parts.len())
+        .map(|n| format!("<li><a href=\"part{n}.xhtml\">Part {n}</a></li>\n"))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="en" lang="en">
+<head><title>{title}</title></head>
+<body>
+<nav epub:type="toc" id="toc">
+<h1>{title}</h1>
+<ol>
+{items}</ol>
+</nav>
+</body>
+</html>
+"#,
+        title = escape_text(&book.title),
+    )
+}
+
+fn package_opf(book: &EpubBook) -> String {
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    let mut durations = String::new();
+    let mut total = 0.0;
+
+    for (i, part) in book.parts.iter().enumerate() {
+        let n = i + 1;
+        match &part.narration {
+            Some((_, timing)) => {
+                manifest.push_str(&format!(
+                    "<item id=\"part{n}\" href=\"part{n}.xhtml\" media-type=\"application/xhtml+xml\" media-overlay=\"smil{n}\"/>\n\
+                     <item id=\"smil{n}\" href=\"part{n}.smil\" media-type=\"application/smil+xml\"/>\n\
+                     <item id=\"audio{n}\" href=\"audio/part{n}.mp3\" media-type=\"audio/mpeg\"/>\n"
+                ));
+                let secs = timing.last().map_or(0.0, |s| s.end);
+                total += secs;
+                durations.push_str(&format!(
+                    "<meta property=\"media:duration\" refines=\"#smil{n}\">{}</meta>\n",
+                    clock(secs)
+                ));
+            }
+            None => manifest.push_str(&format!(
+                "<item id=\"part{n}\" href=\"part{n}.xhtml\" media-type=\"application/xhtml+xml\"/>\n"
+            )),
+        }
+        spine.push_str(&format!("<itemref idref=\"part{n}\"/>\n"));
+    }
+    if !durations.is_empty() {
+        durations.push_str(&format!(
+            "<meta property=\"media:duration\">{}</meta>\n<meta property=\"media:active-class\">{}</meta>\n",
+            clock(total),
+            ACTIVE_CLASS
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="en">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:identifier id="book-id">urn:uuid:{id}</dc:identifier>
+<dc:title>{title}</dc:title>
+<dc:language>en</dc:language>
+<dc:subject>{genre}</dc:subject>
+<dc:creator>Pattikadhai</dc:creator>
+<meta property="dcterms:modified">{modified}</meta>
+{durations}</metadata>
+<manifest>
+<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="css" href="style.css" media-type="text/css"/>
+{manifest}</manifest>
+<spine>
+{spine}</spine>
+</package>
+"#,
+        id = escape_text(&book.id),
+        title = escape_text(&book.title),
+        genre = escape_text(&book.genre),
+        modified = book.modified,
+    )
+}
+
+/// Byte range of the first place `needle`'s words appear in `haystack`,
+/// allowing any whitespace (spaces left by cues, line breaks) between them.
+fn find_words(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    let mut words = needle.split_whitespace();
+    let first = words.next()?;
+    haystack.match_indices(first).find_map(|(start, _)| {
+        let mut end = start + first.len();
+        for word in words.clone() {
+            let after = &haystack[end..];
+            let trimmed = after.trim_start();
+            if trimmed.len() == after.len() || !trimmed.starts_with(word) {
+                return None;
+            }
+            end += after.len() - trimmed.len() + word.len();
+        }
+        Some((start, end))
+    })
+}
+
+/// `H:MM:SS.mmm` clock value for media durations.
+fn clock(secs: f64) -> String {
+    let ms = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Escape text for XHTML, turning line breaks into `<br/>`.
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "<br/>\n")
+}
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+
+const STYLE_CSS: &str = "body { font-family: serif; line-height: 1.6; }
+h2 { text-align: center; }
+.-epub-media-overlay-active { background-color: #ffe9a8; }
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn seg(text: &str, start: f64, end: f64) -> TimingSegment {
+        TimingSegment {
+            text: text.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn sentences_become_spans_around_the_original_text() {
+        let timing = [seg("\"Hi,\" said Tom.", 0.0, 1.0), seg("He ran.", 1.0, 2.0)];
+        let xhtml = part_xhtml(1, "T", "[SFX: door creak] \"Hi,\" said Tom.\n\nHe ran.", Some(&timing));
+        assert!(xhtml.contains(
+            "<span id=\"s0\">&quot;Hi,&quot; said Tom.</span><br/>\n<br/>\n<span id=\"s1\">He ran.</span>"
+        ));
+        assert!(!xhtml.contains("SFX"));
+    }
+
+    #[test]
+    fn spoken_text_that_differs_is_not_repeated() {
+        // The narration has a space where the text breaks the line
+        let timing = [
+            seg("The fox ran away.", 0.0, 1.0),
+            seg("It hid in the barn.", 1.0, 2.0),
+            seg("Then it slept.", 2.0, 3.0),
+        ];
+        let content = "The fox [SFX: rustle] ran\naway. It hid in the shed. Then it slept.";
+        let xhtml = part_xhtml(1, "T", content, Some(&timing));
+        assert!(xhtml.contains("<span id=\"s0\">The fox ran<br/>\naway.</span>"));
+        // The edited sentence is shown once, as it now reads
+        assert!(xhtml.contains(" <span id=\"s1\">It hid in the shed.</span> <span id=\"s2\">Then it slept.</span></p>"));
+        assert_eq!(xhtml.matches("hid").count(), 1);
+    }
+
+    #[test]
+    fn smil_points_spans_at_audio_clips() {
+        let smil = smil(2, &[seg("A.", 0.0, 1.25)]);
+        assert!(smil.contains(
+            "<text src=\"part2.xhtml#s0\"/><audio src=\"audio/part2.mp3\" clipBegin=\"0.000s\" clipEnd=\"1.250s\"/>"
+        ));
+    }
+
+    #[test]
+    fn builds_a_zip_with_mimetype_first() {
+        let book = EpubBook {
+            id: "s1".to_string(),
+            title: "Fox & Crow".to_string(),
+            genre: "Animal Tales".to_string(),
+            modified: "2026-01-01T00:00:00Z".to_string(),
+            parts: vec![
+                EpubPart {
+                    content: "A.".to_string(),
+                    narration: Some((vec![1, 2, 3], vec![seg("A.", 0.0, 61.5)])),
+                },
+                EpubPart {
+                    content: "B.".to_string(),
+                    narration: None,
+                },
+            ],
+        };
+        let bytes = build(&book).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        assert_eq!(archive.by_index(0).unwrap().compression(), CompressionMethod::Stored);
+        assert!(archive.by_name("OEBPS/part1.smil").is_ok());
+        assert!(archive.by_name("OEBPS/part2.smil").is_err());
+
+        let mut opf = String::new();
+        archive.by_name("OEBPS/content.opf").unwrap().read_to_string(&mut opf).unwrap();
+        assert!(opf.contains("<dc:title>Fox &amp; Crow</dc:title>"));
+        assert!(opf.contains("media-overlay=\"smil1\""));
+        assert!(opf.contains("<meta property=\"media:duration\">0:01:01.500</meta>"));
+    }
+}
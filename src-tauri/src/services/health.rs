@@ -80,27 +80,6 @@ pub async fn check_gemma3() -> DependencyStatus {
     }
 }
 
-pub fn check_ffmpeg() -> DependencyStatus {
-    match Command::new("ffmpeg").arg("-version").output() {
-        Ok(output) if output.status.success() => {
-            let full = String::from_utf8_lossy(&output.stdout);
-            let version = full.lines().next().unwrap_or("").to_string();
-            DependencyStatus {
-                name: "ffmpeg".to_string(),
-                installed: true,
-                version: Some(version),
-                install_command: "brew install ffmpeg".to_string(),
-            }
-        }
-        _ => DependencyStatus {
-            name: "ffmpeg".to_string(),
-            installed: false,
-            version: None,
-            install_command: "brew install ffmpeg".to_string(),
-        },
-    }
-}
-
 pub fn check_espeak_ng() -> DependencyStatus {
     match Command::new("espeak-ng").arg("--version").output() {
         Ok(output) if output.status.success() => {
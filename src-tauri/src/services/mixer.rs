@@ -1,47 +1,84 @@
-use tokio::process::Command;
+use std::path::{Path, PathBuf};
+
+use kokoro_tts::audio::mixer::{AudioBuffer, Clip, Timeline};
+use kokoro_tts::audio::AudioFormat;
 
 pub struct AudioMixer;
 
 impl AudioMixer {
-    /// Concatenate multiple WAV files into one
+    /// Concatenate multiple WAV files into one. Inputs may differ in sample
+    /// rate and channel count; the output uses the first input's layout and
+    /// the format implied by `output_path`'s extension.
     pub async fn concat_wav(input_paths: &[&str], output_path: &str) -> Result<String, String> {
-        // Create a temporary file list for ffmpeg concat
-        let file_list: String = input_paths
-            .iter()
-            .map(|p| format!("file '{}'", p))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let list_path = format!("{}.txt", output_path);
-        tokio::fs::write(&list_path, &file_list)
-            .await
-            .map_err(|e| format!("Failed to write concat list: {}", e))?;
-
-        let output = Command::new("ffmpeg")
-            .args([
-                "-y",
-                "-f",
-                "concat",
-                "-safe",
-                "0",
-                "-i",
-                &list_path,
-                "-c",
-                "copy",
-                output_path,
-            ])
-            .output()
+        let inputs: Vec<PathBuf> = input_paths.iter().map(PathBuf::from).collect();
+        let output = PathBuf::from(output_path);
+        tokio::task::spawn_blocking(move || concat_blocking(&inputs, &output))
             .await
-            .map_err(|e| format!("Failed to run ffmpeg concat: {}", e))?;
+            .map_err(|e| format!("Concat task panicked: {}", e))??;
+        Ok(output_path.to_string())
+    }
 
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&list_path).await;
+    /// Render `timeline` to `output_path` in the format implied by its extension.
+    pub async fn render(timeline: Timeline, output_path: &str) -> Result<String, String> {
+        let output = PathBuf::from(output_path);
+        tokio::task::spawn_blocking(move || {
+            timeline
+                .render()
+                .save(&output, AudioFormat::from_path(&output))
+                .map_err(|e| format!("Failed to write mix: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Mix task panicked: {}", e))??;
+        Ok(output_path.to_string())
+    }
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("ffmpeg concat failed: {}", stderr));
-        }
+fn concat_blocking(inputs: &[PathBuf], output: &Path) -> Result<(), String> {
+    let buffers = inputs
+        .iter()
+        .map(|path| {
+            AudioBuffer::load(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = buffers.first() else {
+        return Err("Nothing to concatenate".to_string());
+    };
 
-        Ok(output_path.to_string())
+    let mut timeline = Timeline::new(first.sample_rate, first.channels);
+    let track = timeline.add_track(1.0);
+    for buffer in buffers {
+        track.append(Clip::new(buffer));
+    }
+    timeline
+        .render()
+        .save(output, AudioFormat::from_path(output))
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concat_handles_quotes_and_mixed_rates() {
+        let dir = std::env::temp_dir().join(format!("pattikadhai_mixer_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Paths with quotes broke the old ffmpeg concat list
+        let first = dir.join("Grandma's story.wav");
+        let second = dir.join("part \"two\".wav");
+        kokoro_tts::audio::save_wav(&first, &[0.5; 2400], 24000).unwrap();
+        kokoro_tts::audio::save_wav(&second, &[0.25; 1200], 12000).unwrap();
+
+        let output = dir.join("joined.wav");
+        concat_blocking(&[first, second], &output).unwrap();
+
+        let (samples, rate) = kokoro_tts::audio::load_wav(&output).unwrap();
+        assert_eq!(rate, 24000);
+        assert_eq!(samples.len(), 4800);
+        assert_eq!(samples[0], 0.5);
+        assert_eq!(samples[4000], 0.25);
+
+        assert!(concat_blocking(&[], &output).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
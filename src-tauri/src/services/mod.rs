@@ -1,7 +1,19 @@
+pub mod ambience;
+pub mod audiobook;
+pub mod bundle;
+pub mod captions;
+pub mod dialogue;
+pub mod epub;
 pub mod health;
 pub mod mixer;
+pub mod music;
 pub mod ollama;
+pub mod pdf;
 pub mod pipeline;
+pub mod podcast;
 pub mod process;
 pub mod prompts;
+pub mod scheduler;
+pub mod sfx;
+pub mod stream;
 pub mod tts;
@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+
+use crate::services::pipeline::TimingSegment;
+use kokoro_tts::audio::mixer::{AudioBuffer, Clip, Timeline};
+use kokoro_tts::audio::synth::{self, MusicStyle};
+use kokoro_tts::audio::{self, mix};
+
+/// How loud a bed sits under the narration.
+#[derive(Debug, Clone, Copy)]
+pub struct BedLevel {
+    /// Level between sentences, relative to full scale
+    pub gain: f32,
+    /// Extra attenuation applied while someone is speaking
+    pub ducked_gain: f32,
+}
+
+/// Music fills the pauses and steps well back under speech.
+pub const MUSIC_LEVEL: BedLevel = BedLevel {
+    gain: 0.22,
+    ducked_gain: 0.35,
+};
+
+/// Ambience stays low throughout and only dips a little under speech.
+pub const AMBIENCE_LEVEL: BedLevel = BedLevel {
+    gain: 0.1,
+    ducked_gain: 0.6,
+};
+
+const DUCK_ATTACK_SECS: f32 = 0.15;
+const DUCK_RELEASE_SECS: f32 = 0.6;
+const FADE_IN_SECS: f32 = 2.0;
+const FADE_OUT_SECS: f32 = 3.0;
+/// Music keeps playing this long after the last word before fading out.
+const TAIL_SECS: f32 = 2.5;
+const LOOP_CROSSFADE_SECS: f32 = 1.0;
+
+/// Where a story's music bed comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicSource {
+    /// A WAV file from the music library
+    Track(PathBuf),
+    /// A theme tune synthesized from the story's seed
+    Theme { style: MusicStyle, seed: u64 },
+}
+
+impl MusicSource {
+    /// Decode or synthesize the source at `sample_rate`.
+    pub fn render(&self, sample_rate: u32) -> Result<Vec<f32>, String> {
+        match self {
+            Self::Track(path) => {
+                let (track, track_rate) = audio::load_wav(path).map_err(|e| e.to_string())?;
+                Ok(audio::resample_linear(&track, track_rate, sample_rate))
+            }
+            Self::Theme { style, seed } => Ok(synth::render_theme(*style, *seed, sample_rate)),
+        }
+    }
+}
+
+/// Background music stored under `<dir>/<genre_id>/*.wav`.
+/// WAV files directly in `<dir>` are used for genres without their own folder.
+pub struct MusicLibrary {
+    dir: PathBuf,
+}
+
+impl MusicLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Tracks available for a genre, sorted by path.
+    pub fn tracks_for_genre(&self, genre_id: &str) -> Vec<PathBuf> {
+        let genre_tracks = list_wavs(&self.dir.join(genre_id));
+        if genre_tracks.is_empty() {
+            list_wavs(&self.dir)
+        } else {
+            genre_tracks
+        }
+    }
+
+    /// Pick a track for a genre. The choice is stable for a given `seed`
+    /// (the story ID), so every part of a story gets the same music.
+    pub fn pick_track(&self, genre_id: &str, seed: &str) -> Option<PathBuf> {
+        let tracks = self.tracks_for_genre(genre_id);
+        if tracks.is_empty() {
+            return None;
+        }
+        let index = (fnv1a(seed.as_bytes()) % tracks.len() as u64) as usize;
+        Some(tracks[index].clone())
+    }
+
+    /// Music for a story: a library track when the genre has any, otherwise
+    /// the story's own theme tune when it has a seed.
+    pub fn pick_source(&self, genre_id: &str, story_id: &str, theme_seed: Option<u64>) -> Option<MusicSource> {
+        self.pick_track(genre_id, story_id)
+            .map(MusicSource::Track)
+            .or_else(|| {
+                theme_seed.map(|seed| MusicSource::Theme {
+                    style: MusicStyle::for_genre(genre_id),
+                    seed,
+                })
+            })
+    }
+}
+
+/// Shape `track` (already at `sample_rate`) into a bed for `voice_len`
+/// samples of narration: looped or trimmed, faded, and ducked under each
+/// timed sentence. The bed runs `TAIL_SECS` past the narration.
+pub fn render_bed(
+    track: &[f32],
+    voice_len: usize,
+    sample_rate: u32,
+    timings: &[TimingSegment],
+    level: BedLevel,
+) -> Vec<f32> {
+    let sr = sample_rate as f32;
+    let secs = |s: f32| (s * sr) as usize;
+    let len = bed_len(voice_len, sample_rate);
+
+    let mut bed = mix::loop_to_length(track, len, secs(LOOP_CROSSFADE_SECS));
+    mix::apply_fades(&mut bed, secs(FADE_IN_SECS), secs(FADE_OUT_SECS));
+
+    let regions: Vec<(usize, usize)> = timings
+        .iter()
+        .map(|t| ((t.start * sr as f64) as usize, (t.end * sr as f64) as usize))
+        .collect();
+    let envelope = mix::duck_envelope(
+        len,
+        &regions,
+        level.ducked_gain,
+        secs(DUCK_ATTACK_SECS),
+        secs(DUCK_RELEASE_SECS),
+    );
+    for (s, g) in bed.iter_mut().zip(envelope) {
+        *s *= g * level.gain;
+    }
+    bed
+}
+
+/// Bed length for `voice_len` samples of narration.
+pub fn bed_len(voice_len: usize, sample_rate: u32) -> usize {
+    voice_len + (TAIL_SECS * sample_rate as f32) as usize
+}
+
+/// Lay `beds` under `voice` on a mixer timeline, keeping the result below
+/// full scale.
+pub fn mix_voice_and_beds(voice: Vec<f32>, beds: Vec<Vec<f32>>, sample_rate: u32) -> Vec<f32> {
+    let mut timeline = Timeline::new(sample_rate, 1);
+    timeline
+        .add_track(1.0)
+        .add(Clip::new(AudioBuffer::mono(voice, sample_rate)));
+    for bed in beds {
+        timeline
+            .add_track(1.0)
+            .add(Clip::new(AudioBuffer::mono(bed, sample_rate)));
+    }
+    let mut out = timeline.render().samples;
+    audio::limit_peak(&mut out, 0.98);
+    out
+}
+
+fn list_wavs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+/// FNV-1a, used only to pick a track deterministically.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_library(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pattikadhai_music_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("bedtime")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn pick_track_prefers_genre_folder_and_is_stable() {
+        let dir = temp_library("pick");
+        audio::save_wav(&dir.join("any.wav"), &[0.0; 10], 24000).unwrap();
+        for name in ["a.wav", "b.wav", "c.wav"] {
+            audio::save_wav(&dir.join("bedtime").join(name), &[0.0; 10], 24000).unwrap();
+        }
+        std::fs::write(dir.join("bedtime").join("notes.txt"), "ignore me").unwrap();
+
+        let library = MusicLibrary::new(dir.clone());
+        assert_eq!(library.tracks_for_genre("bedtime").len(), 3);
+        assert_eq!(library.tracks_for_genre("adventure"), vec![dir.join("any.wav")]);
+
+        let first = library.pick_track("bedtime", "story-1").unwrap();
+        assert_eq!(library.pick_track("bedtime", "story-1").unwrap(), first);
+        assert!(first.starts_with(dir.join("bedtime")));
+
+        assert!(MusicLibrary::new(dir.join("missing")).pick_track("bedtime", "s").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn bed_is_ducked_under_speech() {
+        let dir = temp_library("bed");
+        let track = dir.join("bedtime").join("tone.wav");
+        let tone: Vec<f32> = (0..12000).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }).collect();
+        audio::save_wav(&track, &tone, 12000).unwrap();
+
+        let sr = 24000;
+        let timings = vec![TimingSegment {
+            text: "Hello.".to_string(),
+            start: 4.0,
+            end: 6.0,
+        }];
+        let source = MusicSource::Track(track).render(sr).unwrap();
+        let bed = render_bed(&source, 10 * sr as usize, sr, &timings, MUSIC_LEVEL);
+        assert_eq!(bed.len(), (12.5 * sr as f32) as usize);
+
+        let level = |sec: f32| {
+            let i = (sec * sr as f32) as usize;
+            bed[i..i + 100].iter().fold(0.0f32, |m, s| m.max(s.abs()))
+        };
+        assert!(level(0.0) < 0.01, "starts faded in");
+        assert!(level(3.0) > 0.08, "full level between sentences");
+        assert!(level(5.0) < level(3.0) * 0.5, "ducked under speech");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn theme_fills_in_when_library_is_empty() {
+        let dir = temp_library("theme");
+        let library = MusicLibrary::new(dir.clone());
+        assert_eq!(library.pick_source("bedtime", "s", None), None);
+        assert_eq!(
+            library.pick_source("bedtime", "s", Some(9)),
+            Some(MusicSource::Theme {
+                style: MusicStyle::Lullaby,
+                seed: 9
+            })
+        );
+
+        let track = dir.join("bedtime").join("a.wav");
+        audio::save_wav(&track, &[0.0; 10], 24000).unwrap();
+        assert_eq!(library.pick_source("bedtime", "s", Some(9)), Some(MusicSource::Track(track)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
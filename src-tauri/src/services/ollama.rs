@@ -42,6 +42,20 @@ impl OllamaClient {
         system: &str,
         prompt: &str,
         channel: &Channel<StoryToken>,
+    ) -> Result<String, String> {
+        self.generate_streaming_with(model, system, prompt, channel, |_| {})
+            .await
+    }
+
+    /// Like [`Self::generate_streaming`], also handing each token to
+    /// `on_text` as it arrives.
+    pub async fn generate_streaming_with(
+        &self,
+        model: &str,
+        system: &str,
+        prompt: &str,
+        channel: &Channel<StoryToken>,
+        mut on_text: impl FnMut(&str),
     ) -> Result<String, String> {
         let url = format!("{}/api/generate", self.base_url);
         log::info!(
@@ -109,6 +123,7 @@ impl OllamaClient {
                     Ok(parsed) => {
                         if let Some(ref token) = parsed.response {
                             full_text.push_str(token);
+                            on_text(token);
                             let _ = channel.send(StoryToken {
                                 token: token.clone(),
                                 done: false,
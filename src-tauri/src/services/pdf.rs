@@ -0,0 +1,549 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use qrcode::{Color, QrCode};
+
+use crate::services::sfx;
+
+/// A5, in points.
+const PAGE_WIDTH: f32 = 420.0;
+const PAGE_HEIGHT: f32 = 595.0;
+const MARGIN: f32 = 48.0;
+/// Lowest baseline for text, leaving room for the page number.
+const BOTTOM: f32 = MARGIN + 12.0;
+const BODY_SIZE: f32 = 17.0;
+const BODY_LEADING: f32 = 27.0;
+const PARAGRAPH_GAP: f32 = 10.0;
+const QR_SIZE: f32 = 96.0;
+
+/// A story laid out as a printable picture book.
+pub struct PdfBook {
+    pub title: String,
+    pub genre: String,
+    /// Genre ID, used to pick the accent colour
+    pub genre_id: String,
+    /// Genre icon name (`compass`, `moon`, ...)
+    pub icon: Option<String>,
+    /// Text for a QR code on the title page, such as the audiobook's file name
+    pub qr_text: Option<String>,
+    pub parts: Vec<PdfPart>,
+}
+
+pub struct PdfPart {
+    /// Part text; sound effect cues are left out of the print
+    pub content: String,
+    /// Discussion questions printed after the part, if any were generated
+    pub questions: Vec<String>,
+}
+
+/// Render the book: a title page, then each part from a new page as its own
+/// chapter, with page numbers and a bookmark per part.
+pub fn build(book: &PdfBook) -> Result<Vec<u8>, String> {
+    let accent = accent_color(&book.genre_id);
+    let mut layout = Layout::default();
+
+    title_page(&mut layout, book, accent)?;
+    let mut chapter_pages = Vec::new();
+    for (i, part) in book.parts.iter().enumerate() {
+        layout.new_page();
+        chapter_pages.push(layout.pages.len() - 1);
+        layout.text_line(Font::Bold, 26.0, &format!("Part {}", i + 1), Align::Left, accent);
+        layout.y -= 14.0;
+        for paragraph in sfx::strip_cues(&part.content).lines() {
+            let paragraph = paragraph.trim();
+            if !paragraph.is_empty() {
+                layout.paragraph(Font::Regular, BODY_SIZE, BODY_LEADING, paragraph, None);
+                layout.y -= PARAGRAPH_GAP;
+            }
+        }
+        if !part.questions.is_empty() {
+            layout.y -= 12.0;
+            if layout.y - 24.0 - BODY_LEADING < BOTTOM {
+                layout.new_page();
+            }
+            layout.text_line(Font::Bold, 18.0, "Let's talk about it", Align::Left, accent);
+            layout.y -= 6.0;
+            for question in &part.questions {
+                layout.paragraph(Font::Regular, BODY_SIZE, BODY_LEADING, question, Some(accent));
+                layout.y -= PARAGRAPH_GAP / 2.0;
+            }
+        }
+    }
+
+    Ok(write_pdf(book, layout, &chapter_pages))
+}
+
+/// The genre's icon in a large disc, the title and genre, and the QR code.
+fn title_page(layout: &mut Layout, book: &PdfBook, accent: Rgb) -> Result<(), String> {
+    layout.new_page();
+    let page = layout.page();
+    page.set_fill_rgb(accent.0, accent.1, accent.2);
+    circle(page, PAGE_WIDTH / 2.0, PAGE_HEIGHT - 170.0, 64.0);
+    page.fill_nonzero();
+    draw_icon(page, book.icon.as_deref(), PAGE_WIDTH / 2.0, PAGE_HEIGHT - 170.0, 40.0, accent);
+
+    layout.y = PAGE_HEIGHT - 290.0;
+    for line in wrap(&book.title, Font::Bold, 30.0, PAGE_WIDTH - 2.0 * MARGIN) {
+        layout.line_bytes(Font::Bold, 30.0, &line, Align::Center, BLACK);
+        layout.y -= 38.0;
+    }
+    layout.y -= 4.0;
+    layout.text_line(Font::Regular, 16.0, &book.genre, Align::Center, GREY);
+
+    if let Some(text) = &book.qr_text {
+        let code = QrCode::new(text.as_bytes())
+            .map_err(|e| format!("Failed to make the QR code: {}", e))?;
+        draw_qr(layout.page(), &code, (PAGE_WIDTH - QR_SIZE) / 2.0, MARGIN + 18.0, QR_SIZE);
+        layout.y = MARGIN + 4.0;
+        layout.text_line(Font::Regular, 10.0, &format!("Listen along: {}", text), Align::Center, GREY);
+    }
+    Ok(())
+}
+
+/// Assemble the document: pages with numbers (the title page has none),
+/// the two fonts, document info and an outline entry per part.
+fn write_pdf(book: &PdfBook, layout: Layout, chapter_pages: &[usize]) -> Vec<u8> {
+    let mut next_id = 1;
+    let mut alloc = || {
+        let id = Ref::new(next_id);
+        next_id += 1;
+        id
+    };
+    let catalog_id = alloc();
+    let tree_id = alloc();
+    let regular_id = alloc();
+    let bold_id = alloc();
+    let info_id = alloc();
+    let outline_id = alloc();
+    let page_ids: Vec<(Ref, Ref)> = layout.pages.iter().map(|_| (alloc(), alloc())).collect();
+    let item_ids: Vec<Ref> = chapter_pages.iter().map(|_| alloc()).collect();
+
+    let mut pdf = Pdf::new();
+    let mut catalog = pdf.catalog(catalog_id);
+    catalog.pages(tree_id);
+    if !item_ids.is_empty() {
+        catalog.outlines(outline_id);
+    }
+    catalog.finish();
+    pdf.pages(tree_id)
+        .kids(page_ids.iter().map(|(page, _)| *page))
+        .count(page_ids.len() as i32);
+    pdf.type1_font(regular_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.document_info(info_id)
+        .title(TextStr(&book.title))
+        .subject(TextStr(&book.genre))
+        .creator(TextStr("Pattikadhai"));
+
+    for (i, (mut content, (page_id, content_id))) in
+        layout.pages.into_iter().zip(&page_ids).enumerate()
+    {
+        if i > 0 {
+            let number = i.to_string();
+            let x = (PAGE_WIDTH - text_width(number.as_bytes(), Font::Regular, 11.0)) / 2.0;
+            content.set_fill_rgb(GREY.0, GREY.1, GREY.2);
+            content.begin_text();
+            content.set_font(Font::Regular.name(), 11.0);
+            content.next_line(x, MARGIN / 2.0);
+            content.show(Str(number.as_bytes()));
+            content.end_text();
+        }
+        let mut page = pdf.page(*page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(tree_id)
+            .contents(*content_id);
+        let mut resources = page.resources();
+        resources
+            .fonts()
+            .pair(Font::Regular.name(), regular_id)
+            .pair(Font::Bold.name(), bold_id);
+        resources.finish();
+        page.finish();
+        pdf.stream(*content_id, &content.finish());
+    }
+
+    if let (Some(first), Some(last)) = (item_ids.first(), item_ids.last()) {
+        pdf.outline(outline_id)
+            .first(*first)
+            .last(*last)
+            .count(item_ids.len() as i32);
+        for (i, (item_id, page)) in item_ids.iter().zip(chapter_pages).enumerate() {
+            let title = format!("Part {}", i + 1);
+            let mut item = pdf.outline_item(*item_id);
+            item.title(TextStr(&title)).parent(outline_id);
+            if i > 0 {
+                item.prev(item_ids[i - 1]);
+            }
+            if let Some(next) = item_ids.get(i + 1) {
+                item.next(*next);
+            }
+            item.dest().page(page_ids[*page].0).fit();
+        }
+    }
+
+    pdf.finish()
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn name(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+        }
+    }
+
+    /// Glyph width in thousandths of the font size, from the standard
+    /// Helvetica metrics.
+    fn width(self, byte: u8) -> u16 {
+        let table = match self {
+            Font::Regular => &HELVETICA_WIDTHS,
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+        };
+        match byte {
+            32..=126 => table[(byte - 32) as usize],
+            0x91 | 0x92 => 278,
+            0x93 | 0x94 => 500,
+            0x95 => 350,
+            0x85 | 0x97 => 1000,
+            _ => 556,
+        }
+    }
+}
+
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+type Rgb = (f32, f32, f32);
+
+const BLACK: Rgb = (0.1, 0.1, 0.12);
+const GREY: Rgb = (0.42, 0.44, 0.48);
+const WHITE: Rgb = (1.0, 1.0, 1.0);
+
+/// The genre's accent colour, used on the title page and for chapter headings
+/// and discussion questions.
+fn accent_color(genre_id: &str) -> Rgb {
+    let hex: u32 = match genre_id {
+        "adventure" => 0xE07A2F,
+        "fantasy" => 0x7C4DBA,
+        "moral" => 0xD64F6E,
+        "bedtime" => 0x2E3A78,
+        "animal" => 0x4C9A4A,
+        "science" => 0x1F8A8A,
+        _ => 0x5A6472,
+    };
+    let channel = |shift: u32| (hex >> shift & 0xff) as f32 / 255.0;
+    (channel(16), channel(8), channel(0))
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+}
+
+/// Pages being filled top to bottom; `y` is the next baseline on the last page.
+#[derive(Default)]
+struct Layout {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl Layout {
+    fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn page(&mut self) -> &mut Content {
+        self.pages.last_mut().expect("a page has been started")
+    }
+
+    /// One line of text at the current baseline, which moves down by `size`.
+    fn text_line(&mut self, font: Font, size: f32, text: &str, align: Align, color: Rgb) {
+        self.y -= size;
+        self.line_bytes(font, size, &encode(text), align, color);
+    }
+
+    fn line_bytes(&mut self, font: Font, size: f32, bytes: &[u8], align: Align, color: Rgb) {
+        let x = match align {
+            Align::Left => MARGIN,
+            Align::Center => (PAGE_WIDTH - text_width(bytes, font, size)) / 2.0,
+        };
+        let y = self.y;
+        let page = self.page();
+        page.set_fill_rgb(color.0, color.1, color.2);
+        page.begin_text();
+        page.set_font(font.name(), size);
+        page.next_line(x, y);
+        page.show(Str(bytes));
+        page.end_text();
+    }
+
+    /// Wrapped, left-aligned text, starting new pages as needed. With
+    /// `bullet`, a dot in that colour hangs in front of the first line.
+    fn paragraph(&mut self, font: Font, size: f32, leading: f32, text: &str, bullet: Option<Rgb>) {
+        let indent = if bullet.is_some() { 20.0 } else { 0.0 };
+        let lines = wrap(text, font, size, PAGE_WIDTH - 2.0 * MARGIN - indent);
+        for (i, line) in lines.iter().enumerate() {
+            if self.y - leading < BOTTOM {
+                self.new_page();
+            }
+            self.y -= leading;
+            let y = self.y;
+            let page = self.page();
+            if let (0, Some(color)) = (i, bullet) {
+                page.set_fill_rgb(color.0, color.1, color.2);
+                circle(page, MARGIN + 5.0, y + size * 0.33, 4.0);
+                page.fill_nonzero();
+            }
+            page.set_fill_rgb(BLACK.0, BLACK.1, BLACK.2);
+            page.begin_text();
+            page.set_font(font.name(), size);
+            page.next_line(MARGIN + indent, y);
+            page.show(Str(line));
+            page.end_text();
+        }
+    }
+}
+
+/// Text in WinAnsi, the encoding of the built-in fonts. Typographic quotes,
+/// dashes and ellipses have their own codes; other characters outside
+/// Latin-1 print as `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201c}' => 0x93,
+            '\u{201d}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{2026}' => 0x85,
+            '\t' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn text_width(bytes: &[u8], font: Font, size: f32) -> f32 {
+    bytes.iter().map(|&b| font.width(b) as f32).sum::<f32>() * size / 1000.0
+}
+
+/// Break text into encoded lines no wider than `width`. A word longer than
+/// a line gets a line of its own.
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<Vec<u8>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<u8> = Vec::new();
+    for word in text.split_whitespace() {
+        let word = encode(word);
+        if !line.is_empty() {
+            let mut candidate = line.clone();
+            candidate.push(b' ');
+            candidate.extend_from_slice(&word);
+            if text_width(&candidate, font, size) <= width {
+                line = candidate;
+                continue;
+            }
+            lines.push(std::mem::take(&mut line));
+        }
+        line = word;
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Add a circle to the current path.
+fn circle(content: &mut Content, cx: f32, cy: f32, r: f32) {
+    let k = 0.5523 * r;
+    content.move_to(cx + r, cy);
+    content.cubic_to(cx + r, cy + k, cx + k, cy + r, cx, cy + r);
+    content.cubic_to(cx - k, cy + r, cx - r, cy + k, cx - r, cy);
+    content.cubic_to(cx - r, cy - k, cx - k, cy - r, cx, cy - r);
+    content.cubic_to(cx + k, cy - r, cx + r, cy - k, cx + r, cy);
+    content.close_path();
+}
+
+fn polygon(content: &mut Content, points: &[(f32, f32)]) {
+    for (i, &(x, y)) in points.iter().enumerate() {
+        if i == 0 {
+            content.move_to(x, y);
+        } else {
+            content.line_to(x, y);
+        }
+    }
+    content.close_path();
+}
+
+/// A white drawing of the genre's icon, `r` points from its centre. Drawn
+/// in a -1..1 box; unknown icons get sparkles.
+fn draw_icon(content: &mut Content, icon: Option<&str>, cx: f32, cy: f32, r: f32, background: Rgb) {
+    content.save_state();
+    content.transform([r, 0.0, 0.0, r, cx, cy]);
+    content.set_fill_rgb(WHITE.0, WHITE.1, WHITE.2);
+    match icon.unwrap_or_default() {
+        "compass" => {
+            circle(content, 0.0, 0.0, 0.9);
+            circle(content, 0.0, 0.0, 0.75);
+            content.fill_even_odd();
+            polygon(content, &[(0.0, 0.6), (0.16, 0.0), (0.0, -0.6), (-0.16, 0.0)]);
+            content.fill_nonzero();
+        }
+        "heart" => {
+            content.move_to(0.0, -0.75);
+            content.cubic_to(-1.1, 0.05, -0.6, 0.95, 0.0, 0.4);
+            content.cubic_to(0.6, 0.95, 1.1, 0.05, 0.0, -0.75);
+            content.close_path();
+            content.fill_nonzero();
+        }
+        "moon" => {
+            circle(content, -0.1, 0.0, 0.75);
+            content.fill_nonzero();
+            content.set_fill_rgb(background.0, background.1, background.2);
+            circle(content, 0.25, 0.25, 0.65);
+            content.fill_nonzero();
+        }
+        "paw-print" => {
+            circle(content, 0.0, -0.3, 0.38);
+            for (x, y) in [(-0.55, 0.15), (-0.2, 0.52), (0.2, 0.52), (0.55, 0.15)] {
+                circle(content, x, y, 0.17);
+            }
+            content.fill_nonzero();
+        }
+        "flask" => {
+            polygon(
+                content,
+                &[(-0.18, 0.8), (0.18, 0.8), (0.18, 0.2), (0.65, -0.75), (-0.65, -0.75), (-0.18, 0.2)],
+            );
+            content.fill_nonzero();
+        }
+        _ => {
+            for (x, y, s) in [(-0.15, -0.1, 0.75), (0.55, 0.55, 0.3)] {
+                polygon(
+                    content,
+                    &[
+                        (x, y + s),
+                        (x + 0.2 * s, y + 0.2 * s),
+                        (x + s, y),
+                        (x + 0.2 * s, y - 0.2 * s),
+                        (x, y - s),
+                        (x - 0.2 * s, y - 0.2 * s),
+                        (x - s, y),
+                        (x - 0.2 * s, y + 0.2 * s),
+                    ],
+                );
+            }
+            content.fill_nonzero();
+        }
+    }
+    content.restore_state();
+}
+
+/// Dark modules of `code` as squares, with a white quiet zone, filling a
+/// `size` square whose lower-left corner is at (`x`, `y`).
+fn draw_qr(content: &mut Content, code: &QrCode, x: f32, y: f32, size: f32) {
+    let width = code.width();
+    let module = size / (width + 8) as f32;
+    content.set_fill_rgb(WHITE.0, WHITE.1, WHITE.2);
+    content.rect(x, y, size, size);
+    content.fill_nonzero();
+    content.set_fill_rgb(0.0, 0.0, 0.0);
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color == Color::Dark {
+            let (col, row) = ((i % width) as f32, (i / width) as f32);
+            content.rect(
+                x + (col + 4.0) * module,
+                y + size - (row + 5.0) * module,
+                module,
+                module,
+            );
+        }
+    }
+    content.fill_nonzero();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(parts: Vec<PdfPart>, qr_text: Option<&str>) -> PdfBook {
+        PdfBook {
+            title: "The Brave Little Fox".to_string(),
+            genre: "Animal Tales".to_string(),
+            genre_id: "animal".to_string(),
+            icon: Some("paw-print".to_string()),
+            qr_text: qr_text.map(String::from),
+            parts,
+        }
+    }
+
+    #[test]
+    fn wraps_within_the_width() {
+        let text = "The little fox ran all the way home through the tall green grass.";
+        let lines = wrap(text, Font::Regular, BODY_SIZE, 200.0);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(text_width(line, Font::Regular, BODY_SIZE) <= 200.0);
+        }
+        let joined: Vec<String> = lines.iter().map(|l| String::from_utf8(l.clone()).unwrap()).collect();
+        assert_eq!(joined.join(" "), text);
+    }
+
+    #[test]
+    fn encodes_typographic_punctuation() {
+        assert_eq!(encode("\u{201c}Hi\u{201d} \u{2014} it\u{2019}s é✨"), b"\x93Hi\x94 \x97 it\x92s \xe9?");
+    }
+
+    #[test]
+    fn builds_a_chapter_per_part_with_questions() {
+        let parts = vec![
+            PdfPart {
+                content: "Once upon a time. [SFX: door creak]\n\nThe fox woke up.".to_string(),
+                questions: vec!["Why did the fox wake up?".to_string()],
+            },
+            PdfPart {
+                content: "The end.".to_string(),
+                questions: vec![],
+            },
+        ];
+        let pdf = build(&book(parts, Some("The Brave Little Fox.m4b"))).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-"));
+        // Title page and one page per part
+        assert!(text.contains("/Count 3"));
+        assert!(text.contains("(Part 2)"));
+        assert!(text.contains("(Let's talk about it) Tj"));
+        assert!(text.contains("(Why did the fox wake up?) Tj"));
+        assert!(text.contains("(Listen along: The Brave Little Fox.m4b) Tj"));
+        assert!(!text.contains("SFX"));
+    }
+}
@@ -1,11 +1,23 @@
+use std::collections::HashSet;
 use std::path::PathBuf;
 
-use crate::db::queries::VoiceSettings;
-use crate::services::tts::TtsService;
+use crate::db::models::{AudioJobSentence, StoryCharacter};
+use crate::db::queries::{self, VoiceSettings};
+use crate::services::dialogue;
+use crate::services::music::{self, MusicLibrary, MusicSource};
+use crate::services::sfx::{self, SfxCue, SfxLibrary};
+use crate::services::stream::PcmStream;
+use crate::services::tts::{TtsRawResult, TtsService, DEFAULT_SPEED, DEFAULT_VOICE};
+use kokoro_tts::audio::ambience::{self, Soundscape};
+use kokoro_tts::audio::effects::{self, VoiceEffect};
+use kokoro_tts::audio::mastering::{self, MasteringPreset};
+use kokoro_tts::audio::mixer::{AudioBuffer, Clip, Timeline};
+use kokoro_tts::CancelToken;
+use sha2::{Digest, Sha256};
 use tauri::Emitter;
 
 /// Naive sentence splitter: split on ". ", "! ", "? " keeping the delimiter with the preceding text.
-fn split_sentences(text: &str) -> Vec<&str> {
+pub fn split_sentences(text: &str) -> Vec<&str> {
     let mut result = Vec::new();
     let mut start = 0;
     let bytes = text.as_bytes();
@@ -21,9 +33,135 @@ fn split_sentences(text: &str) -> Vec<&str> {
     result
 }
 
+/// Length of the start of `text` (still being written) whose sentences can
+/// no longer change: it ends at a sentence break outside any quote, so later
+/// text neither extends its last sentence nor changes who speaks a quote.
+pub fn settled_len(text: &str) -> usize {
+    let mut settled = 0;
+    let mut in_quote = false;
+    let mut prev = None;
+    for (i, ch) in text.char_indices() {
+        if ch == ' ' && !in_quote && matches!(prev, Some('.' | '!' | '?')) {
+            settled = i + 1;
+        }
+        // Same quote rules as the dialogue splitter
+        if !in_quote && (ch == '"' || ch == '\u{201c}') {
+            in_quote = true;
+        } else if in_quote && (ch == '"' || ch == '\u{201d}') {
+            in_quote = false;
+        }
+        prev = Some(ch);
+    }
+    settled
+}
+
+/// Longest pause made in the narration for a sound effect; longer sounds
+/// carry on under the next sentence.
+const SFX_GAP_SECS: f32 = 1.0;
+/// Sound-effect level relative to the narration.
+const SFX_GAIN: f32 = 0.5;
+
+/// One sentence to synthesize and who reads it.
+#[derive(Debug, PartialEq)]
+pub struct PlannedSentence<'a> {
+    pub text: String,
+    pub voice: Option<&'a str>,
+    pub effect: VoiceEffect,
+    /// Sound effects to play before or after the sentence
+    pub cues: Vec<SfxCue>,
+}
+
+/// Split text into narration and dialogue, then sentences, assigning each
+/// sentence the speaking character's voice or the narrator's. `[SFX: ...]`
+/// cues are removed from the text and attached to the nearest sentence.
+pub fn plan_sentences<'a>(
+    text: &str,
+    characters: &'a [StoryCharacter],
+    narrator_voice: Option<&'a str>,
+    narrator_effect: VoiceEffect,
+) -> Vec<PlannedSentence<'a>> {
+    let mut planned: Vec<PlannedSentence> = Vec::new();
+    // Cues from sentences with nothing to read wait for the next sentence
+    let mut waiting: Vec<SfxCue> = Vec::new();
+    for segment in dialogue::segment_dialogue(text) {
+        let character = segment
+            .speaker
+            .as_ref()
+            .and_then(|name| characters.iter().find(|c| &c.name == name));
+        let (voice, effect) = match character {
+            Some(c) => (Some(c.voice.as_str()), parse_effect(&c.effect)),
+            None => (narrator_voice, narrator_effect),
+        };
+        for sentence in split_sentences(&segment.text) {
+            let (sentence, cues) = sfx::extract_cues(sentence.trim());
+            if sentence.is_empty() {
+                waiting.extend(cues.into_iter().map(|c| SfxCue { after: false, ..c }));
+                continue;
+            }
+            planned.push(PlannedSentence {
+                text: sentence,
+                voice,
+                effect,
+                cues: waiting.drain(..).chain(cues).collect(),
+            });
+        }
+    }
+    // Trailing cues play after the last sentence
+    if let Some(last) = planned.last_mut() {
+        last.cues
+            .extend(waiting.into_iter().map(|c| SfxCue { after: true, ..c }));
+    }
+    planned
+}
+
+/// Cache key for a sentence's audio: a hash of everything that changes how
+/// it sounds, so an edited part only re-synthesizes the sentences that changed.
+/// A missing voice or speed hashes as the default the engine uses for it.
+pub fn segment_key(text: &str, voice: Option<&str>, speed: Option<f32>, effect: VoiceEffect) -> String {
+    let mut hasher = Sha256::new();
+    for field in [
+        text,
+        voice.unwrap_or(DEFAULT_VOICE),
+        &speed.unwrap_or(DEFAULT_SPEED).to_string(),
+        effect.name(),
+    ] {
+        hasher.update(field.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Look up a voice effect by name, falling back to none.
+pub fn parse_effect(name: &str) -> VoiceEffect {
+    VoiceEffect::from_name(name).unwrap_or_else(|| {
+        log::warn!("Unknown voice effect '{}', using none", name);
+        VoiceEffect::None
+    })
+}
+
 pub struct AudioPipeline {
     tts: TtsService,
     audio_dir: PathBuf,
+    music: MusicLibrary,
+    sfx: SfxLibrary,
+    db_path: PathBuf,
+}
+
+/// Story-level details the pipeline needs beyond the part text.
+#[derive(Debug, Clone, Default)]
+pub struct StoryContext {
+    pub story_id: String,
+    pub genre_id: String,
+    pub characters: Vec<StoryCharacter>,
+    /// Seed for the story's theme tune (and its ambience), used when the
+    /// library has no music
+    pub music_seed: Option<u64>,
+    /// Ambient bed mixed under the narration, if the story has one
+    pub soundscape: Option<Soundscape>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -34,18 +172,13 @@ pub struct PipelineProgress {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct SentenceAudio {
-    pub job_id: String,
-    pub index: usize,
-    pub total: usize,
-    pub text: String,
-    pub wav_path: String,
-    pub duration_secs: f64,
-}
-
 pub struct PipelineResult {
+    /// Narration without music
+    pub voice_path: String,
+    /// What the player uses: narration mixed with music, or the narration alone
     pub audio_path: String,
+    /// Library track or generated theme mixed under the narration, if any
+    pub music_path: Option<String>,
     pub timing_path: String,
 }
 
@@ -57,14 +190,51 @@ pub struct TimingSegment {
 }
 
 impl AudioPipeline {
-    pub fn new(audio_dir: PathBuf, models_dir: PathBuf) -> Self {
+    pub fn new(
+        audio_dir: PathBuf,
+        models_dir: PathBuf,
+        music_dir: PathBuf,
+        sfx_dir: PathBuf,
+        db_path: PathBuf,
+    ) -> Self {
         Self {
             tts: TtsService::new(models_dir),
             audio_dir,
+            music: MusicLibrary::new(music_dir),
+            sfx: SfxLibrary::new(sfx_dir),
+            db_path,
         }
     }
 
-    /// Run the voice-only audio pipeline for a story part with per-sentence streaming.
+    /// Record a new job stage in the DB and tell the frontend.
+    fn enter_stage(&self, job_id: &str, stage: &str, app_handle: &tauri::AppHandle) {
+        if let Ok(conn) = rusqlite::Connection::open(&self.db_path) {
+            let _ = queries::update_audio_job_status(&conn, job_id, stage, None);
+        }
+        let _ = app_handle.emit(
+            "audio-progress",
+            PipelineProgress {
+                job_id: job_id.to_string(),
+                stage: stage.to_string(),
+                progress: 0.0,
+                error: None,
+            },
+        );
+    }
+
+    /// Run the audio pipeline for a story part with per-sentence streaming.
+    /// Quoted dialogue by a known character is read in that character's voice,
+    /// and a music bed from the genre's library (or the story's generated theme)
+    /// and the story's ambient soundscape are mixed underneath when enabled.
+    /// `cancel` is checked between sentences and before mastering.
+    /// Each sentence is cached as a segment keyed by [`segment_key`], so after
+    /// an edit only the changed sentences are synthesized again before the
+    /// final file and timing are re-stitched. Each finished sentence is also
+    /// recorded for the job in `audio_job_sentences`, so a job that was
+    /// interrupted or cancelled picks up after the sentences it finished.
+    /// With a `stream`, the narration is also sent to the player as PCM frames
+    /// sentence by sentence with their sound effects mixed in.
+    #[allow(clippy::too_many_arguments)]
     pub async fn process(
         &self,
         job_id: &str,
@@ -72,30 +242,46 @@ impl AudioPipeline {
         text: &str,
         app_handle: &tauri::AppHandle,
         voice_settings: Option<&VoiceSettings>,
+        story: &StoryContext,
+        cancel: &CancelToken,
+        stream: Option<&PcmStream>,
     ) -> Result<PipelineResult, String> {
-        let final_path = self
+        let voice_path = self
             .audio_dir
-            .join(format!("{}_final.wav", part_id))
+            .join(format!("{}_voice.wav", part_id))
             .to_string_lossy()
             .to_string();
-        let timing_path = self
-            .audio_dir
-            .join(format!("{}_timing.json", part_id))
+        let final_path = self.final_path(part_id).to_string_lossy().to_string();
+        let timing_path = timing_path(&self.audio_dir, part_id)
             .to_string_lossy()
             .to_string();
 
-        // Ensure audio directory exists
-        tokio::fs::create_dir_all(&self.audio_dir)
+        // Ensure the audio and segment directories exist
+        tokio::fs::create_dir_all(self.segments_dir(part_id))
             .await
             .map_err(|e| format!("Failed to create audio dir: {}", e))?;
 
         // Extract voice settings
         let voice_name = voice_settings.map(|vs| vs.voice.as_str());
         let speed = voice_settings.and_then(|vs| vs.speed);
-
-        // Split text into sentences
-        let sentences = split_sentences(text);
+        let narrator_effect = voice_settings
+            .map(|vs| parse_effect(&vs.effect))
+            .unwrap_or(VoiceEffect::None);
+        let mastering_preset = match voice_settings.map(|vs| vs.mastering.as_str()) {
+            Some(name) => MasteringPreset::from_name(name).unwrap_or_else(|| {
+                log::warn!("Unknown mastering preset '{}', using none", name);
+                MasteringPreset::None
+            }),
+            None => MasteringPreset::None,
+        };
+
+        // Split text into sentences, each with its reader
+        let sentences = plan_sentences(text, &story.characters, voice_name, narrator_effect);
         let total = sentences.len();
+        let finished = self.finished_sentences(job_id);
+        if !finished.is_empty() {
+            log::info!("Job {} resumes after {} finished sentences", job_id, finished.len());
+        }
 
         // Emit start with total sentence count
         let _ = app_handle.emit(
@@ -110,45 +296,57 @@ impl AudioPipeline {
 
         let mut all_samples: Vec<f32> = Vec::new();
         let mut timing_segments: Vec<TimingSegment> = Vec::new();
-        let mut sentence_wav_paths: Vec<String> = Vec::new();
+        // Segments this render uses; the part's other segments are pruned
+        let mut segment_keys: HashSet<String> = HashSet::new();
         let mut cumulative_secs: f64 = 0.0;
         let mut sample_rate: u32 = 24000;
-
-        for (i, sentence_text) in sentences.iter().enumerate() {
-            let sentence_text = sentence_text.trim();
-            if sentence_text.is_empty() {
-                continue;
+        // Sound effects and the sample offsets they start at
+        let mut sfx_placements: Vec<(usize, Vec<f32>)> = Vec::new();
+        // Samples of `all_samples` already sent to the stream
+        let mut streamed = 0;
+        // Sound effects already streamed that run on past their sentence
+        let mut stream_carry: Vec<f32> = Vec::new();
+
+        for (i, sentence) in sentences.iter().enumerate() {
+            check_cancelled(cancel)?;
+            let sentence_text = sentence.text.as_str();
+
+            for cue in sentence.cues.iter().filter(|c| !c.after) {
+                cumulative_secs +=
+                    self.place_sfx(&cue.name, sample_rate, &mut all_samples, &mut sfx_placements);
             }
 
-            // Generate raw audio for this sentence
-            let raw = self
-                .tts
-                .generate_raw(sentence_text, voice_name, speed)
-                .await?;
-            sample_rate = raw.sample_rate;
-
-            // Save per-sentence WAV
-            let sent_wav_path = self
-                .audio_dir
-                .join(format!("{}_sent_{}.wav", part_id, i))
-                .to_string_lossy()
-                .to_string();
-
-            let path_for_save = sent_wav_path.clone();
-            let samples_for_save = raw.samples.clone();
-            let sr = raw.sample_rate;
-            tokio::task::spawn_blocking(move || {
-                kokoro_tts::audio::save_wav(
-                    std::path::Path::new(&path_for_save),
-                    &samples_for_save,
-                    sr,
-                )
-                .map_err(|e| format!("Failed to save sentence WAV: {}", e))
-            })
-            .await
-            .map_err(|e| format!("Save WAV task panicked: {}", e))??;
+            let key = segment_key(&sentence.text, sentence.voice, speed, sentence.effect);
+            let segment_path = self.segment_path(part_id, &key);
 
-            sentence_wav_paths.push(sent_wav_path.clone());
+            // Reuse the sentence if this job finished it before an
+            // interruption, or from an earlier render of this part (before an
+            // edit) or live narration
+            let recorded = finished.iter().find(|done| {
+                done.sentence_index == i && done.text == sentence.text && done.wav_path == segment_path
+            });
+            let cached = match recorded {
+                Some(done) => load_sentence_wav(&done.wav_path).await,
+                None => load_sentence_wav(&segment_path).await,
+            };
+            let raw = match cached {
+                Some(raw) => raw,
+                None => {
+                    self.synthesize_sentence(sentence, &segment_path, speed, cancel)
+                        .await?
+                }
+            };
+            sample_rate = raw.sample_rate;
+            segment_keys.insert(key);
+            if recorded.is_none() {
+                self.record_sentence(&AudioJobSentence {
+                    job_id: job_id.to_string(),
+                    sentence_index: i,
+                    text: sentence.text.clone(),
+                    wav_path: segment_path.clone(),
+                    duration_secs: raw.duration_secs,
+                });
+            }
 
             // Build timing segment
             let start = cumulative_secs;
@@ -161,19 +359,23 @@ impl AudioPipeline {
 
             // Accumulate samples for final WAV
             all_samples.extend_from_slice(&raw.samples);
+            for cue in sentence.cues.iter().filter(|c| c.after) {
+                cumulative_secs +=
+                    self.place_sfx(&cue.name, sample_rate, &mut all_samples, &mut sfx_placements);
+            }
 
-            // Emit per-sentence event
-            let _ = app_handle.emit(
-                "audio-sentence",
-                SentenceAudio {
-                    job_id: job_id.to_string(),
-                    index: i,
-                    total,
-                    text: sentence_text.to_string(),
-                    wav_path: sent_wav_path,
-                    duration_secs: raw.duration_secs,
-                },
-            );
+            if let Some(stream) = stream {
+                stream.sentence(i, total, start, sentence_text);
+                let placed = sfx_placements
+                    .iter()
+                    .filter(|(offset, _)| *offset >= streamed)
+                    .map(|(offset, sfx)| (offset - streamed, sfx.clone()))
+                    .collect();
+                let chunk =
+                    stream_chunk(&all_samples[streamed..], placed, &mut stream_carry, sample_rate);
+                stream.pcm(&chunk, sample_rate);
+                streamed = all_samples.len();
+            }
 
             // Emit progress update
             let progress = (i + 1) as f32 / total as f32;
@@ -188,22 +390,101 @@ impl AudioPipeline {
             );
         }
 
-        // Write final concatenated WAV from accumulated samples
-        {
-            let final_path_clone = final_path.clone();
-            let samples = all_samples;
-            let sr = sample_rate;
-            tokio::task::spawn_blocking(move || {
-                kokoro_tts::audio::save_wav(
-                    std::path::Path::new(&final_path_clone),
-                    &samples,
-                    sr,
-                )
-                .map_err(|e| format!("Failed to save final WAV: {}", e))
+        if let Some(stream) = stream {
+            if !stream_carry.is_empty() {
+                stream.pcm(&stream_carry, sample_rate);
+            }
+            stream.end();
+        }
+
+        // Master the whole part at once so the compressor and reverb tail
+        // carry across sentence boundaries
+        check_cancelled(cancel)?;
+        let sr = sample_rate;
+        let voice_samples = tokio::task::spawn_blocking(move || {
+            let voice = mastering::master(&all_samples, sr, mastering_preset);
+            mix_sfx(voice, sfx_placements, sr)
+        })
+        .await
+        .map_err(|e| format!("Mastering task panicked: {}", e))?;
+        // Last chance to stop before anything replaces the part's audio
+        check_cancelled(cancel)?;
+
+        let music_enabled = voice_settings.is_some_and(|vs| vs.music_enabled);
+        let source = if music_enabled {
+            self.music
+                .pick_source(&story.genre_id, &story.story_id, story.music_seed)
+        } else {
+            None
+        };
+        let ambience_enabled = voice_settings.is_some_and(|vs| vs.ambience_enabled);
+        let soundscape = story.soundscape.filter(|_| ambience_enabled);
+
+        let mut music_path = None;
+        let voice_path = if source.is_some() || soundscape.is_some() {
+            save_wav_blocking(&voice_path, voice_samples.clone(), sr).await?;
+            self.enter_stage(job_id, "music_generating", app_handle);
+            let voice_len = voice_samples.len();
+            let mut beds = Vec::new();
+
+            if let Some(source) = source {
+                log::info!("Mixing music bed {:?}", source);
+                let track = tokio::task::spawn_blocking({
+                    let source = source.clone();
+                    move || source.render(sr)
+                })
+                .await
+                .map_err(|e| format!("Music task panicked: {}", e))??;
+                music_path = Some(match source {
+                    MusicSource::Track(path) => path.to_string_lossy().to_string(),
+                    MusicSource::Theme { .. } => {
+                        // Keep the theme tune next to the story's audio
+                        let theme_path = self
+                            .audio_dir
+                            .join(format!("{}_theme.wav", story.story_id))
+                            .to_string_lossy()
+                            .to_string();
+                        save_wav_blocking(&theme_path, track.clone(), sr).await?;
+                        theme_path
+                    }
+                });
+
+                let timings = timing_segments.clone();
+                let bed = tokio::task::spawn_blocking(move || {
+                    music::render_bed(&track, voice_len, sr, &timings, music::MUSIC_LEVEL)
+                })
+                .await
+                .map_err(|e| format!("Music task panicked: {}", e))?;
+                beds.push(bed);
+            }
+
+            if let Some(soundscape) = soundscape {
+                log::info!("Mixing {} ambience", soundscape.name());
+                let seed = story.music_seed.unwrap_or_default();
+                let timings = timing_segments.clone();
+                let bed = tokio::task::spawn_blocking(move || {
+                    let len = music::bed_len(voice_len, sr);
+                    let noise = ambience::render_ambience(soundscape, seed, len, sr);
+                    music::render_bed(&noise, voice_len, sr, &timings, music::AMBIENCE_LEVEL)
+                })
+                .await
+                .map_err(|e| format!("Ambience task panicked: {}", e))?;
+                beds.push(bed);
+            }
+
+            self.enter_stage(job_id, "mixing", app_handle);
+            let mixed = tokio::task::spawn_blocking(move || {
+                music::mix_voice_and_beds(voice_samples, beds, sr)
             })
             .await
-            .map_err(|e| format!("Save final WAV task panicked: {}", e))??;
-        }
+            .map_err(|e| format!("Mixing task panicked: {}", e))?;
+            save_wav_blocking(&final_path, mixed, sr).await?;
+            voice_path
+        } else {
+            // Without music or ambience the narration is the final file
+            save_wav_blocking(&final_path, voice_samples, sr).await?;
+            final_path.clone()
+        };
 
         // Write timing JSON sidecar
         let timing_json = serde_json::to_string_pretty(&timing_segments)
@@ -212,10 +493,9 @@ impl AudioPipeline {
             .await
             .map_err(|e| format!("Failed to write timing JSON: {}", e))?;
 
-        // Clean up per-sentence WAV files
-        for path in &sentence_wav_paths {
-            let _ = tokio::fs::remove_file(path).await;
-        }
+        // Keep only the segments this render used
+        self.prune_segments(part_id, &segment_keys).await;
+        self.forget_sentences(job_id);
 
         // Emit completion
         let _ = app_handle.emit(
@@ -229,10 +509,340 @@ impl AudioPipeline {
         );
 
         Ok(PipelineResult {
+            voice_path,
             audio_path: final_path,
+            music_path,
             timing_path,
         })
     }
+
+    fn segments_dir(&self, part_id: &str) -> PathBuf {
+        self.audio_dir.join("segments").join(part_id)
+    }
+
+    fn segment_path(&self, part_id: &str, key: &str) -> String {
+        self.segments_dir(part_id)
+            .join(format!("{}.wav", key))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Delete the part's cached segments that are not in `keep`.
+    async fn prune_segments(&self, part_id: &str, keep: &HashSet<String>) {
+        let Ok(mut entries) = tokio::fs::read_dir(self.segments_dir(part_id)).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let stale = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| !keep.contains(stem));
+            if stale {
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+        }
+    }
+
+    /// Sentences `job_id` finished in earlier runs.
+    fn finished_sentences(&self, job_id: &str) -> Vec<AudioJobSentence> {
+        rusqlite::Connection::open(&self.db_path)
+            .and_then(|conn| queries::get_audio_job_sentences(&conn, job_id))
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to read progress of job {}: {}", job_id, e);
+                Vec::new()
+            })
+    }
+
+    /// Record a finished sentence so the job can resume after it.
+    fn record_sentence(&self, sentence: &AudioJobSentence) {
+        let recorded = rusqlite::Connection::open(&self.db_path)
+            .and_then(|conn| queries::insert_audio_job_sentence(&conn, sentence));
+        if let Err(e) = recorded {
+            log::warn!("Failed to record progress of job {}: {}", sentence.job_id, e);
+        }
+    }
+
+    /// Forget a job's sentence progress once its audio is assembled.
+    fn forget_sentences(&self, job_id: &str) {
+        if let Ok(conn) = rusqlite::Connection::open(&self.db_path) {
+            let _ = queries::delete_audio_job_sentences(&conn, job_id);
+        }
+    }
+
+    /// Synthesize one sentence in its reader's voice and effect, then save it
+    /// to `wav_path`.
+    async fn synthesize_sentence(
+        &self,
+        sentence: &PlannedSentence<'_>,
+        wav_path: &str,
+        speed: Option<f32>,
+        cancel: &CancelToken,
+    ) -> Result<TtsRawResult, String> {
+        let mut raw = self
+            .tts
+            .generate_raw_cancellable(&sentence.text, sentence.voice, speed, cancel.clone())
+            .await?;
+
+        // Apply the character effect (duration is unchanged)
+        apply_effect_blocking(&mut raw, sentence.effect).await?;
+
+        save_wav_blocking(wav_path, raw.samples.clone(), raw.sample_rate).await?;
+        Ok(raw)
+    }
+
+    /// Synthesize a sentence of part `part_id` from hand-edited phonemes, in
+    /// its reader's voice and effect, and put it in the part's segment cache
+    /// in place of the audio made from its text, so the next render of the
+    /// part masters and mixes it with the rest. Returns the segment's path
+    /// and length in seconds.
+    pub async fn replace_sentence(
+        &self,
+        part_id: &str,
+        sentence: &PlannedSentence<'_>,
+        phonemes: &str,
+        speed: Option<f32>,
+    ) -> Result<(String, f64), String> {
+        let mut raw = self
+            .tts
+            .generate_raw_from_phonemes(phonemes, sentence.voice, speed)
+            .await?;
+        apply_effect_blocking(&mut raw, sentence.effect).await?;
+
+        tokio::fs::create_dir_all(self.segments_dir(part_id))
+            .await
+            .map_err(|e| format!("Failed to create audio dir: {}", e))?;
+        let key = segment_key(&sentence.text, sentence.voice, speed, sentence.effect);
+        let wav_path = self.segment_path(part_id, &key);
+        save_wav_blocking(&wav_path, raw.samples, raw.sample_rate).await?;
+        Ok((wav_path, raw.duration_secs))
+    }
+
+    /// Where the finished audio for a part is written.
+    pub fn final_path(&self, part_id: &str) -> PathBuf {
+        final_path(&self.audio_dir, part_id)
+    }
+
+    /// Remove the cached sentences of a part that no longer exists.
+    pub async fn discard_segments(&self, part_id: &str) {
+        let _ = tokio::fs::remove_dir_all(self.segments_dir(part_id)).await;
+    }
+
+    /// Look up a sound-effect cue and schedule it at the end of `narration`,
+    /// padding the narration with a short pause for it. Returns the pause in
+    /// seconds (zero for unknown cues).
+    fn place_sfx(
+        &self,
+        name: &str,
+        sample_rate: u32,
+        narration: &mut Vec<f32>,
+        placements: &mut Vec<(usize, Vec<f32>)>,
+    ) -> f64 {
+        let Some(samples) = self.sfx.load(name, sample_rate) else {
+            return 0.0;
+        };
+        let gap = samples
+            .len()
+            .min((SFX_GAP_SECS * sample_rate as f32) as usize);
+        placements.push((narration.len(), samples));
+        narration.resize(narration.len() + gap, 0.0);
+        gap as f64 / sample_rate as f64
+    }
+}
+
+/// Speaks a story while it is being written, sentence by sentence, to a
+/// player stream. Each sentence goes into the part's segment cache, so the
+/// job run on the finished text reuses it.
+pub struct LiveNarration<'a> {
+    pipeline: &'a AudioPipeline,
+    part_id: String,
+    voice: Option<String>,
+    effect: VoiceEffect,
+    speed: Option<f32>,
+    stream: PcmStream,
+    cancel: CancelToken,
+    /// Sentences sent to the stream so far
+    spoken: usize,
+    position_secs: f64,
+    sample_rate: u32,
+}
+
+impl AudioPipeline {
+    /// Start narrating the part `part_id` as its text arrives.
+    pub fn narrate_live(
+        &self,
+        part_id: &str,
+        voice_settings: Option<&VoiceSettings>,
+        stream: PcmStream,
+        cancel: CancelToken,
+    ) -> LiveNarration<'_> {
+        LiveNarration {
+            pipeline: self,
+            part_id: part_id.to_string(),
+            voice: voice_settings.map(|vs| vs.voice.clone()),
+            effect: voice_settings
+                .map(|vs| parse_effect(&vs.effect))
+                .unwrap_or(VoiceEffect::None),
+            speed: voice_settings.and_then(|vs| vs.speed),
+            stream,
+            cancel,
+            spoken: 0,
+            position_secs: 0.0,
+            sample_rate: 24000,
+        }
+    }
+}
+
+impl LiveNarration<'_> {
+    /// Speak the sentences of `text` (everything written so far) that have
+    /// not been spoken yet and can no longer change. With `finished`, the
+    /// rest of the text is spoken too.
+    pub async fn speak_ready(
+        &mut self,
+        text: &str,
+        characters: &[StoryCharacter],
+        finished: bool,
+    ) -> Result<(), String> {
+        let ready = if finished { text } else { &text[..settled_len(text)] };
+        let planned = plan_sentences(ready, characters, self.voice.as_deref(), self.effect);
+        if self.spoken >= planned.len() {
+            return Ok(());
+        }
+        tokio::fs::create_dir_all(self.pipeline.segments_dir(&self.part_id))
+            .await
+            .map_err(|e| format!("Failed to create audio dir: {}", e))?;
+
+        for (i, sentence) in planned.iter().enumerate().skip(self.spoken) {
+            check_cancelled(&self.cancel)?;
+            let mut samples = Vec::new();
+            let mut placements = Vec::new();
+            for cue in sentence.cues.iter().filter(|c| !c.after) {
+                self.pipeline
+                    .place_sfx(&cue.name, self.sample_rate, &mut samples, &mut placements);
+            }
+            let start = self.position_secs + samples.len() as f64 / self.sample_rate as f64;
+
+            let key = segment_key(&sentence.text, sentence.voice, self.speed, sentence.effect);
+            let wav_path = self.pipeline.segment_path(&self.part_id, &key);
+            let raw = self
+                .pipeline
+                .synthesize_sentence(sentence, &wav_path, self.speed, &self.cancel)
+                .await?;
+            self.sample_rate = raw.sample_rate;
+            samples.extend_from_slice(&raw.samples);
+            for cue in sentence.cues.iter().filter(|c| c.after) {
+                self.pipeline
+                    .place_sfx(&cue.name, self.sample_rate, &mut samples, &mut placements);
+            }
+            let samples = mix_sfx(samples, placements, self.sample_rate);
+
+            self.stream.sentence(i, planned.len(), start, &sentence.text);
+            self.stream.pcm(&samples, self.sample_rate);
+            self.position_secs += samples.len() as f64 / self.sample_rate as f64;
+            self.spoken = i + 1;
+        }
+        Ok(())
+    }
+
+    /// Tell the player the narration is over.
+    pub fn end(&self) {
+        self.stream.end();
+    }
+
+    /// Tell the player the narration stopped early because of `error`.
+    pub fn fail(&self, error: &str) {
+        self.stream.fail(error);
+    }
+}
+
+/// Lay sound effects over `narration` on a mixer timeline, each starting at
+/// its sample offset.
+fn mix_sfx(narration: Vec<f32>, placements: Vec<(usize, Vec<f32>)>, sample_rate: u32) -> Vec<f32> {
+    if placements.is_empty() {
+        return narration;
+    }
+    let mut timeline = Timeline::new(sample_rate, 1);
+    timeline
+        .add_track(1.0)
+        .add(Clip::new(AudioBuffer::mono(narration, sample_rate)));
+    let sfx = timeline.add_track(SFX_GAIN);
+    for (offset, samples) in placements {
+        let at = offset as f64 / sample_rate as f64;
+        sfx.add(Clip::new(AudioBuffer::mono(samples, sample_rate)).at(at));
+    }
+    timeline.render().samples
+}
+
+/// The audio streamed for one sentence: its narration with its sound effects
+/// mixed in, as [`LiveNarration`] streams it. Effects that run past the
+/// sentence are held in `carry` and mixed into the next chunk, so the stream
+/// keeps the timing of the saved file.
+fn stream_chunk(
+    narration: &[f32],
+    placements: Vec<(usize, Vec<f32>)>,
+    carry: &mut Vec<f32>,
+    sample_rate: u32,
+) -> Vec<f32> {
+    let mut chunk = mix_sfx(narration.to_vec(), placements, sample_rate);
+    let held = std::mem::take(carry);
+    if held.len() > chunk.len() {
+        chunk.resize(held.len(), 0.0);
+    }
+    for (sample, tail) in chunk.iter_mut().zip(&held) {
+        *sample += tail;
+    }
+    *carry = chunk.split_off(narration.len());
+    chunk
+}
+
+/// Where the pipeline writes a part's finished audio.
+pub fn final_path(audio_dir: &std::path::Path, part_id: &str) -> PathBuf {
+    audio_dir.join(format!("{}_final.wav", part_id))
+}
+
+/// Where the pipeline writes a part's sentence timing.
+pub fn timing_path(audio_dir: &std::path::Path, part_id: &str) -> PathBuf {
+    audio_dir.join(format!("{}_timing.json", part_id))
+}
+
+/// Load a sentence WAV saved by an earlier run, if it is still on disk.
+async fn load_sentence_wav(path: &str) -> Option<TtsRawResult> {
+    let path = PathBuf::from(path);
+    let loaded = tokio::task::spawn_blocking(move || kokoro_tts::audio::load_wav(&path))
+        .await
+        .ok()?;
+    let (samples, sample_rate) = loaded.ok()?;
+    Some(TtsRawResult {
+        duration_secs: samples.len() as f64 / sample_rate as f64,
+        samples,
+        sample_rate,
+    })
+}
+
+/// Apply a character's voice effect to synthesized audio.
+async fn apply_effect_blocking(raw: &mut TtsRawResult, effect: VoiceEffect) -> Result<(), String> {
+    if effect == VoiceEffect::None {
+        return Ok(());
+    }
+    let samples = std::mem::take(&mut raw.samples);
+    raw.samples = tokio::task::spawn_blocking(move || effects::apply_effect(&samples, effect))
+        .await
+        .map_err(|e| format!("Voice effect task panicked: {}", e))?;
+    Ok(())
+}
+
+async fn save_wav_blocking(path: &str, samples: Vec<f32>, sample_rate: u32) -> Result<(), String> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || {
+        kokoro_tts::audio::save_wav(std::path::Path::new(&path), &samples, sample_rate)
+            .map_err(|e| format!("Failed to save WAV {}: {}", path, e))
+    })
+    .await
+    .map_err(|e| format!("Save WAV task panicked: {}", e))?
+}
+
+fn check_cancelled(cancel: &CancelToken) -> Result<(), String> {
+    cancel.check().map_err(|e| e.to_string())
 }
 
 /// Build the audio output directory path given a part ID
@@ -268,9 +878,104 @@ mod tests {
         assert_eq!(sentences, vec!["One sentence."]);
     }
 
+    #[test]
+    fn settled_len_stops_at_the_last_finished_sentence() {
+        assert_eq!(settled_len("Once upon a time. There was a"), 18);
+        assert_eq!(settled_len("Once upon a time."), 0);
+        assert_eq!(settled_len("A. B! C? D"), 9);
+    }
+
+    #[test]
+    fn settled_len_waits_for_quotes_to_close() {
+        let open = "It was dark. \"Run! Now";
+        assert_eq!(settled_len(open), 13);
+        let closed = "It was dark. \"Run! Now!\" said Ravi. He ran";
+        assert_eq!(settled_len(closed), closed.len() - 6);
+        assert_eq!(settled_len("Dark. \u{201c}Go. Now.\u{201d} Asha said. Then"), 32);
+    }
+
+    #[test]
+    fn segment_key_changes_with_what_is_heard() {
+        let key = segment_key("Hello.", Some("af_nova"), Some(1.0), VoiceEffect::None);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, segment_key("Hello.", Some("af_nova"), Some(1.0), VoiceEffect::None));
+        assert_ne!(key, segment_key("Hello!", Some("af_nova"), Some(1.0), VoiceEffect::None));
+        assert_ne!(key, segment_key("Hello.", Some("am_adam"), Some(1.0), VoiceEffect::None));
+        assert_ne!(key, segment_key("Hello.", Some("af_nova"), Some(1.2), VoiceEffect::None));
+        assert_ne!(key, segment_key("Hello.", Some("af_nova"), Some(1.0), VoiceEffect::Giant));
+        // Unset settings are the defaults the engine reads with
+        assert_eq!(
+            segment_key("Hello.", None, None, VoiceEffect::None),
+            segment_key("Hello.", Some(DEFAULT_VOICE), Some(DEFAULT_SPEED), VoiceEffect::None)
+        );
+        assert_eq!(key, segment_key("Hello.", None, Some(1.0), VoiceEffect::None));
+    }
+
+    #[test]
+    fn stream_chunks_carry_sound_effects_over() {
+        let mut carry = Vec::new();
+        let first = stream_chunk(&[0.0; 4], vec![(2, vec![1.0; 4])], &mut carry, 10);
+        assert_eq!(first, vec![0.0, 0.0, SFX_GAIN, SFX_GAIN]);
+        assert_eq!(carry, vec![SFX_GAIN, SFX_GAIN]);
+
+        let second = stream_chunk(&[0.5; 3], Vec::new(), &mut carry, 10);
+        assert_eq!(second, vec![0.5 + SFX_GAIN, 0.5 + SFX_GAIN, 0.5]);
+        assert!(carry.is_empty());
+    }
+
     #[test]
     fn split_sentences_empty() {
         let sentences = split_sentences("");
         assert!(sentences.is_empty());
     }
+
+    #[test]
+    fn plan_sentences_uses_character_voices() {
+        let characters = vec![StoryCharacter {
+            story_id: "s1".to_string(),
+            name: "Wolf".to_string(),
+            voice: "bm_george".to_string(),
+            effect: "wolf".to_string(),
+        }];
+        let text = "The wolf knocked. \"Let me in. I'm hungry!\" growled the wolf. \"No!\" she said.";
+        let planned = plan_sentences(text, &characters, Some("af_nova"), VoiceEffect::None);
+
+        let readers: Vec<(&str, Option<&str>, VoiceEffect)> = planned
+            .iter()
+            .map(|p| (p.text.as_str(), p.voice, p.effect))
+            .collect();
+        assert_eq!(
+            readers,
+            vec![
+                ("The wolf knocked.", Some("af_nova"), VoiceEffect::None),
+                ("\"Let me in.", Some("bm_george"), VoiceEffect::Wolf),
+                ("I'm hungry!\"", Some("bm_george"), VoiceEffect::Wolf),
+                ("growled the wolf.", Some("af_nova"), VoiceEffect::None),
+                ("\"No!\"", Some("af_nova"), VoiceEffect::None),
+                ("she said.", Some("af_nova"), VoiceEffect::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_sentences_attaches_sfx_cues() {
+        let text = "[SFX: door creak] The door opened. [SFX: footsteps] Someone came in. It was dark. [SFX: thunder]";
+        let planned = plan_sentences(text, &[], None, VoiceEffect::None);
+
+        let cues: Vec<(&str, Vec<(&str, bool)>)> = planned
+            .iter()
+            .map(|p| {
+                let cues = p.cues.iter().map(|c| (c.name.as_str(), c.after)).collect();
+                (p.text.as_str(), cues)
+            })
+            .collect();
+        assert_eq!(
+            cues,
+            vec![
+                ("The door opened.", vec![("door creak", false)]),
+                ("Someone came in.", vec![("footsteps", false)]),
+                ("It was dark.", vec![("thunder", true)]),
+            ]
+        );
+    }
 }
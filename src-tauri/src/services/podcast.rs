@@ -0,0 +1,816 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use rusqlite::Connection;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+use crate::db::models::PodcastEpisode;
+use crate::db::queries;
+use crate::services::captions::{self, CaptionFormat};
+use crate::services::pipeline::{self, TimingSegment};
+use crate::services::sfx;
+
+/// Show artwork for podcast apps.
+const COVER_PNG: &[u8] = include_bytes!("../../icons/256x256.png");
+
+/// Longest request head read before giving up on a client.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// How long a client has to send its request head.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections served at once; further clients wait to be accepted.
+const MAX_CONNECTIONS: usize = 32;
+
+/// Where the feed reads the library from.
+#[derive(Debug, Clone)]
+pub struct FeedSource {
+    pub db_path: PathBuf,
+    pub audio_dir: PathBuf,
+}
+
+struct Running {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// A small HTTP server publishing the library as a podcast feed so podcast
+/// apps on the local network can subscribe. It answers `GET` and `HEAD` for
+/// the feed, each part's audio (with byte ranges for seeking), transcripts
+/// and the cover, and nothing else.
+#[derive(Default)]
+pub struct PodcastServer {
+    running: Mutex<Option<Running>>,
+}
+
+static SERVER: OnceLock<PodcastServer> = OnceLock::new();
+
+impl PodcastServer {
+    /// The app-wide feed server.
+    pub fn global() -> &'static PodcastServer {
+        SERVER.get_or_init(PodcastServer::default)
+    }
+
+    /// Start serving on `addr`, replacing any running server. Returns the
+    /// address actually bound, which differs from `addr` for port 0.
+    pub async fn start(&self, addr: SocketAddr, source: FeedSource) -> Result<SocketAddr, String> {
+        let mut running = self.running.lock().await;
+        if let Some(previous) = running.take() {
+            stop_running(previous).await;
+        }
+
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read listening address: {}", e))?;
+        // Feed URLs use the address the server is bound to, never the
+        // client's Host header
+        let base_url: Arc<str> = format!("http://{}", addr).into();
+        let limit = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        let (shutdown, mut stopped) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut stopped => break,
+                    (permit, accepted) = accept(&listener, &limit) => match accepted {
+                        Ok((stream, peer)) => {
+                            let source = source.clone();
+                            let base_url = base_url.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = handle_connection(stream, &source, &base_url).await {
+                                    log::debug!("Podcast feed request from {} failed: {}", peer, e);
+                                }
+                                drop(permit);
+                            });
+                        }
+                        Err(e) => log::warn!("Podcast feed failed to accept a connection: {}", e),
+                    },
+                }
+            }
+        });
+
+        log::info!("Podcast feed listening on {}", addr);
+        *running = Some(Running {
+            addr,
+            shutdown,
+            task,
+        });
+        Ok(addr)
+    }
+
+    /// Stop the server if it is running. The port is free once this returns;
+    /// downloads already underway are left to finish.
+    pub async fn stop(&self) {
+        if let Some(running) = self.running.lock().await.take() {
+            stop_running(running).await;
+            log::info!("Podcast feed stopped");
+        }
+    }
+
+    /// Where the server is listening, if it is running.
+    pub async fn address(&self) -> Option<SocketAddr> {
+        self.running.lock().await.as_ref().map(|r| r.addr)
+    }
+}
+
+async fn stop_running(running: Running) {
+    let _ = running.shutdown.send(());
+    let _ = running.task.await;
+}
+
+/// Wait for a free connection slot, then for a client.
+async fn accept(
+    listener: &TcpListener,
+    limit: &Arc<Semaphore>,
+) -> (Option<OwnedSemaphorePermit>, std::io::Result<(TcpStream, SocketAddr)>) {
+    // The semaphore is never closed, so this only fails if it were
+    let permit = limit.clone().acquire_owned().await.ok();
+    (permit, listener.accept().await)
+}
+
+/// The parts of a request the server looks at.
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    range: Option<String>,
+}
+
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let path = target.split(['?', '#']).next().unwrap_or("/").to_string();
+
+    let mut request = Request {
+        method,
+        path,
+        range: None,
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        if name.eq_ignore_ascii_case("range") {
+            request.range = Some(value);
+        }
+    }
+    Some(request)
+}
+
+/// Why a request head could not be read.
+enum HeadError {
+    Closed,
+    TooLarge,
+    Io(String),
+}
+
+async fn read_head(stream: &mut TcpStream) -> Result<Vec<u8>, HeadError> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_HEAD_BYTES {
+            return Err(HeadError::TooLarge);
+        }
+        let n = stream.read(&mut buf).await.map_err(|e| HeadError::Io(e.to_string()))?;
+        if n == 0 {
+            return Err(HeadError::Closed);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(head)
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    source: &FeedSource,
+    base_url: &str,
+) -> Result<(), String> {
+    let head = match tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream)).await {
+        Ok(Ok(head)) => head,
+        Ok(Err(HeadError::Closed)) => return Ok(()),
+        Ok(Err(HeadError::TooLarge)) => {
+            return respond(&mut stream, 431, "Request Header Fields Too Large", "text/plain", b"").await
+        }
+        Ok(Err(HeadError::Io(e))) => return Err(e),
+        Err(_) => return respond(&mut stream, 408, "Request Timeout", "text/plain", b"").await,
+    };
+    let Some(request) = parse_request(&String::from_utf8_lossy(&head)) else {
+        return respond(&mut stream, 400, "Bad Request", "text/plain", b"").await;
+    };
+    let head_only = match request.method.as_str() {
+        "GET" => false,
+        "HEAD" => true,
+        _ => return respond(&mut stream, 405, "Method Not Allowed", "text/plain", b"").await,
+    };
+
+    let path = request.path.as_str();
+
+    if path == "/" || path == "/feed.xml" {
+        let xml = build_feed(source, base_url).await?;
+        let body = if head_only { b"" as &[u8] } else { xml.as_bytes() };
+        return respond_sized(&mut stream, "application/rss+xml; charset=utf-8", xml.len(), body)
+            .await;
+    }
+    if path == "/cover.png" {
+        let body = if head_only { b"" as &[u8] } else { COVER_PNG };
+        return respond_sized(&mut stream, "image/png", COVER_PNG.len(), body).await;
+    }
+    if let Some(part_id) = path.strip_prefix("/audio/").and_then(|p| p.strip_suffix(".mp3")) {
+        let Some(episode) = find_episode(source, part_id).await? else {
+            return not_found(&mut stream).await;
+        };
+        let mp3 = episode_mp3_path(&source.audio_dir, part_id);
+        let encoded = {
+            let (wav, mp3) = (PathBuf::from(episode.audio_path), mp3.clone());
+            tokio::task::spawn_blocking(move || ensure_mp3(&wav, &mp3))
+                .await
+                .map_err(|e| format!("Feed task panicked: {}", e))?
+        };
+        if encoded.is_err() {
+            return not_found(&mut stream).await;
+        }
+        return serve_file(&mut stream, &mp3, request.range.as_deref(), head_only).await;
+    }
+    if let Some(part_id) = path.strip_prefix("/transcripts/").and_then(|p| p.strip_suffix(".vtt")) {
+        let Some(episode) = find_episode(source, part_id).await? else {
+            return not_found(&mut stream).await;
+        };
+        let segments = load_timing(&pipeline::timing_path(&source.audio_dir, part_id)).await;
+        if segments.is_empty() {
+            return not_found(&mut stream).await;
+        }
+        let vtt = captions::render(CaptionFormat::WebVtt, &segments, &episode_title(&episode));
+        let body = if head_only { b"" as &[u8] } else { vtt.as_bytes() };
+        return respond_sized(&mut stream, "text/vtt; charset=utf-8", vtt.len(), body).await;
+    }
+    not_found(&mut stream).await
+}
+
+/// Read the library and render the feed for clients reaching the server at
+/// `base_url`.
+async fn build_feed(source: &FeedSource, base_url: &str) -> Result<String, String> {
+    let source = source.clone();
+    let base_url = base_url.to_string();
+    tokio::task::spawn_blocking(move || {
+        let conn = open_db(&source.db_path)?;
+        let episodes = queries::get_podcast_episodes(&conn)
+            .map_err(|e| format!("Failed to read episodes: {}", e))?;
+        let items: Vec<FeedItem> = episodes
+            .into_iter()
+            .filter_map(|episode| {
+                // Audio deleted from disk is left out rather than linked
+                let mp3 = episode_mp3_path(&source.audio_dir, &episode.part_id);
+                let length = match ensure_mp3(Path::new(&episode.audio_path), &mp3) {
+                    Ok(length) => length,
+                    Err(e) => {
+                        log::debug!("Leaving part {} out of the feed: {}", episode.part_id, e);
+                        return None;
+                    }
+                };
+                let duration = read_wav_head(Path::new(&episode.audio_path))
+                    .and_then(|head| wav_duration(&head));
+                let has_transcript =
+                    pipeline::timing_path(&source.audio_dir, &episode.part_id).exists();
+                Some(FeedItem {
+                    episode,
+                    length,
+                    duration,
+                    has_transcript,
+                })
+            })
+            .collect();
+        Ok(feed_xml(&base_url, &items))
+    })
+    .await
+    .map_err(|e| format!("Feed task panicked: {}", e))?
+}
+
+async fn find_episode(source: &FeedSource, part_id: &str) -> Result<Option<PodcastEpisode>, String> {
+    let db_path = source.db_path.clone();
+    let part_id = part_id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let conn = open_db(&db_path)?;
+        queries::get_podcast_episode(&conn, &part_id)
+            .map_err(|e| format!("Failed to read episode {}: {}", part_id, e))
+    })
+    .await
+    .map_err(|e| format!("Feed task panicked: {}", e))?
+}
+
+fn open_db(db_path: &Path) -> Result<Connection, String> {
+    Connection::open(db_path).map_err(|e| format!("Failed to open DB: {}", e))
+}
+
+async fn load_timing(path: &Path) -> Vec<TimingSegment> {
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Where the feed keeps the MP3 it serves for a part.
+pub fn episode_mp3_path(audio_dir: &Path, part_id: &str) -> PathBuf {
+    audio_dir.join(format!("{}_podcast.mp3", part_id))
+}
+
+/// Make sure `mp3` holds `wav` encoded as MP3, encoding it again if the WAV
+/// is newer, and return its size in bytes.
+fn ensure_mp3(wav: &Path, mp3: &Path) -> Result<u64, String> {
+    let wav_modified = std::fs::metadata(wav)
+        .and_then(|meta| meta.modified())
+        .map_err(|e| format!("Failed to read {}: {}", wav.display(), e))?;
+    if let Ok(meta) = std::fs::metadata(mp3) {
+        if meta.modified().is_ok_and(|modified| modified >= wav_modified) {
+            return Ok(meta.len());
+        }
+    }
+
+    let (samples, sample_rate) = kokoro_tts::audio::load_wav(wav)
+        .map_err(|e| format!("Failed to read {}: {}", wav.display(), e))?;
+    let bytes = kokoro_tts::audio::encode_audio(
+        &samples,
+        sample_rate,
+        kokoro_tts::audio::AudioFormat::Mp3,
+    )
+    .map_err(|e| format!("Failed to encode {}: {}", wav.display(), e))?;
+    // Write aside and rename so a download never sees half a file
+    let partial = mp3.with_extension(format!("mp3.{}", uuid::Uuid::new_v4()));
+    std::fs::write(&partial, &bytes)
+        .and_then(|_| std::fs::rename(&partial, mp3))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("Failed to write {}: {}", mp3.display(), e)
+        })?;
+    Ok(bytes.len() as u64)
+}
+
+fn read_wav_head(path: &Path) -> Option<Vec<u8>> {
+    use std::io::Read;
+    let mut head = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(4096)
+        .read_to_end(&mut head)
+        .ok()?;
+    Some(head)
+}
+
+/// Send an MP3 file, or the part of it named by a `Range` header.
+async fn serve_file(
+    stream: &mut TcpStream,
+    path: &Path,
+    range: Option<&str>,
+    head_only: bool,
+) -> Result<(), String> {
+    let mut file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return not_found(stream).await,
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+    };
+    let len = file.metadata().await.map_err(|e| e.to_string())?.len();
+
+    let (status, reason, start, end) = match parse_range(range, len) {
+        ByteRange::Full => (200, "OK", 0, len),
+        ByteRange::Partial { start, end } => (206, "Partial Content", start, end + 1),
+        ByteRange::Unsatisfiable => {
+            let head = format!(
+                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n",
+                len
+            );
+            return stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string());
+        }
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: audio/mpeg\r\nContent-Length: {}\r\n\
+         Accept-Ranges: bytes\r\nConnection: close\r\n",
+        status,
+        reason,
+        end - start
+    );
+    if status == 206 {
+        head.push_str(&format!("Content-Range: bytes {}-{}/{}\r\n", start, end - 1, len));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    if head_only {
+        return Ok(());
+    }
+
+    file.seek(std::io::SeekFrom::Start(start))
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::io::copy(&mut file.take(end - start), stream)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn not_found(stream: &mut TcpStream) -> Result<(), String> {
+    respond(stream, 404, "Not Found", "text/plain", b"Not found").await
+}
+
+async fn respond_sized(
+    stream: &mut TcpStream,
+    content_type: &str,
+    len: usize,
+    body: &[u8],
+) -> Result<(), String> {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        content_type, len
+    );
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.write_all(body).await.map_err(|e| e.to_string())
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: u16,
+    reason: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.write_all(body).await.map_err(|e| e.to_string())
+}
+
+/// Which bytes of a file a request asked for.
+#[derive(Debug, PartialEq)]
+pub enum ByteRange {
+    /// No usable `Range` header: send the whole file
+    Full,
+    /// Inclusive byte offsets
+    Partial { start: u64, end: u64 },
+    /// The range starts past the end of the file
+    Unsatisfiable,
+}
+
+/// Read a `Range` header for a file of `len` bytes. Only the first range of
+/// a multi-range request is honoured, and a header that cannot be read is
+/// ignored as HTTP allows.
+pub fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let first = spec.split(',').next().unwrap_or("").trim();
+    let Some((start, end)) = first.split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // Suffix range: the last `end` bytes
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(n) => ByteRange::Partial {
+                start: len.saturating_sub(n),
+                end: len - 1,
+            },
+            Err(_) => ByteRange::Full,
+        };
+    }
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = if end.is_empty() {
+        len.saturating_sub(1)
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+            _ => return ByteRange::Full,
+        }
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial { start, end }
+}
+
+/// Length in seconds of the WAV file starting with `head`, from its `fmt `
+/// byte rate and `data` chunk size.
+pub fn wav_duration(head: &[u8]) -> Option<f64> {
+    if head.len() < 12 || &head[0..4] != b"RIFF" || &head[8..12] != b"WAVE" {
+        return None;
+    }
+    let mut byte_rate = None;
+    let mut pos = 12;
+    while pos + 8 <= head.len() {
+        let id = &head[pos..pos + 4];
+        let size = u32::from_le_bytes(head[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        if id == b"fmt " && body + 12 <= head.len() {
+            byte_rate = Some(u32::from_le_bytes(head[body + 8..body + 12].try_into().ok()?));
+        } else if id == b"data" {
+            let rate = byte_rate.filter(|&r| r > 0)?;
+            return Some(size as f64 / rate as f64);
+        }
+        // Chunks are padded to an even length
+        pos = body + size + (size & 1);
+    }
+    None
+}
+
+/// A published part with what the feed says about its audio file.
+pub struct FeedItem {
+    pub episode: PodcastEpisode,
+    /// MP3 file size in bytes
+    pub length: u64,
+    /// Audio length in seconds, if the header could be read
+    pub duration: Option<f64>,
+    pub has_transcript: bool,
+}
+
+fn episode_title(episode: &PodcastEpisode) -> String {
+    format!("{}: Part {}", episode.story_title, episode.part_number)
+}
+
+/// Render the RSS feed with iTunes and Podcasting 2.0 tags; every URL
+/// starts with `base_url`.
+pub fn feed_xml(base_url: &str, items: &[FeedItem]) -> String {
+    let base = escape_xml(base_url.trim_end_matches('/'));
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<rss version=\"2.0\" xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\" \
+         xmlns:podcast=\"https://podcastindex.org/namespace/1.0\" \
+         xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n",
+    );
+    xml.push_str("  <channel>\n");
+    xml.push_str("    <title>Pattikadhai Stories</title>\n");
+    xml.push_str(&format!("    <link>{}/</link>\n", base));
+    xml.push_str("    <description>Bedtime stories from the Pattikadhai library.</description>\n");
+    xml.push_str("    <language>en</language>\n");
+    xml.push_str("    <itunes:author>Pattikadhai</itunes:author>\n");
+    xml.push_str(&format!("    <itunes:image href=\"{}/cover.png\"/>\n", base));
+    xml.push_str("    <itunes:category text=\"Kids &amp; Family\"/>\n");
+    xml.push_str("    <itunes:explicit>false</itunes:explicit>\n");
+    xml.push_str("    <podcast:locked>yes</podcast:locked>\n");
+
+    for item in items {
+        let episode = &item.episode;
+        let notes = sfx::strip_cues(&episode.content);
+        let html: String = notes
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("<p>{}</p>", escape_xml(p)))
+            .collect();
+
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!("      <title>{}</title>\n", escape_xml(&episode_title(episode))));
+        xml.push_str(&format!(
+            "      <guid isPermaLink=\"false\">{}</guid>\n",
+            escape_xml(&episode.part_id)
+        ));
+        if let Some(date) = rfc2822(&episode.updated_at) {
+            xml.push_str(&format!("      <pubDate>{}</pubDate>\n", date));
+        }
+        xml.push_str(&format!("      <description>{}</description>\n", escape_xml(notes.trim())));
+        xml.push_str(&format!("      <content:encoded>{}</content:encoded>\n", escape_xml(&html)));
+        xml.push_str(&format!(
+            "      <enclosure url=\"{}/audio/{}.mp3\" length=\"{}\" type=\"audio/mpeg\"/>\n",
+            base,
+            escape_xml(&episode.part_id),
+            item.length
+        ));
+        if let Some(duration) = item.duration {
+            xml.push_str(&format!(
+                "      <itunes:duration>{}</itunes:duration>\n",
+                duration.round() as u64
+            ));
+        }
+        xml.push_str(&format!("      <itunes:episode>{}</itunes:episode>\n", episode.part_number));
+        xml.push_str(&format!(
+            "      <itunes:subtitle>{}</itunes:subtitle>\n",
+            escape_xml(&episode.genre_name)
+        ));
+        if item.has_transcript {
+            xml.push_str(&format!(
+                "      <podcast:transcript url=\"{}/transcripts/{}.vtt\" type=\"text/vtt\"/>\n",
+                base,
+                escape_xml(&episode.part_id)
+            ));
+        }
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+/// A SQLite `datetime('now')` timestamp (UTC) as an RFC 2822 date.
+fn rfc2822(timestamp: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|t| t.and_utc().to_rfc2822())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(part_id: &str, part_number: i32, content: &str) -> PodcastEpisode {
+        PodcastEpisode {
+            part_id: part_id.to_string(),
+            story_id: "s1".to_string(),
+            story_title: "Fox & Crow".to_string(),
+            genre_name: "Pets".to_string(),
+            part_number,
+            content: content.to_string(),
+            audio_path: format!("/audio/{}_final.wav", part_id),
+            updated_at: "2026-03-01 20:15:00".to_string(),
+        }
+    }
+
+    fn wav(sample_rate: u32, samples: u32) -> Vec<u8> {
+        let data_len = samples * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-5"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=10-19"), 100), ByteRange::Partial { start: 10, end: 19 });
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial { start: 90, end: 99 });
+        assert_eq!(parse_range(Some("bytes=-10"), 100), ByteRange::Partial { start: 90, end: 99 });
+        assert_eq!(parse_range(Some("bytes=-500"), 100), ByteRange::Partial { start: 0, end: 99 });
+        assert_eq!(parse_range(Some("bytes=50-500"), 100), ByteRange::Partial { start: 50, end: 99 });
+        assert_eq!(parse_range(Some("bytes=0-0, 5-9"), 100), ByteRange::Partial { start: 0, end: 0 });
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=9-3"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=abc-"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_wav_duration() {
+        assert_eq!(wav_duration(&wav(24000, 48000)), Some(2.0));
+        assert_eq!(wav_duration(&wav(24000, 12000)[..44]), Some(0.5));
+        assert_eq!(wav_duration(b"not a wav file"), None);
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse_request(
+            "GET /audio/p1.mp3?t=1 HTTP/1.1\r\nHost: 192.168.1.5:8765\r\nrange: bytes=0-99\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            Request {
+                method: "GET".to_string(),
+                path: "/audio/p1.mp3".to_string(),
+                range: Some("bytes=0-99".to_string()),
+            }
+        );
+        assert_eq!(parse_request(""), None);
+    }
+
+    #[test]
+    fn test_feed_xml() {
+        let items = vec![
+            FeedItem {
+                episode: episode("p2", 2, "The crow <sang>.\n\nThe end."),
+                length: 4800,
+                duration: Some(61.6),
+                has_transcript: true,
+            },
+            FeedItem {
+                episode: episode("p1", 1, "Once upon a time."),
+                length: 1200,
+                duration: None,
+                has_transcript: false,
+            },
+        ];
+        let xml = feed_xml("http://192.168.1.5:8765/", &items);
+
+        assert!(xml.contains("<itunes:image href=\"http://192.168.1.5:8765/cover.png\"/>"));
+        assert!(xml.contains("<title>Fox &amp; Crow: Part 2</title>"));
+        assert!(xml.contains(
+            "<enclosure url=\"http://192.168.1.5:8765/audio/p2.mp3\" length=\"4800\" type=\"audio/mpeg\"/>"
+        ));
+        assert!(xml.contains("<itunes:duration>62</itunes:duration>"));
+        assert!(xml.contains("<pubDate>Sun, 1 Mar 2026 20:15:00 +0000</pubDate>"));
+        assert!(xml.contains("<description>The crow &lt;sang&gt;.\n\nThe end.</description>"));
+        assert!(xml.contains(
+            "<content:encoded>&lt;p&gt;The crow &amp;lt;sang&amp;gt;.&lt;/p&gt;&lt;p&gt;The end.&lt;/p&gt;</content:encoded>"
+        ));
+        assert!(xml.contains(
+            "<podcast:transcript url=\"http://192.168.1.5:8765/transcripts/p2.vtt\" type=\"text/vtt\"/>"
+        ));
+        assert_eq!(xml.matches("<item>").count(), 2);
+        assert_eq!(xml.matches("<podcast:transcript").count(), 1);
+        assert_eq!(xml.matches("<itunes:duration>").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_serves_feed_and_ranges() {
+        let dir = std::env::temp_dir().join(format!("pattikadhai_podcast_{}", std::process::id()));
+        let db_path = dir.join("test.db");
+        let audio_dir = dir.join("audio");
+        std::fs::create_dir_all(&audio_dir).unwrap();
+        let audio_path = pipeline::final_path(&audio_dir, "p1");
+        std::fs::write(&audio_path, wav(24000, 24000)).unwrap();
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+            conn.execute_batch(
+                "INSERT INTO genres (id, name, description) VALUES ('pets', 'Pets', 'd');
+                 INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Fox', 'pets');",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO story_parts (id, story_id, part_number, content, audio_path, status)
+                 VALUES ('p1', 's1', 1, 'Once.', ?1, 'audio_ready')",
+                [audio_path.to_string_lossy().to_string()],
+            )
+            .unwrap();
+        }
+
+        let server = PodcastServer::default();
+        let source = FeedSource { db_path, audio_dir };
+        let addr = server.start("127.0.0.1:0".parse().unwrap(), source).await.unwrap();
+        assert_eq!(server.address().await, Some(addr));
+
+        let fetch = |request: String| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).await.unwrap();
+            response
+        };
+
+        let feed = fetch("GET /feed.xml HTTP/1.1\r\nHost: stories.local:1234\r\n\r\n".to_string()).await;
+        let feed = String::from_utf8(feed).unwrap();
+        assert!(feed.starts_with("HTTP/1.1 200 OK\r\n"));
+        // The Host header is not trusted for links
+        assert!(feed.contains(&format!("url=\"http://{}/audio/p1.mp3\"", addr)));
+        assert!(feed.contains("<itunes:duration>1</itunes:duration>"));
+        let audio = std::fs::read(episode_mp3_path(&dir.join("audio"), "p1")).unwrap();
+        assert!(feed.contains(&format!("length=\"{}\" type=\"audio/mpeg\"", audio.len())));
+
+        let partial = fetch("GET /audio/p1.mp3 HTTP/1.1\r\nRange: bytes=4-11\r\n\r\n".to_string()).await;
+        let split = partial.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&partial[..split]).to_string();
+        assert!(head.starts_with("HTTP/1.1 206 Partial Content"));
+        assert!(head.contains("Content-Type: audio/mpeg"));
+        assert!(head.contains(&format!("Content-Range: bytes 4-11/{}", audio.len())));
+        assert_eq!(&partial[split + 4..], &audio[4..12]);
+
+        let missing = fetch("GET /audio/nope.mp3 HTTP/1.1\r\n\r\n".to_string()).await;
+        assert!(missing.starts_with(b"HTTP/1.1 404"));
+        let post = fetch("POST /feed.xml HTTP/1.1\r\n\r\n".to_string()).await;
+        assert!(post.starts_with(b"HTTP/1.1 405"));
+
+        server.stop().await;
+        assert_eq!(server.address().await, None);
+        assert!(TcpStream::connect(addr).await.is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::services::sfx::SFX_CUES;
+
 /// Build a system prompt for story generation
 pub fn build_system_prompt(genre_name: &str, genre_description: &str) -> String {
     format!(
@@ -11,10 +13,13 @@ Guidelines:
 - Include descriptive scenes that spark imagination
 - Keep stories positive and uplifting
 - Stories should be 300-500 words
-- Include dialogue between characters
+- Include dialogue between characters, in double quotes, and name the speaker (e.g. "said the fox")
 - End with a satisfying conclusion or gentle lesson
+- You may add a few sound effects (at most one every few paragraphs) by writing a cue like [SFX: door creak] right where the sound happens. Only use these cues: {}
 - Do NOT include any meta-commentary or instructions - just tell the story"#,
-        genre_name, genre_description
+        genre_name,
+        genre_description,
+        SFX_CUES.join(", ")
     )
 }
 
@@ -50,6 +55,34 @@ pub fn build_summary_prompt(story_text: &str) -> String {
     )
 }
 
+/// Build a prompt asking for questions to talk about after reading a part
+pub fn build_discussion_questions_prompt(part_text: &str) -> String {
+    format!(
+        r#"Write 3 short questions a grown-up could ask a child (ages 4-10) after reading this part of a story together. Mix a question about what happened, one about how a character felt, and one that connects the story to the child's own life. Write one question per line, with no numbering or other text.
+
+{}"#,
+        part_text
+    )
+}
+
+/// Pull the questions out of a model reply, dropping numbering, bullets and
+/// any line that is not a question. At most five are kept.
+pub fn parse_questions(reply: &str) -> Vec<String> {
+    reply
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ')' | '-' | '*' | '•'))
+                .trim()
+                .trim_matches('*')
+                .trim()
+        })
+        .filter(|line| line.ends_with('?'))
+        .take(5)
+        .map(String::from)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,6 +93,8 @@ mod tests {
         assert!(prompt.contains("children"));
         assert!(prompt.contains("Adventure"));
         assert!(prompt.contains("Exciting journeys"));
+        assert!(prompt.contains("[SFX: door creak]"));
+        assert!(prompt.contains("magic chime"));
     }
 
     #[test]
@@ -87,6 +122,26 @@ mod tests {
         assert!(prompt.contains("Once upon a time..."));
     }
 
+    #[test]
+    fn questions_prompt_includes_text() {
+        let prompt = build_discussion_questions_prompt("The fox shared his berries.");
+        assert!(prompt.contains("The fox shared his berries."));
+        assert!(prompt.contains("one question per line"));
+    }
+
+    #[test]
+    fn parses_questions_from_reply() {
+        let reply = "Here are some questions:\n1. Why did the fox share?\n- How did the owl feel?\n**Have you ever shared a snack?**\n\n";
+        assert_eq!(
+            parse_questions(reply),
+            vec![
+                "Why did the fox share?",
+                "How did the owl feel?",
+                "Have you ever shared a snack?",
+            ]
+        );
+    }
+
     use proptest::prelude::*;
 
     proptest! {
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use kokoro_tts::CancelToken;
+use rusqlite::Connection;
+
+use crate::db::queries;
+use crate::services::stream::PcmStream;
+
+/// How urgently a queued audio job is wanted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobPriority {
+    /// Someone is waiting to listen; runs before background work
+    PlayNow,
+    /// Pre-rendering ahead of time
+    Background,
+}
+
+impl JobPriority {
+    /// Parse `play_now` or `background`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "play_now" => Some(Self::PlayNow),
+            "background" => Some(Self::Background),
+            _ => None,
+        }
+    }
+
+    /// Value stored in `audio_job_queue.priority`; higher runs first.
+    pub fn value(&self) -> i64 {
+        match self {
+            Self::PlayNow => 1,
+            Self::Background => 0,
+        }
+    }
+}
+
+/// A job the scheduler has taken off the queue and marked as running.
+pub struct ClaimedJob {
+    pub job_id: String,
+    pub part_id: String,
+    pub cancel: CancelToken,
+    /// Where to send audio frames, if a player is listening
+    pub stream: Option<PcmStream>,
+}
+
+/// Starts queued audio jobs without exceeding the concurrency limit and
+/// keeps the cancellation token of each running job.
+#[derive(Default)]
+pub struct AudioScheduler {
+    running: Mutex<HashMap<String, CancelToken>>,
+    /// Players waiting for jobs that have not started yet
+    streams: Mutex<HashMap<String, PcmStream>>,
+}
+
+static SCHEDULER: OnceLock<AudioScheduler> = OnceLock::new();
+
+impl AudioScheduler {
+    /// The app-wide scheduler.
+    pub fn global() -> &'static AudioScheduler {
+        SCHEDULER.get_or_init(AudioScheduler::default)
+    }
+
+    /// Take pending jobs off the queue until `limit` jobs are running. Each
+    /// claimed job is marked `voice_generating` so it is not picked twice.
+    pub fn claim_next(&self, conn: &Connection, limit: usize) -> Result<Vec<ClaimedJob>, String> {
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|e| format!("Failed to lock running jobs: {}", e))?;
+        let mut claimed = Vec::new();
+
+        while running.len() < limit {
+            let Some((job_id, part_id)) = queries::next_queued_audio_job(conn)
+                .map_err(|e| format!("Failed to read audio queue: {}", e))?
+            else {
+                break;
+            };
+            queries::update_audio_job_status(conn, &job_id, "voice_generating", None)
+                .and_then(|_| queries::record_audio_job_attempt(conn, &job_id))
+                .map_err(|e| format!("Failed to start job {}: {}", job_id, e))?;
+
+            let cancel = CancelToken::new();
+            running.insert(job_id.clone(), cancel.clone());
+            let stream = self.streams.lock().ok().and_then(|mut s| s.remove(&job_id));
+            claimed.push(ClaimedJob {
+                job_id,
+                part_id,
+                cancel,
+                stream,
+            });
+        }
+        Ok(claimed)
+    }
+
+    /// Track a job started outside the queue, such as narration of a story
+    /// that is still being written, so it can be cancelled. It starts at once,
+    /// even when the queue is at its limit, and counts as running, so queued
+    /// jobs wait for a slot until it finishes.
+    pub fn register(&self, job_id: &str) -> CancelToken {
+        let cancel = CancelToken::new();
+        if let Ok(mut running) = self.running.lock() {
+            running.insert(job_id.to_string(), cancel.clone());
+        }
+        cancel
+    }
+
+    /// Send a queued job's audio to `stream` once it starts.
+    pub fn attach_stream(&self, job_id: &str, stream: PcmStream) {
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.insert(job_id.to_string(), stream);
+        }
+    }
+
+    /// Take back the stream attached to a job that has not started.
+    pub fn detach_stream(&self, job_id: &str) -> Option<PcmStream> {
+        self.streams.lock().ok().and_then(|mut s| s.remove(job_id))
+    }
+
+    /// Forget a job that has stopped, freeing its slot.
+    pub fn finish(&self, job_id: &str) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(job_id);
+        }
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.remove(job_id);
+        }
+    }
+
+    /// Signal a running job to stop. Returns `false` if it is not running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        let Ok(running) = self.running.lock() else {
+            return false;
+        };
+        match running.get(job_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pending jobs j1..jN for parts p1..pN in the app's real schema
+    fn setup(jobs: usize) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/001_initial.sql")).unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_app_settings.sql")).unwrap();
+        queries::apply_rusqlite_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO genres (id, name, description) VALUES ('g', 'G', 'd');
+             INSERT INTO stories (id, title, genre_id) VALUES ('s1', 'Story', 'g');",
+        )
+        .unwrap();
+        for i in 1..=jobs {
+            queries::insert_story_part(&conn, &format!("p{}", i), "s1", i as i32, "Text.", "text_ready")
+                .unwrap();
+            queries::insert_audio_job(&conn, &format!("j{}", i), &format!("p{}", i)).unwrap();
+        }
+        conn
+    }
+
+    fn ids(claimed: &[ClaimedJob]) -> Vec<&str> {
+        claimed.iter().map(|job| job.job_id.as_str()).collect()
+    }
+
+    #[test]
+    fn priorities_parse() {
+        assert_eq!(JobPriority::from_name("play_now"), Some(JobPriority::PlayNow));
+        assert_eq!(JobPriority::from_name("background"), Some(JobPriority::Background));
+        assert_eq!(JobPriority::from_name("soon"), None);
+        assert!(JobPriority::PlayNow.value() > JobPriority::Background.value());
+    }
+
+    #[test]
+    fn claims_up_to_the_limit() {
+        let conn = setup(3);
+        let scheduler = AudioScheduler::default();
+
+        assert_eq!(ids(&scheduler.claim_next(&conn, 2).unwrap()), vec!["j1", "j2"]);
+        // Both slots are busy
+        assert!(scheduler.claim_next(&conn, 2).unwrap().is_empty());
+
+        scheduler.finish("j1");
+        assert_eq!(ids(&scheduler.claim_next(&conn, 2).unwrap()), vec!["j3"]);
+        scheduler.finish("j2");
+        assert!(scheduler.claim_next(&conn, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cancels_only_running_jobs() {
+        let conn = setup(2);
+        let scheduler = AudioScheduler::default();
+        let claimed = scheduler.claim_next(&conn, 1).unwrap();
+
+        assert!(!scheduler.cancel("j2"));
+        assert!(scheduler.cancel("j1"));
+        assert!(claimed[0].cancel.is_cancelled());
+    }
+
+    #[test]
+    fn registered_jobs_hold_a_slot() {
+        let conn = setup(1);
+        let scheduler = AudioScheduler::default();
+        let cancel = scheduler.register("live");
+
+        assert!(scheduler.claim_next(&conn, 1).unwrap().is_empty());
+        assert!(scheduler.cancel("live"));
+        assert!(cancel.is_cancelled());
+        scheduler.finish("live");
+        assert_eq!(ids(&scheduler.claim_next(&conn, 1).unwrap()), vec!["j1"]);
+    }
+}
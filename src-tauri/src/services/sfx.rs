@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use kokoro_tts::audio;
+
+/// Cues the story prompt offers the LLM. Each maps to `<sfx_dir>/<name>.wav`
+/// with spaces replaced by underscores (`door creak` → `door_creak.wav`).
+pub const SFX_CUES: &[&str] = &[
+    "door creak",
+    "door knock",
+    "footsteps",
+    "thunder",
+    "splash",
+    "bird song",
+    "owl hoot",
+    "dog bark",
+    "cat meow",
+    "rooster",
+    "horse gallop",
+    "bell",
+    "clock tick",
+    "magic chime",
+    "laughter",
+    "yawn",
+];
+
+const CUE_PREFIX: &str = "[sfx:";
+
+/// A sound-effect cue pulled out of a sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfxCue {
+    /// Normalised cue name, e.g. `door creak`
+    pub name: String,
+    /// The cue followed the sentence's words rather than preceding them
+    pub after: bool,
+}
+
+/// Remove `[SFX: name]` cues from `text`, returning the text to read aloud
+/// and the cues in order.
+pub fn extract_cues(text: &str) -> (String, Vec<SfxCue>) {
+    let lower = text.to_ascii_lowercase();
+    let mut clean = String::with_capacity(text.len());
+    let mut pending = Vec::new();
+    let mut cues = Vec::new();
+    let mut rest = 0;
+
+    while let Some(found) = lower[rest..].find(CUE_PREFIX) {
+        let start = rest + found;
+        let Some(len) = text[start..].find(']') else {
+            break;
+        };
+        clean.push_str(&text[rest..start]);
+        let name = normalise(&text[start + CUE_PREFIX.len()..start + len]);
+        if !name.is_empty() {
+            pending.push((name, has_words(&clean)));
+        }
+        rest = start + len + 1;
+    }
+    clean.push_str(&text[rest..]);
+
+    // Only a cue with nothing spoken after it belongs at the sentence's end
+    let tail_has_words = has_words(&text[rest..]);
+    for (name, words_before) in pending {
+        cues.push(SfxCue {
+            name,
+            after: words_before && !tail_has_words,
+        });
+    }
+
+    if cues.is_empty() {
+        return (text.to_string(), cues);
+    }
+    (clean.split_whitespace().collect::<Vec<_>>().join(" "), cues)
+}
+
+/// Text with every cue removed, for display and phonemizing.
+pub fn strip_cues(text: &str) -> String {
+    extract_cues(text).0
+}
+
+fn normalise(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn has_words(text: &str) -> bool {
+    text.chars().any(|c| c.is_alphanumeric())
+}
+
+/// Sound effects stored as `<dir>/<cue_name>.wav`.
+pub struct SfxLibrary {
+    dir: PathBuf,
+}
+
+impl SfxLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// File for a cue name, if the library has one.
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        let file = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let path = self.dir.join(format!("{}.wav", file));
+        path.is_file().then_some(path)
+    }
+
+    /// Load a cue at `sample_rate`. Unknown or unreadable cues are logged
+    /// and skipped.
+    pub fn load(&self, name: &str, sample_rate: u32) -> Option<Vec<f32>> {
+        let Some(path) = self.find(name) else {
+            log::warn!("No sound effect for cue '{}' in {}, skipping", name, self.dir.display());
+            return None;
+        };
+        match load_resampled(&path, sample_rate) {
+            Ok(samples) => Some(samples),
+            Err(e) => {
+                log::warn!("Failed to load sound effect {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+fn load_resampled(path: &Path, sample_rate: u32) -> Result<Vec<f32>, String> {
+    let (samples, rate) = audio::load_wav(path).map_err(|e| e.to_string())?;
+    Ok(audio::resample_linear(&samples, rate, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(name: &str, after: bool) -> SfxCue {
+        SfxCue {
+            name: name.to_string(),
+            after,
+        }
+    }
+
+    #[test]
+    fn extracts_leading_and_trailing_cues() {
+        let (text, cues) = extract_cues("[SFX: Door  Creak] The door swung open.");
+        assert_eq!(text, "The door swung open.");
+        assert_eq!(cues, vec![cue("door creak", false)]);
+
+        let (text, cues) = extract_cues("Thunder rumbled. [sfx: thunder]");
+        assert_eq!(text, "Thunder rumbled.");
+        assert_eq!(cues, vec![cue("thunder", true)]);
+    }
+
+    #[test]
+    fn mid_sentence_cue_plays_before() {
+        let (text, cues) = extract_cues("Knock knock [SFX: door knock] went the door.");
+        assert_eq!(text, "Knock knock went the door.");
+        assert_eq!(cues, vec![cue("door knock", false)]);
+    }
+
+    #[test]
+    fn text_without_cues_is_unchanged() {
+        let text = "No  cues [here] at all.";
+        assert_eq!(extract_cues(text), (text.to_string(), vec![]));
+        assert_eq!(strip_cues("[SFX: bell]"), "");
+        // An unterminated cue is left alone
+        assert_eq!(strip_cues("Oops [SFX: bell"), "Oops [SFX: bell");
+    }
+
+    #[test]
+    fn library_maps_names_to_files() {
+        let dir = std::env::temp_dir().join(format!("pattikadhai_sfx_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        audio::save_wav(&dir.join("door_creak.wav"), &[0.5; 100], 12000).unwrap();
+
+        let library = SfxLibrary::new(dir.clone());
+        assert_eq!(library.find("door creak"), Some(dir.join("door_creak.wav")));
+        assert_eq!(library.load("door creak", 24000).map(|s| s.len()), Some(200));
+        assert!(library.load("dragon roar", 24000).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
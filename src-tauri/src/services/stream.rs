@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use tauri::ipc::{Channel, InvokeResponseBody};
+
+/// Samples per PCM frame (about 170 ms at 24 kHz).
+pub const FRAME_SAMPLES: usize = 4096;
+
+/// Frame kinds, the first byte of every message.
+const KIND_SENTENCE: u8 = 0;
+const KIND_PCM: u8 = 1;
+const KIND_END: u8 = 2;
+
+/// Binary audio frames sent to the player while a job runs.
+///
+/// Every message starts with a kind byte and a little-endian `u32` sequence
+/// number so the player can spot gaps:
+///
+/// - sentence marker (`0`): `u32` index, `u32` total, `f64` start seconds,
+///   then the sentence text as UTF-8
+/// - PCM (`1`): `u32` sample rate, then mono 16-bit little-endian samples
+/// - end (`2`): empty when the narration finished, otherwise the reason it
+///   stopped as UTF-8; nothing follows
+///
+/// The end frame is sent once. A stream dropped without one (the job hit an
+/// error or panicked) sends it on the way out, so the player never waits on
+/// audio that is not coming.
+pub struct PcmStream {
+    channel: Channel<InvokeResponseBody>,
+    seq: AtomicU32,
+    ended: AtomicBool,
+}
+
+impl PcmStream {
+    pub fn new(channel: Channel<InvokeResponseBody>) -> Self {
+        Self {
+            channel,
+            seq: AtomicU32::new(0),
+            ended: AtomicBool::new(false),
+        }
+    }
+
+    /// Announce the sentence whose samples follow.
+    pub fn sentence(&self, index: usize, total: usize, start_secs: f64, text: &str) {
+        let seq = self.next_seq();
+        self.send(encode_sentence(seq, index, total, start_secs, text));
+    }
+
+    /// Send `samples` as one or more PCM frames.
+    pub fn pcm(&self, samples: &[f32], sample_rate: u32) {
+        for chunk in samples.chunks(FRAME_SAMPLES) {
+            let seq = self.next_seq();
+            self.send(encode_pcm(seq, chunk, sample_rate));
+        }
+    }
+
+    /// Tell the player no more audio is coming.
+    pub fn end(&self) {
+        self.finish("");
+    }
+
+    /// Tell the player the narration stopped early because of `error`.
+    pub fn fail(&self, error: &str) {
+        self.finish(error);
+    }
+
+    fn finish(&self, error: &str) {
+        if self.ended.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let seq = self.next_seq();
+        self.send(encode_end(seq, error));
+    }
+
+    fn next_seq(&self) -> u32 {
+        self.seq.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn send(&self, frame: Vec<u8>) {
+        if let Err(e) = self.channel.send(InvokeResponseBody::Raw(frame)) {
+            log::warn!("Failed to send audio frame: {}", e);
+        }
+    }
+}
+
+impl Drop for PcmStream {
+    fn drop(&mut self) {
+        self.fail("Narration stopped unexpectedly");
+    }
+}
+
+fn header(kind: u8, seq: u32) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(5);
+    frame.push(kind);
+    frame.extend_from_slice(&seq.to_le_bytes());
+    frame
+}
+
+fn encode_sentence(seq: u32, index: usize, total: usize, start_secs: f64, text: &str) -> Vec<u8> {
+    let mut frame = header(KIND_SENTENCE, seq);
+    frame.extend_from_slice(&(index as u32).to_le_bytes());
+    frame.extend_from_slice(&(total as u32).to_le_bytes());
+    frame.extend_from_slice(&start_secs.to_le_bytes());
+    frame.extend_from_slice(text.as_bytes());
+    frame
+}
+
+fn encode_pcm(seq: u32, samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let mut frame = header(KIND_PCM, seq);
+    frame.extend_from_slice(&sample_rate.to_le_bytes());
+    frame.extend(kokoro_tts::audio::encode_pcm16(samples));
+    frame
+}
+
+fn encode_end(seq: u32, error: &str) -> Vec<u8> {
+    let mut frame = header(KIND_END, seq);
+    frame.extend_from_slice(error.as_bytes());
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(frame: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(frame[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn sentence_marker_layout() {
+        let frame = encode_sentence(7, 2, 10, 1.5, "Hello.");
+        assert_eq!(frame[0], KIND_SENTENCE);
+        assert_eq!(u32_at(&frame, 1), 7);
+        assert_eq!(u32_at(&frame, 5), 2);
+        assert_eq!(u32_at(&frame, 9), 10);
+        assert_eq!(f64::from_le_bytes(frame[13..21].try_into().unwrap()), 1.5);
+        assert_eq!(&frame[21..], b"Hello.");
+    }
+
+    #[test]
+    fn pcm_layout_clamps_samples() {
+        let frame = encode_pcm(3, &[0.0, 1.0, -2.0], 24000);
+        assert_eq!(frame[0], KIND_PCM);
+        assert_eq!(u32_at(&frame, 1), 3);
+        assert_eq!(u32_at(&frame, 5), 24000);
+        let samples: Vec<i16> = frame[9..]
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(samples, vec![0, i16::MAX, -i16::MAX]);
+    }
+
+    #[test]
+    fn end_frame_is_header_only() {
+        assert_eq!(encode_end(1, ""), vec![KIND_END, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn end_frame_carries_the_error() {
+        let frame = encode_end(4, "TTS failed");
+        assert_eq!(frame[0], KIND_END);
+        assert_eq!(u32_at(&frame, 1), 4);
+        assert_eq!(&frame[5..], b"TTS failed");
+    }
+}
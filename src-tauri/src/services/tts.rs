@@ -1,12 +1,18 @@
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 
-use kokoro_tts::Kokoro;
+use kokoro_tts::voices::Voice;
+use kokoro_tts::{AudioData, CancelToken, Kokoro, KokoroError};
 
 /// Thread-safe singleton for the Kokoro TTS engine.
 /// Wraps in Result so initialization failures can be retried.
 static KOKORO: OnceLock<Result<Mutex<Kokoro>, String>> = OnceLock::new();
 
+/// Voice used when none is given.
+pub const DEFAULT_VOICE: &str = "af_nova";
+/// Speed used when none is given.
+pub const DEFAULT_SPEED: f32 = 0.5;
+
 pub struct TtsRawResult {
     pub samples: Vec<f32>,
     pub sample_rate: u32,
@@ -54,6 +60,20 @@ impl TtsService {
         result.as_ref().map_err(|e| e.clone())
     }
 
+    /// Make a newly imported voice available to the loaded engine.
+    /// If the engine has not been loaded yet, the voice will be picked up
+    /// from the voices directory on first use instead.
+    pub fn register_voice(name: &str, voice: Voice) -> Result<(), String> {
+        if let Some(Ok(kokoro_mutex)) = KOKORO.get() {
+            let mut kokoro = kokoro_mutex
+                .lock()
+                .map_err(|e| format!("Failed to lock Kokoro: {}", e))?;
+            kokoro.add_voice(name, voice);
+            log::info!("Registered voice '{}' with loaded Kokoro engine", name);
+        }
+        Ok(())
+    }
+
     /// Generate raw audio samples without writing to disk.
     pub async fn generate_raw(
         &self,
@@ -61,10 +81,53 @@ impl TtsService {
         voice: Option<&str>,
         speed: Option<f32>,
     ) -> Result<TtsRawResult, String> {
-        let voice = voice.unwrap_or("af_nova").to_string();
-        let speed = speed.unwrap_or(0.5);
+        self.generate_raw_cancellable(text, voice, speed, CancelToken::new())
+            .await
+    }
+
+    /// Like [`generate_raw`](Self::generate_raw), but long text stops between
+    /// chunks once `cancel` is triggered.
+    pub async fn generate_raw_cancellable(
+        &self,
+        text: &str,
+        voice: Option<&str>,
+        speed: Option<f32>,
+        cancel: CancelToken,
+    ) -> Result<TtsRawResult, String> {
+        let voice = voice.unwrap_or(DEFAULT_VOICE).to_string();
+        let speed = speed.unwrap_or(DEFAULT_SPEED);
         let text = text.to_string();
+        let label = format!("{} chars", text.len());
+
+        self.run_blocking(label, move |kokoro| {
+            kokoro.create_cancellable(&text, &voice, speed, "en-us", &cancel)
+        })
+        .await
+    }
+
+    /// Generate raw audio samples from hand-written IPA phonemes.
+    pub async fn generate_raw_from_phonemes(
+        &self,
+        phonemes: &str,
+        voice: Option<&str>,
+        speed: Option<f32>,
+    ) -> Result<TtsRawResult, String> {
+        let voice = voice.unwrap_or(DEFAULT_VOICE).to_string();
+        let speed = speed.unwrap_or(DEFAULT_SPEED);
+        let phonemes = phonemes.to_string();
+        let label = format!("{} phoneme chars", phonemes.chars().count());
+
+        self.run_blocking(label, move |kokoro| {
+            kokoro.create_from_phonemes(&phonemes, &voice, speed)
+        })
+        .await
+    }
 
+    /// Run a synthesis closure against the locked engine on the blocking pool.
+    async fn run_blocking<F>(&self, label: String, synthesize: F) -> Result<TtsRawResult, String>
+    where
+        F: FnOnce(&mut Kokoro) -> Result<AudioData, KokoroError> + Send + 'static,
+    {
         let kokoro_mutex = self.get_kokoro()?;
 
         tokio::task::spawn_blocking(move || {
@@ -74,15 +137,14 @@ impl TtsService {
                 .lock()
                 .map_err(|e| format!("Failed to lock Kokoro: {}", e))?;
 
-            let audio = kokoro
-                .create(&text, &voice, speed, "en-us")
+            let audio = synthesize(&mut kokoro)
                 .map_err(|e| format!("TTS generation failed: {}", e))?;
 
             let elapsed = start.elapsed();
             let duration_secs = audio.samples.len() as f64 / audio.sample_rate as f64;
             log::info!(
-                "TTS raw generated: {} chars, {:.2}s audio in {:.2}s",
-                text.len(),
+                "TTS raw generated: {}, {:.2}s audio in {:.2}s",
+                label,
                 duration_secs,
                 elapsed.as_secs_f64()
             );
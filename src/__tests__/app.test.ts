@@ -1,7 +1,8 @@
 import { describe, it, expect } from "vitest";
 import * as fc from "fast-check";
-import { cn } from "@/lib/utils";
+import { cn, stripSfxCues } from "@/lib/utils";
 import { GENRE_ICONS, AUDIO_STAGES, DEPENDENCY_STEPS } from "@/lib/constants";
+import { parseAudioFrame } from "@/lib/audioStream";
 import type { Genre } from "@/types";
 
 describe("cn utility", () => {
@@ -18,6 +19,61 @@ describe("cn utility", () => {
   });
 });
 
+describe("stripSfxCues", () => {
+  it("removes cues and the space around them", () => {
+    expect(stripSfxCues("[SFX: door creak] The door opened.")).toBe("The door opened.");
+    expect(stripSfxCues("Boom. [sfx: thunder] It rained.")).toBe("Boom. It rained.");
+  });
+
+  it("leaves other brackets alone", () => {
+    expect(stripSfxCues("A [note] here")).toBe("A [note] here");
+  });
+});
+
+describe("parseAudioFrame", () => {
+  const header = (kind: number, seq: number, body: number) => {
+    const buffer = new ArrayBuffer(5 + body);
+    const view = new DataView(buffer);
+    view.setUint8(0, kind);
+    view.setUint32(1, seq, true);
+    return { buffer, view };
+  };
+
+  it("reads sentence markers", () => {
+    const text = new TextEncoder().encode("Hi.");
+    const { buffer, view } = header(0, 4, 16 + text.length);
+    view.setUint32(5, 1, true);
+    view.setUint32(9, 3, true);
+    view.setFloat64(13, 2.5, true);
+    new Uint8Array(buffer, 21).set(text);
+    expect(parseAudioFrame(buffer)).toEqual({
+      kind: "sentence",
+      seq: 4,
+      index: 1,
+      total: 3,
+      start: 2.5,
+      text: "Hi.",
+    });
+  });
+
+  it("reads 16-bit PCM as floats", () => {
+    const { buffer, view } = header(1, 5, 8);
+    view.setUint32(5, 24000, true);
+    view.setInt16(9, 32767, true);
+    view.setInt16(11, -32767, true);
+    const frame = parseAudioFrame(buffer);
+    expect(frame.kind).toBe("pcm");
+    if (frame.kind === "pcm") {
+      expect(frame.sampleRate).toBe(24000);
+      expect(Array.from(frame.samples)).toEqual([1, -1]);
+    }
+  });
+
+  it("reads the end marker", () => {
+    expect(parseAudioFrame(header(2, 9, 0).buffer)).toEqual({ kind: "end", seq: 9 });
+  });
+});
+
 describe("GENRE_ICONS", () => {
   it("has 6 genres", () => {
     expect(Object.keys(GENRE_ICONS)).toHaveLength(6);
@@ -37,8 +93,12 @@ describe("GENRE_ICONS", () => {
 });
 
 describe("AUDIO_STAGES", () => {
-  it("has 2 stages", () => {
-    expect(AUDIO_STAGES).toHaveLength(2);
+  it("has 5 stages", () => {
+    expect(AUDIO_STAGES).toHaveLength(5);
+  });
+
+  it("starts with queued", () => {
+    expect(AUDIO_STAGES[0].key).toBe("queued");
   });
 
   it("ends with complete", () => {
@@ -52,8 +112,8 @@ describe("AUDIO_STAGES", () => {
 });
 
 describe("DEPENDENCY_STEPS", () => {
-  it("has 5 steps", () => {
-    expect(DEPENDENCY_STEPS).toHaveLength(5);
+  it("has 4 steps", () => {
+    expect(DEPENDENCY_STEPS).toHaveLength(4);
   });
 
   it("all steps have required fields", () => {
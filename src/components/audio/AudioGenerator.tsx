@@ -5,14 +5,12 @@ import { Music } from "lucide-react";
 
 interface AudioGeneratorProps {
   partId: string;
-  text: string;
   audioPath: string | null;
   title?: string;
 }
 
 export function AudioGenerator({
   partId,
-  text,
   audioPath,
   title,
 }: AudioGeneratorProps) {
@@ -33,7 +31,7 @@ export function AudioGenerator({
 
   return (
     <button
-      onClick={() => startGeneration(partId, text)}
+      onClick={() => startGeneration(partId)}
       className="flex items-center gap-2 rounded-lg border border-dashed border-border px-4 py-3 text-sm text-muted-foreground hover:border-primary hover:text-primary"
     >
       <Music className="h-4 w-4" />
@@ -0,0 +1,164 @@
+import { useCallback, useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { ArrowDown, ArrowUp, Loader2, RotateCcw, X } from "lucide-react";
+import { cn } from "@/lib/utils";
+import {
+  cancelAudioGeneration,
+  listAudioQueue,
+  reorderAudioJob,
+  retryAudioJob,
+} from "@/lib/api";
+import { getPartLabels, getStoppedAudioJobs } from "@/lib/database";
+import type { AudioJob, QueuedAudioJob } from "@/types";
+
+const iconButton = cn(
+  "flex h-7 w-7 items-center justify-center rounded-md",
+  "text-muted-foreground transition-colors hover:bg-secondary hover:text-foreground",
+  "disabled:cursor-not-allowed disabled:opacity-40",
+);
+
+/** Audio jobs running and waiting, in the order they start, and the jobs that stopped. */
+export function AudioQueue() {
+  const [queue, setQueue] = useState<QueuedAudioJob[]>([]);
+  const [stopped, setStopped] = useState<AudioJob[]>([]);
+  const [labels, setLabels] = useState<Map<string, string>>(new Map());
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [jobs, stoppedJobs, partLabels] = await Promise.all([
+        listAudioQueue(),
+        getStoppedAudioJobs(),
+        getPartLabels(),
+      ]);
+      setQueue(jobs);
+      setStopped(stoppedJobs);
+      setLabels(partLabels);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen("audio-progress", () => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+    await refresh();
+  };
+
+  const running = queue.filter((job) => job.status !== "pending");
+  const waiting = queue.filter((job) => job.status === "pending");
+  const label = (partId: string) => labels.get(partId) ?? partId;
+
+  return (
+    <div className="mx-auto flex max-w-3xl flex-col gap-6 p-8">
+      <h1 className="text-2xl font-bold text-foreground">Audio Queue</h1>
+
+      <section className="flex flex-col gap-2">
+        <h2 className="text-sm font-semibold text-foreground">Running</h2>
+        {running.length === 0 && (
+          <p className="text-xs text-muted-foreground">Nothing is being narrated.</p>
+        )}
+        {running.map((job) => (
+          <div
+            key={job.job_id}
+            className="flex items-center gap-3 rounded-lg border border-border bg-card px-4 py-2"
+          >
+            <Loader2 className="h-3.5 w-3.5 animate-spin text-primary" />
+            <span className="flex-1 text-sm text-card-foreground">{label(job.story_part_id)}</span>
+            <span className="text-xs text-muted-foreground">{job.status.replace("_", " ")}</span>
+            <button
+              onClick={() => run(() => cancelAudioGeneration(job.job_id))}
+              className={iconButton}
+              title="Cancel"
+            >
+              <X className="h-3.5 w-3.5" />
+            </button>
+          </div>
+        ))}
+      </section>
+
+      <section className="flex flex-col gap-2">
+        <h2 className="text-sm font-semibold text-foreground">Waiting</h2>
+        {waiting.length === 0 && (
+          <p className="text-xs text-muted-foreground">No jobs are waiting.</p>
+        )}
+        {waiting.map((job, position) => (
+          <div
+            key={job.job_id}
+            className="flex items-center gap-3 rounded-lg border border-border bg-card px-4 py-2"
+          >
+            <span className="flex-1 text-sm text-card-foreground">{label(job.story_part_id)}</span>
+            {job.priority > 0 && (
+              <span className="text-xs text-muted-foreground">play now</span>
+            )}
+            <button
+              onClick={() => run(() => reorderAudioJob(job.job_id, position - 1))}
+              disabled={position === 0}
+              className={iconButton}
+              title="Move up"
+            >
+              <ArrowUp className="h-3.5 w-3.5" />
+            </button>
+            <button
+              onClick={() => run(() => reorderAudioJob(job.job_id, position + 1))}
+              disabled={position === waiting.length - 1}
+              className={iconButton}
+              title="Move down"
+            >
+              <ArrowDown className="h-3.5 w-3.5" />
+            </button>
+            <button
+              onClick={() => run(() => cancelAudioGeneration(job.job_id))}
+              className={iconButton}
+              title="Cancel"
+            >
+              <X className="h-3.5 w-3.5" />
+            </button>
+          </div>
+        ))}
+      </section>
+
+      {stopped.length > 0 && (
+        <section className="flex flex-col gap-2">
+          <h2 className="text-sm font-semibold text-foreground">Stopped</h2>
+          {stopped.map((job) => (
+            <div
+              key={job.id}
+              className="flex items-center gap-3 rounded-lg border border-border bg-card px-4 py-2"
+            >
+              <div className="flex flex-1 flex-col">
+                <span className="text-sm text-card-foreground">{label(job.story_part_id)}</span>
+                <span className="text-xs text-muted-foreground">
+                  {job.status === "failed" ? job.error_message ?? "Failed" : "Cancelled"}
+                </span>
+              </div>
+              <button
+                onClick={() => run(() => retryAudioJob(job.id))}
+                className={iconButton}
+                title="Retry"
+              >
+                <RotateCcw className="h-3.5 w-3.5" />
+              </button>
+            </div>
+          ))}
+        </section>
+      )}
+
+      {error && <span className="text-xs text-destructive">{error}</span>}
+    </div>
+  );
+}
@@ -1,14 +1,15 @@
 import { useState } from "react";
-import { BookOpen, PenLine, Settings, Sun, Moon } from "lucide-react";
+import { BookOpen, ListOrdered, PenLine, Settings, Sun, Moon } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { StoryLibrary } from "@/components/story/StoryLibrary";
 import { StoryGenerator } from "@/components/story/StoryGenerator";
 import { StoryDetail } from "@/components/story/StoryDetail";
 import { VoiceSettings } from "@/components/settings/VoiceSettings";
+import { AudioQueue } from "@/components/audio/AudioQueue";
 import { useTheme } from "@/hooks/useTheme";
 import pattiAvatar from "@/assets/patti-avatar.jpeg";
 
-type Page = "library" | "create" | "story-detail" | "settings";
+type Page = "library" | "create" | "story-detail" | "queue" | "settings";
 
 interface NavItem {
   page: Page;
@@ -19,6 +20,7 @@ interface NavItem {
 const navItems: NavItem[] = [
   { page: "library", label: "Library", icon: BookOpen },
   { page: "create", label: "Create Story", icon: PenLine },
+  { page: "queue", label: "Audio Queue", icon: ListOrdered },
   { page: "settings", label: "Settings", icon: Settings },
 ];
 
@@ -122,6 +124,7 @@ export function AppShell() {
             onBack={() => setPage("library")}
           />
         )}
+        {page === "queue" && <AudioQueue />}
         {page === "settings" && <VoiceSettings />}
       </main>
     </div>
@@ -0,0 +1,130 @@
+import { useEffect, useState } from "react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { applyPodcastFeed, getPodcastFeedStatus, listNetworkInterfaces } from "@/lib/api";
+import { getPodcastFeedSettings, updateSetting } from "@/lib/database";
+import type { NetworkInterface, PodcastFeedStatus } from "@/types";
+
+/** Publish the library as a podcast feed for podcast apps on the local network. */
+export function PodcastFeedSettings() {
+  const [enabled, setEnabled] = useState(false);
+  const [address, setAddress] = useState("127.0.0.1");
+  const [port, setPort] = useState("8765");
+  const [interfaces, setInterfaces] = useState<NetworkInterface[]>([]);
+  const [status, setStatus] = useState<PodcastFeedStatus | null>(null);
+  const [applying, setApplying] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPodcastFeedSettings()
+      .then((settings) => {
+        setEnabled(settings.podcast_feed_enabled === "true");
+        setAddress(settings.podcast_feed_address);
+        setPort(settings.podcast_feed_port);
+      })
+      .catch(() => {});
+    listNetworkInterfaces()
+      .then(setInterfaces)
+      .catch(() => setInterfaces([]));
+    getPodcastFeedStatus()
+      .then(setStatus)
+      .catch(() => setStatus(null));
+  }, []);
+
+  const portNumber = Number(port);
+  const validPort = Number.isInteger(portNumber) && portNumber >= 1024 && portNumber <= 65535;
+  // Keep a saved address selectable even if its interface is down right now
+  const options = interfaces.some((i) => i.address === address)
+    ? interfaces
+    : [...interfaces, { name: "saved", address }];
+
+  const handleApply = async () => {
+    setApplying(true);
+    setError(null);
+    try {
+      await updateSetting("podcast_feed_enabled", enabled ? "true" : "false");
+      await updateSetting("podcast_feed_address", address);
+      await updateSetting("podcast_feed_port", port);
+      setStatus(await applyPodcastFeed());
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+      setStatus(await getPodcastFeedStatus().catch(() => null));
+    } finally {
+      setApplying(false);
+    }
+  };
+
+  return (
+    <Card className="mt-6">
+      <CardHeader>
+        <CardTitle>Podcast Feed</CardTitle>
+        <CardDescription>
+          Share finished stories with podcast apps on your home network. Each
+          part with audio is an episode.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <label className="flex items-center gap-2 text-sm font-medium" htmlFor="podcast-feed">
+          <input
+            id="podcast-feed"
+            type="checkbox"
+            checked={enabled}
+            onChange={(e) => setEnabled(e.target.checked)}
+          />
+          Publish a podcast feed
+        </label>
+
+        <div className="space-y-2">
+          <label className="text-sm font-medium" htmlFor="podcast-address">
+            Network
+          </label>
+          <select
+            id="podcast-address"
+            value={address}
+            onChange={(e) => setAddress(e.target.value)}
+            disabled={!enabled}
+            className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+          >
+            {options.map((i) => (
+              <option key={`${i.name}-${i.address}`} value={i.address}>
+                {i.address.startsWith("127.") ? "This computer only" : i.name} ({i.address})
+              </option>
+            ))}
+          </select>
+          <p className="text-xs text-muted-foreground">
+            The feed only answers on this address. Pick your Wi-Fi or Ethernet
+            connection to reach it from a phone or speaker.
+          </p>
+        </div>
+
+        <div className="space-y-2">
+          <label className="text-sm font-medium" htmlFor="podcast-port">
+            Port
+          </label>
+          <input
+            id="podcast-port"
+            type="number"
+            min="1024"
+            max="65535"
+            value={port}
+            onChange={(e) => setPort(e.target.value)}
+            disabled={!enabled}
+            className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+          />
+        </div>
+
+        <div className="flex items-center gap-3 pt-2">
+          <Button onClick={handleApply} disabled={applying || !validPort}>
+            {applying ? "Applying..." : "Apply"}
+          </Button>
+          {status?.running && status.url ? (
+            <span className="select-all text-sm text-muted-foreground">{status.url}</span>
+          ) : (
+            <span className="text-sm text-muted-foreground">Not running</span>
+          )}
+        </div>
+        {error && <p className="text-sm text-red-600">{error}</p>}
+      </CardContent>
+    </Card>
+  );
+}
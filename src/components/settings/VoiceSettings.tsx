@@ -2,41 +2,88 @@ import { useState, useEffect } from "react";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { useVoiceSettings } from "@/hooks/useVoiceSettings";
-import { applyMigrations } from "@/lib/api";
-
-const VOICE_PRESETS = [
-  { value: "af_nova", label: "Nova (storytelling)" },
-  { value: "bf_emma", label: "Emma (narration)" },
-  { value: "af_heart", label: "Heart (warm)" },
-  { value: "af_bella", label: "Bella" },
-  { value: "af_jessica", label: "Jessica" },
-  { value: "af_sarah", label: "Sarah" },
-  { value: "af_sky", label: "Sky" },
-  { value: "am_adam", label: "Adam" },
-  { value: "am_michael", label: "Michael" },
-  { value: "bm_george", label: "George" },
-  { value: "bf_lily", label: "Lily" },
-  { value: "am_echo", label: "Echo" },
+import { open } from "@tauri-apps/plugin-dialog";
+import { applyMigrations, importVoice, listVoices } from "@/lib/api";
+import { EFFECT_PRESETS, VOICE_PRESETS } from "@/lib/constants";
+import { PodcastFeedSettings } from "./PodcastFeedSettings";
+
+const MASTERING_PRESETS = [
+  { value: "none", label: "None (dry)" },
+  { value: "clean", label: "Clean" },
+  { value: "cozy_room", label: "Cozy room" },
+  { value: "campfire", label: "Campfire" },
 ];
 
 export function VoiceSettings() {
   const { settings, loading, saving, save } = useVoiceSettings();
   const [voice, setVoice] = useState(settings.tts_voice);
   const [speed, setSpeed] = useState(settings.tts_speed);
+  const [effect, setEffect] = useState(settings.tts_effect);
+  const [mastering, setMastering] = useState(settings.audio_mastering);
+  const [musicEnabled, setMusicEnabled] = useState(settings.music_enabled);
+  const [ambienceEnabled, setAmbienceEnabled] = useState(settings.ambience_enabled);
+  const [concurrency, setConcurrency] = useState(settings.audio_job_concurrency);
   const [saved, setSaved] = useState(false);
   const [migrationStatus, setMigrationStatus] = useState<"running" | "done" | "error" | null>(null);
+  const [customVoices, setCustomVoices] = useState<string[]>([]);
+  const [importName, setImportName] = useState("");
+  const [importStatus, setImportStatus] = useState<string | null>(null);
 
   useEffect(() => {
     setVoice(settings.tts_voice);
     setSpeed(settings.tts_speed);
+    setEffect(settings.tts_effect);
+    setMastering(settings.audio_mastering);
+    setMusicEnabled(settings.music_enabled);
+    setAmbienceEnabled(settings.ambience_enabled);
+    setConcurrency(settings.audio_job_concurrency);
   }, [settings]);
 
-  const hasChanges = voice !== settings.tts_voice || speed !== settings.tts_speed;
+  useEffect(() => {
+    listVoices()
+      .then((names) =>
+        setCustomVoices(
+          names.filter((n) => !VOICE_PRESETS.some((p) => p.value === n)),
+        ),
+      )
+      .catch(() => setCustomVoices([]));
+  }, []);
+
+  const handleImportVoice = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Kokoro voice", extensions: ["bin", "npy", "zip"] }],
+    });
+    if (!selected || Array.isArray(selected)) return;
+    try {
+      const imported = await importVoice(selected, importName);
+      const names = imported.map((v) => v.name);
+      setCustomVoices((prev) => [...prev, ...names]);
+      setImportName("");
+      setImportStatus(`Imported ${names.join(", ")}`);
+    } catch (e) {
+      setImportStatus(String(e));
+    }
+  };
+
+  const hasChanges =
+    voice !== settings.tts_voice ||
+    speed !== settings.tts_speed ||
+    effect !== settings.tts_effect ||
+    mastering !== settings.audio_mastering ||
+    musicEnabled !== settings.music_enabled ||
+    ambienceEnabled !== settings.ambience_enabled ||
+    concurrency !== settings.audio_job_concurrency;
 
   const handleSave = async () => {
     await save({
       tts_voice: voice,
       tts_speed: speed,
+      tts_effect: effect,
+      audio_mastering: mastering,
+      music_enabled: musicEnabled,
+      ambience_enabled: ambienceEnabled,
+      audio_job_concurrency: concurrency,
     });
     setSaved(true);
     setTimeout(() => setSaved(false), 2000);
@@ -77,6 +124,11 @@ export function VoiceSettings() {
                   {preset.label}
                 </option>
               ))}
+              {customVoices.map((name) => (
+                <option key={name} value={name}>
+                  {name} (custom)
+                </option>
+              ))}
             </select>
             <p className="text-xs text-muted-foreground">
               Choose a voice preset. Nova and Emma work best for storytelling.
@@ -102,6 +154,130 @@ export function VoiceSettings() {
             </p>
           </div>
 
+          <div className="space-y-2">
+            <label className="text-sm font-medium" htmlFor="effect">
+              Voice effect
+            </label>
+            <select
+              id="effect"
+              value={effect}
+              onChange={(e) => setEffect(e.target.value)}
+              className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+            >
+              {EFFECT_PRESETS.map((preset) => (
+                <option key={preset.value} value={preset.value}>
+                  {preset.label}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-muted-foreground">
+              Shift pitch and formants to give the narrator a character voice.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label className="text-sm font-medium" htmlFor="mastering">
+              Room sound
+            </label>
+            <select
+              id="mastering"
+              value={mastering}
+              onChange={(e) => setMastering(e.target.value)}
+              className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+            >
+              {MASTERING_PRESETS.map((preset) => (
+                <option key={preset.value} value={preset.value}>
+                  {preset.label}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-muted-foreground">
+              EQ, compression and reverb applied to the finished story.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label className="flex items-center gap-2 text-sm font-medium" htmlFor="music">
+              <input
+                id="music"
+                type="checkbox"
+                checked={musicEnabled === "true"}
+                onChange={(e) => setMusicEnabled(e.target.checked ? "true" : "false")}
+              />
+              Background music
+            </label>
+            <p className="text-xs text-muted-foreground">
+              Mixes a track from ~/.pattikadhai/music/&lt;genre&gt;/ (or the
+              story&apos;s own generated theme tune) under the narration, lowering
+              it while someone is speaking.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label className="flex items-center gap-2 text-sm font-medium" htmlFor="ambience">
+              <input
+                id="ambience"
+                type="checkbox"
+                checked={ambienceEnabled === "true"}
+                onChange={(e) => setAmbienceEnabled(e.target.checked ? "true" : "false")}
+              />
+              Ambient sounds
+            </label>
+            <p className="text-xs text-muted-foreground">
+              Quiet rain, waves, wind, fire or crickets to match where the story
+              takes place.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label className="text-sm font-medium" htmlFor="concurrency">
+              Audio jobs at once
+            </label>
+            <select
+              id="concurrency"
+              value={concurrency}
+              onChange={(e) => setConcurrency(e.target.value)}
+              className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+            >
+              {["1", "2", "3"].map((n) => (
+                <option key={n} value={n}>
+                  {n}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-muted-foreground">
+              Other parts wait in a queue. Parts you ask to hear go ahead of
+              ones rendering in the background.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label className="text-sm font-medium" htmlFor="import-voice-name">
+              Import custom voice
+            </label>
+            <div className="flex gap-2">
+              <input
+                id="import-voice-name"
+                type="text"
+                placeholder="Display name, e.g. grandma"
+                value={importName}
+                onChange={(e) => setImportName(e.target.value)}
+                className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm"
+              />
+              <Button
+                variant="outline"
+                onClick={handleImportVoice}
+                disabled={!importName.trim()}
+              >
+                Choose file...
+              </Button>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              {importStatus ??
+                "Kokoro voice embeddings as .bin, .npy, or a .zip of them."}
+            </p>
+          </div>
+
           <div className="flex items-center gap-3 pt-2">
             <Button onClick={handleSave} disabled={!hasChanges || saving}>
               {saving ? "Saving..." : "Save"}
@@ -113,6 +289,8 @@ export function VoiceSettings() {
         </CardContent>
       </Card>
 
+      <PodcastFeedSettings />
+
       <Card className="mt-6">
         <CardHeader>
           <CardTitle>Database</CardTitle>
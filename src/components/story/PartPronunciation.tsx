@@ -0,0 +1,106 @@
+import { useState } from "react";
+import { Languages, Loader2 } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { phonemizeText, synthesizePhonemes, type SentencePhonemes } from "@/lib/api";
+
+interface PartPronunciationProps {
+  partId: string;
+  /** Called with the job that re-renders the part after a sentence is re-spoken */
+  onQueued: (jobId: string) => void;
+}
+
+/**
+ * The phonemes each sentence of a part is read with. A sentence whose
+ * phonemes are edited is spoken again from them and the part re-rendered.
+ */
+export function PartPronunciation({ partId, onQueued }: PartPronunciationProps) {
+  const [sentences, setSentences] = useState<SentencePhonemes[] | null>(null);
+  const [edits, setEdits] = useState<Record<number, string>>({});
+  const [busy, setBusy] = useState<number | "loading" | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleLoad = async () => {
+    setBusy("loading");
+    setError(null);
+    try {
+      setSentences(await phonemizeText(partId));
+      setEdits({});
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleRespeak = async (sentence: SentencePhonemes) => {
+    const phonemes = edits[sentence.index] ?? sentence.phonemes;
+    setBusy(sentence.index);
+    setError(null);
+    try {
+      const { job_id } = await synthesizePhonemes(partId, sentence.index, phonemes);
+      setSentences((prev) =>
+        prev?.map((s) => (s.index === sentence.index ? { ...s, phonemes } : s)) ?? null,
+      );
+      onQueued(job_id);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-2 rounded-lg bg-secondary/50 p-3">
+      <div className="flex items-center gap-2">
+        <Languages className="h-3.5 w-3.5 text-muted-foreground" />
+        <span className="flex-1 text-xs font-medium text-foreground">Pronunciation</span>
+        <button
+          onClick={sentences ? () => setSentences(null) : handleLoad}
+          disabled={busy !== null}
+          className={cn(
+            "flex items-center gap-1.5 rounded-md px-2 py-1 text-xs",
+            "text-muted-foreground transition-colors hover:text-foreground",
+            "disabled:cursor-not-allowed disabled:opacity-50",
+          )}
+        >
+          {busy === "loading" && <Loader2 className="h-3 w-3 animate-spin" />}
+          {sentences ? "Hide phonemes" : "Show phonemes"}
+        </button>
+      </div>
+      {sentences?.map((sentence) => {
+        const value = edits[sentence.index] ?? sentence.phonemes;
+        return (
+          <div key={sentence.index} className="flex flex-col gap-1">
+            <span className="text-xs text-card-foreground">{sentence.text}</span>
+            <div className="flex items-center gap-2">
+              <input
+                value={value}
+                onChange={(e) =>
+                  setEdits((prev) => ({ ...prev, [sentence.index]: e.target.value }))
+                }
+                spellCheck={false}
+                className={cn(
+                  "h-8 flex-1 rounded-md border border-input bg-background px-2 font-mono text-xs",
+                  "focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring",
+                )}
+              />
+              <button
+                onClick={() => handleRespeak(sentence)}
+                disabled={busy !== null || value === sentence.phonemes || !value.trim()}
+                className={cn(
+                  "flex items-center gap-1.5 rounded-md px-2 py-1 text-xs",
+                  "bg-primary text-primary-foreground transition-colors hover:bg-primary/90",
+                  "disabled:cursor-not-allowed disabled:opacity-50",
+                )}
+              >
+                {busy === sentence.index && <Loader2 className="h-3 w-3 animate-spin" />}
+                Re-speak
+              </button>
+            </div>
+          </div>
+        );
+      })}
+      {error && <span className="text-xs text-destructive">{error}</span>}
+    </div>
+  );
+}
@@ -0,0 +1,65 @@
+import { useEffect, useState } from "react";
+import { Loader2, MessagesSquare } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { getPartQuestions } from "@/lib/database";
+import { generateDiscussionQuestions } from "@/lib/api";
+
+interface PartQuestionsProps {
+  partId: string;
+}
+
+/** Questions to talk about after reading a part; printed in the PDF export. */
+export function PartQuestions({ partId }: PartQuestionsProps) {
+  const [questions, setQuestions] = useState<string[]>([]);
+  const [generating, setGenerating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPartQuestions(partId)
+      .then(setQuestions)
+      .catch(() => setQuestions([]));
+  }, [partId]);
+
+  const handleGenerate = async () => {
+    setGenerating(true);
+    setError(null);
+    try {
+      setQuestions(await generateDiscussionQuestions(partId));
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setGenerating(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-2 rounded-lg bg-secondary/50 p-3">
+      <div className="flex items-center gap-2">
+        <MessagesSquare className="h-3.5 w-3.5 text-muted-foreground" />
+        <span className="flex-1 text-xs font-medium text-foreground">
+          Let's talk about it
+        </span>
+        <button
+          onClick={handleGenerate}
+          disabled={generating}
+          className={cn(
+            "flex items-center gap-1.5 rounded-md px-2 py-1 text-xs",
+            "text-muted-foreground transition-colors hover:text-foreground",
+            "disabled:cursor-not-allowed disabled:opacity-50",
+          )}
+        >
+          {generating && <Loader2 className="h-3 w-3 animate-spin" />}
+          {questions.length > 0 ? "Suggest new questions" : "Suggest questions"}
+        </button>
+      </div>
+      {questions.length > 0 && (
+        <ul className="list-disc pl-6 text-xs text-card-foreground">
+          {questions.map((question) => (
+            <li key={question}>{question}</li>
+          ))}
+        </ul>
+      )}
+      {error && <span className="text-xs text-destructive">{error}</span>}
+    </div>
+  );
+}
@@ -0,0 +1,89 @@
+import { useEffect, useState } from "react";
+import { Users } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { getStoryCharacters, updateStoryCharacter } from "@/lib/database";
+import { EFFECT_PRESETS, VOICE_PRESETS } from "@/lib/constants";
+import type { StoryCharacter } from "@/types";
+
+interface StoryCastProps {
+  storyId: string;
+  /** Bump to reload after audio generation discovers new speakers */
+  refreshKey: number;
+}
+
+const selectClass = cn(
+  "h-8 rounded-md border border-input bg-background px-2 text-xs",
+  "focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring",
+);
+
+/** Character → voice map, filled in automatically when audio is generated. */
+export function StoryCast({ storyId, refreshKey }: StoryCastProps) {
+  const [characters, setCharacters] = useState<StoryCharacter[]>([]);
+
+  useEffect(() => {
+    getStoryCharacters(storyId)
+      .then(setCharacters)
+      .catch(() => setCharacters([]));
+  }, [storyId, refreshKey]);
+
+  if (characters.length === 0) return null;
+
+  const handleChange = async (
+    character: StoryCharacter,
+    changes: Partial<Pick<StoryCharacter, "voice" | "effect">>,
+  ) => {
+    const updated = { ...character, ...changes };
+    await updateStoryCharacter(storyId, updated.name, updated.voice, updated.effect);
+    setCharacters((prev) =>
+      prev.map((c) => (c.name === updated.name ? updated : c)),
+    );
+  };
+
+  return (
+    <div className="mb-6 rounded-xl border border-border bg-card p-4">
+      <h3 className="mb-3 flex items-center gap-2 text-sm font-semibold text-card-foreground">
+        <Users className="h-4 w-4" />
+        Cast
+      </h3>
+      <div className="flex flex-col gap-2">
+        {characters.map((character) => (
+          <div key={character.name} className="flex items-center gap-2">
+            <span className="flex-1 truncate text-sm text-foreground">
+              {character.name}
+            </span>
+            <select
+              aria-label={`${character.name} voice`}
+              value={character.voice}
+              onChange={(e) => handleChange(character, { voice: e.target.value })}
+              className={selectClass}
+            >
+              {!VOICE_PRESETS.some((p) => p.value === character.voice) && (
+                <option value={character.voice}>{character.voice}</option>
+              )}
+              {VOICE_PRESETS.map((preset) => (
+                <option key={preset.value} value={preset.value}>
+                  {preset.label}
+                </option>
+              ))}
+            </select>
+            <select
+              aria-label={`${character.name} effect`}
+              value={character.effect}
+              onChange={(e) => handleChange(character, { effect: e.target.value })}
+              className={selectClass}
+            >
+              {EFFECT_PRESETS.map((preset) => (
+                <option key={preset.value} value={preset.value}>
+                  {preset.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        ))}
+      </div>
+      <p className="mt-3 text-xs text-muted-foreground">
+        Changes apply the next time audio is generated.
+      </p>
+    </div>
+  );
+}
@@ -13,7 +13,7 @@ import {
   Play,
 } from "lucide-react";
 import { getAudioUrl, invalidateAudioUrl } from "@/lib/audio";
-import { cn } from "@/lib/utils";
+import { cn, stripSfxCues } from "@/lib/utils";
 import { useStory, useStoryParts, useGenres } from "@/hooks/useDatabase";
 import { useStoryGeneration } from "@/hooks/useStoryGeneration";
 import { useAudioGeneration } from "@/hooks/useAudioGeneration";
@@ -21,6 +21,11 @@ import { useStreamingAudio } from "@/hooks/useStreamingAudio";
 import { useAudioHighlight } from "@/hooks/useAudioHighlight";
 import { loadTimingData, type TimingSegment } from "@/lib/timing";
 import { HighlightedText } from "@/components/story/HighlightedText";
+import { StoryCast } from "@/components/story/StoryCast";
+import { StorySoundscape } from "@/components/story/StorySoundscape";
+import { StoryExport } from "@/components/story/StoryExport";
+import { PartQuestions } from "@/components/story/PartQuestions";
+import { PartPronunciation } from "@/components/story/PartPronunciation";
 import {
   updateStoryPartContent,
   createStoryPart,
@@ -47,15 +52,16 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
     stage: audioStage,
     progress: audioProgress,
     error: audioError,
-    jobId: audioJobId,
     startGeneration,
+    follow: followAudioJob,
+    cancel: cancelAudio,
     reset: _resetAudio,
   } = useAudioGeneration();
   const [audioPartId, setAudioPartId] = useState<string | null>(null);
   const [audioGenCounter, setAudioGenCounter] = useState(0);
 
   // Streaming audio playback
-  const streaming = useStreamingAudio(audioJobId);
+  const streaming = useStreamingAudio();
 
   // Auto-expand all parts once loaded
   useEffect(() => {
@@ -80,6 +86,15 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
     }
   }, [audioStage, refreshParts, audioPartId, parts]);
 
+  // A cancelled job puts the part back the way it was
+  const { pause: pauseStreaming } = streaming;
+  useEffect(() => {
+    if (audioStage === "cancelled") {
+      pauseStreaming();
+      refreshParts();
+    }
+  }, [audioStage, pauseStreaming, refreshParts]);
+
   // Continuation generation state
   const {
     state: contState,
@@ -134,7 +149,12 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
   const handleGenerateAudio = async (part: StoryPart) => {
     if (!genre) return;
     setAudioPartId(part.id);
-    await startGeneration(part.id, part.content);
+    await startGeneration(part.id, streaming.open());
+  };
+
+  const handlePhonemesQueued = (part: StoryPart, jobId: string) => {
+    setAudioPartId(part.id);
+    followAudioJob(jobId);
   };
 
   const handleAddContinuation = async () => {
@@ -227,13 +247,17 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
         </div>
       </div>
 
+      <StorySoundscape storyId={storyId} refreshKey={audioGenCounter} />
+      <StoryCast storyId={storyId} refreshKey={audioGenCounter} />
+      <StoryExport storyId={storyId} title={story.title} />
+
       {/* Story parts */}
       <div className="flex flex-col gap-4">
         {parts.map((part) => {
           const isExpanded = expandedParts.has(part.id);
           const isEditing = editingPartId === part.id;
           const isThisPartGenerating =
-            audioPartId === part.id && audioStage !== "idle" && audioStage !== "complete" && audioStage !== "failed";
+            audioPartId === part.id && audioStage !== "idle" && audioStage !== "complete" && audioStage !== "failed" && audioStage !== "cancelled";
           const isStreamingThisPart = isThisPartGenerating && streaming.sentences.length > 0;
 
           return (
@@ -279,7 +303,7 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                 {/* Truncated preview when collapsed */}
                 {!isExpanded && (
                   <span className="ml-3 max-w-[200px] truncate text-xs text-muted-foreground">
-                    {part.content.slice(0, 80)}...
+                    {stripSfxCues(part.content).slice(0, 80)}...
                   </span>
                 )}
               </button>
@@ -334,7 +358,7 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                           <HighlightedText
                             segments={streaming.sentences.map((s) => ({ text: s.text }))}
                             activeIndex={streaming.activeIndex}
-                            fallbackContent={part.content}
+                            fallbackContent={stripSfxCues(part.content)}
                           />
                           {/* Streaming playback controls */}
                           <div className="flex items-center gap-3 rounded-lg bg-secondary/50 p-3">
@@ -360,7 +384,9 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                                     ? `Playing sentence ${streaming.activeIndex + 1}`
                                     : streaming.state === "complete"
                                       ? "Playback complete"
-                                      : "Paused"}
+                                      : streaming.state === "failed"
+                                        ? `Narration stopped: ${streaming.error}`
+                                        : "Paused"}
                               </span>
                               <span className="text-[10px] text-muted-foreground">
                                 {streaming.sentences.length} sentences ready
@@ -372,13 +398,13 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                         /* Replay mode — audio_ready with highlighting */
                         <ReplayPartContent
                           key={`${part.id}-${audioGenCounter}`}
-                          content={part.content}
+                          content={stripSfxCues(part.content)}
                           audioPath={part.audio_path}
                         />
                       ) : (
                         /* Plain text — no audio */
                         <p className="whitespace-pre-wrap text-sm leading-relaxed text-card-foreground">
-                          {part.content}
+                          {stripSfxCues(part.content)}
                         </p>
                       )}
 
@@ -409,6 +435,15 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                         </div>
                       )}
 
+                      <PartQuestions partId={part.id} />
+
+                      {part.status === "audio_ready" && !isThisPartGenerating && (
+                        <PartPronunciation
+                          partId={part.id}
+                          onQueued={(jobId) => handlePhonemesQueued(part, jobId)}
+                        />
+                      )}
+
                       {/* Action buttons */}
                       <div className="flex items-center gap-2">
                         <button
@@ -436,6 +471,20 @@ export function StoryDetail({ storyId, onBack }: StoryDetailProps) {
                             {part.status === "audio_ready" ? "Regenerate Audio" : "Generate Audio"}
                           </button>
                         )}
+
+                        {isThisPartGenerating && (
+                          <button
+                            onClick={cancelAudio}
+                            className={cn(
+                              "flex items-center gap-1.5 rounded-lg px-3 py-1.5",
+                              "bg-secondary text-secondary-foreground text-xs font-medium",
+                              "transition-colors hover:bg-secondary/80",
+                            )}
+                          >
+                            <X className="h-3 w-3" />
+                            Cancel Audio
+                          </button>
+                        )}
                       </div>
                     </div>
                   )}
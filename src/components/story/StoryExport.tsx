@@ -0,0 +1,172 @@
+import { useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { Download, Loader2 } from "lucide-react";
+import { cn } from "@/lib/utils";
+import {
+  exportAudiobook,
+  exportStoryBundle,
+  exportStoryCaptions,
+  exportStoryEpub,
+  exportStoryPdf,
+} from "@/lib/api";
+import type { AudiobookFormat, CaptionFormat } from "@/types";
+
+interface StoryExportProps {
+  storyId: string;
+  title: string;
+}
+
+interface ExportSettings {
+  spokenTitle: boolean;
+  /** Text for the PDF title page's QR code */
+  qrText: string | null;
+}
+
+interface ExportOption {
+  value: string;
+  label: string;
+  extension: string;
+  /** Whether the narrator can read the title first */
+  spokenTitle?: boolean;
+  /** Whether a QR code can point to the audiobook */
+  qrCode?: boolean;
+  run: (storyId: string, path: string, settings: ExportSettings) => Promise<void>;
+}
+
+const captions = (format: CaptionFormat) => (storyId: string, path: string) =>
+  exportStoryCaptions(storyId, format, path);
+
+const audiobook =
+  (format: AudiobookFormat) => (storyId: string, path: string, settings: ExportSettings) =>
+    exportAudiobook(storyId, format, path, settings.spokenTitle);
+
+const EXPORT_OPTIONS: ExportOption[] = [
+  {
+    value: "m4b",
+    label: "Audiobook (M4B)",
+    extension: "m4b",
+    spokenTitle: true,
+    run: audiobook("m4b"),
+  },
+  {
+    value: "mp3",
+    label: "Audiobook (MP3)",
+    extension: "mp3",
+    spokenTitle: true,
+    run: audiobook("mp3"),
+  },
+  {
+    value: "pdf",
+    label: "Picture book (PDF)",
+    extension: "pdf",
+    qrCode: true,
+    run: (storyId, path, settings) => exportStoryPdf(storyId, path, settings.qrText),
+  },
+  {
+    value: "epub",
+    label: "Read-along book (EPUB)",
+    extension: "epub",
+    run: (storyId, path) => exportStoryEpub(storyId, path),
+  },
+  { value: "vtt", label: "Captions (WebVTT)", extension: "vtt", run: captions("vtt") },
+  { value: "srt", label: "Subtitles (SRT)", extension: "srt", run: captions("srt") },
+  { value: "lrc", label: "Lyrics (LRC)", extension: "lrc", run: captions("lrc") },
+  {
+    value: "bundle",
+    label: "Story bundle (for another computer)",
+    extension: "pattikadhai",
+    run: (storyId, path) => exportStoryBundle(storyId, path),
+  },
+];
+
+/** Save the story's narration in formats other apps and devices can use. */
+export function StoryExport({ storyId, title }: StoryExportProps) {
+  const [selected, setSelected] = useState(EXPORT_OPTIONS[0].value);
+  const [exporting, setExporting] = useState(false);
+  const [spokenTitle, setSpokenTitle] = useState(true);
+  const [qrCode, setQrCode] = useState(true);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const option = EXPORT_OPTIONS.find((o) => o.value === selected);
+
+  const handleExport = async () => {
+    if (!option) return;
+    const path = await save({
+      defaultPath: `${title}.${option.extension}`,
+      filters: [{ name: option.label, extensions: [option.extension] }],
+    });
+    if (!path) return;
+
+    setExporting(true);
+    setMessage(null);
+    try {
+      await option.run(storyId, path, {
+        spokenTitle,
+        // Points to the audiobook saved under its default name
+        qrText: qrCode ? `${title}.m4b` : null,
+      });
+      setMessage("Exported");
+    } catch (e) {
+      setMessage(e instanceof Error ? e.message : String(e));
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  return (
+    <div className="mb-4 flex items-center gap-2 text-sm">
+      <Download className="h-4 w-4 text-muted-foreground" />
+      <label htmlFor="story-export" className="text-muted-foreground">
+        Export
+      </label>
+      <select
+        id="story-export"
+        value={selected}
+        onChange={(e) => setSelected(e.target.value)}
+        className={cn(
+          "h-8 rounded-md border border-input bg-background px-2 text-xs",
+          "focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring",
+        )}
+      >
+        {EXPORT_OPTIONS.map((o) => (
+          <option key={o.value} value={o.value}>
+            {o.label}
+          </option>
+        ))}
+      </select>
+      {option?.spokenTitle && (
+        <label className="flex items-center gap-1.5 text-xs text-muted-foreground">
+          <input
+            type="checkbox"
+            checked={spokenTitle}
+            onChange={(e) => setSpokenTitle(e.target.checked)}
+          />
+          Read the title first
+        </label>
+      )}
+      {option?.qrCode && (
+        <label className="flex items-center gap-1.5 text-xs text-muted-foreground">
+          <input
+            type="checkbox"
+            checked={qrCode}
+            onChange={(e) => setQrCode(e.target.checked)}
+          />
+          QR code for the audiobook
+        </label>
+      )}
+      <button
+        onClick={handleExport}
+        disabled={exporting}
+        className={cn(
+          "flex h-8 items-center gap-1.5 rounded-md px-3 text-xs font-medium",
+          "bg-secondary text-secondary-foreground transition-colors",
+          "hover:bg-secondary/80 disabled:cursor-not-allowed disabled:opacity-50",
+        )}
+      >
+        {exporting && <Loader2 className="h-3.5 w-3.5 animate-spin" />}
+        {exporting ? "Exporting..." : "Save as..."}
+      </button>
+      {message && <span className="text-xs text-muted-foreground">{message}</span>}
+    </div>
+  );
+}
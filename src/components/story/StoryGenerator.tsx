@@ -1,14 +1,21 @@
 import { useState } from "react";
-import { ArrowLeft, Wand2, Save } from "lucide-react";
+import { ArrowLeft, Wand2, Save, Volume2, Pause, Play, BookOpen } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { GenreSelector } from "@/components/story/GenreSelector";
 import { useStoryGeneration } from "@/hooks/useStoryGeneration";
+import { useStreamingAudio } from "@/hooks/useStreamingAudio";
 import { createStory, createStoryPart } from "@/lib/database";
+import { cancelAudioGeneration } from "@/lib/api";
 import type { Genre } from "@/types";
 import pattiAvatar from "@/assets/patti-avatar.jpeg";
 
 type Step = 1 | 2 | 3 | 4;
 
+interface Narration {
+  storyId: string;
+  jobId: string;
+}
+
 interface StoryGeneratorProps {
   onSave: (storyId: string) => void;
 }
@@ -19,9 +26,12 @@ export function StoryGenerator({ onSave }: StoryGeneratorProps) {
   const [titleHint, setTitleHint] = useState("");
   const [editableText, setEditableText] = useState("");
   const [saving, setSaving] = useState(false);
+  // Story saved while it was narrated and its audio job, if the user chose to listen
+  const [narration, setNarration] = useState<Narration | null>(null);
 
-  const { state, text, error, generate, reset, setText } =
+  const { state, text, error, generate, narrate, reset, setText } =
     useStoryGeneration();
+  const streaming = useStreamingAudio();
 
   const handleGenreSelect = (genre: Genre) => {
     setSelectedGenre(genre);
@@ -38,6 +48,25 @@ export function StoryGenerator({ onSave }: StoryGeneratorProps) {
     );
   };
 
+  const handleGenerateAndNarrate = async () => {
+    if (!selectedGenre) return;
+    const ids = {
+      storyId: crypto.randomUUID(),
+      partId: crypto.randomUUID(),
+      jobId: crypto.randomUUID(),
+    };
+    const title = titleHint.trim() || `${selectedGenre.name} Story`;
+    setNarration({ storyId: ids.storyId, jobId: ids.jobId });
+    setStep(3);
+    await narrate(
+      ids,
+      title,
+      selectedGenre,
+      titleHint.trim() || null,
+      streaming.open(),
+    );
+  };
+
   const handleGoToEdit = () => {
     setEditableText(text);
     setStep(4);
@@ -67,6 +96,12 @@ export function StoryGenerator({ onSave }: StoryGeneratorProps) {
       setSelectedGenre(null);
       setStep(1);
     } else if (step === 3) {
+      if (narration) {
+        // Stops the writing and the narration; an unfinished story is discarded
+        cancelAudioGeneration(narration.jobId).catch(() => {});
+        streaming.pause();
+        setNarration(null);
+      }
       reset();
       setStep(2);
     } else if (step === 4) {
@@ -182,6 +217,22 @@ export function StoryGenerator({ onSave }: StoryGeneratorProps) {
               <Wand2 className="h-4 w-4" />
               Generate Story
             </button>
+            <button
+              onClick={handleGenerateAndNarrate}
+              className={cn(
+                "flex items-center justify-center gap-2 self-start rounded-lg px-6 py-3",
+                "bg-secondary text-secondary-foreground font-medium text-sm",
+                "transition-colors hover:bg-secondary/80",
+                "active:scale-[0.98]",
+              )}
+            >
+              <Volume2 className="h-4 w-4" />
+              Generate & Narrate
+            </button>
+            <p className="text-xs text-muted-foreground">
+              Patti starts reading as soon as the first sentence is written.
+              The story is saved to your library as it goes.
+            </p>
           </div>
         </div>
       )}
@@ -237,7 +288,57 @@ export function StoryGenerator({ onSave }: StoryGeneratorProps) {
             )}
           </div>
 
-          {state === "complete" && (
+          {narration && (
+            <div className="flex items-center gap-3 rounded-lg bg-secondary/50 p-3">
+              <button
+                onClick={streaming.isPlaying ? streaming.pause : streaming.play}
+                className={cn(
+                  "flex h-8 w-8 items-center justify-center rounded-full",
+                  "bg-primary text-primary-foreground",
+                  "transition-colors hover:bg-primary/90",
+                )}
+              >
+                {streaming.isPlaying ? (
+                  <Pause className="h-3.5 w-3.5" />
+                ) : (
+                  <Play className="h-3.5 w-3.5 ml-0.5" />
+                )}
+              </button>
+              <div className="flex flex-col gap-1">
+                <span className="text-xs font-medium text-foreground">
+                  {streaming.isBuffering
+                    ? "Waiting for the next sentence..."
+                    : streaming.isPlaying
+                      ? `Playing sentence ${streaming.activeIndex + 1}`
+                      : streaming.state === "complete"
+                        ? "Playback complete"
+                        : streaming.state === "failed"
+                          ? `Narration stopped: ${streaming.error}`
+                          : "Paused"}
+                </span>
+                <span className="text-[10px] text-muted-foreground">
+                  {streaming.sentences.length} sentences ready
+                </span>
+              </div>
+            </div>
+          )}
+
+          {state === "complete" && narration && (
+            <button
+              onClick={() => onSave(narration.storyId)}
+              className={cn(
+                "flex items-center justify-center gap-2 self-start rounded-lg px-6 py-3",
+                "bg-primary text-primary-foreground font-medium text-sm",
+                "transition-colors hover:bg-primary/90",
+                "active:scale-[0.98]",
+              )}
+            >
+              <BookOpen className="h-4 w-4" />
+              Open Story
+            </button>
+          )}
+
+          {state === "complete" && !narration && (
             <button
               onClick={handleGoToEdit}
               className={cn(
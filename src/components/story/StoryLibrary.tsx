@@ -1,5 +1,8 @@
-import { Plus, BookOpen } from "lucide-react";
+import { useState } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Plus, BookOpen, Upload } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { importStoryBundle } from "@/lib/api";
 import { useStories, useGenres } from "@/hooks/useDatabase";
 import pattiAvatar from "@/assets/patti-avatar.jpeg";
 
@@ -15,8 +18,39 @@ export function StoryLibrary({
   const { stories, loading } = useStories();
   const { genres } = useGenres();
 
+  const [importError, setImportError] = useState<string | null>(null);
+
   const genreMap = new Map(genres.map((g) => [g.id, g.name]));
 
+  // Bring in a story exported as a bundle on another computer
+  const handleImport = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Pattikadhai story", extensions: ["pattikadhai"] }],
+    });
+    if (!selected || Array.isArray(selected)) return;
+    setImportError(null);
+    try {
+      onSelectStory(await importStoryBundle(selected));
+    } catch (e) {
+      setImportError(String(e));
+    }
+  };
+
+  const importButton = (
+    <button
+      onClick={handleImport}
+      className={cn(
+        "flex items-center gap-2 rounded-lg px-4 py-2",
+        "bg-secondary text-secondary-foreground text-sm font-medium",
+        "transition-colors hover:bg-secondary/80",
+      )}
+    >
+      <Upload className="h-4 w-4" />
+      Import story
+    </button>
+  );
+
   const formatDate = (dateStr: string) => {
     try {
       const date = new Date(dateStr);
@@ -74,6 +108,8 @@ export function StoryLibrary({
             <Plus className="h-4 w-4" />
             Create your first story
           </button>
+          {importButton}
+          {importError && <p className="text-xs text-destructive">{importError}</p>}
         </div>
       </div>
     );
@@ -89,6 +125,10 @@ export function StoryLibrary({
             your collection
           </p>
         </div>
+        <div className="flex flex-col items-end gap-1">
+          {importButton}
+          {importError && <p className="text-xs text-destructive">{importError}</p>}
+        </div>
       </div>
 
       <div className="grid grid-cols-3 gap-4">
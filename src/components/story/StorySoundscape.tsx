@@ -0,0 +1,54 @@
+import { useEffect, useState } from "react";
+import { Waves } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { getStorySoundscape, setStorySoundscape } from "@/lib/database";
+import { SOUNDSCAPE_PRESETS } from "@/lib/constants";
+
+interface StorySoundscapeProps {
+  storyId: string;
+  /** Bump to reload after audio generation detects a soundscape */
+  refreshKey: number;
+}
+
+const AUTO = "auto";
+
+/** Ambient bed for the story; detected from the text unless picked here. */
+export function StorySoundscape({ storyId, refreshKey }: StorySoundscapeProps) {
+  const [soundscape, setSoundscape] = useState<string>(AUTO);
+
+  useEffect(() => {
+    getStorySoundscape(storyId)
+      .then((value) => setSoundscape(value ?? AUTO))
+      .catch(() => setSoundscape(AUTO));
+  }, [storyId, refreshKey]);
+
+  const handleChange = async (value: string) => {
+    await setStorySoundscape(storyId, value === AUTO ? null : value);
+    setSoundscape(value);
+  };
+
+  return (
+    <div className="mb-4 flex items-center gap-2 text-sm">
+      <Waves className="h-4 w-4 text-muted-foreground" />
+      <label htmlFor="story-soundscape" className="text-muted-foreground">
+        Ambience
+      </label>
+      <select
+        id="story-soundscape"
+        value={soundscape}
+        onChange={(e) => handleChange(e.target.value)}
+        className={cn(
+          "h-8 rounded-md border border-input bg-background px-2 text-xs",
+          "focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring",
+        )}
+      >
+        <option value={AUTO}>Auto (from the story)</option>
+        {SOUNDSCAPE_PRESETS.map((preset) => (
+          <option key={preset.value} value={preset.value}>
+            {preset.label}
+          </option>
+        ))}
+      </select>
+    </div>
+  );
+}
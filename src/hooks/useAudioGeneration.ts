@@ -1,14 +1,19 @@
 import { useState, useEffect, useCallback } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { startAudioGeneration } from "@/lib/api";
+import type { Channel } from "@tauri-apps/api/core";
+import { cancelAudioGeneration, startAudioGeneration } from "@/lib/api";
 import { createAudioJob } from "@/lib/database";
 import type { PipelineProgress } from "@/types";
 
 type AudioStage =
   | "idle"
+  | "queued"
   | "voice_generating"
+  | "music_generating"
+  | "mixing"
   | "complete"
-  | "failed";
+  | "failed"
+  | "cancelled";
 
 export function useAudioGeneration() {
   const [stage, setStage] = useState<AudioStage>("idle");
@@ -35,17 +40,17 @@ export function useAudioGeneration() {
   }, [jobId]);
 
   const startGeneration = useCallback(
-    async (partId: string, text: string) => {
+    async (partId: string, onAudio?: Channel<ArrayBuffer>) => {
       const newJobId = crypto.randomUUID();
       setJobId(newJobId);
-      setStage("voice_generating");
+      setStage("queued");
       setProgress(0);
       setError(null);
       setFinalPath(null);
 
       try {
         await createAudioJob(newJobId, partId);
-        await startAudioGeneration(newJobId, partId, text);
+        await startAudioGeneration(newJobId, partId, "play_now", onAudio);
       } catch (e) {
         const msg = e instanceof Error ? e.message : String(e);
         setError(msg);
@@ -55,6 +60,24 @@ export function useAudioGeneration() {
     [],
   );
 
+  /** Track a job queued elsewhere, such as a re-render after a phoneme edit */
+  const follow = useCallback((existingJobId: string) => {
+    setJobId(existingJobId);
+    setStage("queued");
+    setProgress(0);
+    setError(null);
+    setFinalPath(null);
+  }, []);
+
+  const cancel = useCallback(async () => {
+    if (!jobId) return;
+    try {
+      await cancelAudioGeneration(jobId);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+  }, [jobId]);
+
   const reset = useCallback(() => {
     setStage("idle");
     setProgress(0);
@@ -63,5 +86,5 @@ export function useAudioGeneration() {
     setFinalPath(null);
   }, []);
 
-  return { stage, progress, error, jobId, finalPath, startGeneration, reset };
+  return { stage, progress, error, jobId, finalPath, startGeneration, follow, cancel, reset };
 }
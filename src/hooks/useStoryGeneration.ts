@@ -1,5 +1,7 @@
 import { useState, useCallback, useRef } from "react";
-import { generateStoryText, continueStory } from "@/lib/api";
+import type { Channel } from "@tauri-apps/api/core";
+import { generateStoryText, continueStory, narrateNewStory } from "@/lib/api";
+import type { Genre } from "@/types";
 
 type GenerationState = "idle" | "generating" | "complete" | "error";
 
@@ -43,6 +45,45 @@ export function useStoryGeneration() {
     [],
   );
 
+  /** Like `generate`, but the story is saved and narrated to `onAudio` as it is written */
+  const narrate = useCallback(
+    async (
+      ids: { storyId: string; partId: string; jobId: string },
+      title: string,
+      genre: Genre,
+      titleHint: string | null,
+      onAudio: Channel<ArrayBuffer>,
+    ) => {
+      setState("generating");
+      setText("");
+      setError(null);
+      textRef.current = "";
+
+      try {
+        await narrateNewStory(
+          ids,
+          title,
+          genre,
+          titleHint,
+          (token) => {
+            if (token.done) {
+              setState("complete");
+            } else {
+              textRef.current += token.token;
+              setText(textRef.current);
+            }
+          },
+          onAudio,
+        );
+      } catch (e) {
+        const msg = e instanceof Error ? e.message : String(e);
+        setError(msg);
+        setState("error");
+      }
+    },
+    [],
+  );
+
   const generateContinuation = useCallback(
     async (
       genreName: string,
@@ -86,5 +127,14 @@ export function useStoryGeneration() {
     textRef.current = "";
   }, []);
 
-  return { state, text, error, generate, generateContinuation, reset, setText };
+  return {
+    state,
+    text,
+    error,
+    generate,
+    narrate,
+    generateContinuation,
+    reset,
+    setText,
+  };
 }
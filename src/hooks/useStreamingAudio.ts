@@ -1,164 +1,173 @@
 import { useState, useEffect, useCallback, useRef } from "react";
-import { listen } from "@tauri-apps/api/event";
-import { getAudioUrl } from "@/lib/audio";
-
-interface SentenceAudioEvent {
-  job_id: string;
-  index: number;
-  total: number;
-  text: string;
-  wav_path: string;
-  duration_secs: number;
-}
+import { Channel } from "@tauri-apps/api/core";
+import { parseAudioFrame } from "@/lib/audioStream";
 
 interface SentenceInfo {
   index: number;
   text: string;
-  wavPath: string;
-  durationSecs: number;
-  blobUrl?: string;
+  /** Start time in the stream, in seconds */
+  start: number;
 }
 
-type StreamState = "idle" | "buffering" | "playing" | "paused" | "complete";
+type StreamState = "idle" | "buffering" | "playing" | "paused" | "complete" | "failed";
 
-const BUFFER_THRESHOLD = 5;
+/** Head start given to the first frame so scheduling never lands in the past */
+const START_DELAY_SECS = 0.1;
 
-export function useStreamingAudio(jobId: string | null) {
+/**
+ * Plays narration streamed from the audio pipeline as binary PCM frames.
+ * Frames are scheduled back to back on one AudioContext so sentences join
+ * without gaps. Call `open()` for a channel to pass to `startAudioGeneration`.
+ * If the narration stops early, `error` says why and the state becomes
+ * `failed` once the audio received so far has played.
+ */
+export function useStreamingAudio() {
   const [sentences, setSentences] = useState<SentenceInfo[]>([]);
   const [activeIndex, setActiveIndex] = useState(-1);
   const [state, setState] = useState<StreamState>("idle");
-  const [totalExpected, setTotalExpected] = useState(0);
-
-  const audioRef = useRef<HTMLAudioElement | null>(null);
-  const allDoneRef = useRef(false);
-  const playStartedRef = useRef(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const contextRef = useRef<AudioContext | null>(null);
+  const buffersRef = useRef<AudioBuffer[]>([]);
+  // Stream seconds received so far, and the context time stream second 0 plays at
+  const queuedSecsRef = useRef(0);
+  const originRef = useRef(0);
+  const endedRef = useRef(false);
+  const errorRef = useRef<string | null>(null);
+  const nextSeqRef = useRef(0);
   const sentencesRef = useRef<SentenceInfo[]>([]);
 
-  // Keep ref in sync
-  useEffect(() => {
-    sentencesRef.current = sentences;
-  }, [sentences]);
+  const schedule = useCallback((buffer: AudioBuffer, at: number) => {
+    const ctx = contextRef.current;
+    if (!ctx) return;
+    const source = ctx.createBufferSource();
+    source.buffer = buffer;
+    source.connect(ctx.destination);
+    source.start(originRef.current + at);
+  }, []);
 
-  // Listen for audio-sentence events
-  useEffect(() => {
-    if (!jobId) return;
+  const handleFrame = useCallback(
+    (data: ArrayBuffer) => {
+      const frame = parseAudioFrame(data);
+      if (frame.seq !== nextSeqRef.current) {
+        console.warn(`Audio frame ${frame.seq} arrived, expected ${nextSeqRef.current}`);
+      }
+      nextSeqRef.current = frame.seq + 1;
 
-    setState("buffering");
+      if (frame.kind === "sentence") {
+        const sentence = { index: frame.index, text: frame.text, start: frame.start };
+        sentencesRef.current = [...sentencesRef.current, sentence];
+        setSentences(sentencesRef.current);
+        return;
+      }
+      if (frame.kind === "end") {
+        endedRef.current = true;
+        if (frame.error) {
+          errorRef.current = frame.error;
+          setError(frame.error);
+          // Nothing to play out first
+          if (buffersRef.current.length === 0) setState("failed");
+        }
+        return;
+      }
+
+      const ctx = contextRef.current;
+      if (!ctx) return;
+      const buffer = ctx.createBuffer(1, frame.samples.length, frame.sampleRate);
+      buffer.copyToChannel(frame.samples, 0);
+      buffersRef.current.push(buffer);
+
+      if (buffersRef.current.length === 1) {
+        originRef.current = ctx.currentTime + START_DELAY_SECS;
+        setState("playing");
+      } else if (originRef.current + queuedSecsRef.current < ctx.currentTime) {
+        // Ran dry: slide the stream forward so this frame plays now
+        originRef.current = ctx.currentTime + START_DELAY_SECS - queuedSecsRef.current;
+      }
+      schedule(buffer, queuedSecsRef.current);
+      queuedSecsRef.current += buffer.duration;
+    },
+    [schedule],
+  );
+
+  const close = useCallback(() => {
+    contextRef.current?.close();
+    contextRef.current = null;
+  }, []);
+
+  const open = useCallback(() => {
+    close();
+    contextRef.current = new AudioContext();
+    buffersRef.current = [];
+    queuedSecsRef.current = 0;
+    endedRef.current = false;
+    errorRef.current = null;
+    setError(null);
+    nextSeqRef.current = 0;
+    sentencesRef.current = [];
     setSentences([]);
     setActiveIndex(-1);
-    allDoneRef.current = false;
-    playStartedRef.current = false;
-
-    const unlisten = listen<SentenceAudioEvent>(
-      "audio-sentence",
-      async (event) => {
-        const data = event.payload;
-        if (data.job_id !== jobId) return;
-
-        setTotalExpected(data.total);
-
-        // Load blob URL for playback
-        const blobUrl = await getAudioUrl(data.wav_path);
-
-        const newSentence: SentenceInfo = {
-          index: data.index,
-          text: data.text,
-          wavPath: data.wav_path,
-          durationSecs: data.duration_secs,
-          blobUrl,
-        };
-
-        setSentences((prev) => {
-          const next = [...prev, newSentence];
-          return next;
-        });
-      },
-    );
-
-    return () => {
-      unlisten.then((fn) => fn());
-    };
-  }, [jobId]);
-
-  // Auto-start playback once buffer threshold reached
-  useEffect(() => {
-    if (playStartedRef.current) return;
-    if (state !== "buffering") return;
-
-    const readyCount = sentences.filter((s) => s.blobUrl).length;
-    const allReceived = totalExpected > 0 && sentences.length >= totalExpected;
-    const bufferReady = readyCount >= BUFFER_THRESHOLD || allReceived;
+    setState("buffering");
 
-    if (bufferReady && readyCount > 0) {
-      playStartedRef.current = true;
-      setActiveIndex(0);
-      setState("playing");
-    }
-  }, [sentences, state, totalExpected]);
+    const channel = new Channel<ArrayBuffer>();
+    channel.onmessage = handleFrame;
+    return channel;
+  }, [close, handleFrame]);
 
-  // Play the active sentence
+  // Follow the playhead to highlight the sentence being read
   useEffect(() => {
-    if (state !== "playing" || activeIndex < 0) return;
-
-    const sentence = sentencesRef.current[activeIndex];
-    if (!sentence?.blobUrl) return;
+    if (state !== "playing" && state !== "buffering") return;
 
-    const audio = new Audio(sentence.blobUrl);
-    audioRef.current = audio;
+    const timer = setInterval(() => {
+      const ctx = contextRef.current;
+      if (!ctx || buffersRef.current.length === 0) return;
+      const position = ctx.currentTime - originRef.current;
 
-    audio.onended = () => {
-      const nextIndex = activeIndex + 1;
-      const currentSentences = sentencesRef.current;
-
-      if (nextIndex < currentSentences.length && currentSentences[nextIndex]?.blobUrl) {
-        setActiveIndex(nextIndex);
-      } else if (totalExpected > 0 && nextIndex >= totalExpected) {
-        // All sentences played
-        setState("complete");
-        setActiveIndex(-1);
+      let index = -1;
+      for (const s of sentencesRef.current) {
+        if (s.start <= position) index = s.index;
+      }
+      setActiveIndex(index);
+
+      if (position >= queuedSecsRef.current) {
+        if (endedRef.current) {
+          setState(errorRef.current ? "failed" : "complete");
+          setActiveIndex(-1);
+        } else {
+          setState("buffering");
+        }
       } else {
-        // Wait for more sentences — go back to buffering
-        setState("buffering");
-        setActiveIndex(nextIndex);
+        setState("playing");
       }
-    };
+    }, 100);
 
-    audio.play().catch(() => {
-      // Browser may block autoplay; that's OK, user can trigger via pause/play
-    });
-
-    return () => {
-      audio.pause();
-      audio.onended = null;
-    };
-  }, [activeIndex, state, totalExpected]);
+    return () => clearInterval(timer);
+  }, [state]);
 
-  // If we were waiting for more sentences and they arrived, resume playing
-  useEffect(() => {
-    if (state !== "buffering" || activeIndex < 0) return;
-    const sentence = sentences[activeIndex];
-    if (sentence?.blobUrl) {
-      setState("playing");
-    }
-  }, [sentences, state, activeIndex]);
+  useEffect(() => close, [close]);
 
   const pause = useCallback(() => {
-    if (audioRef.current) {
-      audioRef.current.pause();
-    }
+    contextRef.current?.suspend();
     setState("paused");
   }, []);
 
   const play = useCallback(() => {
-    if (state === "paused" && audioRef.current) {
-      audioRef.current.play();
+    const ctx = contextRef.current;
+    if (!ctx) return;
+    if (state === "paused") {
+      ctx.resume();
       setState("playing");
-    } else if (state === "complete") {
-      // Restart from beginning
-      setActiveIndex(0);
+    } else if (state === "complete" || state === "failed") {
+      // Replay everything received from the beginning
+      originRef.current = ctx.currentTime + START_DELAY_SECS;
+      let at = 0;
+      for (const buffer of buffersRef.current) {
+        schedule(buffer, at);
+        at += buffer.duration;
+      }
       setState("playing");
     }
-  }, [state]);
+  }, [state, schedule]);
 
   const isPlaying = state === "playing";
   const isBuffering = state === "buffering";
@@ -169,6 +178,8 @@ export function useStreamingAudio(jobId: string | null) {
     isPlaying,
     isBuffering,
     state,
+    error,
+    open,
     play,
     pause,
   };
@@ -9,6 +9,11 @@ export function useVoiceSettings() {
   const [settings, setSettings] = useState<VoiceSettingsData>({
     tts_voice: "af_nova",
     tts_speed: "0.5",
+    tts_effect: "none",
+    audio_mastering: "none",
+    music_enabled: "false",
+    ambience_enabled: "false",
+    audio_job_concurrency: "1",
   });
   const [loading, setLoading] = useState(true);
   const [saving, setSaving] = useState(false);
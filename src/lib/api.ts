@@ -1,5 +1,14 @@
 import { invoke, Channel } from "@tauri-apps/api/core";
-import type { DependencyStatus, StoryToken } from "@/types";
+import type {
+  AudioJobPriority,
+  AudiobookFormat,
+  CaptionFormat,
+  DependencyStatus,
+  NetworkInterface,
+  PodcastFeedStatus,
+  QueuedAudioJob,
+  StoryToken,
+} from "@/types";
 
 export async function checkDependency(
   name: string,
@@ -52,15 +61,67 @@ export async function continueStory(
 export async function startAudioGeneration(
   jobId: string,
   partId: string,
-  text: string,
+  priority: AudioJobPriority = "play_now",
+  onAudio?: Channel<ArrayBuffer>,
 ): Promise<{ job_id: string; status: string }> {
   return invoke("start_audio_generation", {
     jobId,
     partId,
-    text,
+    priority,
+    onAudio,
   });
 }
 
+/**
+ * Write a new story and narrate it as it is written. Tokens arrive on
+ * `onToken` and audio frames on `onAudio` (see `useStreamingAudio`); the
+ * story is saved as part 1 with audio job `jobId`, queued for mixing once
+ * the text is done.
+ */
+export async function narrateNewStory(
+  ids: { storyId: string; partId: string; jobId: string },
+  title: string,
+  genre: { id: string; name: string; description: string },
+  titleHint: string | null,
+  onToken: (token: StoryToken) => void,
+  onAudio: Channel<ArrayBuffer>,
+): Promise<string> {
+  const channel = new Channel<StoryToken>();
+  channel.onmessage = onToken;
+
+  return invoke<string>("narrate_new_story", {
+    ...ids,
+    title,
+    genreId: genre.id,
+    genreName: genre.name,
+    genreDescription: genre.description,
+    titleHint,
+    onToken: channel,
+    onAudio,
+  });
+}
+
+export async function cancelAudioGeneration(jobId: string): Promise<void> {
+  return invoke("cancel_audio_generation", { jobId });
+}
+
+export async function listAudioQueue(): Promise<QueuedAudioJob[]> {
+  return invoke<QueuedAudioJob[]>("list_audio_queue");
+}
+
+export async function reorderAudioJob(
+  jobId: string,
+  position: number,
+): Promise<QueuedAudioJob[]> {
+  return invoke<QueuedAudioJob[]>("reorder_audio_job", { jobId, position });
+}
+
+export async function retryAudioJob(
+  jobId: string,
+): Promise<{ job_id: string; status: string }> {
+  return invoke("retry_audio_job", { jobId });
+}
+
 export async function applyMigrations(): Promise<string> {
   return invoke<string>("apply_migrations");
 }
@@ -75,3 +136,85 @@ export async function getAudioJobStatus(
 }> {
   return invoke("get_audio_job_status", { jobId });
 }
+
+export async function listVoices(): Promise<string[]> {
+  return invoke<string[]>("list_voices");
+}
+
+export async function importVoice(
+  path: string,
+  name: string,
+): Promise<{ name: string; path: string }[]> {
+  return invoke("import_voice", { path, name });
+}
+
+export interface SentencePhonemes {
+  index: number;
+  text: string;
+  phonemes: string;
+}
+
+export async function phonemizeText(partId: string): Promise<SentencePhonemes[]> {
+  return invoke<SentencePhonemes[]>("phonemize_text", { partId });
+}
+
+export async function synthesizePhonemes(
+  partId: string,
+  index: number,
+  phonemes: string,
+): Promise<{ wav_path: string; duration_secs: number; job_id: string }> {
+  return invoke("synthesize_phonemes", { partId, index, phonemes });
+}
+
+export async function exportStoryCaptions(
+  storyId: string,
+  format: CaptionFormat,
+  path: string,
+): Promise<void> {
+  return invoke("export_story_captions", { storyId, format, path });
+}
+
+export async function exportAudiobook(
+  storyId: string,
+  format: AudiobookFormat,
+  path: string,
+  spokenTitle: boolean,
+): Promise<void> {
+  return invoke("export_audiobook", { storyId, format, path, spokenTitle });
+}
+
+export async function exportStoryEpub(storyId: string, path: string): Promise<void> {
+  return invoke("export_story_epub", { storyId, path });
+}
+
+export async function exportStoryPdf(
+  storyId: string,
+  path: string,
+  qrText: string | null,
+): Promise<void> {
+  return invoke("export_story_pdf", { storyId, path, qrText });
+}
+
+export async function generateDiscussionQuestions(partId: string): Promise<string[]> {
+  return invoke<string[]>("generate_discussion_questions", { partId });
+}
+
+export async function exportStoryBundle(storyId: string, path: string): Promise<void> {
+  return invoke("export_story_bundle", { storyId, path });
+}
+
+export async function importStoryBundle(path: string): Promise<string> {
+  return invoke<string>("import_story_bundle", { path });
+}
+
+export async function listNetworkInterfaces(): Promise<NetworkInterface[]> {
+  return invoke<NetworkInterface[]>("list_network_interfaces");
+}
+
+export async function applyPodcastFeed(): Promise<PodcastFeedStatus> {
+  return invoke<PodcastFeedStatus>("apply_podcast_feed");
+}
+
+export async function getPodcastFeedStatus(): Promise<PodcastFeedStatus> {
+  return invoke<PodcastFeedStatus>("get_podcast_feed_status");
+}
@@ -0,0 +1,54 @@
+/**
+ * Binary audio frames streamed by the audio pipeline (see
+ * `src-tauri/src/services/stream.rs`). Every frame starts with a kind byte
+ * and a little-endian u32 sequence number.
+ */
+export type AudioFrame =
+  | {
+      kind: "sentence";
+      seq: number;
+      index: number;
+      total: number;
+      /** Where the sentence starts in the stream, in seconds */
+      start: number;
+      text: string;
+    }
+  | { kind: "pcm"; seq: number; sampleRate: number; samples: Float32Array }
+  | {
+      kind: "end";
+      seq: number;
+      /** Why the narration stopped early, if it did */
+      error?: string;
+    };
+
+export function parseAudioFrame(buffer: ArrayBuffer): AudioFrame {
+  const view = new DataView(buffer);
+  const kind = view.getUint8(0);
+  const seq = view.getUint32(1, true);
+
+  switch (kind) {
+    case 0:
+      return {
+        kind: "sentence",
+        seq,
+        index: view.getUint32(5, true),
+        total: view.getUint32(9, true),
+        start: view.getFloat64(13, true),
+        text: new TextDecoder().decode(new Uint8Array(buffer, 21)),
+      };
+    case 1: {
+      const count = (buffer.byteLength - 9) / 2;
+      const samples = new Float32Array(count);
+      for (let i = 0; i < count; i++) {
+        samples[i] = view.getInt16(9 + i * 2, true) / 32767;
+      }
+      return { kind: "pcm", seq, sampleRate: view.getUint32(5, true), samples };
+    }
+    case 2: {
+      const error = new TextDecoder().decode(new Uint8Array(buffer, 5));
+      return error ? { kind: "end", seq, error } : { kind: "end", seq };
+    }
+    default:
+      throw new Error(`Unknown audio frame kind ${kind}`);
+  }
+}
@@ -8,14 +8,16 @@ export const GENRE_ICONS: Record<string, string> = {
 };
 
 export const AUDIO_STAGES = [
+  { key: "queued", label: "Waiting in Queue" },
   { key: "voice_generating", label: "Generating Voice" },
+  { key: "music_generating", label: "Adding Music" },
+  { key: "mixing", label: "Mixing" },
   { key: "complete", label: "Done" },
 ] as const;
 
 export const DEPENDENCY_STEPS = [
   { name: "ollama", label: "Ollama", description: "Local AI model server" },
   { name: "gemma3:4b", label: "Gemma 3 4B", description: "Story generation model" },
-  { name: "ffmpeg", label: "ffmpeg", description: "Audio processing tool" },
   {
     name: "espeak_ng",
     label: "espeak-ng",
@@ -27,3 +29,36 @@ export const DEPENDENCY_STEPS = [
     description: "Kokoro voice model (~115 MB)",
   },
 ] as const;
+
+export const VOICE_PRESETS = [
+  { value: "af_nova", label: "Nova (storytelling)" },
+  { value: "bf_emma", label: "Emma (narration)" },
+  { value: "af_heart", label: "Heart (warm)" },
+  { value: "af_bella", label: "Bella" },
+  { value: "af_jessica", label: "Jessica" },
+  { value: "af_sarah", label: "Sarah" },
+  { value: "af_sky", label: "Sky" },
+  { value: "am_adam", label: "Adam" },
+  { value: "am_michael", label: "Michael" },
+  { value: "bm_george", label: "George" },
+  { value: "bf_lily", label: "Lily" },
+  { value: "am_echo", label: "Echo" },
+];
+
+export const EFFECT_PRESETS = [
+  { value: "none", label: "None" },
+  { value: "giant", label: "Giant (deep, big)" },
+  { value: "wolf", label: "Wolf (low, gruff)" },
+  { value: "mouse", label: "Mouse (tiny, squeaky)" },
+  { value: "child", label: "Child" },
+  { value: "fairy", label: "Fairy (light, airy)" },
+];
+
+export const SOUNDSCAPE_PRESETS = [
+  { value: "none", label: "None" },
+  { value: "rain", label: "Rain" },
+  { value: "ocean", label: "Ocean waves" },
+  { value: "wind", label: "Wind" },
+  { value: "fire", label: "Crackling fire" },
+  { value: "crickets", label: "Night crickets" },
+];
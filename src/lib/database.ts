@@ -1,5 +1,11 @@
 import Database from "@tauri-apps/plugin-sql";
-import type { Genre, Story, StoryPart, AudioJob } from "@/types";
+import type {
+  Genre,
+  Story,
+  StoryPart,
+  AudioJob,
+  StoryCharacter,
+} from "@/types";
 
 let db: Database | null = null;
 
@@ -148,20 +154,113 @@ export async function getAudioJobForPart(
   return rows[0] ?? null;
 }
 
+/** The latest job of each part whose audio failed or was cancelled */
+export async function getStoppedAudioJobs(): Promise<AudioJob[]> {
+  const conn = await getDb();
+  return conn.select<AudioJob[]>(
+    `SELECT j.* FROM audio_jobs j
+     WHERE j.status IN ('failed', 'cancelled')
+       AND j.created_at = (SELECT MAX(created_at) FROM audio_jobs WHERE story_part_id = j.story_part_id)
+     ORDER BY j.updated_at DESC`,
+  );
+}
+
+/** "Story title, part N" for every story part, by part ID */
+export async function getPartLabels(): Promise<Map<string, string>> {
+  const conn = await getDb();
+  const rows = await conn.select<{ id: string; part_number: number; title: string }[]>(
+    "SELECT p.id, p.part_number, s.title FROM story_parts p JOIN stories s ON s.id = p.story_id",
+  );
+  return new Map(rows.map((r) => [r.id, `${r.title}, part ${r.part_number}`]));
+}
+
+// Character voice queries
+export async function getStoryCharacters(
+  storyId: string,
+): Promise<StoryCharacter[]> {
+  const conn = await getDb();
+  return conn.select<StoryCharacter[]>(
+    "SELECT story_id, name, voice, effect FROM story_characters WHERE story_id = $1 ORDER BY created_at, name",
+    [storyId],
+  );
+}
+
+export async function updateStoryCharacter(
+  storyId: string,
+  name: string,
+  voice: string,
+  effect: string,
+): Promise<void> {
+  const conn = await getDb();
+  await conn.execute(
+    "UPDATE story_characters SET voice = $1, effect = $2, updated_at = datetime('now') WHERE story_id = $3 AND name = $4",
+    [voice, effect, storyId, name],
+  );
+}
+
+// Soundscape queries. No row means "detect from the story text".
+export async function getStorySoundscape(
+  storyId: string,
+): Promise<string | null> {
+  const conn = await getDb();
+  const rows = await conn.select<{ soundscape: string }[]>(
+    "SELECT soundscape FROM story_soundscapes WHERE story_id = $1",
+    [storyId],
+  );
+  return rows[0]?.soundscape ?? null;
+}
+
+export async function setStorySoundscape(
+  storyId: string,
+  soundscape: string | null,
+): Promise<void> {
+  const conn = await getDb();
+  if (soundscape === null) {
+    await conn.execute("DELETE FROM story_soundscapes WHERE story_id = $1", [
+      storyId,
+    ]);
+    return;
+  }
+  await conn.execute(
+    "INSERT INTO story_soundscapes (story_id, soundscape) VALUES ($1, $2) ON CONFLICT(story_id) DO UPDATE SET soundscape = excluded.soundscape, updated_at = datetime('now')",
+    [storyId, soundscape],
+  );
+}
+
+// Discussion questions, generated per part and printed in the PDF export
+export async function getPartQuestions(partId: string): Promise<string[]> {
+  const conn = await getDb();
+  const rows = await conn.select<{ question: string }[]>(
+    "SELECT question FROM story_part_questions WHERE story_part_id = $1 ORDER BY position",
+    [partId],
+  );
+  return rows.map((r) => r.question);
+}
+
 // Settings queries
 export interface VoiceSettingsData {
   tts_voice: string;
   tts_speed: string;
+  tts_effect: string;
+  audio_mastering: string;
+  music_enabled: string;
+  ambience_enabled: string;
+  audio_job_concurrency: string;
 }
 
 export async function getVoiceSettings(): Promise<VoiceSettingsData> {
   const conn = await getDb();
   const rows = await conn.select<{ key: string; value: string }[]>(
-    "SELECT key, value FROM app_settings WHERE key IN ('tts_voice', 'tts_speed')",
+    "SELECT key, value FROM app_settings WHERE key IN ('tts_voice', 'tts_speed', 'tts_effect', 'audio_mastering', 'music_enabled', 'ambience_enabled', 'audio_job_concurrency')",
   );
   const settings: VoiceSettingsData = {
     tts_voice: "af_nova",
     tts_speed: "1.0",
+    tts_effect: "none",
+    audio_mastering: "none",
+    music_enabled: "false",
+    ambience_enabled: "false",
+    audio_job_concurrency: "1",
   };
   for (const row of rows) {
     if (row.key in settings) {
@@ -171,6 +270,30 @@ export async function getVoiceSettings(): Promise<VoiceSettingsData> {
   return settings;
 }
 
+export interface PodcastFeedSettingsData {
+  podcast_feed_enabled: string;
+  podcast_feed_address: string;
+  podcast_feed_port: string;
+}
+
+export async function getPodcastFeedSettings(): Promise<PodcastFeedSettingsData> {
+  const conn = await getDb();
+  const rows = await conn.select<{ key: string; value: string }[]>(
+    "SELECT key, value FROM app_settings WHERE key IN ('podcast_feed_enabled', 'podcast_feed_address', 'podcast_feed_port')",
+  );
+  const settings: PodcastFeedSettingsData = {
+    podcast_feed_enabled: "false",
+    podcast_feed_address: "127.0.0.1",
+    podcast_feed_port: "8765",
+  };
+  for (const row of rows) {
+    if (row.key in settings) {
+      settings[row.key as keyof PodcastFeedSettingsData] = row.value;
+    }
+  }
+  return settings;
+}
+
 export async function updateSetting(
   key: string,
   value: string,
@@ -4,3 +4,8 @@ import { twMerge } from "tailwind-merge";
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+/** Remove `[SFX: ...]` sound-effect cues from story text for display. */
+export function stripSfxCues(text: string): string {
+  return text.replace(/[ \t]*\[sfx:[^\]]*\][ \t]*/gi, " ").replace(/ *\n */g, "\n").trim();
+}
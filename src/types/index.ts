@@ -37,17 +37,56 @@ export interface AudioJob {
   id: string;
   story_part_id: string;
   voice_path: string | null;
+  music_path: string | null;
   final_path: string | null;
   status:
     | "pending"
     | "voice_generating"
+    | "music_generating"
+    | "mixing"
     | "complete"
-    | "failed";
+    | "failed"
+    | "cancelled";
   error_message: string | null;
   created_at: string;
   updated_at: string;
 }
 
+export type AudioJobPriority = "play_now" | "background";
+
+/** Caption files a story's timing can be exported as */
+export type CaptionFormat = "vtt" | "srt" | "lrc";
+
+/** Audiobook containers a whole story can be exported as */
+export type AudiobookFormat = "m4b" | "mp3";
+
+export interface QueuedAudioJob {
+  job_id: string;
+  story_part_id: string;
+  status: AudioJob["status"];
+  /** 1 for "play now", 0 for background pre-rendering */
+  priority: number;
+}
+
+/** A network interface the podcast feed can listen on */
+export interface NetworkInterface {
+  name: string;
+  address: string;
+}
+
+export interface PodcastFeedStatus {
+  running: boolean;
+  /** Feed address to subscribe to, while running */
+  url: string | null;
+}
+
+export interface StoryCharacter {
+  story_id: string;
+  name: string;
+  voice: string;
+  effect: string;
+}
+
 export interface StoryToken {
   token: string;
   done: boolean;